@@ -0,0 +1,8 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Bundled protoc, so building needs no system protobuf compiler
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    tonic_build::configure()
+        .build_server(false)
+        .compile(&["../../proto/neurorift/bridge/v1/bridge.proto"], &["../../proto"])?;
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use crate::agents::ScanRequest;
+use crate::state::{AgentType, Severity};
+use crate::websocket::{events::WSEvent, WebSocketServer};
+
+/// Messages buffered per subscriber before it lags
+const BUS_CAPACITY: usize = 256;
+
+/// Structured content agents hand to each other
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AgentMessage {
+    /// Planner: the next steps toward a goal
+    PlanProposal {
+        goal: String,
+        steps: Vec<ScanRequest>,
+    },
+    /// Operator: how the planned tasks ended
+    TaskResults {
+        results: Vec<TaskSummary>,
+    },
+    /// Analyst: findings drawn from the results
+    AnalysisResult {
+        findings: Vec<FindingSummary>,
+    },
+    /// Scribe: a piece of the report
+    ReportFragment {
+        section: String,
+        text: String,
+    },
+}
+
+/// One task's outcome, without its output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskSummary {
+    pub task_id: String,
+    pub tool_name: String,
+    pub target: String,
+    pub success: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// A recorded finding, by reference
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindingSummary {
+    pub finding_id: String,
+    pub title: String,
+    pub severity: Severity,
+}
+
+/// A message with its sender and recipient
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentEnvelope {
+    pub session_id: String,
+    pub run_id: String,
+    pub from: AgentType,
+    /// `None` for messages meant for every agent
+    pub to: Option<AgentType>,
+    pub message: AgentMessage,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Carries messages between agents and mirrors each one to clients as an
+/// `AgentMessage` event
+pub struct AgentBus {
+    tx: broadcast::Sender<AgentEnvelope>,
+    ws_server: Arc<WebSocketServer>,
+}
+
+impl AgentBus {
+    pub fn new(ws_server: Arc<WebSocketServer>) -> Self {
+        let (tx, _) = broadcast::channel(BUS_CAPACITY);
+        Self { tx, ws_server }
+    }
+    
+    /// Deliver a message to subscribed agents and observers
+    pub fn publish(&self, envelope: AgentEnvelope) {
+        tracing::debug!("{:?} -> {:?}: {:?}", envelope.from, envelope.to, envelope.message);
+        // No agent listening is fine; observers still see the event
+        let _ = self.tx.send(envelope.clone());
+        self.ws_server.broadcast(WSEvent::AgentMessage { envelope });
+    }
+    
+    /// Receive every message published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<AgentEnvelope> {
+        self.tx.subscribe()
+    }
+}
@@ -0,0 +1,465 @@
+pub mod bus;
+pub mod rules;
+
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, oneshot};
+use crate::agents::bus::{AgentEnvelope, AgentMessage, FindingSummary, TaskSummary};
+use crate::ai::Message;
+use crate::report::{ReportFormat, EXECUTIVE_SUMMARY_KEY};
+use crate::state::{Actor, AgentPhase, AgentState, AgentType, FindingUpsert, NewFinding, Severity, TaskStatus};
+use crate::telemetry::TraceContext;
+use crate::websocket::events::{TaskResult, WSEvent};
+use crate::NeuroRiftCore;
+
+/// Planning rounds when a run does not say
+const DEFAULT_MAX_CYCLES: u32 = 3;
+
+/// Upper bound on planning rounds per run
+const MAX_CYCLES: u32 = 10;
+
+/// How long the Operator waits for one round of tasks, approvals included
+const TASK_WAIT: Duration = Duration::from_secs(30 * 60);
+
+/// How often the Operator reports progress while tasks run
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Tool output the Analyst sees per task
+const ANALYST_OUTPUT_CHARS: usize = 4000;
+
+/// A tool run proposed by the Planner
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanRequest {
+    pub tool_name: String,
+    pub target: String,
+    #[serde(default)]
+    pub args: serde_json::Map<String, Value>,
+    #[serde(default)]
+    pub reasoning: Option<String>,
+}
+
+/// Where a plan came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanSource {
+    Ai,
+    Rules,
+}
+
+/// A finding as the Analyst writes it
+#[derive(Debug, Deserialize)]
+struct ProposedFinding {
+    title: String,
+    #[serde(default)]
+    severity: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    target: Option<String>,
+    #[serde(default)]
+    tool_source: Option<String>,
+}
+
+/// What a finished task left for the Analyst
+struct Outcome {
+    task_id: String,
+    tool_name: String,
+    target: String,
+    result: Result<TaskResult, String>,
+}
+
+/// Start the Planner→Operator→Analyst→Scribe loop on a session; one run
+/// per session at a time
+pub fn start(core: Arc<NeuroRiftCore>, session_id: String, goal: String, max_cycles: Option<u32>) -> Result<String> {
+    if core.session(&session_id).is_none() {
+        bail!("Session not loaded: {}", session_id);
+    }
+    if core.is_halted() {
+        bail!("Kill switch is engaged");
+    }
+    
+    let run = AgentRun {
+        run_id: format!("run_{}", &uuid::Uuid::new_v4().to_string().replace("-", "")[..8]),
+        core: core.clone(),
+        session_id: session_id.clone(),
+        goal,
+        max_cycles: max_cycles.unwrap_or(DEFAULT_MAX_CYCLES).clamp(1, MAX_CYCLES),
+        cycle: 0,
+        attempted: HashSet::new(),
+    };
+    let run_id = run.run_id.clone();
+    
+    // Hold the run until it is registered, so a fast finish cannot race it
+    let (registered_tx, registered_rx) = oneshot::channel::<()>();
+    let handle = tokio::spawn(async move {
+        if registered_rx.await.is_ok() {
+            run.run().await;
+        }
+    });
+    core.track_agent_run(&session_id, handle.abort_handle())?;
+    let _ = registered_tx.send(());
+    Ok(run_id)
+}
+
+/// One orchestration run over a session
+struct AgentRun {
+    run_id: String,
+    core: Arc<NeuroRiftCore>,
+    session_id: String,
+    goal: String,
+    max_cycles: u32,
+    /// Current planning round, from 1
+    cycle: u32,
+    /// Tool and target pairs already run, so rounds do not repeat work
+    attempted: HashSet<(String, String)>,
+}
+
+impl AgentRun {
+    async fn run(mut self) {
+        tracing::info!("Agent run {} started on {}: {}", self.run_id, self.session_id, self.goal);
+        let outcome = self.cycle().await;
+        
+        for agent in [AgentType::Planner, AgentType::Operator, AgentType::Analyst, AgentType::Scribe] {
+            self.core.update_agent_status_in(&self.session_id, agent, AgentState::Idle, None);
+        }
+        match outcome {
+            Ok(()) => self.phase(AgentPhase::Done, None),
+            Err(e) => {
+                tracing::warn!("Agent run {} failed: {:#}", self.run_id, e);
+                self.phase(AgentPhase::Failed, Some(format!("{:#}", e)));
+            }
+        }
+        self.core.agent_run_finished(&self.session_id);
+    }
+    
+    /// The state machine: plan, operate and analyze until the Planner runs
+    /// out of new steps or the cycle limit, then report
+    async fn cycle(&mut self) -> Result<()> {
+        while self.cycle < self.max_cycles {
+            self.cycle += 1;
+            self.phase(AgentPhase::Planning, None);
+            let plan = self.plan().await?;
+            if plan.is_empty() {
+                tracing::info!("Agent run {}: nothing new to do after {} cycles", self.run_id, self.cycle - 1);
+                break;
+            }
+            
+            self.send(AgentType::Planner, Some(AgentType::Operator), AgentMessage::PlanProposal {
+                goal: self.goal.clone(),
+                steps: plan.clone(),
+            });
+            self.phase(AgentPhase::Operating, Some(format!("{} steps", plan.len())));
+            let outcomes = self.operate(plan).await?;
+            
+            self.phase(AgentPhase::Analyzing, None);
+            self.analyze(outcomes).await?;
+        }
+        
+        self.phase(AgentPhase::Reporting, None);
+        self.report().await
+    }
+    
+    /// Planner: propose new, in-scope tool runs, falling back to the rule
+    /// planner when no model answers
+    async fn plan(&mut self) -> Result<Vec<ScanRequest>> {
+        self.core.update_agent_status_in(&self.session_id, AgentType::Planner, AgentState::Planning, Some(self.goal.clone()));
+        let reply = self.ask(AgentType::Planner, format!(
+            "Goal: {}\n\nReply with only the JSON array of next steps, or [] if the goal is met.",
+            self.goal,
+        )).await;
+        self.core.update_agent_status_in(&self.session_id, AgentType::Planner, AgentState::Idle, None);
+        
+        let session = self.core.session(&self.session_id).context("Session was unloaded")?;
+        let session = session.read();
+        let baseline = rules::baseline_plan(&session, &self.core.tools());
+        let (source, proposed) = match reply {
+            Ok(reply) => (PlanSource::Ai, parse_json_array::<ScanRequest>(&reply)?),
+            Err(e) => {
+                tracing::warn!("Agent run {}: using the rule planner: {:#}", self.run_id, e);
+                (PlanSource::Rules, baseline.clone())
+            }
+        };
+        
+        let tools = self.core.tools();
+        let mut plan = Vec::new();
+        for step in proposed {
+            if tools.describe(&step.tool_name).is_none() {
+                tracing::warn!("Planner proposed unknown tool {}", step.tool_name);
+                continue;
+            }
+            if let Err(reason) = session.check_target(&step.target) {
+                tracing::warn!("Planner proposed {} against {}: {}", step.tool_name, step.target, reason);
+                continue;
+            }
+            if self.attempted.insert(step_key(&step)) {
+                plan.push(step);
+            }
+        }
+        
+        // Rule steps the plan leaves out that have not been tried, for comparison
+        let planned: HashSet<_> = plan.iter().map(step_key).collect();
+        let missed: Vec<ScanRequest> = baseline.into_iter()
+            .filter(|step| !planned.contains(&step_key(step)) && !self.attempted.contains(&step_key(step)))
+            .filter(|step| session.check_target(&step.target).is_ok())
+            .collect();
+        if source == PlanSource::Ai && !missed.is_empty() {
+            tracing::info!("Agent run {}: AI plan skips {} rule-based steps", self.run_id, missed.len());
+        }
+        
+        self.core.ws_server().broadcast(WSEvent::PlanGenerated {
+            session_id: self.session_id.clone(),
+            run_id: self.run_id.clone(),
+            cycle: self.cycle,
+            source,
+            plan: plan.clone(),
+            baseline_missed: missed,
+        });
+        Ok(plan)
+    }
+    
+    /// Operator: queue the plan and wait for every task to finish
+    async fn operate(&self, plan: Vec<ScanRequest>) -> Result<Vec<Outcome>> {
+        // Subscribe first so no completion is missed
+        let mut rx = self.core.ws_server().get_sender().subscribe();
+        
+        let mut pending = HashMap::new();
+        for step in plan {
+            if self.core.is_halted() {
+                bail!("Kill switch is engaged");
+            }
+            self.core.update_agent_status_in(
+                &self.session_id,
+                AgentType::Operator,
+                AgentState::Executing,
+                Some(format!("{} {}", step.tool_name, step.target)),
+            );
+            let task_id = self.core.queue_task_in(
+                &self.session_id,
+                step.tool_name.clone(),
+                step.target.clone(),
+                Value::Object(step.args),
+                None,
+                Actor::Agent(AgentType::Operator),
+            )?;
+            pending.insert(task_id, (step.tool_name, step.target));
+        }
+        
+        let mut outcomes = Vec::new();
+        let deadline = tokio::time::Instant::now() + TASK_WAIT;
+        while !pending.is_empty() {
+            let wake = deadline.min(tokio::time::Instant::now() + HEARTBEAT_INTERVAL);
+            let (task_id, result) = match tokio::time::timeout_at(wake, rx.recv()).await {
+                Err(_) if wake < deadline => {
+                    // Long tasks are not a stall
+                    self.core.agent_heartbeat(&self.session_id, AgentType::Operator);
+                    continue;
+                }
+                Err(_) => {
+                    tracing::warn!("Agent run {}: {} tasks still unfinished, moving on", self.run_id, pending.len());
+                    break;
+                }
+                Ok(Ok(WSEvent::TaskCompleted { task_id, result })) => (task_id, Ok(result)),
+                Ok(Ok(WSEvent::TaskFailed { task_id, error })) => (task_id, Err(error)),
+                Ok(Ok(WSEvent::TaskCancelled { task_id, reason })) => (task_id, Err(reason)),
+                Ok(Ok(_)) => continue,
+                Ok(Err(broadcast::error::RecvError::Lagged(skipped))) => {
+                    // Outputs in the skipped events are lost; stop waiting for tasks that already ended
+                    tracing::warn!("Agent run {} lagged, skipped {} events", self.run_id, skipped);
+                    self.drop_finished(&mut pending);
+                    continue;
+                }
+                Ok(Err(broadcast::error::RecvError::Closed)) => bail!("Event channel closed"),
+            };
+            if let Some((tool_name, target)) = pending.remove(&task_id) {
+                outcomes.push(Outcome { task_id, tool_name, target, result });
+            }
+        }
+        
+        let results = outcomes.iter()
+            .map(|o| TaskSummary {
+                task_id: o.task_id.clone(),
+                tool_name: o.tool_name.clone(),
+                target: o.target.clone(),
+                success: o.result.as_ref().is_ok_and(|r| r.success),
+                error: o.result.as_ref().err().cloned(),
+            })
+            .collect();
+        self.send(AgentType::Operator, Some(AgentType::Analyst), AgentMessage::TaskResults { results });
+        self.core.update_agent_status_in(&self.session_id, AgentType::Operator, AgentState::Idle, None);
+        Ok(outcomes)
+    }
+    
+    /// Forget pending tasks the session already shows as finished
+    fn drop_finished(&self, pending: &mut HashMap<String, (String, String)>) {
+        let Some(session) = self.core.session(&self.session_id) else {
+            return;
+        };
+        let session = session.read();
+        pending.retain(|task_id, _| {
+            session.task_queue.iter()
+                .find(|t| &t.id == task_id)
+                .is_some_and(|t| !matches!(t.status, TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled | TaskStatus::Interrupted))
+        });
+    }
+    
+    /// Analyst: turn tool output into findings
+    async fn analyze(&self, outcomes: Vec<Outcome>) -> Result<()> {
+        if outcomes.is_empty() {
+            return Ok(());
+        }
+        self.core.update_agent_status_in(&self.session_id, AgentType::Analyst, AgentState::Analyzing, None);
+        
+        let results: Vec<String> = outcomes.iter()
+            .map(|o| match &o.result {
+                Ok(result) => format!(
+                    "Tool: {}\nTarget: {}\nOutput:\n{}",
+                    o.tool_name,
+                    o.target,
+                    result.output.chars().take(ANALYST_OUTPUT_CHARS).collect::<String>(),
+                ),
+                Err(error) => format!("Tool: {}\nTarget: {}\nFailed: {}", o.tool_name, o.target, error),
+            })
+            .collect();
+        let reply = match self.ask(AgentType::Analyst, format!(
+            "{}\n\nReply with only the JSON array of findings, or [] if there are none.",
+            results.join("\n---\n"),
+        )).await {
+            Ok(reply) => reply,
+            Err(e) => {
+                // The parsers have already recorded what the tools reported
+                tracing::warn!("Agent run {}: skipping analysis: {:#}", self.run_id, e);
+                self.core.update_agent_status_in(&self.session_id, AgentType::Analyst, AgentState::Idle, None);
+                return Ok(());
+            }
+        };
+        
+        let mut findings = Vec::new();
+        for proposed in parse_json_array::<ProposedFinding>(&reply)? {
+            let severity = parse_severity(&proposed.severity);
+            let finding = NewFinding {
+                title: proposed.title.clone(),
+                severity: severity.clone(),
+                description: proposed.description,
+                tool_source: proposed.tool_source.unwrap_or_else(|| "analyst".to_string()),
+                target: proposed.target,
+                details: serde_json::json!({ "run_id": self.run_id }),
+                cvss_vector: None,
+                asset_id: None,
+            };
+            let (FindingUpsert::Added(finding_id) | FindingUpsert::Merged(finding_id)) =
+                self.core.add_finding_to(&self.session_id, finding, Actor::Agent(AgentType::Analyst))?;
+            findings.push(FindingSummary {
+                finding_id,
+                title: proposed.title,
+                severity,
+            });
+        }
+        // The Planner sees these through its prompt's findings on the next round
+        self.send(AgentType::Analyst, Some(AgentType::Planner), AgentMessage::AnalysisResult { findings });
+        
+        self.core.update_agent_status_in(&self.session_id, AgentType::Analyst, AgentState::Idle, None);
+        Ok(())
+    }
+    
+    /// Scribe: write the executive summary and regenerate the report
+    async fn report(&self) -> Result<()> {
+        self.core.update_agent_status_in(&self.session_id, AgentType::Scribe, AgentState::Writing, None);
+        let summary = self.ask(AgentType::Scribe, format!(
+            "Write the executive summary for this engagement, whose goal was: {}\n\nReply with only the summary.",
+            self.goal,
+        )).await;
+        
+        // Without a model the report keeps its generated narrative
+        match summary {
+            Ok(summary) => {
+                let session = self.core.session(&self.session_id).context("Session was unloaded")?;
+                {
+                    let mut session = session.write();
+                    session.metadata.insert(EXECUTIVE_SUMMARY_KEY.to_string(), summary.trim().to_string());
+                    session.touch();
+                }
+                self.send(AgentType::Scribe, None, AgentMessage::ReportFragment {
+                    section: EXECUTIVE_SUMMARY_KEY.to_string(),
+                    text: summary.trim().to_string(),
+                });
+            }
+            Err(e) => tracing::warn!("Agent run {}: report has no executive summary: {:#}", self.run_id, e),
+        }
+        let path = self.core.generate_report(&self.session_id, ReportFormat::Markdown, None)?;
+        tracing::info!("Agent run {} wrote {}", self.run_id, path.display());
+        Ok(())
+    }
+    
+    /// One AI call with the agent's system prompt
+    async fn ask(&self, agent: AgentType, instruction: String) -> Result<String> {
+        let messages = [
+            self.core.agent_prompt(agent, Some(&self.session_id))?,
+            Message::user(instruction),
+        ];
+        let generation = self.core.models()
+            .generate(&messages, None, Some(&TraceContext::new_root()))
+            .await
+            .with_context(|| format!("{:?} could not reach a model", agent))?;
+        Ok(generation.text)
+    }
+    
+    /// Publish a hand-off on the agent bus
+    fn send(&self, from: AgentType, to: Option<AgentType>, message: AgentMessage) {
+        self.core.agent_bus().publish(AgentEnvelope {
+            session_id: self.session_id.clone(),
+            run_id: self.run_id.clone(),
+            from,
+            to,
+            message,
+            timestamp: chrono::Utc::now(),
+        });
+    }
+    
+    fn phase(&self, phase: AgentPhase, detail: Option<String>) {
+        self.core.set_run_phase(&self.session_id, phase, self.cycle, self.max_cycles);
+        self.core.ws_server().broadcast(WSEvent::AgentRunPhase {
+            session_id: self.session_id.clone(),
+            run_id: Some(self.run_id.clone()),
+            phase,
+            cycle: self.cycle,
+            detail,
+        });
+    }
+}
+
+/// Tool and target, case-folded, identifying a step across rounds
+fn step_key(step: &ScanRequest) -> (String, String) {
+    (step.tool_name.to_ascii_lowercase(), step.target.to_ascii_lowercase())
+}
+
+/// Items of the first JSON array in a model reply, skipping malformed ones
+fn parse_json_array<T: DeserializeOwned>(reply: &str) -> Result<Vec<T>> {
+    let (Some(start), Some(end)) = (reply.find('['), reply.rfind(']')) else {
+        bail!("Reply has no JSON array: {}", reply.chars().take(200).collect::<String>());
+    };
+    if end < start {
+        bail!("Reply has no JSON array: {}", reply.chars().take(200).collect::<String>());
+    }
+    
+    let items: Vec<Value> = serde_json::from_str(&reply[start..=end]).context("Reply has malformed JSON")?;
+    Ok(items.into_iter()
+        .filter_map(|item| serde_json::from_value(item)
+            .inspect_err(|e| tracing::warn!("Skipping malformed item in model reply: {}", e))
+            .ok())
+        .collect())
+}
+
+fn parse_severity(severity: &str) -> Severity {
+    match severity.trim().to_ascii_uppercase().as_str() {
+        "CRITICAL" => Severity::Critical,
+        "HIGH" => Severity::High,
+        "MEDIUM" => Severity::Medium,
+        "LOW" => Severity::Low,
+        _ => Severity::Info,
+    }
+}
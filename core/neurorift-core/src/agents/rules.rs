@@ -0,0 +1,104 @@
+use serde_json::Map;
+use crate::agents::ScanRequest;
+use crate::security::scope::target_host;
+use crate::state::asset::{Asset, Service};
+use crate::state::SessionState;
+use crate::tools::{Capability, ToolRegistry};
+
+/// Ports assumed to be HTTP when the scanner did not name the service
+const HTTP_PORTS: [u16; 6] = [80, 443, 8000, 8008, 8080, 8443];
+
+/// Ports assumed to be HTTPS when the scanner did not say
+const HTTPS_PORTS: [u16; 2] = [443, 8443];
+
+/// Deterministic next steps from the asset model: port-scan hosts with no
+/// known services, then probe and vulnerability-scan every HTTP service.
+///
+/// Used when no AI model is reachable, and as the baseline an AI plan is
+/// compared against. Scope and repeats are left to the caller.
+pub fn baseline_plan(session: &SessionState, tools: &ToolRegistry) -> Vec<ScanRequest> {
+    let port_scan = pick_tool(tools, Capability::PortScan);
+    let http_probe = pick_tool(tools, Capability::HttpProbe);
+    let vuln_scan = pick_tool(tools, Capability::VulnerabilityScan);
+    let mut plan = Vec::new();
+    
+    // Registered hosts nothing has reported on yet
+    for target in session.targets.iter().filter(|t| t.in_scope) {
+        let Some(host) = target_host(&target.value) else {
+            continue;
+        };
+        if session.assets.iter().any(|a| a.is_host(&host)) {
+            continue;
+        }
+        if let Some(tool) = &port_scan {
+            plan.push(step(tool, &host, "New target with no known services"));
+        }
+    }
+    
+    for asset in &session.assets {
+        let Some(host) = asset_host(asset) else {
+            continue;
+        };
+        if asset.services.is_empty() {
+            if let Some(tool) = &port_scan {
+                plan.push(step(tool, &host, "New host with no known services"));
+            }
+            continue;
+        }
+        
+        for service in asset.services.iter().filter(|s| is_http(s)) {
+            let url = service_url(&host, service);
+            let reason = format!("HTTP service {}", service.label());
+            if let Some(tool) = &http_probe {
+                plan.push(step(tool, &url, &reason));
+            }
+            if let Some(tool) = &vuln_scan {
+                plan.push(step(tool, &url, &reason));
+            }
+        }
+    }
+    plan
+}
+
+/// Tool offering a capability, preferring one that is installed
+fn pick_tool(tools: &ToolRegistry, capability: Capability) -> Option<String> {
+    let candidates = tools.with_capability(capability);
+    candidates.iter()
+        .find(|d| d.missing_binaries().is_empty())
+        .or(candidates.first())
+        .map(|d| d.name.clone())
+}
+
+fn step(tool_name: &str, target: &str, reasoning: &str) -> ScanRequest {
+    ScanRequest {
+        tool_name: tool_name.to_string(),
+        target: target.to_string(),
+        args: Map::new(),
+        reasoning: Some(reasoning.to_string()),
+    }
+}
+
+/// Name to scan an asset by: its first hostname, else its first address
+fn asset_host(asset: &Asset) -> Option<String> {
+    asset.hostnames.first().cloned()
+        .or_else(|| asset.addresses.first().map(|ip| ip.to_string()))
+}
+
+fn is_http(service: &Service) -> bool {
+    match service.name.as_deref() {
+        Some(name) => name.to_ascii_lowercase().contains("http"),
+        None => HTTP_PORTS.contains(&service.port),
+    }
+}
+
+fn service_url(host: &str, service: &Service) -> String {
+    let name = service.name.as_deref().unwrap_or_default().to_ascii_lowercase();
+    let tls = name.contains("https") || name.contains("ssl") || HTTPS_PORTS.contains(&service.port);
+    let host = if host.contains(':') { format!("[{}]", host) } else { host.to_string() };
+    match (tls, service.port) {
+        (true, 443) => format!("https://{}", host),
+        (false, 80) => format!("http://{}", host),
+        (true, port) => format!("https://{}:{}", host, port),
+        (false, port) => format!("http://{}:{}", host, port),
+    }
+}
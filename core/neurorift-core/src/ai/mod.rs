@@ -0,0 +1,412 @@
+pub mod ollama;
+pub mod openai;
+pub mod prompts;
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use reqwest::{Client, Response};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::ai::ollama::OllamaClient;
+use crate::ai::openai::OpenAiClient;
+use crate::python_bridge::PythonBridge;
+use crate::security::vault::SecretsVault;
+use crate::telemetry::TraceContext;
+
+/// AI backend settings file name under the base directory
+const AI_FILE: &str = "ai.json";
+
+/// How long a failed backend is passed over before it is tried again
+const UNAVAILABLE_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Who wrote a chat message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+}
+
+/// One message of a conversation sent to a model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub role: Role,
+    pub content: String,
+}
+
+impl Message {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self { role: Role::System, content: content.into() }
+    }
+    
+    pub fn user(content: impl Into<String>) -> Self {
+        Self { role: Role::User, content: content.into() }
+    }
+    
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self { role: Role::Assistant, content: content.into() }
+    }
+}
+
+/// A model's reply and where it came from
+#[derive(Debug, Clone)]
+pub struct Generation {
+    pub text: String,
+    pub model: String,
+    pub backend: String,
+}
+
+/// How a backend is reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendKind {
+    /// An Ollama server, through its native API
+    Ollama,
+    /// Any OpenAI-compatible `/chat/completions` API
+    Openai,
+    /// The Python bridge's `ai_generate` command
+    Bridge,
+}
+
+/// One AI backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendConfig {
+    /// Unique name; `name/model` in a request targets this backend only
+    pub name: String,
+    pub kind: BackendKind,
+    /// API root; `ollama` defaults to `OLLAMA_HOST` or the local port
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Bearer token, which may reference the vault as `{{secret:name}}`
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Models served here, exact or with a trailing `*`; empty serves any
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// Model used when a request names none; `ollama` falls back to its
+    /// first pulled model
+    #[serde(default)]
+    pub default_model: Option<String>,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    120
+}
+
+/// Ordered AI backends; requests go to the first one that serves the model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiConfig {
+    #[serde(default = "default_backends")]
+    pub backends: Vec<BackendConfig>,
+    /// Model used when neither the request nor the backend names one
+    #[serde(default)]
+    pub default_model: Option<String>,
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        Self {
+            backends: default_backends(),
+            default_model: None,
+        }
+    }
+}
+
+/// Local Ollama first, then the Python bridge
+fn default_backends() -> Vec<BackendConfig> {
+    vec![
+        BackendConfig {
+            name: "ollama".to_string(),
+            kind: BackendKind::Ollama,
+            url: None,
+            api_key: None,
+            models: Vec::new(),
+            default_model: None,
+            timeout_secs: default_timeout_secs(),
+        },
+        BackendConfig {
+            name: "bridge".to_string(),
+            kind: BackendKind::Bridge,
+            url: None,
+            api_key: None,
+            models: Vec::new(),
+            default_model: None,
+            timeout_secs: default_timeout_secs(),
+        },
+    ]
+}
+
+impl AiConfig {
+    /// Load AI backends from the base directory (defaults if absent)
+    pub fn load(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let path = base_dir.as_ref().join(AI_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let json = fs::read_to_string(&path)
+            .context("Failed to read AI config")?;
+        let config: Self = serde_json::from_str(&json)
+            .context("Failed to parse AI config")?;
+        
+        for (i, backend) in config.backends.iter().enumerate() {
+            if config.backends[..i].iter().any(|b| b.name == backend.name) {
+                bail!("Duplicate AI backend name: {}", backend.name);
+            }
+            if backend.kind == BackendKind::Openai && backend.url.is_none() {
+                bail!("AI backend {} needs a url", backend.name);
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Availability of one backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendStatus {
+    pub name: String,
+    pub kind: BackendKind,
+    pub available: bool,
+    pub last_error: Option<String>,
+    /// When a failed backend is next tried
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+struct Backend {
+    config: BackendConfig,
+    client: Client,
+}
+
+/// Routes AI requests to the configured backends by model, falling back
+/// down the list when a backend fails
+pub struct ModelManager {
+    backends: Vec<Backend>,
+    default_model: Option<String>,
+    health: DashMap<String, BackendStatus>,
+    bridge: Arc<PythonBridge>,
+    vault: Arc<SecretsVault>,
+}
+
+impl ModelManager {
+    pub fn new(config: AiConfig, bridge: Arc<PythonBridge>, vault: Arc<SecretsVault>) -> Result<Self> {
+        let health = DashMap::new();
+        let backends = config.backends.into_iter()
+            .map(|config| {
+                health.insert(config.name.clone(), BackendStatus {
+                    name: config.name.clone(),
+                    kind: config.kind,
+                    available: true,
+                    last_error: None,
+                    retry_at: None,
+                });
+                let client = Client::builder()
+                    .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+                    .build()?;
+                Ok(Backend { config, client })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        
+        Ok(Self {
+            backends,
+            default_model: config.default_model,
+            health,
+            bridge,
+            vault,
+        })
+    }
+    
+    /// Availability of every backend, in routing order
+    pub fn status(&self) -> Vec<BackendStatus> {
+        self.backends.iter()
+            .filter_map(|b| self.health.get(&b.config.name).map(|s| s.clone()))
+            .collect()
+    }
+    
+    /// Answer a conversation with the model requested, or a default.
+    ///
+    /// `backend/model` goes to that backend only; a bare model goes to each
+    /// backend serving it in turn until one answers. Backends that failed
+    /// recently are tried last.
+    pub async fn generate(&self, messages: &[Message], model: Option<&str>, trace: Option<&TraceContext>) -> Result<Generation> {
+        self.generate_streaming(messages, model, trace, &mut |_| {}).await
+    }
+    
+    /// Like [`generate`](Self::generate), passing each piece of the reply to
+    /// `on_delta` as it arrives. Once a backend has streamed part of a reply
+    /// its failure is final, since falling back would repeat the text.
+    pub async fn generate_streaming(
+        &self,
+        messages: &[Message],
+        model: Option<&str>,
+        trace: Option<&TraceContext>,
+        on_delta: &mut (dyn FnMut(&str) + Send),
+    ) -> Result<Generation> {
+        let candidates = self.candidates(model)?;
+        let mut errors = Vec::new();
+        
+        for (backend, model) in candidates {
+            let name = &backend.config.name;
+            let mut streamed = false;
+            let mut forward = |delta: &str| {
+                streamed = true;
+                on_delta(delta);
+            };
+            match self.call(backend, model.as_deref(), messages, trace, &mut forward).await {
+                Ok(generation) => {
+                    self.mark(name, None);
+                    return Ok(generation);
+                }
+                Err(e) => {
+                    tracing::warn!("AI backend {} failed: {:#}", name, e);
+                    self.mark(name, Some(format!("{:#}", e)));
+                    if streamed {
+                        return Err(e.context(format!("AI backend {} failed mid-reply", name)));
+                    }
+                    errors.push(format!("{}: {:#}", name, e));
+                }
+            }
+        }
+        bail!("No AI backend could answer ({})", errors.join("; "))
+    }
+    
+    /// Backends to try for a model, with the model name each should use
+    fn candidates(&self, model: Option<&str>) -> Result<Vec<(&Backend, Option<String>)>> {
+        if let Some((prefix, rest)) = model.and_then(|m| m.split_once('/')) {
+            if let Some(backend) = self.backends.iter().find(|b| b.config.name == prefix) {
+                return Ok(vec![(backend, Some(rest.to_string()))]);
+            }
+        }
+        
+        let mut candidates: Vec<_> = self.backends.iter()
+            .filter(|b| match model {
+                Some(model) => b.config.models.is_empty() || b.config.models.iter().any(|p| model_matches(p, model)),
+                None => true,
+            })
+            .map(|b| {
+                let model = model.map(str::to_string)
+                    .or_else(|| b.config.default_model.clone())
+                    .or_else(|| self.default_model.clone());
+                (b, model)
+            })
+            .collect();
+        if candidates.is_empty() {
+            bail!("No AI backend serves model {}", model.unwrap_or_default());
+        }
+        
+        // Stable sort: available backends keep their order ahead of failed ones
+        let now = Utc::now();
+        candidates.sort_by_key(|(b, _)| {
+            self.health.get(&b.config.name)
+                .and_then(|s| s.retry_at)
+                .is_some_and(|retry_at| retry_at > now)
+        });
+        Ok(candidates)
+    }
+    
+    async fn call(
+        &self,
+        backend: &Backend,
+        model: Option<&str>,
+        messages: &[Message],
+        trace: Option<&TraceContext>,
+        on_delta: &mut (dyn FnMut(&str) + Send),
+    ) -> Result<Generation> {
+        let config = &backend.config;
+        let (text, model) = match config.kind {
+            BackendKind::Ollama => {
+                let url = config.url.clone().unwrap_or_else(OllamaClient::default_url);
+                let ollama = OllamaClient::new(backend.client.clone(), url);
+                let model = match model {
+                    Some(model) => model.to_string(),
+                    None => ollama.list_models().await?
+                        .into_iter()
+                        .next()
+                        .ok_or_else(|| anyhow!("no model requested and none pulled"))?,
+                };
+                ollama.chat(&model, messages, on_delta).await?
+            }
+            BackendKind::Openai => {
+                let model = model.ok_or_else(|| anyhow!("no model requested or configured"))?;
+                let url = config.url.clone().context("no url configured")?;
+                let api_key = config.api_key.as_deref()
+                    .map(|key| self.vault.resolve_str(key))
+                    .transpose()?;
+                OpenAiClient::new(backend.client.clone(), url)
+                    .chat(model, messages, api_key.as_deref(), on_delta)
+                    .await?
+            }
+            BackendKind::Bridge => {
+                // The bridge answers in one piece
+                let (text, reported) = self.bridge.ai_generate(&flatten(messages), model, trace).await?;
+                on_delta(&text);
+                (text, reported.or_else(|| model.map(str::to_string)).unwrap_or_else(|| "default".to_string()))
+            }
+        };
+        
+        Ok(Generation {
+            text,
+            model,
+            backend: config.name.clone(),
+        })
+    }
+    
+    /// Record a backend's last outcome
+    fn mark(&self, name: &str, error: Option<String>) {
+        if let Some(mut status) = self.health.get_mut(name) {
+            let was_available = status.available;
+            status.available = error.is_none();
+            status.retry_at = error.as_ref()
+                .map(|_| Utc::now() + chrono::Duration::from_std(UNAVAILABLE_BACKOFF).unwrap_or_default());
+            status.last_error = error;
+            if status.available && !was_available {
+                tracing::info!("AI backend {} is available again", name);
+            }
+        }
+    }
+}
+
+/// Feed each line of a streamed response body to `on_line` as it arrives
+async fn for_each_line(mut response: Response, mut on_line: impl FnMut(&str) -> Result<()>) -> Result<()> {
+    let mut buffer = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        buffer.extend_from_slice(&chunk);
+        while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            on_line(String::from_utf8_lossy(&line).trim())?;
+        }
+    }
+    if !buffer.is_empty() {
+        on_line(String::from_utf8_lossy(&buffer).trim())?;
+    }
+    Ok(())
+}
+
+/// Match a model against a backend's pattern: exact, or a prefix ending in `*`
+fn model_matches(pattern: &str, model: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => model.starts_with(prefix),
+        None => pattern == model,
+    }
+}
+
+/// Render a conversation as one prompt for backends that take plain text
+fn flatten(messages: &[Message]) -> String {
+    if let [only] = messages {
+        return only.content.clone();
+    }
+    messages.iter()
+        .map(|m| match m.role {
+            Role::System => m.content.clone(),
+            Role::User => format!("User: {}", m.content),
+            Role::Assistant => format!("Assistant: {}", m.content),
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
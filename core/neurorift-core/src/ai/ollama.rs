@@ -0,0 +1,129 @@
+use anyhow::{bail, Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use crate::ai::{for_each_line, Message};
+
+/// Environment variable Ollama itself uses for its address
+const OLLAMA_HOST_ENV: &str = "OLLAMA_HOST";
+
+/// Where Ollama listens by default
+const DEFAULT_OLLAMA_URL: &str = "http://127.0.0.1:11434";
+
+/// Client for Ollama's native HTTP API
+pub struct OllamaClient {
+    client: Client,
+    base_url: String,
+}
+
+/// One line of a streamed `/api/chat` response
+#[derive(Deserialize)]
+struct ChatChunk {
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    message: Option<ChatMessage>,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct Tags {
+    models: Vec<LocalModel>,
+}
+
+#[derive(Deserialize)]
+struct LocalModel {
+    name: String,
+}
+
+impl OllamaClient {
+    /// `base_url` is the server root, e.g. `http://127.0.0.1:11434`
+    pub fn new(client: Client, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+    
+    /// Address from `OLLAMA_HOST`, or the local default
+    pub fn default_url() -> String {
+        match std::env::var(OLLAMA_HOST_ENV) {
+            Ok(host) if host.contains("://") => host,
+            Ok(host) if !host.is_empty() => format!("http://{}", host),
+            _ => DEFAULT_OLLAMA_URL.to_string(),
+        }
+    }
+    
+    /// One streamed `/api/chat` call, passing each piece of text to
+    /// `on_delta` as it arrives; returns the whole reply and the model that
+    /// produced it
+    pub async fn chat(
+        &self,
+        model: &str,
+        messages: &[Message],
+        on_delta: &mut (dyn FnMut(&str) + Send),
+    ) -> Result<(String, String)> {
+        let response = self.client
+            .post(format!("{}/api/chat", self.base_url))
+            .json(&json!({
+                "model": model,
+                "messages": messages,
+                "stream": true,
+            }))
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!("Ollama returned HTTP {}: {}", status, body.chars().take(200).collect::<String>());
+        }
+        
+        let mut text = String::new();
+        let mut reported = None;
+        let mut done = false;
+        for_each_line(response, |line| {
+            if line.is_empty() {
+                return Ok(());
+            }
+            let chunk: ChatChunk = serde_json::from_str(line).context("Invalid Ollama chat chunk")?;
+            if let Some(error) = chunk.error {
+                bail!("Ollama failed mid-reply: {}", error);
+            }
+            if chunk.model.is_some() {
+                reported = chunk.model;
+            }
+            if let Some(message) = chunk.message.filter(|m| !m.content.is_empty()) {
+                on_delta(&message.content);
+                text.push_str(&message.content);
+            }
+            done |= chunk.done;
+            Ok(())
+        }).await?;
+        
+        if !done {
+            bail!("Ollama reply ended early");
+        }
+        Ok((text, reported.unwrap_or_else(|| model.to_string())))
+    }
+    
+    /// Models pulled on this server (`/api/tags`)
+    pub async fn list_models(&self) -> Result<Vec<String>> {
+        let tags: Tags = self.client
+            .get(format!("{}/api/tags", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Invalid Ollama model list")?;
+        Ok(tags.models.into_iter().map(|m| m.name).collect())
+    }
+}
@@ -0,0 +1,101 @@
+use anyhow::{bail, Context, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use crate::ai::{for_each_line, Message};
+
+/// Client for OpenAI-compatible `/chat/completions` endpoints (OpenAI,
+/// vLLM, LM Studio, llama.cpp server, Ollama's `/v1`)
+pub struct OpenAiClient {
+    client: Client,
+    base_url: String,
+}
+
+/// One server-sent event of a streamed completion
+#[derive(Deserialize)]
+struct CompletionChunk {
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    choices: Vec<Choice>,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    #[serde(default)]
+    delta: Delta,
+}
+
+#[derive(Default, Deserialize)]
+struct Delta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+impl OpenAiClient {
+    /// `base_url` is the API root, e.g. `https://api.openai.com/v1`
+    pub fn new(client: Client, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+    
+    /// One streamed completion, passing each piece of text to `on_delta` as
+    /// it arrives; returns the whole reply and the model that produced it
+    pub async fn chat(
+        &self,
+        model: &str,
+        messages: &[Message],
+        api_key: Option<&str>,
+        on_delta: &mut (dyn FnMut(&str) + Send),
+    ) -> Result<(String, String)> {
+        let mut request = self.client
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(&json!({
+                "model": model,
+                "messages": messages,
+                "stream": true,
+            }));
+        if let Some(key) = api_key {
+            request = request.bearer_auth(key);
+        }
+        
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!("HTTP {}: {}", status, body.chars().take(200).collect::<String>());
+        }
+        
+        let mut text = String::new();
+        let mut reported = None;
+        let mut done = false;
+        for_each_line(response, |line| {
+            let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+                return Ok(());
+            };
+            if data == "[DONE]" {
+                done = true;
+                return Ok(());
+            }
+            
+            let chunk: CompletionChunk = serde_json::from_str(data).context("Invalid completion chunk")?;
+            if chunk.model.is_some() {
+                reported = chunk.model;
+            }
+            if let Some(delta) = chunk.choices.into_iter().next().and_then(|c| c.delta.content) {
+                if !delta.is_empty() {
+                    on_delta(&delta);
+                    text.push_str(&delta);
+                }
+            }
+            Ok(())
+        }).await?;
+        
+        if !done {
+            bail!("Completion stream ended early");
+        }
+        Ok((text, reported.unwrap_or_else(|| model.to_string())))
+    }
+}
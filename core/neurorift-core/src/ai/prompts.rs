@@ -0,0 +1,220 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::state::{AgentType, SessionState};
+use crate::state::summary::SessionSummary;
+use crate::tools::catalog::ToolDescriptor;
+
+/// Directory of per-agent templates under the base directory
+const PROMPTS_DIR: &str = "prompts";
+
+/// Newest findings included in `{{findings}}`
+const RECENT_FINDINGS: usize = 10;
+
+const PLANNER: &str = "\
+You are the Planner for the NeuroRift security system, working on session {{session}} in {{mode}} mode.
+
+Scope:
+{{scope}}
+
+Registered targets:
+{{targets}}
+
+Recent findings:
+{{findings}}
+
+Available tools:
+{{tools}}
+
+Propose the next steps as a JSON array. Each step has tool_name (exact name from the list), target, args and reasoning. Never propose a target outside the scope.
+";
+
+const OPERATOR: &str = "\
+You are the Operator for NeuroRift session {{session}} ({{mode}} mode). You run the planned tools and report exactly what they did.
+
+Scope:
+{{scope}}
+
+Available tools:
+{{tools}}
+";
+
+const NAVIGATOR: &str = "\
+You are the Navigator for NeuroRift session {{session}} ({{mode}} mode). You drive a browser through the in-scope web applications and describe what you observe.
+
+Scope:
+{{scope}}
+
+Registered targets:
+{{targets}}
+";
+
+const ANALYST: &str = "\
+You are the Analyst for NeuroRift session {{session}} ({{mode}} mode). Turn tool output into security findings.
+
+Scope:
+{{scope}}
+
+Findings so far (do not report these again):
+{{findings}}
+
+Return a JSON array of findings. Each has title, severity (CRITICAL, HIGH, MEDIUM, LOW or INFO), description, target and tool_source.
+";
+
+const SCRIBE: &str = "\
+You are the Scribe for NeuroRift session {{session}} ({{mode}} mode). Write clear, professional security reporting in Markdown.
+
+Scope:
+{{scope}}
+
+Session summary:
+{{summary}}
+
+Recent findings:
+{{findings}}
+
+Include an executive summary, technical details and recommendations.
+";
+
+/// System prompts for each agent, editable as `prompts/<agent>.txt` under
+/// the base directory.
+///
+/// Templates may use `{{session}}`, `{{mode}}`, `{{scope}}`, `{{targets}}`,
+/// `{{findings}}`, `{{summary}}` and `{{tools}}`; any other `{{...}}` is
+/// left as written.
+pub struct PromptTemplates {
+    dir: PathBuf,
+}
+
+impl PromptTemplates {
+    /// Use the templates under the base directory, writing out the built-in
+    /// ones that are missing so they can be edited
+    pub fn load(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = base_dir.as_ref().join(PROMPTS_DIR);
+        fs::create_dir_all(&dir).context("Failed to create prompts directory")?;
+        
+        for agent in [AgentType::Planner, AgentType::Operator, AgentType::Navigator, AgentType::Analyst, AgentType::Scribe] {
+            let path = dir.join(file_name(agent));
+            if !path.exists() {
+                fs::write(&path, builtin(agent))
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+            }
+        }
+        Ok(Self { dir })
+    }
+    
+    /// Current template for an agent; read on each call so edits apply
+    /// without a restart
+    pub fn template(&self, agent: AgentType) -> String {
+        let path = self.dir.join(file_name(agent));
+        match fs::read_to_string(&path) {
+            Ok(template) => template,
+            Err(e) => {
+                tracing::warn!("Using built-in {:?} prompt, could not read {}: {}", agent, path.display(), e);
+                builtin(agent).to_string()
+            }
+        }
+    }
+    
+    /// An agent's system prompt filled in with a session's context
+    pub fn render(&self, agent: AgentType, session: Option<&SessionState>, tools: &[ToolDescriptor]) -> String {
+        interpolate(&self.template(agent), &context(session, tools))
+    }
+}
+
+fn file_name(agent: AgentType) -> String {
+    format!("{:?}.txt", agent).to_lowercase()
+}
+
+fn builtin(agent: AgentType) -> &'static str {
+    match agent {
+        AgentType::Planner => PLANNER,
+        AgentType::Operator => OPERATOR,
+        AgentType::Navigator => NAVIGATOR,
+        AgentType::Analyst => ANALYST,
+        AgentType::Scribe => SCRIBE,
+    }
+}
+
+/// Template variables for a session
+fn context(session: Option<&SessionState>, tools: &[ToolDescriptor]) -> HashMap<&'static str, String> {
+    let mut vars = HashMap::new();
+    
+    let mut tools: Vec<_> = tools.iter()
+        .map(|t| if t.description.is_empty() { format!("- {}", t.name) } else { format!("- {}: {}", t.name, t.description) })
+        .collect();
+    tools.sort();
+    vars.insert("tools", or_none(tools, "(no tools available)"));
+    
+    let Some(session) = session else {
+        for name in ["session", "mode", "scope", "targets", "findings", "summary"] {
+            vars.insert(name, "(no session)".to_string());
+        }
+        return vars;
+    };
+    
+    vars.insert("session", session.name.clone());
+    vars.insert("mode", format!("{:?}", session.mode).to_uppercase());
+    
+    let scope = &session.scope;
+    let mut lines: Vec<String> = scope.cidrs.iter().map(|c| format!("- {}", c))
+        .chain(scope.domains.iter().map(|d| format!("- {}", d)))
+        .chain(scope.url_patterns.iter().map(|u| format!("- {}", u)))
+        .collect();
+    lines.extend(scope.exclusions.iter().map(|e| format!("- excluded: {}", e)));
+    lines.extend(scope.sensitive.iter().map(|s| format!("- sensitive: {}", s)));
+    vars.insert("scope", or_none(lines, "(no scope defined)"));
+    
+    let targets = session.targets.iter()
+        .map(|t| if t.in_scope { format!("- {}", t.value) } else { format!("- {} (out of scope)", t.value) })
+        .collect();
+    vars.insert("targets", or_none(targets, "(none registered)"));
+    
+    let mut findings: Vec<_> = session.findings.iter().collect();
+    findings.sort_by_key(|f| std::cmp::Reverse(f.discovered_at));
+    let findings = findings.into_iter()
+        .take(RECENT_FINDINGS)
+        .map(|f| {
+            let severity = format!("{:?}", f.severity).to_uppercase();
+            match &f.target {
+                Some(target) => format!("- [{}] {} on {}", severity, f.title, target),
+                None => format!("- [{}] {}", severity, f.title),
+            }
+        })
+        .collect();
+    vars.insert("findings", or_none(findings, "(none yet)"));
+    vars.insert("summary", SessionSummary::of(session).digest());
+    
+    vars
+}
+
+fn or_none(lines: Vec<String>, none: &str) -> String {
+    if lines.is_empty() {
+        none.to_string()
+    } else {
+        lines.join("\n")
+    }
+}
+
+/// Replace each known `{{name}}` with its value
+fn interpolate(template: &str, vars: &HashMap<&'static str, String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}").and_then(|end| vars.get(after[..end].trim()).map(|v| (end, v))) {
+            Some((end, value)) => {
+                out.push_str(value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                out.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
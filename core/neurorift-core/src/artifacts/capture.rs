@@ -0,0 +1,75 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::PathBuf;
+use super::{ArtifactStore, StoredFile};
+
+/// Key under which tools and browser actions report captured images
+pub const CAPTURES_KEY: &str = "screenshots";
+
+/// An image a tool, browser action or screen capture produced, either
+/// written to a file or returned inline
+#[derive(Debug, Clone, Deserialize)]
+pub struct Capture {
+    /// File the image was written to
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    /// Base64 image content, when not written to a file
+    #[serde(default)]
+    pub data: Option<String>,
+    /// Image format of inline data (`png`, `jpeg`)
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Page shown when the image was taken
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+impl Capture {
+    /// Name for the artifact: the file name, or one made up for inline data
+    pub fn name(&self) -> String {
+        self.path.as_deref()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| format!("capture-{}.{}", chrono::Utc::now().format("%Y%m%dT%H%M%S%3f"), self.extension()))
+    }
+    
+    fn extension(&self) -> &str {
+        self.format.as_deref().unwrap_or("png")
+    }
+    
+    /// Copy the image into the store. A captured file is removed once
+    /// stored, so captures do not pile up outside the store.
+    pub fn store(&self, store: &ArtifactStore) -> Result<StoredFile> {
+        if let Some(path) = &self.path {
+            let stored = store.ingest(path)?;
+            if !store.holds(path) {
+                if let Err(e) = std::fs::remove_file(path) {
+                    tracing::debug!("Leaving capture {:?} in place: {}", path, e);
+                }
+            }
+            return Ok(stored);
+        }
+        let data = self.data.as_deref().context("Capture has neither a path nor data")?;
+        let bytes = base64::engine::general_purpose::STANDARD.decode(data.trim())
+            .context("Capture data is not valid base64")?;
+        store.ingest_reader(bytes.as_slice(), Some(self.extension()))
+    }
+}
+
+/// Captures listed in a result's `screenshots` field, each an object or a
+/// bare file path
+pub fn captures(data: &Value) -> Vec<Capture> {
+    let Some(entries) = data.get(CAPTURES_KEY).and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    entries.iter()
+        .filter_map(|entry| match entry {
+            Value::String(path) => Some(Capture { path: Some(PathBuf::from(path)), data: None, format: None, url: None }),
+            entry => serde_json::from_value(entry.clone())
+                .inspect_err(|e| tracing::warn!("Ignoring malformed capture: {}", e))
+                .ok(),
+        })
+        .collect()
+}
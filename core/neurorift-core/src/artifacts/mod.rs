@@ -0,0 +1,228 @@
+pub mod capture;
+pub mod retention;
+pub mod transfer;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use crate::state::Artifact;
+
+/// Content kept in the store
+#[derive(Debug, Clone)]
+pub struct StoredFile {
+    /// Hex SHA-256 of the content
+    pub sha256: String,
+    pub size: u64,
+    pub path: PathBuf,
+    /// Identical content was already stored, so nothing new was written
+    pub deduplicated: bool,
+}
+
+/// Content-addressed file storage under `<base>/artifacts`. Each distinct
+/// content is kept once, at `<first two hex digits>/<sha256>.<ext>`; the
+/// extension is kept so viewers and reports can tell the file type.
+pub struct ArtifactStore {
+    root: PathBuf,
+}
+
+impl ArtifactStore {
+    /// Open the store, creating its directory if needed
+    pub fn new(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let root = base_dir.as_ref().join("artifacts");
+        fs::create_dir_all(root.join("tmp"))
+            .context("Failed to create artifact directory")?;
+        Ok(Self { root })
+    }
+    
+    /// Where content with this hash and extension is kept
+    pub fn path_for(&self, sha256: &str, extension: Option<&str>) -> PathBuf {
+        stored_path(&self.root, sha256, extension)
+    }
+    
+    /// Copy a file into the store
+    pub fn ingest(&self, source: &Path) -> Result<StoredFile> {
+        let file = File::open(source)
+            .with_context(|| format!("Failed to open {:?}", source))?;
+        self.ingest_reader(file, extension(source).as_deref())
+    }
+    
+    /// Store everything read from `reader`
+    pub fn ingest_reader(&self, mut reader: impl Read, extension: Option<&str>) -> Result<StoredFile> {
+        let mut writer = self.writer(extension)?;
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            let read = reader.read(&mut buffer).context("Failed to read artifact content")?;
+            if read == 0 {
+                break;
+            }
+            writer.write(&buffer[..read])?;
+        }
+        writer.finish()
+    }
+    
+    /// Whether a path is inside the store
+    pub fn holds(&self, path: &Path) -> bool {
+        path.starts_with(&self.root)
+    }
+    
+    /// Delete stored content; only paths inside the store are touched
+    pub fn remove(&self, path: &Path) -> Result<()> {
+        if !self.holds(path) {
+            anyhow::bail!("{:?} is not in the artifact store", path);
+        }
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to delete {:?}", path))
+            }
+            _ => Ok(()),
+        }
+    }
+    
+    /// Start storing content that arrives a piece at a time
+    pub fn writer(&self, extension: Option<&str>) -> Result<ArtifactWriter> {
+        let temp = self.root.join("tmp").join(Uuid::new_v4().to_string());
+        let out = BufWriter::new(File::create(&temp).context("Failed to create artifact file")?);
+        Ok(ArtifactWriter {
+            root: self.root.clone(),
+            temp,
+            out,
+            hasher: Sha256::new(),
+            size: 0,
+            extension: extension.map(str::to_string),
+        })
+    }
+}
+
+/// Content on its way into the store. It is hashed as it is written to a
+/// temporary file, which `finish` moves into place; dropping the writer
+/// unfinished discards it.
+pub struct ArtifactWriter {
+    root: PathBuf,
+    temp: PathBuf,
+    out: BufWriter<File>,
+    hasher: Sha256,
+    size: u64,
+    extension: Option<String>,
+}
+
+impl ArtifactWriter {
+    pub fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.hasher.update(data);
+        self.out.write_all(data).context("Failed to write artifact file")?;
+        self.size += data.len() as u64;
+        Ok(())
+    }
+    
+    /// Bytes written so far
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+    
+    /// Hex SHA-256 of what has been written so far
+    pub fn sha256(&self) -> String {
+        hex::encode(self.hasher.clone().finalize())
+    }
+    
+    pub fn finish(mut self) -> Result<StoredFile> {
+        self.out.flush().context("Failed to write artifact file")?;
+        self.out.get_ref().sync_all()?;
+        
+        let sha256 = hex::encode(std::mem::take(&mut self.hasher).finalize());
+        let path = stored_path(&self.root, &sha256, self.extension.as_deref());
+        let deduplicated = path.is_file();
+        if !deduplicated {
+            fs::create_dir_all(path.parent().expect("stored paths have a parent"))?;
+            fs::rename(&self.temp, &path).context("Failed to move artifact into place")?;
+            // Stored content never changes; guard against accidental edits
+            let mut permissions = fs::metadata(&path)?.permissions();
+            permissions.set_readonly(true);
+            fs::set_permissions(&path, permissions)?;
+        }
+        tracing::debug!("Stored {} bytes as {} (duplicate: {})", self.size, sha256, deduplicated);
+        Ok(StoredFile { sha256, size: self.size, path, deduplicated })
+    }
+}
+
+impl Drop for ArtifactWriter {
+    fn drop(&mut self) {
+        // Already gone once moved into place
+        let _ = fs::remove_file(&self.temp);
+    }
+}
+
+fn stored_path(root: &Path, sha256: &str, extension: Option<&str>) -> PathBuf {
+    let name = match extension {
+        Some(ext) => format!("{}.{}", sha256, ext),
+        None => sha256.to_string(),
+    };
+    root.join(&sha256[..2]).join(name)
+}
+
+/// How an artifact's file compares with the checksum taken when it was
+/// registered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityStatus {
+    Intact,
+    Missing,
+    /// Content differs from the recorded checksum
+    Tampered,
+    /// No checksum was recorded, so the file cannot be verified
+    Unhashed,
+}
+
+/// Outcome of re-hashing one artifact
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactCheck {
+    pub artifact_id: String,
+    pub name: String,
+    pub status: IntegrityStatus,
+    pub expected: Option<String>,
+    /// Hash of the file as found, when it could be read
+    pub actual: Option<String>,
+}
+
+/// Re-hash an artifact's file and compare it with its recorded checksum
+pub fn verify(artifact: &Artifact) -> ArtifactCheck {
+    let hashed = hash_file(Path::new(&artifact.path));
+    let actual = hashed.as_ref().ok().map(|(sha256, _)| sha256.clone());
+    let status = match (&artifact.sha256, &hashed) {
+        (_, Err(_)) => IntegrityStatus::Missing,
+        (None, Ok(_)) => IntegrityStatus::Unhashed,
+        (Some(expected), Ok((sha256, size))) => {
+            if expected.eq_ignore_ascii_case(sha256) && artifact.size.is_none_or(|s| s == *size) {
+                IntegrityStatus::Intact
+            } else {
+                IntegrityStatus::Tampered
+            }
+        }
+    };
+    ArtifactCheck {
+        artifact_id: artifact.id.clone(),
+        name: artifact.name.clone(),
+        status,
+        expected: artifact.sha256.clone(),
+        actual,
+    }
+}
+
+/// Hex SHA-256 and size of a file
+pub fn hash_file(path: &Path) -> Result<(String, u64)> {
+    let mut file = File::open(path)
+        .with_context(|| format!("Failed to open {:?}", path))?;
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("Failed to read {:?}", path))?;
+    Ok((hex::encode(hasher.finalize()), size))
+}
+
+/// Lowercase extension of a file name, if it is a plausible one
+pub fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .filter(|e| !e.is_empty() && e.len() <= 10 && e.chars().all(|c| c.is_ascii_alphanumeric()))
+}
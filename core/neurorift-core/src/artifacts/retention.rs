@@ -0,0 +1,76 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use crate::state::{Artifact, ArtifactType};
+
+/// Retention policy file name under the base directory
+const RETENTION_FILE: &str = "retention.json";
+
+/// How long artifacts are kept, by type. Types without a rule are kept
+/// forever.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Days to keep each artifact type
+    #[serde(default = "default_rules")]
+    pub rules: HashMap<ArtifactType, u32>,
+    /// Hours between the `artifact_expiring` warning and deletion
+    #[serde(default = "default_notice_hours")]
+    pub notice_hours: u32,
+    /// Never delete artifacts attached to a finding as evidence
+    #[serde(default = "default_keep_evidence")]
+    pub keep_evidence: bool,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            rules: default_rules(),
+            notice_hours: default_notice_hours(),
+            keep_evidence: default_keep_evidence(),
+        }
+    }
+}
+
+/// Raw tool logs go after 30 days; reports, screenshots and data stay
+fn default_rules() -> HashMap<ArtifactType, u32> {
+    HashMap::from([(ArtifactType::Log, 30)])
+}
+
+fn default_notice_hours() -> u32 {
+    24
+}
+
+fn default_keep_evidence() -> bool {
+    true
+}
+
+impl RetentionPolicy {
+    /// Load the retention policy from the base directory (defaults if absent)
+    pub fn load(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let path = base_dir.as_ref().join(RETENTION_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let json = fs::read_to_string(&path)
+            .context("Failed to read retention policy")?;
+        let policy = serde_json::from_str(&json)
+            .context("Failed to parse retention policy")?;
+        
+        Ok(policy)
+    }
+    
+    /// When an artifact expires, or `None` if it is kept forever
+    pub fn expiry(&self, artifact: &Artifact) -> Option<DateTime<Utc>> {
+        let days = self.rules.get(&artifact.artifact_type)?;
+        Some(artifact.created_at + Duration::days(i64::from(*days)))
+    }
+    
+    /// Warning given before an artifact is deleted
+    pub fn notice(&self) -> Duration {
+        Duration::hours(i64::from(self.notice_hours))
+    }
+}
@@ -0,0 +1,141 @@
+use anyhow::{bail, Context, Result};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncReadExt;
+use tokio::sync::Semaphore;
+use tokio::task::AbortHandle;
+use uuid::Uuid;
+use crate::artifacts::ArtifactWriter;
+use crate::state::ArtifactType;
+use crate::websocket::WebSocketServer;
+use crate::websocket::events::WSEvent;
+
+/// Payload bytes per binary frame
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Download frames queued for a client's socket at once
+const WINDOW: usize = 8;
+
+/// Largest upload accepted
+pub const MAX_UPLOAD_SIZE: u64 = 4 * 1024 * 1024 * 1024;
+
+/// Uploads that receive nothing for this long are abandoned
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Bytes of transfer ID at the start of every binary frame
+const ID_LEN: usize = 16;
+
+/// Which way an artifact is moving, from the server's side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferDirection {
+    Upload,
+    Download,
+}
+
+/// An upload waiting for the rest of its frames
+pub struct Upload {
+    pub client_id: String,
+    pub session_id: String,
+    pub name: String,
+    pub artifact_type: Option<ArtifactType>,
+    /// Announced size; the upload completes when this much has arrived
+    pub size: u64,
+    /// Announced hash, checked before the content is stored
+    pub sha256: Option<String>,
+    pub uploaded_by: String,
+    pub writer: ArtifactWriter,
+    pub progress: Progress,
+    pub last_activity: Instant,
+}
+
+/// Decides when a transfer has moved far enough to report: every 5%, at
+/// least a chunk apart, and at the end
+pub struct Progress {
+    total: u64,
+    reported: u64,
+}
+
+impl Progress {
+    pub fn new(total: u64) -> Self {
+        Self { total, reported: 0 }
+    }
+    
+    /// Whether `done` bytes is worth a progress event
+    pub fn due(&mut self, done: u64) -> bool {
+        let step = (self.total / 20).max(CHUNK_SIZE as u64);
+        if done >= self.total || done - self.reported >= step {
+            self.reported = done;
+            return true;
+        }
+        false
+    }
+}
+
+/// Transfers in flight, by transfer ID
+#[derive(Default)]
+pub struct Transfers {
+    pub uploads: DashMap<String, Upload>,
+    /// Running downloads with the client receiving each
+    pub downloads: DashMap<String, (String, AbortHandle)>,
+}
+
+/// A fresh transfer ID: a UUID, sent in JSON as 32 hex digits
+pub fn new_id() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+/// Binary frame: the transfer ID's 16 bytes, then the payload
+pub fn frame(transfer_id: &str, payload: &[u8]) -> Vec<u8> {
+    let id = Uuid::parse_str(transfer_id).expect("transfer IDs are UUIDs");
+    let mut frame = Vec::with_capacity(ID_LEN + payload.len());
+    frame.extend_from_slice(id.as_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Transfer ID and payload of a binary frame from a client
+pub fn split_frame(data: &[u8]) -> Option<(String, &[u8])> {
+    if data.len() < ID_LEN {
+        return None;
+    }
+    let id = Uuid::from_slice(&data[..ID_LEN]).ok()?;
+    Some((id.simple().to_string(), &data[ID_LEN..]))
+}
+
+/// Stream a file to a client as binary frames with progress events. At
+/// most a few frames wait in the client's queue, so large files are not
+/// read into memory faster than the socket drains.
+pub async fn send_file(ws: &WebSocketServer, client_id: &str, transfer_id: &str, path: &Path, size: u64) -> Result<()> {
+    let mut file = tokio::fs::File::open(path).await
+        .with_context(|| format!("Failed to open {:?}", path))?;
+    let window = Arc::new(Semaphore::new(WINDOW));
+    let mut progress = Progress::new(size);
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut sent = 0u64;
+    loop {
+        let read = file.read(&mut buffer).await.context("Failed to read artifact")?;
+        if read == 0 {
+            break;
+        }
+        let permit = window.clone().acquire_owned().await?;
+        if !ws.send_binary(client_id, frame(transfer_id, &buffer[..read]), Some(permit)) {
+            bail!("Client disconnected");
+        }
+        sent += read as u64;
+        if progress.due(sent) {
+            ws.send_to(client_id, WSEvent::TransferProgress {
+                transfer_id: transfer_id.to_string(),
+                bytes: sent,
+                total: size,
+            });
+        }
+    }
+    if sent != size {
+        bail!("Artifact changed size during the transfer");
+    }
+    Ok(())
+}
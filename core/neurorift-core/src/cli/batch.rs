@@ -0,0 +1,196 @@
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::cli::EventJournaler;
+use crate::executor::{native, TaskExecutor};
+use crate::report::ReportFormat;
+use crate::security::approval::Decision;
+use crate::security::roe::RulesOfEngagement;
+use crate::security::scope::EngagementScope;
+use crate::state::{Actor, OperationalMode, Severity, TaskStatus};
+use crate::NeuroRiftCore;
+
+/// Some tasks failed, were cancelled or were refused
+pub const EXIT_TASKS_FAILED: u8 = 2;
+
+/// A finding reached the plan's `fail_on` severity
+pub const EXIT_FINDINGS: u8 = 3;
+
+/// Tasks were still running when the plan's timeout hit
+pub const EXIT_TIMED_OUT: u8 = 4;
+
+/// How long a plan may run when it does not say
+const DEFAULT_TIMEOUT_MINUTES: u64 = 120;
+
+/// How often task progress is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An assessment described in YAML
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchPlan {
+    /// Session name
+    pub name: String,
+    #[serde(default = "default_mode")]
+    pub mode: OperationalMode,
+    /// In-scope targets registered on the session
+    #[serde(default)]
+    pub targets: Vec<String>,
+    #[serde(default)]
+    pub scope: Option<EngagementScope>,
+    #[serde(default)]
+    pub rules_of_engagement: Option<RulesOfEngagement>,
+    pub tasks: Vec<PlannedTask>,
+    /// Report formats to write once the tasks finish
+    #[serde(default = "default_reports")]
+    pub reports: Vec<ReportFormat>,
+    #[serde(default)]
+    pub timeout_minutes: Option<u64>,
+    /// Exit with [`EXIT_FINDINGS`] when a finding is at least this severe
+    #[serde(default)]
+    pub fail_on: Option<Severity>,
+}
+
+/// One tool run in a plan
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlannedTask {
+    pub tool: String,
+    pub target: String,
+    #[serde(default)]
+    pub args: Map<String, Value>,
+    /// Egress proxy for this task instead of the global one
+    #[serde(default)]
+    pub proxy: Option<String>,
+}
+
+fn default_mode() -> OperationalMode {
+    OperationalMode::Offensive
+}
+
+fn default_reports() -> Vec<ReportFormat> {
+    vec![ReportFormat::Markdown]
+}
+
+impl BatchPlan {
+    /// Parse a plan file, rejecting tools that cannot run natively before
+    /// anything runs
+    pub fn load(path: &Path, core: &NeuroRiftCore) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let plan: Self = serde_yaml::from_str(&text)
+            .with_context(|| format!("Invalid plan in {}", path.display()))?;
+        
+        if plan.tasks.is_empty() {
+            bail!("Plan {} has no tasks", path.display());
+        }
+        for task in &plan.tasks {
+            if core.tools().get(&task.tool).is_none() {
+                bail!("Plan uses {}, which has no native adapter", task.tool);
+            }
+        }
+        Ok(plan)
+    }
+}
+
+/// Create a session for the plan, run its tasks to completion, write the
+/// reports and return the exit status
+pub async fn run(core: Arc<NeuroRiftCore>, journal: &mut EventJournaler, path: &Path, operator: Actor) -> Result<ExitCode> {
+    let plan = BatchPlan::load(path, &core)?;
+    
+    // Batch runs do not depend on the Python bridge; missing binaries fail their task
+    std::env::set_var(native::BACKEND_ENV, "native");
+    
+    let session_id = core.create_session(plan.name.clone(), plan.mode, None)?;
+    if let Some(scope) = plan.scope.clone() {
+        core.set_scope(&session_id, scope)?;
+    }
+    if let Some(roe) = plan.rules_of_engagement.clone() {
+        core.set_rules_of_engagement(&session_id, roe)?;
+    }
+    for target in &plan.targets {
+        core.add_target(&session_id, target.clone(), true, None, operator.clone())?;
+    }
+    eprintln!("Session {} ({})", session_id, plan.name);
+    
+    let mut task_ids = Vec::new();
+    for task in &plan.tasks {
+        let task_id = core.queue_task_in(&session_id, task.tool.clone(), task.target.clone(), Value::Object(task.args.clone()), task.proxy.clone(), operator.clone())?;
+        task_ids.push(task_id);
+    }
+    // Nobody is around to approve out-of-scope targets
+    for approval in core.pending_approvals() {
+        let reason = Some("Batch runs do not wait for approvals".to_string());
+        core.decide_approval(&approval.id, Decision::Deny { reason }, Actor::System)?;
+    }
+    
+    let executor = tokio::spawn(TaskExecutor::new(core.clone()).run());
+    let timeout = Duration::from_secs(plan.timeout_minutes.unwrap_or(DEFAULT_TIMEOUT_MINUTES) * 60);
+    let deadline = tokio::time::Instant::now() + timeout;
+    let finished = loop {
+        journal.flush(&core);
+        if unfinished(&core, &session_id, &task_ids) == 0 {
+            break true;
+        }
+        if tokio::time::Instant::now() >= deadline {
+            break false;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    };
+    if !finished {
+        eprintln!("Timed out after {} minutes; stopping", timeout.as_secs() / 60);
+        core.engage_kill_switch(Actor::System);
+    }
+    executor.abort();
+    
+    for format in &plan.reports {
+        let report = core.generate_report(&session_id, *format, None)?;
+        eprintln!("Report: {}", report.display());
+    }
+    core.save_session(&session_id)?;
+    journal.flush(&core);
+    
+    let session = core.session(&session_id).context("Session was unloaded")?;
+    let session = session.read();
+    let failed = session.task_queue.iter()
+        .filter(|t| task_ids.contains(&t.id) && t.status != TaskStatus::Completed)
+        .count();
+    let mut by_severity: BTreeMap<Severity, usize> = BTreeMap::new();
+    for finding in &session.findings {
+        *by_severity.entry(finding.severity.clone()).or_default() += 1;
+    }
+    let over_threshold = plan.fail_on.as_ref()
+        .is_some_and(|threshold| session.findings.iter().any(|f| &f.severity >= threshold));
+    
+    eprintln!("Tasks: {} completed, {} not completed", task_ids.len() - failed, failed);
+    let counts: Vec<String> = by_severity.iter().rev().map(|(severity, count)| format!("{:?} {}", severity, count)).collect();
+    eprintln!("Findings: {}", if counts.is_empty() { "none".to_string() } else { counts.join(", ") });
+    println!("{}", session_id);
+    
+    Ok(if !finished {
+        ExitCode::from(EXIT_TIMED_OUT)
+    } else if over_threshold {
+        ExitCode::from(EXIT_FINDINGS)
+    } else if failed > 0 {
+        ExitCode::from(EXIT_TASKS_FAILED)
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// Plan tasks that have not reached a final state
+fn unfinished(core: &NeuroRiftCore, session_id: &str, task_ids: &[String]) -> usize {
+    let Some(session) = core.session(session_id) else {
+        return 0;
+    };
+    let session = session.read();
+    session.task_queue.iter()
+        .filter(|t| task_ids.contains(&t.id))
+        .filter(|t| !matches!(t.status, TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled | TaskStatus::Interrupted))
+        .count()
+}
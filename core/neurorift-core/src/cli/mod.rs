@@ -0,0 +1,410 @@
+pub mod batch;
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::{Map, Value};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use crate::config::CoreConfig;
+#[cfg(unix)]
+use crate::daemon;
+use crate::session::ExportFormat;
+use crate::state::Actor;
+use crate::websocket::events::WSEvent;
+use crate::NeuroRiftCore;
+
+/// Command line of the core binary
+#[derive(Debug, Parser)]
+#[command(name = "neurorift", version, about = "NeuroRift core")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Same as `serve --stdio`, kept for launchers that pass it bare
+    #[arg(long, hide = true)]
+    pub stdio: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the core (the default)
+    Serve {
+        /// Speak JSON-RPC on stdin/stdout instead of opening ports
+        #[arg(long)]
+        stdio: bool,
+        /// Detach from the terminal, logging to `<base>/neurorift.log`
+        #[arg(long, conflicts_with = "stdio")]
+        daemon: bool,
+    },
+    /// Show whether a core is running for this base directory
+    Status,
+    /// Stop the running core
+    Stop {
+        /// Seconds to wait for it to exit
+        #[arg(long, default_value_t = 30)]
+        timeout: u64,
+    },
+    /// Inspect and export saved sessions
+    #[command(subcommand)]
+    Sessions(SessionsCommand),
+    /// Manage a session's task queue
+    #[command(subcommand)]
+    Task(TaskCommand),
+    /// Run a YAML plan in a new session, write its reports and exit:
+    /// 0 when every task completed, 2 when some did not, 3 when a finding
+    /// reached `fail_on`, 4 on timeout
+    Run {
+        plan: PathBuf,
+    },
+    /// Open the terminal console on a running core
+    #[cfg(feature = "tui")]
+    Tui {
+        /// Core WebSocket URL; defaults to the configured `ws_addr`
+        #[arg(long)]
+        url: Option<String>,
+        #[arg(long, env = "NEURORIFT_API_KEY", hide_env_values = true)]
+        api_key: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SessionsCommand {
+    /// List saved sessions, most recently updated first
+    List {
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Export a session and print where it was written
+    Export {
+        session_id: String,
+        #[arg(long, value_enum, default_value_t = ExportArg::Nrs)]
+        format: ExportArg,
+        /// Copy the export here instead of leaving it in the exports directory
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        /// Password for the `archive` format
+        #[arg(long, env = "NEURORIFT_EXPORT_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+    },
+    /// Decrypt an `archive` export into the tar it holds
+    Decrypt {
+        archive: PathBuf,
+        /// Where to write the tar
+        #[arg(long, short)]
+        output: PathBuf,
+        #[arg(long, env = "NEURORIFT_EXPORT_PASSWORD", hide_env_values = true)]
+        password: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TaskCommand {
+    /// Queue a tool run in a saved session; a core picks it up when it
+    /// next loads the session
+    Queue {
+        tool: String,
+        target: String,
+        #[arg(long, short)]
+        session: String,
+        /// Tool argument as `key=value`; values are read as JSON when they parse
+        #[arg(long = "arg", value_name = "KEY=VALUE")]
+        args: Vec<String>,
+        /// Egress proxy for this task instead of the global one
+        #[arg(long, value_name = "URL")]
+        proxy: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ExportArg {
+    Nrs,
+    Jsonl,
+    Archive,
+    Cef,
+    Leef,
+    Stix,
+}
+
+impl From<ExportArg> for ExportFormat {
+    fn from(format: ExportArg) -> Self {
+        match format {
+            ExportArg::Nrs => ExportFormat::Nrs,
+            ExportArg::Jsonl => ExportFormat::Jsonl,
+            ExportArg::Archive => ExportFormat::Archive,
+            ExportArg::Cef => ExportFormat::Cef,
+            ExportArg::Leef => ExportFormat::Leef,
+            ExportArg::Stix => ExportFormat::Stix,
+        }
+    }
+}
+
+/// How to run the core when serving
+#[derive(Debug, Clone, Copy)]
+pub struct ServeMode {
+    pub stdio: bool,
+    pub daemon: bool,
+}
+
+impl Cli {
+    /// How this invocation runs the core, if it does
+    pub fn serve_mode(&self) -> Option<ServeMode> {
+        match &self.command {
+            None => Some(ServeMode { stdio: self.stdio, daemon: false }),
+            Some(Command::Serve { stdio, daemon }) => Some(ServeMode { stdio: *stdio || self.stdio, daemon: *daemon }),
+            Some(_) => None,
+        }
+    }
+}
+
+/// How long `status` waits for the running core to answer
+const STATUS_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Exit status of `status` when no core is running, as LSB init scripts use
+const EXIT_NOT_RUNNING: u8 = 3;
+
+/// Run a management subcommand against the store, without serving.
+///
+/// These edit session files directly, so a running core that has the same
+/// session loaded will overwrite the change on its next save.
+pub async fn run(command: Command) -> Result<ExitCode> {
+    let config = CoreConfig::load()?;
+    let operator_name = std::env::var("USER").unwrap_or_else(|_| "cli".to_string());
+    
+    // The console talks to a running core rather than the store
+    #[cfg(feature = "tui")]
+    if let Command::Tui { url, api_key } = command {
+        let url = url.unwrap_or_else(|| format!("ws://{}", config.ws_addr));
+        crate::tui::run(crate::tui::ConsoleOptions { url, operator: operator_name, api_key }).await?;
+        return Ok(ExitCode::SUCCESS);
+    }
+    
+    // These only look at the running core's PID file, or not at the store at all
+    match command {
+        Command::Status => return status(&config).await,
+        Command::Stop { timeout } => return stop(&config, Duration::from_secs(timeout)),
+        Command::Sessions(SessionsCommand::Decrypt { archive, output, password }) => {
+            return decrypt_archive(&archive, &output, &password).map(|()| ExitCode::SUCCESS);
+        }
+        _ => {}
+    }
+    
+    let core = Arc::new(NeuroRiftCore::new(config)?);
+    let operator = Actor::Operator(operator_name);
+    
+    // Journal what the command changes, as the serving core would
+    let mut journal = EventJournaler::new(&core);
+    let result = match command {
+        Command::Serve { .. } | Command::Status | Command::Stop { .. } | Command::Sessions(SessionsCommand::Decrypt { .. }) => bail!("not a store command"),
+        Command::Sessions(SessionsCommand::List { json }) => list_sessions(&core, json),
+        Command::Sessions(SessionsCommand::Export { session_id, format, output, password }) => {
+            export_session(&core, &session_id, format.into(), output, password.as_deref())
+        }
+        Command::Task(TaskCommand::Queue { tool, target, session, args, proxy }) => {
+            queue_task(&core, operator, &session, tool, target, &args, proxy)
+        }
+        Command::Run { plan } => return batch::run(core.clone(), &mut journal, &plan, operator).await,
+        #[cfg(feature = "tui")]
+        Command::Tui { .. } => unreachable!("handled above"),
+    };
+    journal.flush(&core);
+    result.map(|()| ExitCode::SUCCESS)
+}
+
+#[cfg(unix)]
+async fn status(config: &CoreConfig) -> Result<ExitCode> {
+    let Some(pid) = daemon::running_pid(&config.base_dir) else {
+        println!("Not running");
+        return Ok(ExitCode::from(EXIT_NOT_RUNNING));
+    };
+    let uptime = daemon::started_at(&config.base_dir)
+        .and_then(|started| started.elapsed().ok())
+        .map(|elapsed| format!(", up {}", format_uptime(elapsed)))
+        .unwrap_or_default();
+    println!("Running (pid {}{})", pid, uptime);
+    println!("WebSocket: ws://{}", config.ws_addr);
+    
+    // Ask the instance itself through its metrics endpoint
+    let url = format!("http://{}/metrics", config.metrics_addr);
+    let scrape = async { reqwest::get(&url).await?.error_for_status()?.text().await };
+    match tokio::time::timeout(STATUS_TIMEOUT, scrape).await {
+        Ok(Ok(metrics)) => {
+            let value = |name: &str| metric(&metrics, name).unwrap_or(0.0);
+            println!("Clients: {}", value("neurorift_ws_clients"));
+            println!("Sessions in memory: {}", value("neurorift_sessions"));
+            println!(
+                "Tasks: {} running, {} queued, {} awaiting approval",
+                value("neurorift_tasks{status=\"running\"}"),
+                value("neurorift_tasks{status=\"queued\"}"),
+                value("neurorift_tasks{status=\"awaiting_approval\"}"),
+            );
+        }
+        Ok(Err(e)) => println!("Metrics endpoint {} unreachable: {}", url, e),
+        Err(_) => println!("Metrics endpoint {} did not answer", url),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(unix)]
+fn stop(config: &CoreConfig, timeout: Duration) -> Result<ExitCode> {
+    let Some(pid) = daemon::running_pid(&config.base_dir) else {
+        eprintln!("Not running");
+        return Ok(ExitCode::SUCCESS);
+    };
+    if !daemon::stop(pid, timeout)? {
+        bail!("Core (pid {}) still running after {}s", pid, timeout.as_secs());
+    }
+    eprintln!("Stopped (pid {})", pid);
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Without a PID file there is no record of a running core
+#[cfg(not(unix))]
+async fn status(_config: &CoreConfig) -> Result<ExitCode> {
+    bail!("status is only supported on Unix")
+}
+
+#[cfg(not(unix))]
+fn stop(_config: &CoreConfig, _timeout: Duration) -> Result<ExitCode> {
+    bail!("stop is only supported on Unix")
+}
+
+/// Value of an unlabelled or fully labelled sample in Prometheus text output
+fn metric(text: &str, name: &str) -> Option<f64> {
+    text.lines()
+        .filter(|line| !line.starts_with('#'))
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(' ')?.trim().parse().ok())
+}
+
+fn format_uptime(elapsed: Duration) -> String {
+    let minutes = elapsed.as_secs() / 60;
+    match (minutes / (24 * 60), minutes / 60 % 24, minutes % 60) {
+        (0, 0, m) => format!("{}m", m),
+        (0, h, m) => format!("{}h {}m", h, m),
+        (d, h, _) => format!("{}d {}h", d, h),
+    }
+}
+
+fn list_sessions(core: &NeuroRiftCore, json: bool) -> Result<()> {
+    let sessions = core.session_manager().list_sessions()?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&sessions)?);
+        return Ok(());
+    }
+    
+    println!("{:<22} {:<24} {:<10} {:<10} {:>5} {:>8}  UPDATED", "ID", "NAME", "STATUS", "MODE", "TASKS", "FINDINGS");
+    for session in sessions {
+        println!(
+            "{:<22} {:<24} {:<10} {:<10} {:>5} {:>8}  {}",
+            session.id,
+            session.name,
+            format!("{:?}", session.status),
+            format!("{:?}", session.mode),
+            session.task_count,
+            session.finding_count,
+            session.updated_at.format("%Y-%m-%d %H:%M"),
+        );
+    }
+    Ok(())
+}
+
+fn export_session(core: &NeuroRiftCore, session_id: &str, format: ExportFormat, output: Option<PathBuf>, password: Option<&str>) -> Result<()> {
+    let path = core.export_session(session_id, format, password)?;
+    let path = match output {
+        Some(output) => {
+            std::fs::copy(&path, &output)
+                .with_context(|| format!("Failed to copy export to {}", output.display()))?;
+            output
+        }
+        None => path,
+    };
+    println!("{}", path.display());
+    Ok(())
+}
+
+/// Decrypt an archive export, leaving no partial tar behind if it does not verify
+fn decrypt_archive(archive: &Path, output: &Path, password: &str) -> Result<()> {
+    let file = std::fs::File::create(output)
+        .with_context(|| format!("Failed to create {}", output.display()))?;
+    let mut writer = std::io::BufWriter::new(file);
+    let result = crate::session::archive::decrypt(archive, password, &mut writer)
+        .and_then(|()| writer.flush().context("Failed to write the tar"));
+    drop(writer);
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(output);
+        return Err(e);
+    }
+    println!("{}", output.display());
+    Ok(())
+}
+
+fn queue_task(core: &NeuroRiftCore, operator: Actor, session_id: &str, tool: String, target: String, args: &[String], proxy: Option<String>) -> Result<()> {
+    if core.tools().describe(&tool).is_none() {
+        bail!("Unknown tool: {}", tool);
+    }
+    let args = parse_args(args)?;
+    
+    core.load_session(session_id)?;
+    let task_id = core.queue_task_in(session_id, tool.clone(), target.clone(), Value::Object(args.clone()), proxy.clone(), operator.clone())?;
+    core.save_session(session_id)?;
+    
+    let details = serde_json::json!({ "source": "cli", "task_id": task_id, "tool_name": tool, "target": target, "args": args, "proxy": proxy });
+    core.audit().record(operator, None, "queue_task", Some(session_id.to_string()), details, "accepted")?;
+    
+    let awaiting_approval = core.session(session_id)
+        .and_then(|s| s.read().task_queue.iter().find(|t| t.id == task_id).map(|t| t.approval_id.is_some()))
+        .unwrap_or(false);
+    if awaiting_approval {
+        eprintln!("Target is out of scope; the task waits for approval");
+    }
+    println!("{}", task_id);
+    Ok(())
+}
+
+/// `key=value` pairs, with values parsed as JSON when they are valid JSON
+fn parse_args(args: &[String]) -> Result<Map<String, Value>> {
+    args.iter()
+        .map(|arg| {
+            let (key, value) = arg.split_once('=')
+                .with_context(|| format!("Argument '{}' is not key=value", arg))?;
+            let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+            Ok((key.trim().to_string(), value))
+        })
+        .collect()
+}
+
+/// Writes the core's events to the journal, as the serving core's journal
+/// task does, whenever flushed
+pub struct EventJournaler {
+    events: broadcast::Receiver<WSEvent>,
+}
+
+impl EventJournaler {
+    /// Start collecting the core's events
+    pub fn new(core: &NeuroRiftCore) -> Self {
+        Self { events: core.ws_server().get_sender().subscribe() }
+    }
+    
+    /// Journal everything broadcast since the last flush
+    pub fn flush(&mut self, core: &NeuroRiftCore) {
+        use broadcast::error::TryRecvError;
+        let active = core.active_session_id();
+        loop {
+            match self.events.try_recv() {
+                Ok(event) if event.is_transient() => {}
+                Ok(event) => {
+                    if let Err(e) = core.journal().append(&event, active.as_deref()) {
+                        tracing::error!("Failed to journal event: {}", e);
+                    }
+                }
+                Err(TryRecvError::Lagged(skipped)) => {
+                    tracing::error!("Event journal lagged, {} events not recorded", skipped);
+                }
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            }
+        }
+    }
+}
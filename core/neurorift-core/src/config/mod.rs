@@ -0,0 +1,232 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// File under the base directory holding the core's settings
+const CONFIG_FILE: &str = "config.toml";
+
+/// Names a config file other than `<base>/config.toml`
+const CONFIG_ENV: &str = "NEURORIFT_CONFIG";
+
+/// Base directory for sessions, keys and every per-feature config
+const HOME_ENV: &str = "NEURORIFT_HOME";
+
+const WS_ADDR_ENV: &str = "NEURORIFT_WS_ADDR";
+const WS_SOCKET_ENV: &str = "NEURORIFT_WS_SOCKET";
+const BRIDGE_URL_ENV: &str = "NEURORIFT_BRIDGE_URL";
+const METRICS_ADDR_ENV: &str = "NEURORIFT_METRICS_ADDR";
+const AUTOSAVE_ENV: &str = "NEURORIFT_AUTOSAVE_SECS";
+const AGENT_STALL_ENV: &str = "NEURORIFT_AGENT_STALL_SECS";
+const MAX_TASKS_ENV: &str = "NEURORIFT_MAX_CONCURRENT_TASKS";
+const HEALTH_ENV: &str = "NEURORIFT_HEALTH_SECS";
+const DISK_WARN_ENV: &str = "NEURORIFT_DISK_WARN_MB";
+const DISK_CRITICAL_ENV: &str = "NEURORIFT_DISK_CRITICAL_MB";
+const REQUEUE_ENV: &str = "NEURORIFT_REQUEUE_INTERRUPTED";
+const DRAIN_ENV: &str = "NEURORIFT_DRAIN_SECS";
+
+/// Core settings from `config.toml`, each overridable by a `NEURORIFT_*`
+/// variable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CoreConfig {
+    /// Where sessions and the per-feature configs live
+    pub base_dir: PathBuf,
+    /// WebSocket listen address
+    pub ws_addr: SocketAddr,
+    /// Optional Unix socket for local clients
+    pub ws_socket: Option<PathBuf>,
+    pub python_bridge_url: String,
+    /// Prometheus endpoint
+    pub metrics_addr: SocketAddr,
+    /// How often the active session is saved; 0 disables auto-save
+    pub autosave_interval_secs: u64,
+    /// How long a busy agent may go without progress before it is flagged
+    pub agent_stall_secs: u64,
+    /// Tools allowed to run at once
+    pub max_concurrent_tasks: usize,
+    /// How often `SystemHealth` is broadcast; 0 disables it
+    pub health_interval_secs: u64,
+    /// Free space under the base directory below which operators are warned
+    pub disk_warn_mb: u64,
+    /// Free space below which artifact-heavy tasks are held
+    pub disk_critical_mb: u64,
+    /// Queue tasks a crash cut off again when their session is loaded
+    pub requeue_interrupted: bool,
+    /// How long running tasks get to finish on shutdown before they are
+    /// cancelled
+    pub shutdown_drain_secs: u64,
+    /// Directories ffuf wordlists are read from; tasks name a file under
+    /// one of them
+    pub wordlist_dirs: Vec<PathBuf>,
+    /// Directories nuclei templates are read from, likewise
+    pub nuclei_template_dirs: Vec<PathBuf>,
+}
+
+impl Default for CoreConfig {
+    fn default() -> Self {
+        Self {
+            base_dir: default_base_dir(),
+            ws_addr: SocketAddr::from(([127, 0, 0, 1], 8765)),
+            ws_socket: None,
+            python_bridge_url: "http://127.0.0.1:8766".to_string(),
+            metrics_addr: SocketAddr::from(([127, 0, 0, 1], 8767)),
+            autosave_interval_secs: 300,
+            agent_stall_secs: 15 * 60,
+            max_concurrent_tasks: 4,
+            health_interval_secs: 10,
+            disk_warn_mb: 2048,
+            disk_critical_mb: 512,
+            requeue_interrupted: false,
+            shutdown_drain_secs: 30,
+            wordlist_dirs: vec![PathBuf::from("/usr/share/wordlists"), PathBuf::from("/usr/share/seclists")],
+            nuclei_template_dirs: vec![home_dir().join("nuclei-templates")],
+        }
+    }
+}
+
+impl CoreConfig {
+    /// Read the config file, if any, then apply environment overrides.
+    ///
+    /// The file is `$NEURORIFT_CONFIG`, else `config.toml` under
+    /// `$NEURORIFT_HOME` or `~/.neurorift`.
+    pub fn load() -> Result<Self> {
+        let path = match std::env::var_os(CONFIG_ENV) {
+            Some(path) => PathBuf::from(path),
+            None => default_base_dir().join(CONFIG_FILE),
+        };
+        let mut config = Self::from_file(&path)?;
+        config.apply_env()?;
+        Ok(config)
+    }
+    
+    /// Settings from a TOML file, or the defaults when it does not exist
+    pub fn from_file(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("Invalid config in {}", path.display()))
+    }
+    
+    /// Auto-save period, if enabled
+    pub fn autosave_interval(&self) -> Option<Duration> {
+        (self.autosave_interval_secs > 0).then(|| Duration::from_secs(self.autosave_interval_secs))
+    }
+    
+    /// `SystemHealth` period, if enabled
+    pub fn health_interval(&self) -> Option<Duration> {
+        (self.health_interval_secs > 0).then(|| Duration::from_secs(self.health_interval_secs))
+    }
+    
+    pub fn agent_stall(&self) -> Duration {
+        Duration::from_secs(self.agent_stall_secs)
+    }
+    
+    pub fn shutdown_drain(&self) -> Duration {
+        Duration::from_secs(self.shutdown_drain_secs)
+    }
+    
+    /// Take the settings that can change while running from `fresh`,
+    /// returning the names of those that did
+    pub fn apply_reloadable(&mut self, fresh: &CoreConfig) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        if self.autosave_interval_secs != fresh.autosave_interval_secs {
+            self.autosave_interval_secs = fresh.autosave_interval_secs;
+            changed.push("autosave_interval_secs");
+        }
+        if self.agent_stall_secs != fresh.agent_stall_secs {
+            self.agent_stall_secs = fresh.agent_stall_secs;
+            changed.push("agent_stall_secs");
+        }
+        if self.max_concurrent_tasks != fresh.max_concurrent_tasks {
+            self.max_concurrent_tasks = fresh.max_concurrent_tasks;
+            changed.push("max_concurrent_tasks");
+        }
+        if self.health_interval_secs != fresh.health_interval_secs {
+            self.health_interval_secs = fresh.health_interval_secs;
+            changed.push("health_interval_secs");
+        }
+        if self.disk_warn_mb != fresh.disk_warn_mb {
+            self.disk_warn_mb = fresh.disk_warn_mb;
+            changed.push("disk_warn_mb");
+        }
+        if self.disk_critical_mb != fresh.disk_critical_mb {
+            self.disk_critical_mb = fresh.disk_critical_mb;
+            changed.push("disk_critical_mb");
+        }
+        if self.requeue_interrupted != fresh.requeue_interrupted {
+            self.requeue_interrupted = fresh.requeue_interrupted;
+            changed.push("requeue_interrupted");
+        }
+        if self.shutdown_drain_secs != fresh.shutdown_drain_secs {
+            self.shutdown_drain_secs = fresh.shutdown_drain_secs;
+            changed.push("shutdown_drain_secs");
+        }
+        changed
+    }
+    
+    /// Names of settings that differ in `fresh` but only apply on restart
+    pub fn restart_required(&self, fresh: &CoreConfig) -> Vec<&'static str> {
+        [
+            ("base_dir", self.base_dir != fresh.base_dir),
+            ("ws_addr", self.ws_addr != fresh.ws_addr),
+            ("ws_socket", self.ws_socket != fresh.ws_socket),
+            ("python_bridge_url", self.python_bridge_url != fresh.python_bridge_url),
+            ("metrics_addr", self.metrics_addr != fresh.metrics_addr),
+            ("wordlist_dirs", self.wordlist_dirs != fresh.wordlist_dirs),
+            ("nuclei_template_dirs", self.nuclei_template_dirs != fresh.nuclei_template_dirs),
+        ]
+        .into_iter()
+        .filter_map(|(name, differs)| differs.then_some(name))
+        .collect()
+    }
+    
+    fn apply_env(&mut self) -> Result<()> {
+        if let Some(home) = std::env::var_os(HOME_ENV) {
+            self.base_dir = PathBuf::from(home);
+        }
+        if let Some(socket) = std::env::var_os(WS_SOCKET_ENV) {
+            self.ws_socket = Some(PathBuf::from(socket));
+        }
+        if let Ok(url) = std::env::var(BRIDGE_URL_ENV) {
+            self.python_bridge_url = url;
+        }
+        override_parsed(&mut self.ws_addr, WS_ADDR_ENV)?;
+        override_parsed(&mut self.metrics_addr, METRICS_ADDR_ENV)?;
+        override_parsed(&mut self.autosave_interval_secs, AUTOSAVE_ENV)?;
+        override_parsed(&mut self.agent_stall_secs, AGENT_STALL_ENV)?;
+        override_parsed(&mut self.max_concurrent_tasks, MAX_TASKS_ENV)?;
+        override_parsed(&mut self.health_interval_secs, HEALTH_ENV)?;
+        override_parsed(&mut self.disk_warn_mb, DISK_WARN_ENV)?;
+        override_parsed(&mut self.disk_critical_mb, DISK_CRITICAL_ENV)?;
+        override_parsed(&mut self.requeue_interrupted, REQUEUE_ENV)?;
+        override_parsed(&mut self.shutdown_drain_secs, DRAIN_ENV)?;
+        self.max_concurrent_tasks = self.max_concurrent_tasks.max(1);
+        Ok(())
+    }
+}
+
+/// `$NEURORIFT_HOME`, else `~/.neurorift`
+fn default_base_dir() -> PathBuf {
+    match std::env::var_os(HOME_ENV) {
+        Some(home) => PathBuf::from(home),
+        None => home_dir().join(".neurorift"),
+    }
+}
+
+fn home_dir() -> PathBuf {
+    PathBuf::from(std::env::var_os("HOME").unwrap_or_default())
+}
+
+fn override_parsed<T: std::str::FromStr>(field: &mut T, var: &str) -> Result<()>
+where
+    T::Err: std::fmt::Display,
+{
+    if let Ok(value) = std::env::var(var) {
+        *field = value.parse().map_err(|e| anyhow::anyhow!("Invalid {}: {}", var, e))?;
+    }
+    Ok(())
+}
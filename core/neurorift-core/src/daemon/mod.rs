@@ -0,0 +1,152 @@
+use anyhow::{bail, Context, Result};
+use std::fs::OpenOptions;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// File under the base directory holding the serving core's process ID
+pub const PID_FILE: &str = "neurorift.pid";
+
+/// Where a detached core writes its output
+pub const LOG_FILE: &str = "neurorift.log";
+
+/// How long `serve --daemon` waits for the detached core to write its PID file
+const START_TIMEOUT: Duration = Duration::from_secs(10);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The serving core's claim on the base directory, released on drop
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// Record this process as the core serving `base_dir`, refusing if
+    /// another live core already does. A file left by a crashed core is
+    /// replaced.
+    pub fn create(base_dir: &Path) -> Result<Self> {
+        if let Some(pid) = running_pid(base_dir) {
+            if pid != std::process::id() {
+                bail!("A core is already running for {} (pid {})", base_dir.display(), pid);
+            }
+        }
+        std::fs::create_dir_all(base_dir)?;
+        let path = base_dir.join(PID_FILE);
+        std::fs::write(&path, format!("{}\n", std::process::id()))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(Self { path })
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        // Leave the file alone if another core has since claimed it
+        if read_pid(&self.path) == Some(std::process::id()) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Process ID of the live core serving `base_dir`, if any
+pub fn running_pid(base_dir: &Path) -> Option<u32> {
+    read_pid(&base_dir.join(PID_FILE)).filter(|pid| is_alive(*pid))
+}
+
+/// When the running core started, taken from its PID file
+pub fn started_at(base_dir: &Path) -> Option<std::time::SystemTime> {
+    std::fs::metadata(base_dir.join(PID_FILE)).and_then(|m| m.modified()).ok()
+}
+
+/// Start this binary again, detached from the terminal, with the same
+/// arguments minus `--daemon`, and wait until it has claimed the PID file.
+///
+/// Output goes to `<base>/neurorift.log`.
+pub fn spawn_detached(base_dir: &Path) -> Result<u32> {
+    if let Some(pid) = running_pid(base_dir) {
+        bail!("A core is already running for {} (pid {})", base_dir.display(), pid);
+    }
+    std::fs::create_dir_all(base_dir)?;
+    let log_path = base_dir.join(LOG_FILE);
+    let log = OpenOptions::new().create(true).append(true).open(&log_path)
+        .with_context(|| format!("Failed to open {}", log_path.display()))?;
+    
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .args(std::env::args_os().skip(1).filter(|arg| arg != "--daemon"))
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+    // SAFETY: setsid is async-signal-safe and touches no state shared with the parent
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setsid() == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    let mut child = command.spawn().context("Failed to start the core")?;
+    let pid = child.id();
+    
+    let deadline = Instant::now() + START_TIMEOUT;
+    while Instant::now() < deadline {
+        if let Some(status) = child.try_wait()? {
+            bail!("The core exited during startup ({}); see {}", status, log_path.display());
+        }
+        if running_pid(base_dir) == Some(pid) {
+            return Ok(pid);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    tracing::warn!("The core has not reported in after {}s; see {}", START_TIMEOUT.as_secs(), log_path.display());
+    Ok(pid)
+}
+
+/// Ask a core to shut down and wait up to `timeout` for it to exit.
+/// Returns whether it did.
+pub fn stop(pid: u32, timeout: Duration) -> Result<bool> {
+    // SAFETY: plain syscall on a PID we read from our own PID file
+    if unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) } == -1 {
+        return Err(std::io::Error::last_os_error()).with_context(|| format!("Failed to signal pid {}", pid));
+    }
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if !is_alive(pid) {
+            return Ok(true);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    Ok(false)
+}
+
+/// PIDs are positive `pid_t`s; 0 or a value wrapping negative would make
+/// `kill` signal a whole process group
+fn read_pid(path: &Path) -> Option<u32> {
+    let pid: u32 = std::fs::read_to_string(path).ok()?.trim().parse().ok()?;
+    (1..=i32::MAX as u32).contains(&pid).then_some(pid)
+}
+
+fn is_alive(pid: u32) -> bool {
+    // SAFETY: signal 0 only checks that the process exists
+    if unsafe { libc::kill(pid as libc::pid_t, 0) } == 0 {
+        return true;
+    }
+    // It exists but belongs to someone else
+    std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn pids_outside_pid_t_are_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PID_FILE);
+        for (contents, expected) in [("1234\n", Some(1234)), ("0\n", None), ("4294967295\n", None), ("2147483648", None), ("-1", None)] {
+            std::fs::write(&path, contents).unwrap();
+            assert_eq!(read_pid(&path), expected, "{:?}", contents);
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use anyhow::{bail, Context, Result};
+use parking_lot::RwLock;
+use reqwest::{Client, Method, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::enrichment::extract_cves;
+use crate::security::vault::SecretsVault;
+use crate::state::{Finding, SessionState, Severity};
+
+/// DefectDojo configuration file name under the base directory
+const DEFECTDOJO_FILE: &str = "defectdojo.json";
+
+/// Session metadata keys holding what a session was uploaded to
+pub const ENGAGEMENT_ID_KEY: &str = "defectdojo_engagement_id";
+pub const TEST_ID_KEY: &str = "defectdojo_test_id";
+
+/// DefectDojo's limit on `unique_id_from_tool`
+const MAX_UNIQUE_ID: usize = 500;
+
+/// A DefectDojo instance and the product findings are filed under
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DefectDojoConfig {
+    /// Base URL, e.g. `https://defectdojo.example.org`
+    pub url: String,
+    /// API v2 token, which may reference the vault as `{{secret:name}}`
+    pub api_key: String,
+    /// Product each session's engagement is created in
+    pub product_id: u64,
+    /// Test type the uploaded findings are recorded as
+    #[serde(default = "default_test_type")]
+    pub test_type: String,
+    /// Least severe finding uploaded when none are picked
+    #[serde(default = "default_min_severity")]
+    pub min_severity: Severity,
+    #[serde(default = "default_verify_tls")]
+    pub verify_tls: bool,
+}
+
+fn default_test_type() -> String {
+    "Pen Test".to_string()
+}
+
+fn default_min_severity() -> Severity {
+    Severity::Low
+}
+
+fn default_verify_tls() -> bool {
+    true
+}
+
+impl DefectDojoConfig {
+    /// Load the DefectDojo config from the base directory; `None` when absent
+    pub fn load(base_dir: impl AsRef<Path>) -> Result<Option<Self>> {
+        let path = base_dir.as_ref().join(DEFECTDOJO_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        
+        let json = fs::read_to_string(&path)
+            .context("Failed to read DefectDojo config")?;
+        let config = serde_json::from_str(&json)
+            .context("Failed to parse DefectDojo config")?;
+        Ok(Some(config))
+    }
+}
+
+/// Where an upload went and what it did
+#[derive(Debug, Clone)]
+pub struct Upload {
+    pub engagement_id: u64,
+    pub test_id: u64,
+    pub created: usize,
+    pub updated: usize,
+}
+
+/// Files a session's findings in DefectDojo: one engagement per session,
+/// one test inside it, findings matched on their dedup key
+pub struct DefectDojoClient {
+    config: RwLock<Option<DefectDojoConfig>>,
+    vault: Arc<SecretsVault>,
+}
+
+impl DefectDojoClient {
+    pub fn new(config: Option<DefectDojoConfig>, vault: Arc<SecretsVault>) -> Self {
+        Self { config: RwLock::new(config), vault }
+    }
+    
+    /// Replace the instance, returning whether it changed; uploads under
+    /// way finish against the old one
+    pub fn reload(&self, config: Option<DefectDojoConfig>) -> bool {
+        let mut current = self.config.write();
+        let changed = *current != config;
+        *current = config;
+        changed
+    }
+    
+    pub fn config(&self) -> Option<DefectDojoConfig> {
+        self.config.read().clone()
+    }
+    
+    /// Upload findings, reusing the engagement and test from an earlier
+    /// upload while they still exist. Findings already in the test are
+    /// updated rather than duplicated.
+    pub async fn upload(&self, session: &SessionState, findings: &[&Finding]) -> Result<Upload> {
+        let api = self.connect()?;
+        let known = |key: &str| session.metadata.get(key).and_then(|id| id.parse::<u64>().ok());
+        
+        let engagement_id = match known(ENGAGEMENT_ID_KEY) {
+            Some(id) if api.exists(&format!("engagements/{}/", id)).await? => id,
+            _ => api.create_engagement(session).await?,
+        };
+        let test_type = api.test_type().await?;
+        let test_id = match known(TEST_ID_KEY) {
+            Some(id) if known(ENGAGEMENT_ID_KEY) == Some(engagement_id) && api.exists(&format!("tests/{}/", id)).await? => id,
+            _ => api.create_test(session, engagement_id, test_type).await?,
+        };
+        
+        let mut upload = Upload { engagement_id, test_id, created: 0, updated: 0 };
+        for finding in findings {
+            let body = finding_body(finding, test_id, test_type);
+            let existing = api.request(Method::GET, "findings/", None, &[
+                ("test", test_id.to_string()),
+                ("unique_id_from_tool", unique_id(finding)),
+            ]).await?;
+            match existing["results"][0]["id"].as_u64() {
+                Some(id) => {
+                    api.request(Method::PATCH, &format!("findings/{}/", id), Some(body), &[]).await?;
+                    upload.updated += 1;
+                }
+                None => {
+                    api.request(Method::POST, "findings/", Some(body), &[]).await?;
+                    upload.created += 1;
+                }
+            }
+        }
+        Ok(upload)
+    }
+    
+    fn connect(&self) -> Result<Api> {
+        let Some(config) = self.config() else {
+            bail!("DefectDojo is not configured; add {} to the base directory", DEFECTDOJO_FILE);
+        };
+        let token = self.vault.resolve_str(&config.api_key)?;
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .danger_accept_invalid_certs(!config.verify_tls)
+            .build()?;
+        Ok(Api { client, token, config })
+    }
+}
+
+/// Findings worth uploading when none are picked
+pub fn default_selection<'a>(session: &'a SessionState, config: &DefectDojoConfig) -> Vec<&'a Finding> {
+    session.findings.iter()
+        .filter(|f| f.severity >= config.min_severity)
+        .collect()
+}
+
+/// An authenticated connection to the API
+struct Api {
+    client: Client,
+    token: String,
+    config: DefectDojoConfig,
+}
+
+impl Api {
+    async fn request(&self, method: Method, path: &str, body: Option<Value>, query: &[(&str, String)]) -> Result<Value> {
+        let response = self.send(method.clone(), path, body, query).await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!("DefectDojo {} {} returned HTTP {}: {}", method, path, status, body.chars().take(200).collect::<String>());
+        }
+        response.json().await.context("DefectDojo returned invalid JSON")
+    }
+    
+    /// Whether an object is still there
+    async fn exists(&self, path: &str) -> Result<bool> {
+        let response = self.send(Method::GET, path, None, &[]).await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(false),
+            status if status.is_success() => Ok(true),
+            status => bail!("DefectDojo GET {} returned HTTP {}", path, status),
+        }
+    }
+    
+    async fn send(&self, method: Method, path: &str, body: Option<Value>, query: &[(&str, String)]) -> Result<Response> {
+        let url = format!("{}/api/v2/{}", self.config.url.trim_end_matches('/'), path);
+        let mut request = self.client.request(method, &url)
+            .header(reqwest::header::AUTHORIZATION, format!("Token {}", self.token))
+            .header(reqwest::header::ACCEPT, "application/json")
+            .query(query);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        request.send().await
+            .with_context(|| format!("Failed to reach DefectDojo at {}", self.config.url))
+    }
+    
+    async fn test_type(&self) -> Result<u64> {
+        let found = self.request(Method::GET, "test_types/", None, &[("name", self.config.test_type.clone())]).await?;
+        found["results"][0]["id"].as_u64()
+            .with_context(|| format!("DefectDojo has no test type named {:?}", self.config.test_type))
+    }
+    
+    async fn create_engagement(&self, session: &SessionState) -> Result<u64> {
+        let created = self.request(Method::POST, "engagements/", Some(json!({
+            "name": session.name,
+            "product": self.config.product_id,
+            "target_start": session.created_at.format("%Y-%m-%d").to_string(),
+            "target_end": session.updated_at.format("%Y-%m-%d").to_string(),
+            "status": "In Progress",
+            "engagement_type": "Interactive",
+            "description": format!("NeuroRift session {}", session.id),
+        })), &[]).await?;
+        created["id"].as_u64().context("DefectDojo did not return the engagement ID")
+    }
+    
+    async fn create_test(&self, session: &SessionState, engagement_id: u64, test_type: u64) -> Result<u64> {
+        let created = self.request(Method::POST, "tests/", Some(json!({
+            "engagement": engagement_id,
+            "test_type": test_type,
+            "title": format!("NeuroRift: {}", session.name),
+            "target_start": session.created_at.to_rfc3339(),
+            "target_end": session.updated_at.to_rfc3339(),
+        })), &[]).await?;
+        created["id"].as_u64().context("DefectDojo did not return the test ID")
+    }
+}
+
+/// Finding fields in DefectDojo's API
+fn finding_body(finding: &Finding, test_id: u64, test_type: u64) -> Value {
+    let (severity, numerical) = match finding.severity {
+        Severity::Critical => ("Critical", "S0"),
+        Severity::High => ("High", "S1"),
+        Severity::Medium => ("Medium", "S2"),
+        Severity::Low => ("Low", "S3"),
+        Severity::Info => ("Info", "S4"),
+    };
+    let mut description = finding.description.clone();
+    if let Some(target) = &finding.target {
+        description.push_str(&format!("\n\n**Target:** {}", target));
+    }
+    description.push_str(&format!("\n\n**Reported by:** {} ({} times)", finding.tool_source, finding.seen_count));
+    
+    let mut body = json!({
+        "test": test_id,
+        "found_by": [test_type],
+        "title": finding.title,
+        "description": description,
+        "severity": severity,
+        "numerical_severity": numerical,
+        "date": finding.discovered_at.format("%Y-%m-%d").to_string(),
+        "active": true,
+        "verified": false,
+        "dynamic_finding": true,
+        "static_finding": false,
+        "unique_id_from_tool": unique_id(finding),
+        "vuln_id_from_tool": finding.tool_source,
+        "vulnerability_ids": extract_cves(finding).into_iter()
+            .map(|cve| json!({ "vulnerability_id": cve }))
+            .collect::<Vec<_>>(),
+    });
+    if let Some(vector) = &finding.cvss_vector {
+        body["cvssv3"] = json!(vector);
+    }
+    if let Some(score) = finding.cvss_score {
+        body["cvssv3_score"] = json!(score);
+    }
+    body
+}
+
+/// Identity DefectDojo matches uploads on: the finding's dedup key
+fn unique_id(finding: &Finding) -> String {
+    let key = if finding.dedup_key.is_empty() { &finding.id } else { &finding.dedup_key };
+    key.chars().take(MAX_UNIQUE_ID).collect()
+}
@@ -0,0 +1,321 @@
+use anyhow::{Context, Result};
+use dashmap::{DashMap, DashSet};
+use futures_util::stream::{self, StreamExt};
+use hickory_resolver::config::{NameServerConfigGroup, ResolverConfig, ResolverOpts};
+use hickory_resolver::error::ResolveErrorKind;
+use hickory_resolver::proto::rr::{RData, RecordType};
+use hickory_resolver::TokioAsyncResolver;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+use crate::state::asset::{normalize_hostname, DnsRecord, DnsRecordType};
+use crate::state::AssetObservation;
+use crate::websocket::events::{LogLevel, WSEvent};
+use crate::NeuroRiftCore;
+
+/// DNS configuration file name under the base directory
+const DNS_FILE: &str = "dns.json";
+
+/// Source recorded on assets learned by resolving a known hostname
+const DNS_SOURCE: &str = "dns";
+
+/// Resolution of discovered hostnames and enumerated subdomains
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Resolvers to ask instead of the system's, e.g. the customer's own
+    #[serde(default)]
+    pub nameservers: Vec<IpAddr>,
+    /// Lookups in flight at once
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    /// Per-query timeout
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for DnsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            nameservers: Vec::new(),
+            concurrency: default_concurrency(),
+            timeout_secs: default_timeout_secs(),
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_concurrency() -> usize {
+    32
+}
+
+fn default_timeout_secs() -> u64 {
+    5
+}
+
+impl DnsConfig {
+    /// Load DNS config from the base directory (defaults if absent)
+    pub fn load(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let path = base_dir.as_ref().join(DNS_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let json = fs::read_to_string(&path)
+            .context("Failed to read DNS config")?;
+        let config = serde_json::from_str(&json)
+            .context("Failed to parse DNS config")?;
+        
+        Ok(config)
+    }
+}
+
+/// What a name resolved to
+#[derive(Debug, Clone, Default)]
+pub struct Resolution {
+    pub addresses: Vec<IpAddr>,
+    /// A, AAAA and any CNAMEs followed on the way
+    pub records: Vec<DnsRecord>,
+}
+
+impl Resolution {
+    /// One observation per address, all under `name`
+    pub fn observations(&self, name: &str) -> Vec<AssetObservation> {
+        self.addresses.iter()
+            .enumerate()
+            .map(|(i, address)| AssetObservation {
+                address: Some(*address),
+                hostname: Some(name.to_string()),
+                // They all land on the same asset, so the records go once
+                dns: if i == 0 { self.records.clone() } else { Vec::new() },
+                ..Default::default()
+            })
+            .collect()
+    }
+}
+
+/// Async resolver that also remembers which domains are wildcards
+pub struct DnsResolver {
+    resolver: TokioAsyncResolver,
+    /// Wildcard addresses by domain, `None` for a domain that is not one
+    wildcards: DashMap<String, Option<Vec<IpAddr>>>,
+    concurrency: usize,
+}
+
+impl DnsResolver {
+    pub fn new(config: &DnsConfig) -> Result<Self> {
+        let mut options = ResolverOpts::default();
+        options.timeout = Duration::from_secs(config.timeout_secs.max(1));
+        let resolver = if config.nameservers.is_empty() {
+            let (system, _) = hickory_resolver::system_conf::read_system_conf()
+                .context("Failed to read the system resolver configuration")?;
+            TokioAsyncResolver::tokio(system, options)
+        } else {
+            let group = NameServerConfigGroup::from_ips_clear(&config.nameservers, 53, true);
+            TokioAsyncResolver::tokio(ResolverConfig::from_parts(None, Vec::new(), group), options)
+        };
+        Ok(Self {
+            resolver,
+            wildcards: DashMap::new(),
+            concurrency: config.concurrency.max(1),
+        })
+    }
+    
+    /// A and AAAA records for a name, following CNAMEs; a name that does
+    /// not exist resolves to nothing rather than an error
+    pub async fn resolve(&self, name: &str) -> Result<Resolution> {
+        let name = normalize_hostname(name);
+        let (v4, v6) = tokio::join!(self.lookup(&name, RecordType::A), self.lookup(&name, RecordType::AAAA));
+        let mut resolution = Resolution::default();
+        for record in v4?.into_iter().chain(v6?) {
+            if matches!(record.record_type, DnsRecordType::A | DnsRecordType::Aaaa) {
+                if let Ok(address) = record.value.parse() {
+                    if !resolution.addresses.contains(&address) {
+                        resolution.addresses.push(address);
+                    }
+                }
+            }
+            if !resolution.records.contains(&record) {
+                resolution.records.push(record);
+            }
+        }
+        Ok(resolution)
+    }
+    
+    async fn lookup(&self, name: &str, record_type: RecordType) -> Result<Vec<DnsRecord>> {
+        let lookup = match self.resolver.lookup(format!("{}.", name), record_type).await {
+            Ok(lookup) => lookup,
+            Err(e) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to resolve {} {}", record_type, name)),
+        };
+        let records = lookup.record_iter()
+            .filter_map(|record| {
+                let (record_type, value) = match record.data()? {
+                    RData::A(a) => (DnsRecordType::A, a.0.to_string()),
+                    RData::AAAA(aaaa) => (DnsRecordType::Aaaa, aaaa.0.to_string()),
+                    RData::CNAME(cname) => (DnsRecordType::Cname, normalize_hostname(&cname.0.to_utf8())),
+                    _ => return None,
+                };
+                Some(DnsRecord { name: normalize_hostname(&record.name().to_utf8()), record_type, value })
+            })
+            .collect();
+        Ok(records)
+    }
+    
+    /// Resolve many names at once, skipping those that fail
+    pub async fn resolve_all(&self, names: Vec<String>) -> Vec<(String, Resolution)> {
+        stream::iter(names)
+            .map(|name| async move {
+                let resolution = self.resolve(&name).await
+                    .inspect_err(|e| tracing::debug!("{:#}", e))
+                    .unwrap_or_default();
+                (name, resolution)
+            })
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await
+    }
+    
+    /// Addresses that any name directly under `domain` resolves to, if it
+    /// has a wildcard record. Probed once with a random label and cached.
+    pub async fn wildcard(&self, domain: &str) -> Option<Vec<IpAddr>> {
+        if let Some(known) = self.wildcards.get(domain) {
+            return known.clone();
+        }
+        let probe = format!("{}.{}", Uuid::new_v4().simple(), domain);
+        let addresses = self.resolve(&probe).await
+            .ok()
+            .map(|resolution| resolution.addresses)
+            .filter(|addresses| !addresses.is_empty());
+        self.wildcards.insert(domain.to_string(), addresses.clone());
+        addresses
+    }
+}
+
+/// Resolves hostnames as they show up in the asset inventory and turns
+/// enumerated subdomains into assets
+pub struct DnsService {
+    core: Arc<NeuroRiftCore>,
+    resolver: DnsResolver,
+    /// `(session, name)` pairs already looked up, so misses are not retried
+    attempted: DashSet<(String, String)>,
+    /// `(session, domain)` wildcards already announced
+    announced: DashSet<(String, String)>,
+}
+
+impl DnsService {
+    pub fn new(core: Arc<NeuroRiftCore>, config: &DnsConfig) -> Result<Self> {
+        Ok(Self {
+            core,
+            resolver: DnsResolver::new(config)?,
+            attempted: DashSet::new(),
+            announced: DashSet::new(),
+        })
+    }
+    
+    /// Handle asset and subdomain events until the core shuts down; each
+    /// batch resolves in the background so events are not missed meanwhile
+    pub async fn run(self, mut rx: broadcast::Receiver<WSEvent>) {
+        let service = Arc::new(self);
+        loop {
+            let event = match rx.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("DNS service lagged, skipped {} events", skipped);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            
+            match event {
+                WSEvent::SubdomainsDiscovered { session_id, source, hostnames } => {
+                    let service = service.clone();
+                    tokio::spawn(async move { service.ingest(&session_id, &source, hostnames).await });
+                }
+                WSEvent::AssetDiscovered { session_id, asset } | WSEvent::AssetUpdated { session_id, asset } => {
+                    let names: Vec<String> = asset.hostnames.iter()
+                        .filter(|name| !asset.is_resolved(name))
+                        .filter(|name| service.attempted.insert((session_id.clone(), (*name).clone())))
+                        .cloned()
+                        .collect();
+                    if !names.is_empty() {
+                        let service = service.clone();
+                        tokio::spawn(async move { service.resolve_known(&session_id, names).await });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    
+    /// Attach records to hostnames already in the inventory
+    async fn resolve_known(&self, session_id: &str, names: Vec<String>) {
+        let observations: Vec<AssetObservation> = self.resolver.resolve_all(names).await
+            .into_iter()
+            .flat_map(|(name, resolution)| resolution.observations(&name))
+            .collect();
+        if !observations.is_empty() {
+            self.core.record_assets(session_id, observations, DNS_SOURCE);
+        }
+    }
+    
+    /// Resolve enumerated names, recording those that exist and are not
+    /// just answers from a wildcard record
+    async fn ingest(&self, session_id: &str, source: &str, hostnames: Vec<String>) {
+        let names: Vec<String> = hostnames.into_iter()
+            .filter(|name| self.attempted.insert((session_id.to_string(), name.clone())))
+            .collect();
+        let total = names.len();
+        
+        let mut observations = Vec::new();
+        let (mut unresolved, mut wildcard_hits) = (0, 0);
+        for (name, resolution) in self.resolver.resolve_all(names).await {
+            if resolution.addresses.is_empty() {
+                unresolved += 1;
+                continue;
+            }
+            if let Some(domain) = name.split_once('.').map(|(_, parent)| parent).filter(|p| p.contains('.')) {
+                if let Some(wildcard) = self.resolver.wildcard(domain).await {
+                    self.announce_wildcard(session_id, domain, &wildcard);
+                    if resolution.addresses.iter().all(|a| wildcard.contains(a)) {
+                        wildcard_hits += 1;
+                        continue;
+                    }
+                }
+            }
+            observations.extend(resolution.observations(&name));
+        }
+        
+        tracing::info!(
+            "🔎 Resolved {} of {} subdomain(s) from {} ({} unresolved, {} wildcard answers dropped)",
+            total - unresolved - wildcard_hits, total, source, unresolved, wildcard_hits,
+        );
+        if !observations.is_empty() {
+            self.core.record_assets(session_id, observations, source);
+        }
+    }
+    
+    fn announce_wildcard(&self, session_id: &str, domain: &str, addresses: &[IpAddr]) {
+        if !self.announced.insert((session_id.to_string(), domain.to_string())) {
+            return;
+        }
+        tracing::info!("🔎 *.{} is a wildcard resolving to {:?}", domain, addresses);
+        let message = format!("*.{} is a DNS wildcard; names that only resolve there are dropped", domain);
+        self.core.ws_server().broadcast(WSEvent::log(LogLevel::Info, message, None));
+        self.core.ws_server().broadcast(WSEvent::DnsWildcard {
+            session_id: session_id.to_string(),
+            domain: domain.to_string(),
+            addresses: addresses.to_vec(),
+        });
+    }
+}
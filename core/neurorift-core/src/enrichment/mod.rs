@@ -0,0 +1,214 @@
+use anyhow::{bail, Context, Result};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use crate::NeuroRiftCore;
+use crate::state::Finding;
+use crate::websocket::events::WSEvent;
+
+/// NVD CVE API 2.0 endpoint
+const NVD_API_URL: &str = "https://services.nvd.nist.gov/rest/json/cves/2.0";
+
+/// Environment variable holding an NVD API key (raises the rate limit)
+const NVD_API_KEY_ENV: &str = "NEURORIFT_NVD_API_KEY";
+
+/// Environment variable that disables NVD lookups when set to `0`/`false`
+const NVD_ENABLED_ENV: &str = "NEURORIFT_NVD_ENRICHMENT";
+
+/// Pause between uncached requests, per NVD's published rate limits
+const DELAY_WITHOUT_KEY: Duration = Duration::from_secs(6);
+const DELAY_WITH_KEY: Duration = Duration::from_millis(600);
+
+/// Fetches and caches NVD metadata for CVE identifiers
+pub struct NvdClient {
+    client: Client,
+    cache_dir: PathBuf,
+    api_key: Option<String>,
+}
+
+impl NvdClient {
+    /// Create a client caching responses under `<base>/cache/nvd`
+    pub fn new(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let cache_dir = base_dir.as_ref().join("cache").join("nvd");
+        fs::create_dir_all(&cache_dir)?;
+        
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()?;
+        
+        Ok(Self {
+            client,
+            cache_dir,
+            api_key: std::env::var(NVD_API_KEY_ENV).ok().filter(|k| !k.is_empty()),
+        })
+    }
+    
+    /// Whether NVD lookups are enabled
+    pub fn is_enabled() -> bool {
+        !matches!(std::env::var(NVD_ENABLED_ENV).as_deref(), Ok("0") | Ok("false"))
+    }
+    
+    /// Summary for a CVE, and whether it came from the local cache
+    pub async fn lookup(&self, cve_id: &str) -> Result<(Value, bool)> {
+        let cache_path = self.cache_dir.join(format!("{}.json", cve_id));
+        if let Ok(cached) = fs::read_to_string(&cache_path) {
+            if let Ok(summary) = serde_json::from_str(&cached) {
+                return Ok((summary, true));
+            }
+        }
+        
+        let mut request = self.client.get(NVD_API_URL).query(&[("cveId", cve_id)]);
+        if let Some(key) = &self.api_key {
+            request = request.header("apiKey", key);
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            bail!("NVD returned HTTP {} for {}", response.status(), cve_id);
+        }
+        
+        let raw: Value = response.json().await?;
+        let Some(cve) = raw.pointer("/vulnerabilities/0/cve") else {
+            bail!("NVD has no record for {}", cve_id);
+        };
+        let summary = summarize(cve);
+        
+        fs::write(&cache_path, serde_json::to_string_pretty(&summary)?)
+            .context("Failed to cache NVD record")?;
+        Ok((summary, false))
+    }
+    
+    /// Delay to observe after an uncached request
+    fn pacing(&self) -> Duration {
+        if self.api_key.is_some() { DELAY_WITH_KEY } else { DELAY_WITHOUT_KEY }
+    }
+}
+
+/// Enriches newly discovered findings that reference CVEs
+pub struct EnrichmentService {
+    core: Arc<NeuroRiftCore>,
+    nvd: NvdClient,
+}
+
+impl EnrichmentService {
+    /// Create the service
+    pub fn new(core: Arc<NeuroRiftCore>, base_dir: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {
+            core,
+            nvd: NvdClient::new(base_dir)?,
+        })
+    }
+    
+    /// Enrich findings as they are discovered, one at a time to respect
+    /// NVD rate limits
+    pub async fn run(self, mut rx: broadcast::Receiver<WSEvent>) {
+        loop {
+            let finding = match rx.recv().await {
+                Ok(WSEvent::FindingDiscovered { finding }) => finding,
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Enrichment service lagged, skipped {} events", skipped);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            
+            let cves = extract_cves(&finding);
+            if cves.is_empty() {
+                continue;
+            }
+            
+            let mut records = BTreeMap::new();
+            for cve in &cves {
+                match self.nvd.lookup(cve).await {
+                    Ok((summary, cached)) => {
+                        records.insert(cve.clone(), summary);
+                        if !cached {
+                            tokio::time::sleep(self.nvd.pacing()).await;
+                        }
+                    }
+                    Err(e) => tracing::warn!("NVD lookup for {} failed: {:#}", cve, e),
+                }
+            }
+            
+            if !records.is_empty() {
+                self.core.enrich_finding(&finding.id, records);
+            }
+        }
+    }
+}
+
+/// CVE identifiers mentioned anywhere in a finding, deduplicated
+pub fn extract_cves(finding: &Finding) -> Vec<String> {
+    let text = format!("{} {} {}", finding.title, finding.description, finding.details);
+    let upper = text.to_ascii_uppercase();
+    let bytes = upper.as_bytes();
+    
+    let mut cves = Vec::new();
+    let mut index = 0;
+    while let Some(offset) = upper[index..].find("CVE-") {
+        let start = index + offset;
+        let mut end = start + 4;
+        
+        let year_end = end + bytes[end..].iter().take_while(|b| b.is_ascii_digit()).count();
+        if year_end - end == 4 && bytes.get(year_end) == Some(&b'-') {
+            let seq_start = year_end + 1;
+            let seq_end = seq_start + bytes[seq_start..].iter().take_while(|b| b.is_ascii_digit()).count();
+            if seq_end - seq_start >= 4 {
+                let cve = upper[start..seq_end].to_string();
+                if !cves.contains(&cve) {
+                    cves.push(cve);
+                }
+                end = seq_end;
+            }
+        }
+        index = end;
+    }
+    cves
+}
+
+/// Reduce an NVD CVE record to what reports need
+fn summarize(cve: &Value) -> Value {
+    let description = cve["descriptions"].as_array()
+        .and_then(|d| d.iter().find(|d| d["lang"] == "en"))
+        .and_then(|d| d["value"].as_str())
+        .unwrap_or_default();
+    
+    // Prefer the newest CVSS version NVD has scored
+    let cvss = ["cvssMetricV31", "cvssMetricV30", "cvssMetricV2"].iter()
+        .find_map(|key| cve.pointer(&format!("/metrics/{}/0/cvssData", key)))
+        .map(|data| json!({
+            "version": data["version"],
+            "vector": data["vectorString"],
+            "base_score": data["baseScore"],
+            "severity": data["baseSeverity"],
+        }));
+    
+    let cwes: Vec<&str> = cve["weaknesses"].as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|w| w["description"].as_array().into_iter().flatten())
+        .filter_map(|d| d["value"].as_str())
+        .filter(|v| v.starts_with("CWE-"))
+        .collect();
+    
+    let references: Vec<&str> = cve["references"].as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|r| r["url"].as_str())
+        .collect();
+    
+    json!({
+        "id": cve["id"],
+        "published": cve["published"],
+        "last_modified": cve["lastModified"],
+        "description": description,
+        "cvss": cvss,
+        "cwes": cwes,
+        "references": references,
+    })
+}
@@ -0,0 +1,17 @@
+use anyhow::{bail, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use crate::executor::stream::OutputStream;
+use crate::{prober, scanner};
+use crate::tools::{httpprobe, portscan};
+use crate::websocket::events::TaskResult;
+
+/// Run a tool implemented inside the core rather than as a process
+pub async fn run(tool_name: &str, target: &str, args: &HashMap<String, Value>, proxy: Option<&str>, stream: Option<&mut OutputStream>) -> Result<TaskResult> {
+    match tool_name.to_ascii_lowercase().as_str() {
+        scanner::TOOL_NAME if proxy.is_some() => bail!("{} probes hosts directly and cannot go through a proxy", tool_name),
+        scanner::TOOL_NAME => scanner::run_task(target, &portscan::options(args)?, stream).await,
+        prober::TOOL_NAME => prober::run_task(target, &httpprobe::options(args)?, proxy, stream).await,
+        other => bail!("{} is not built into the core", other),
+    }
+}
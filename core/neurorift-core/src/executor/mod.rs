@@ -0,0 +1,190 @@
+pub mod builtin;
+pub mod native;
+pub mod stream;
+
+use anyhow::{anyhow, bail, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::Semaphore;
+use tokio::time::Duration;
+use crate::NeuroRiftCore;
+use crate::state::Task;
+use crate::telemetry::TraceContext;
+use crate::websocket::events::TaskResult;
+use self::native::Backend;
+use self::stream::OutputStream;
+
+/// Fallback poll interval when no queue notification arrives
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Dispatches queued tasks, natively or through the Python bridge
+pub struct TaskExecutor {
+    core: Arc<NeuroRiftCore>,
+    slots: Arc<Semaphore>,
+    /// Slots in circulation, following `max_concurrent_tasks`
+    limit: usize,
+}
+
+impl TaskExecutor {
+    /// Create an executor for the given core
+    pub fn new(core: Arc<NeuroRiftCore>) -> Self {
+        let limit = core.config().max_concurrent_tasks;
+        Self {
+            core,
+            slots: Arc::new(Semaphore::new(limit)),
+            limit,
+        }
+    }
+    
+    /// Run until the process exits, picking up tasks as they are queued
+    pub async fn run(mut self) {
+        let notify = self.core.task_notify();
+        
+        loop {
+            self.resize().await;
+            
+            // Wait for a free slot before claiming, so tasks stay queued
+            let Ok(permit) = self.slots.clone().acquire_owned().await else {
+                break;
+            };
+            
+            // Nothing starts while the kill switch is engaged
+            let next = if self.core.is_halted() { None } else { self.core.claim_next_task() };
+            
+            match next {
+                Some((session_id, task)) => {
+                    let core = self.core.clone();
+                    let (tracked_session, task_id) = (session_id.clone(), task.id.clone());
+                    let handle = tokio::spawn(async move {
+                        let outcome = dispatch(&core, &session_id, &task).await;
+                        core.finish_task(&session_id, &task.id, outcome);
+                        drop(permit);
+                    });
+                    if !handle.is_finished() {
+                        self.core.track_running(&tracked_session, &task_id, handle.abort_handle());
+                    }
+                }
+                None => {
+                    drop(permit);
+                    let _ = tokio::time::timeout(POLL_INTERVAL, notify.notified()).await;
+                }
+            }
+        }
+    }
+    
+    /// Follow a reloaded concurrency limit; shrinking waits for running
+    /// tasks to free their slots, and never cancels them
+    async fn resize(&mut self) {
+        let limit = self.core.config().max_concurrent_tasks.max(1);
+        if limit > self.limit {
+            self.slots.add_permits(limit - self.limit);
+        } else if limit < self.limit {
+            let Ok(surplus) = self.slots.acquire_many((self.limit - limit) as u32).await else {
+                return;
+            };
+            surplus.forget();
+        }
+        if limit != self.limit {
+            tracing::info!("Executor now runs up to {} tasks at once", limit);
+            self.limit = limit;
+        }
+    }
+}
+
+/// Execute one task, resolving secret references only for the outgoing call.
+///
+/// Tools with an adapter run as local processes when the backend allows
+/// it; everything else goes through the Python bridge.
+async fn dispatch(core: &NeuroRiftCore, session_id: &str, task: &Task) -> Result<TaskResult> {
+    // The scope may have been narrowed since the task was queued
+    core.check_scope(session_id, task)?;
+    
+    let args = Value::Object(task.args.clone().into_iter().collect());
+    let args = core.vault().resolve(&args)?;
+    let target = core.vault().resolve_str(&task.target)?;
+    
+    // Arguments are checked against the tool's schema, and adapters reject
+    // anything their tool does not accept, before anything runs
+    if let Some(descriptor) = core.tools().describe(&task.tool_name) {
+        descriptor.validate_args(&task.args)?;
+    }
+    let adapter = core.tools().get(&task.tool_name);
+    if let Some(adapter) = &adapter {
+        let command = adapter.build_command(&task.target, &task.args)?;
+        tracing::debug!("Task {} runs: {}", task.id, command);
+    }
+    
+    // Dark-web tools only run through Tor, and never natively past it;
+    // everything else uses the task's proxy or the global one
+    let tor = match core.tools().describe(&task.tool_name) {
+        Some(descriptor) if descriptor.tor => core.tor().route()?,
+        _ => None,
+    };
+    let proxy = tor.clone().or_else(|| core.proxy().resolve(task.proxy.as_deref()));
+    
+    if core.tools().describe(&task.tool_name).is_some_and(|d| d.builtin) {
+        let args: HashMap<String, Value> = args.as_object()
+            .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default();
+        let mut stream = OutputStream::new(core.ws_server(), &task.id);
+        return builtin::run(&task.tool_name, &target, &args, proxy.as_deref(), Some(&mut stream)).await;
+    }
+    
+    if let Some(adapter) = adapter.filter(|a| tor.is_none() && Backend::from_env().runs_natively(a.describe())) {
+        let args: HashMap<String, Value> = args.as_object()
+            .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default();
+        let mut command = adapter.build_command(&target, &args)?;
+        if let Some(proxy) = &proxy {
+            // Proxy variables alone are not enough: nmap, for one, ignores them
+            let Some(proxy_args) = adapter.proxy_args(proxy) else {
+                bail!("{} probes hosts directly and cannot go through a proxy", task.tool_name);
+            };
+            command.args.splice(0..0, proxy_args);
+            command.env = core.proxy().env(proxy);
+        }
+        let mut stream = OutputStream::new(core.ws_server(), &task.id);
+        let output = native::run(&command, native::DEFAULT_TIMEOUT, Some(&mut stream)).await?;
+        tracing::info!("Task {} ran {} natively, exit {:?} in {}ms", task.id, command.program, output.exit_code, output.duration_ms);
+        return output.into_result(&command.program);
+    }
+    
+    let trace = task.trace_id.as_deref().map(TraceContext::from_trace_id);
+    let started = Instant::now();
+    let mut stream = OutputStream::new(core.ws_server(), &task.id);
+    let response = core.python_bridge()
+        .execute_tool(&task.tool_name, &target, args, proxy.as_deref(), trace.as_ref(), Some(&mut stream))
+        .await;
+    if tor.is_some() {
+        core.tor().observe(&response);
+    }
+    let response = response?;
+    
+    parse_response(&response, started.elapsed().as_millis() as u64)
+}
+
+/// Convert a bridge `tool_execute` response into a task result
+fn parse_response(response: &Value, duration_ms: u64) -> Result<TaskResult> {
+    if !response.get("success").and_then(|v| v.as_bool()).unwrap_or(false) {
+        let error = response.get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("tool execution failed");
+        return Err(anyhow!("{}", error));
+    }
+    
+    let data = response.get("data").cloned().unwrap_or(Value::Null);
+    if let Some(error) = data.get("error").and_then(|v| v.as_str()) {
+        return Err(anyhow!("{}", error));
+    }
+    
+    Ok(TaskResult {
+        success: true,
+        output: data.get("raw_output").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+        structured_data: data.get("structured_output").cloned().filter(|v| !v.is_null()),
+        duration_ms,
+        exit_code: None,
+        stderr: None,
+    })
+}
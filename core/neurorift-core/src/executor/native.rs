@@ -0,0 +1,206 @@
+use anyhow::{anyhow, Context, Result};
+use dashmap::DashMap;
+use std::process::Stdio;
+use std::sync::LazyLock;
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
+use tokio::process::Command;
+use tokio::time::Duration;
+use crate::executor::stream::{OutputStream, FLUSH_INTERVAL};
+use crate::tools::{ToolCommand, ToolDescriptor};
+use crate::websocket::events::TaskResult;
+
+/// Environment variable choosing where tools run: `auto` (default), `native` or `bridge`
+pub(crate) const BACKEND_ENV: &str = "NEURORIFT_TOOL_BACKEND";
+
+/// Longest a tool may run before it is killed
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Tail of stderr kept in results and error messages
+const STDERR_LIMIT: usize = 16 * 1024;
+
+/// Process IDs of tools running natively, by task ID
+pub static TOOL_PROCESSES: LazyLock<DashMap<String, u32>> = LazyLock::new(DashMap::new);
+
+/// Where tasks for tools with an adapter are executed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Natively when the tool's binaries are installed, otherwise through the bridge
+    Auto,
+    /// Always natively, failing if a binary is missing
+    Native,
+    /// Always through the Python bridge
+    Bridge,
+}
+
+impl Backend {
+    /// Backend selected by `NEURORIFT_TOOL_BACKEND`
+    pub fn from_env() -> Self {
+        match std::env::var(BACKEND_ENV).as_deref().map(str::trim) {
+            Ok("native") => Self::Native,
+            Ok("bridge") => Self::Bridge,
+            Ok("auto") | Ok("") | Err(_) => Self::Auto,
+            Ok(other) => {
+                tracing::warn!("Unknown {} '{}', using auto", BACKEND_ENV, other);
+                Self::Auto
+            }
+        }
+    }
+    
+    /// Whether a tool runs in-process rather than through the bridge
+    pub fn runs_natively(&self, descriptor: &ToolDescriptor) -> bool {
+        match self {
+            Self::Auto => descriptor.missing_binaries().is_empty(),
+            Self::Native => true,
+            Self::Bridge => false,
+        }
+    }
+}
+
+/// What a finished tool process produced
+#[derive(Debug, Clone)]
+pub struct ProcessOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// `None` when the process was killed by a signal
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+}
+
+impl ProcessOutput {
+    /// Task result for a clean exit, or the failure with the end of stderr
+    pub fn into_result(self, program: &str) -> Result<TaskResult> {
+        if self.exit_code != Some(0) {
+            let status = match self.exit_code {
+                Some(code) => format!("exited with status {}", code),
+                None => "was killed by a signal".to_string(),
+            };
+            let stderr = self.stderr.trim();
+            return Err(match stderr.is_empty() {
+                true => anyhow!("{} {}", program, status),
+                false => anyhow!("{} {}: {}", program, status, stderr),
+            });
+        }
+        
+        Ok(TaskResult {
+            success: true,
+            output: self.stdout,
+            structured_data: None,
+            duration_ms: self.duration_ms,
+            exit_code: self.exit_code,
+            stderr: Some(self.stderr).filter(|s| !s.trim().is_empty()),
+        })
+    }
+}
+
+/// Run a tool without a shell, capturing its output.
+///
+/// Stdout lines are passed to `stream` as they arrive. The child is killed
+/// if the timeout elapses or the task is aborted.
+pub async fn run(command: &ToolCommand, timeout: Duration, stream: Option<&mut OutputStream>) -> Result<ProcessOutput> {
+    let started = Instant::now();
+    let mut child = Command::new(&command.program)
+        .args(&command.args)
+        .envs(command.env.iter().map(|(k, v)| (k, v)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Failed to start {}", command.program))?;
+    let _registration = stream.as_deref()
+        .zip(child.id())
+        .map(|(stream, pid)| ProcessRegistration::new(stream.task_id(), pid));
+    
+    let stdout = child.stdout.take().context("Child stdout was not captured")?;
+    let stderr = child.stderr.take().context("Child stderr was not captured")?;
+    let stderr = tokio::spawn(read_all(stderr));
+    
+    let collect = async {
+        let stdout = read_lines(stdout, stream).await?;
+        let status = child.wait().await?;
+        anyhow::Ok((stdout, status))
+    };
+    let (stdout, status) = tokio::time::timeout(timeout, collect)
+        .await
+        .map_err(|_| anyhow!("{} timed out after {}s", command.program, timeout.as_secs()))?
+        .with_context(|| format!("Failed to collect output of {}", command.program))?;
+    let stderr = stderr.await.unwrap_or_default();
+    
+    Ok(ProcessOutput {
+        stdout,
+        stderr: tail(&stderr, STDERR_LIMIT),
+        exit_code: status.code(),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Entry in [`TOOL_PROCESSES`] for as long as the tool runs, including
+/// when the task is aborted
+struct ProcessRegistration {
+    task_id: String,
+}
+
+impl ProcessRegistration {
+    fn new(task_id: &str, pid: u32) -> Self {
+        TOOL_PROCESSES.insert(task_id.to_string(), pid);
+        Self { task_id: task_id.to_string() }
+    }
+}
+
+impl Drop for ProcessRegistration {
+    fn drop(&mut self) {
+        TOOL_PROCESSES.remove(&self.task_id);
+    }
+}
+
+/// Read stdout to the end, forwarding each line and flushing on a timer
+/// so quiet stretches still deliver what was printed
+async fn read_lines(stdout: impl AsyncRead + Unpin, mut stream: Option<&mut OutputStream>) -> Result<String> {
+    let mut reader = BufReader::new(stdout);
+    let mut output = Vec::new();
+    let mut line = Vec::new();
+    let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+    
+    loop {
+        tokio::select! {
+            read = reader.read_until(b'\n', &mut line) => {
+                if read? == 0 {
+                    break;
+                }
+                if let Some(stream) = stream.as_deref_mut() {
+                    stream.push(&String::from_utf8_lossy(&line));
+                }
+                output.append(&mut line);
+            }
+            _ = ticker.tick() => {
+                if let Some(stream) = stream.as_deref_mut() {
+                    stream.flush();
+                }
+            }
+        }
+    }
+    if let Some(stream) = stream {
+        stream.flush();
+    }
+    Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+/// Read a pipe to the end, lossily decoded
+async fn read_all(mut pipe: impl AsyncRead + Unpin) -> String {
+    let mut buffer = Vec::new();
+    let _ = pipe.read_to_end(&mut buffer).await;
+    String::from_utf8_lossy(&buffer).into_owned()
+}
+
+/// Last `limit` bytes of a string, cut at a character boundary
+fn tail(text: &str, limit: usize) -> String {
+    if text.len() <= limit {
+        return text.to_string();
+    }
+    let mut start = text.len() - limit;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    text[start..].to_string()
+}
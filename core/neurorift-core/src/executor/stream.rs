@@ -0,0 +1,84 @@
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::time::Duration;
+use crate::websocket::WebSocketServer;
+use crate::websocket::events::WSEvent;
+
+/// How often buffered lines are sent
+pub const FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Most lines sent per interval; the rest are only counted
+const MAX_LINES_PER_FLUSH: usize = 200;
+
+/// Longer lines are cut to this many bytes
+const MAX_LINE_LEN: usize = 4096;
+
+/// Batches a running tool's stdout into throttled `TaskOutput` events
+pub struct OutputStream {
+    ws_server: Arc<WebSocketServer>,
+    task_id: String,
+    pending: Vec<String>,
+    skipped: u64,
+    last_flush: Instant,
+}
+
+impl OutputStream {
+    /// Stream for one task
+    pub fn new(ws_server: Arc<WebSocketServer>, task_id: impl Into<String>) -> Self {
+        Self {
+            ws_server,
+            task_id: task_id.into(),
+            pending: Vec::new(),
+            skipped: 0,
+            last_flush: Instant::now(),
+        }
+    }
+    
+    /// Queue one line, sending the batch if the interval has passed
+    pub fn push(&mut self, line: &str) {
+        if self.pending.len() < MAX_LINES_PER_FLUSH {
+            self.pending.push(truncate(line.trim_end_matches(['\r', '\n']), MAX_LINE_LEN));
+        } else {
+            self.skipped += 1;
+        }
+        if self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.flush();
+        }
+    }
+    
+    pub fn task_id(&self) -> &str {
+        &self.task_id
+    }
+    
+    /// Send whatever is buffered
+    pub fn flush(&mut self) {
+        self.last_flush = Instant::now();
+        if self.pending.is_empty() && self.skipped == 0 {
+            return;
+        }
+        self.ws_server.broadcast(WSEvent::TaskOutput {
+            task_id: self.task_id.clone(),
+            line: self.pending.join("\n"),
+            skipped: std::mem::take(&mut self.skipped),
+        });
+        self.pending.clear();
+    }
+}
+
+impl Drop for OutputStream {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+/// At most `limit` bytes of a line, cut at a character boundary
+fn truncate(line: &str, limit: usize) -> String {
+    if line.len() <= limit {
+        return line.to_string();
+    }
+    let mut end = limit;
+    while !line.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &line[..end])
+}
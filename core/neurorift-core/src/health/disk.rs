@@ -0,0 +1,101 @@
+use chrono::Utc;
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::config::CoreConfig;
+use crate::websocket::events::{LogLevel, WSEvent};
+use crate::NeuroRiftCore;
+
+/// How often free space under the base directory is checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+const MB: u64 = 1024 * 1024;
+
+/// Free space under the base directory against the configured thresholds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DiskLevel {
+    Ok,
+    /// Below `disk_warn_mb`
+    Low,
+    /// Below `disk_critical_mb`; artifact-heavy tasks are held
+    Critical,
+}
+
+impl DiskLevel {
+    pub fn classify(free_bytes: u64, config: &CoreConfig) -> Self {
+        if free_bytes < config.disk_critical_mb * MB {
+            Self::Critical
+        } else if free_bytes < config.disk_warn_mb * MB {
+            Self::Low
+        } else {
+            Self::Ok
+        }
+    }
+}
+
+/// Bytes available to this user on the filesystem holding `path`
+pub fn free_bytes(path: &Path) -> std::io::Result<u64> {
+    let path = CString::new(path.as_os_str().as_bytes())?;
+    let mut stats = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: `path` is NUL-terminated and `stats` is only read after success
+    if unsafe { libc::statvfs(path.as_ptr(), stats.as_mut_ptr()) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let stats = unsafe { stats.assume_init() };
+    // Field widths differ between platforms
+    #[allow(clippy::unnecessary_cast)]
+    Ok(stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+/// Check free space every minute, announcing each change of level
+pub async fn run(core: Arc<NeuroRiftCore>) {
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+        check(&core);
+    }
+}
+
+/// Classify free space now; warns via `DiskSpaceLow` and the log when the
+/// level changes, and lets held tasks resume once it recovers
+pub fn check(core: &NeuroRiftCore) {
+    let config = core.config();
+    let free = match free_bytes(&config.base_dir) {
+        Ok(free) => free,
+        Err(e) => {
+            tracing::debug!("Failed to check free space under {}: {}", config.base_dir.display(), e);
+            return;
+        }
+    };
+    let level = DiskLevel::classify(free, &config);
+    if core.set_disk_level(level) == level {
+        return;
+    }
+    
+    let path = config.base_dir.display().to_string();
+    let (threshold_mb, log_level, message) = match level {
+        DiskLevel::Ok => {
+            tracing::info!("💾 Disk space recovered: {} MB free under {}", free / MB, path);
+            let message = format!("Disk space recovered: {} MB free", free / MB);
+            core.ws_server().broadcast(WSEvent::log(LogLevel::Info, message, None));
+            return;
+        }
+        DiskLevel::Low => (config.disk_warn_mb, LogLevel::Warn, "Disk space low"),
+        DiskLevel::Critical => (
+            config.disk_critical_mb,
+            LogLevel::Error,
+            "Disk space critically low; artifact-heavy tasks are held",
+        ),
+    };
+    tracing::warn!("💾 {}: {} MB free under {}", message, free / MB, path);
+    core.ws_server().broadcast(WSEvent::log(log_level, format!("{}: {} MB free", message, free / MB), None));
+    core.ws_server().broadcast(WSEvent::DiskSpaceLow {
+        path,
+        free_bytes: free,
+        threshold_bytes: threshold_mb * MB,
+        critical: level == DiskLevel::Critical,
+        timestamp: Utc::now(),
+    });
+}
@@ -0,0 +1,87 @@
+pub mod disk;
+
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use crate::executor::native::TOOL_PROCESSES;
+use crate::websocket::events::{TaskResourceUsage, WSEvent};
+use crate::NeuroRiftCore;
+
+/// How often a disabled health task checks whether a reload enabled it
+const HEALTH_RECHECK: Duration = Duration::from_secs(30);
+
+/// Samples host and tool process usage between calls
+pub struct HealthSampler {
+    system: System,
+}
+
+impl HealthSampler {
+    pub fn new() -> Self {
+        let mut system = System::new();
+        // CPU use is measured between refreshes, so take the first reading now
+        system.refresh_cpu_usage();
+        Self { system }
+    }
+    
+    /// Usage since the previous sample
+    pub fn sample(&mut self) -> WSEvent {
+        self.system.refresh_cpu_usage();
+        self.system.refresh_memory();
+        
+        let running: Vec<(String, u32)> = TOOL_PROCESSES.iter()
+            .map(|entry| (entry.key().clone(), *entry.value()))
+            .collect();
+        let pids: Vec<Pid> = running.iter().map(|(_, pid)| Pid::from_u32(*pid)).collect();
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&pids),
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory(),
+        );
+        let tasks = running.into_iter()
+            .filter_map(|(task_id, pid)| {
+                let process = self.system.process(Pid::from_u32(pid))?;
+                Some(TaskResourceUsage {
+                    task_id,
+                    pid,
+                    cpu: process.cpu_usage(),
+                    memory_bytes: process.memory(),
+                })
+            })
+            .collect();
+        
+        let total = self.system.total_memory();
+        let memory = match total {
+            0 => 0.0,
+            total => self.system.used_memory() as f32 / total as f32 * 100.0,
+        };
+        let load = System::load_average();
+        WSEvent::SystemHealth {
+            cpu: self.system.global_cpu_usage(),
+            memory,
+            load: [load.one, load.five, load.fifteen],
+            tasks,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+impl Default for HealthSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Broadcast `SystemHealth` every `health_interval_secs`, re-reading the
+/// interval each time so a reload applies
+pub async fn run(core: Arc<NeuroRiftCore>) {
+    let mut sampler = HealthSampler::new();
+    loop {
+        let Some(period) = core.config().health_interval() else {
+            tokio::time::sleep(HEALTH_RECHECK).await;
+            continue;
+        };
+        tokio::time::sleep(period).await;
+        core.ws_server().broadcast(sampler.sample());
+    }
+}
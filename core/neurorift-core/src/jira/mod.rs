@@ -0,0 +1,207 @@
+use anyhow::{bail, Context, Result};
+use parking_lot::RwLock;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::enrichment::extract_cves;
+use crate::security::vault::SecretsVault;
+use crate::state::{Finding, SessionState, Severity};
+
+/// Jira configuration file name under the base directory
+const JIRA_FILE: &str = "jira.json";
+
+/// A Jira project findings are filed in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JiraConfig {
+    /// Base URL, e.g. `https://example.atlassian.net`
+    pub url: String,
+    /// Account email for Jira Cloud; without it the token is sent as a
+    /// Data Center personal access token
+    #[serde(default)]
+    pub email: Option<String>,
+    /// API token, which may reference the vault as `{{secret:name}}`
+    pub api_token: String,
+    /// Key of the project issues are created in, e.g. `SEC`
+    pub project_key: String,
+    #[serde(default = "default_issue_type")]
+    pub issue_type: String,
+    /// Jira priority per finding severity; severities left out get the
+    /// project's default priority
+    #[serde(default = "default_priorities")]
+    pub priorities: BTreeMap<Severity, String>,
+    #[serde(default = "default_labels")]
+    pub labels: Vec<String>,
+    /// Link to an evidence artifact, with `{session_id}` and `{artifact_id}`
+    /// filled in; without it the artifact's stored path is shown
+    #[serde(default)]
+    pub evidence_url: Option<String>,
+    #[serde(default = "default_verify_tls")]
+    pub verify_tls: bool,
+}
+
+fn default_issue_type() -> String {
+    "Bug".to_string()
+}
+
+fn default_priorities() -> BTreeMap<Severity, String> {
+    BTreeMap::from([
+        (Severity::Critical, "Highest".to_string()),
+        (Severity::High, "High".to_string()),
+        (Severity::Medium, "Medium".to_string()),
+        (Severity::Low, "Low".to_string()),
+        (Severity::Info, "Lowest".to_string()),
+    ])
+}
+
+fn default_labels() -> Vec<String> {
+    vec!["neurorift".to_string()]
+}
+
+fn default_verify_tls() -> bool {
+    true
+}
+
+impl JiraConfig {
+    /// Load the Jira config from the base directory; `None` when absent
+    pub fn load(base_dir: impl AsRef<Path>) -> Result<Option<Self>> {
+        let path = base_dir.as_ref().join(JIRA_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        
+        let json = fs::read_to_string(&path)
+            .context("Failed to read Jira config")?;
+        let config = serde_json::from_str(&json)
+            .context("Failed to parse Jira config")?;
+        Ok(Some(config))
+    }
+}
+
+/// Creates Jira issues for findings
+pub struct JiraClient {
+    config: RwLock<Option<JiraConfig>>,
+    vault: Arc<SecretsVault>,
+}
+
+impl JiraClient {
+    pub fn new(config: Option<JiraConfig>, vault: Arc<SecretsVault>) -> Self {
+        Self { config: RwLock::new(config), vault }
+    }
+    
+    /// Replace the instance, returning whether it changed; requests under
+    /// way finish against the old one
+    pub fn reload(&self, config: Option<JiraConfig>) -> bool {
+        let mut current = self.config.write();
+        let changed = *current != config;
+        *current = config;
+        changed
+    }
+    
+    /// Create an issue for a finding, returning its key and browse URL
+    pub async fn create_issue(&self, session: &SessionState, finding: &Finding) -> Result<(String, String)> {
+        let Some(config) = self.config.read().clone() else {
+            bail!("Jira is not configured; add {} to the base directory", JIRA_FILE);
+        };
+        let token = self.vault.resolve_str(&config.api_token)?;
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .danger_accept_invalid_certs(!config.verify_tls)
+            .build()?;
+        
+        let base = config.url.trim_end_matches('/');
+        let request = client.post(format!("{}/rest/api/2/issue", base))
+            .header(reqwest::header::ACCEPT, "application/json")
+            .json(&issue(session, finding, &config));
+        let request = match &config.email {
+            Some(email) => request.basic_auth(email, Some(&token)),
+            None => request.bearer_auth(&token),
+        };
+        let response = request.send().await
+            .with_context(|| format!("Failed to reach Jira at {}", config.url))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            bail!("Jira returned HTTP {}: {}", status, body.chars().take(200).collect::<String>());
+        }
+        let body: Value = response.json().await.context("Jira returned invalid JSON")?;
+        let key = body["key"].as_str().context("Jira response has no issue key")?.to_string();
+        let url = format!("{}/browse/{}", base, key);
+        Ok((key, url))
+    }
+}
+
+/// Issue body for a finding
+fn issue(session: &SessionState, finding: &Finding, config: &JiraConfig) -> Value {
+    let mut fields = json!({
+        "project": { "key": config.project_key },
+        "issuetype": { "name": config.issue_type },
+        "summary": summary(finding),
+        "description": description(session, finding, config),
+        "labels": config.labels,
+    });
+    if let Some(priority) = config.priorities.get(&finding.severity) {
+        fields["priority"] = json!({ "name": priority });
+    }
+    json!({ "fields": fields })
+}
+
+/// Jira caps summaries at 255 characters and rejects line breaks
+fn summary(finding: &Finding) -> String {
+    let severity = format!("{:?}", finding.severity).to_uppercase();
+    let summary = format!("[{}] {}", severity, finding.title.replace(['\r', '\n'], " "));
+    summary.chars().take(255).collect()
+}
+
+/// Description in Jira wiki markup
+fn description(session: &SessionState, finding: &Finding, config: &JiraConfig) -> String {
+    let mut text = format!("{}\n\n", finding.description);
+    text.push_str(&format!("*Severity:* {:?}", finding.severity));
+    if let Some(score) = finding.cvss_score {
+        text.push_str(&format!(" (CVSS {:.1})", score));
+    }
+    text.push('\n');
+    if let Some(vector) = &finding.cvss_vector {
+        text.push_str(&format!("*CVSS vector:* {{{{{}}}}}\n", vector));
+    }
+    if let Some(target) = &finding.target {
+        text.push_str(&format!("*Target:* {{{{{}}}}}\n", target));
+    }
+    let cves = extract_cves(finding);
+    if !cves.is_empty() {
+        let links: Vec<String> = cves.iter()
+            .map(|cve| format!("[{}|https://nvd.nist.gov/vuln/detail/{}]", cve, cve))
+            .collect();
+        text.push_str(&format!("*CVEs:* {}\n", links.join(", ")));
+    }
+    text.push_str(&format!("*Reported by:* {} ({} times, first {})\n", finding.tool_source, finding.seen_count, finding.discovered_at.format("%Y-%m-%d %H:%M UTC")));
+    text.push_str(&format!("*NeuroRift:* session {{{{{}}}}}, finding {{{{{}}}}}\n", session.id, finding.id));
+    
+    if !finding.evidence.is_empty() {
+        text.push_str("\nh3. Evidence\n");
+        for evidence in &finding.evidence {
+            let artifact = session.artifacts.iter().find(|a| a.id == evidence.artifact_id);
+            let name = artifact.map_or(evidence.artifact_id.as_str(), |a| a.name.as_str());
+            let link = match (&config.evidence_url, artifact) {
+                (Some(template), _) => format!("[{}|{}]", name, template
+                    .replace("{session_id}", &session.id)
+                    .replace("{artifact_id}", &evidence.artifact_id)),
+                (None, Some(artifact)) => format!("{} ({{{{{}}}}})", name, artifact.path),
+                (None, None) => name.to_string(),
+            };
+            text.push_str(&format!("* {}", link));
+            if let Some(caption) = &evidence.caption {
+                text.push_str(&format!(" — {}", caption));
+            }
+            if let Some(sha256) = artifact.and_then(|a| a.sha256.as_deref()) {
+                text.push_str(&format!(" (SHA-256 {{{{{}}}}})", sha256));
+            }
+            text.push('\n');
+        }
+    }
+    text
+}
@@ -0,0 +1,158 @@
+pub mod replay;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use crate::state::is_valid_session_id;
+use crate::websocket::events::WSEvent;
+
+/// Journal for events not tied to any session
+const GLOBAL_JOURNAL: &str = "_core";
+
+/// One journaled event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub seq: u64,
+    pub timestamp: DateTime<Utc>,
+    pub session_id: Option<String>,
+    pub event: WSEvent,
+}
+
+/// An open journal file and its next sequence number
+struct JournalFile {
+    file: File,
+    next_seq: u64,
+}
+
+/// Append-only per-session event journal
+pub struct EventJournal {
+    journal_dir: PathBuf,
+    files: Mutex<HashMap<String, JournalFile>>,
+}
+
+impl EventJournal {
+    /// Create a new event journal
+    pub fn new(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let journal_dir = base_dir.as_ref().join("journal");
+        fs::create_dir_all(&journal_dir)
+            .context("Failed to create journal directory")?;
+        
+        Ok(Self {
+            journal_dir,
+            files: Mutex::new(HashMap::new()),
+        })
+    }
+    
+    /// Append an event to its session's journal.
+    ///
+    /// Events naming a session go to that session; everything else is
+    /// attributed to `active_session`, or the core journal if none.
+    pub fn append(&self, event: &WSEvent, active_session: Option<&str>) -> Result<u64> {
+        let session_id = event_session_id(event).or(active_session);
+        let key = session_id.unwrap_or(GLOBAL_JOURNAL);
+        
+        let mut files = self.files.lock();
+        if !files.contains_key(key) {
+            let path = self.journal_path(key)?;
+            let next_seq = last_seq(&path)?.map_or(0, |seq| seq + 1);
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .context("Failed to open journal file")?;
+            files.insert(key.to_string(), JournalFile { file, next_seq });
+        }
+        let journal = files.get_mut(key).expect("journal file was just opened");
+        
+        let entry = JournalEntry {
+            seq: journal.next_seq,
+            timestamp: Utc::now(),
+            session_id: session_id.map(str::to_string),
+            event: event.clone(),
+        };
+        let line = serde_json::to_string(&entry)?;
+        writeln!(journal.file, "{}", line).context("Failed to write journal entry")?;
+        journal.file.flush()?;
+        
+        journal.next_seq += 1;
+        Ok(entry.seq)
+    }
+    
+    /// Read all entries of a session's journal in order
+    pub fn read(&self, session_id: &str) -> Result<Vec<JournalEntry>> {
+        let path = self.journal_path(session_id)?;
+        let file = File::open(&path).context("Failed to open journal file")?;
+        
+        let mut entries = Vec::new();
+        for (line_no, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<JournalEntry>(&line) {
+                Ok(entry) => entries.push(entry),
+                // A torn final write must not hide the rest of the record
+                Err(e) => tracing::warn!("Skipping corrupt journal line {} in {}: {}", line_no + 1, session_id, e),
+            }
+        }
+        
+        Ok(entries)
+    }
+    
+    /// Path of a session's journal file
+    pub fn journal_path(&self, session_id: &str) -> Result<PathBuf> {
+        if session_id != GLOBAL_JOURNAL && !is_valid_session_id(session_id) {
+            bail!("Invalid session ID {:?}", session_id);
+        }
+        Ok(self.journal_dir.join(format!("{}.jsonl", session_id)))
+    }
+}
+
+/// Session an event explicitly belongs to
+fn event_session_id(event: &WSEvent) -> Option<&str> {
+    match event {
+        WSEvent::SessionCreated { session_id, .. }
+        | WSEvent::SessionLoaded { session_id, .. }
+        | WSEvent::SessionUpdated { session_id, .. }
+        | WSEvent::SessionSaved { session_id, .. }
+        | WSEvent::SessionDeleted { session_id }
+        | WSEvent::TargetAdded { session_id, .. }
+        | WSEvent::TargetRemoved { session_id, .. }
+        | WSEvent::AssetDiscovered { session_id, .. }
+        | WSEvent::AssetUpdated { session_id, .. }
+        | WSEvent::ServiceDiscovered { session_id, .. }
+        | WSEvent::WebServiceProbed { session_id, .. }
+        | WSEvent::ArtifactCreated { session_id, .. }
+        | WSEvent::ArtifactExpiring { session_id, .. }
+        | WSEvent::ArtifactDeleted { session_id, .. }
+        | WSEvent::ArtifactsVerified { session_id, .. }
+        | WSEvent::EvidenceAttached { session_id, .. }
+        | WSEvent::TasksInterrupted { session_id, .. }
+        | WSEvent::MispEventPushed { session_id, .. }
+        | WSEvent::MispEnriched { session_id, .. }
+        | WSEvent::DefectDojoPushed { session_id, .. }
+        | WSEvent::TicketCreated { session_id, .. } => Some(session_id),
+        _ => None,
+    }
+}
+
+/// Sequence number of the last readable entry in a journal file
+fn last_seq(path: &Path) -> Result<Option<u64>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    
+    let file = File::open(path)?;
+    let mut last = None;
+    for line in BufReader::new(file).lines() {
+        if let Ok(entry) = serde_json::from_str::<JournalEntry>(&line?) {
+            last = Some(entry.seq);
+        }
+    }
+    Ok(last)
+}
@@ -0,0 +1,194 @@
+use anyhow::{bail, Result};
+use crate::journal::JournalEntry;
+use crate::defectdojo;
+use crate::misp;
+use crate::state::{ApprovalStatus, SessionState, TaskStatus};
+use crate::websocket::events::WSEvent;
+
+/// Reconstruct a session by replaying its journal.
+///
+/// Replay starts from the latest full snapshot (`SessionLoaded`) or the
+/// `SessionCreated` event, then applies every later state-changing event.
+pub fn replay(session_id: &str, entries: &[JournalEntry]) -> Result<SessionState> {
+    // Only the events after the last snapshot matter
+    let start = entries.iter()
+        .rposition(|e| matches!(&e.event, WSEvent::SessionLoaded { session_id: id, .. } if id == session_id))
+        .or_else(|| entries.iter().position(|e| matches!(&e.event, WSEvent::SessionCreated { session_id: id, .. } if id == session_id)));
+    
+    let Some(start) = start else {
+        bail!("Journal for {} has no SessionCreated or SessionLoaded event", session_id);
+    };
+    
+    let mut session = match &entries[start].event {
+        WSEvent::SessionLoaded { state, .. } => (**state).clone(),
+        WSEvent::SessionCreated { name, mode, .. } => {
+            let mut session = SessionState::new(name.clone(), *mode);
+            session.id = session_id.to_string();
+            session.created_at = entries[start].timestamp;
+            session.updated_at = entries[start].timestamp;
+            session
+        }
+        _ => unreachable!("start index always points at a snapshot or creation"),
+    };
+    
+    for entry in &entries[start + 1..] {
+        apply(&mut session, entry);
+    }
+    
+    Ok(session)
+}
+
+/// Bring a session read from its `.nrs` file up to date with the events
+/// journaled after that file was written, returning how many changed it.
+///
+/// The journal doubles as a write-ahead log: the file was written at the
+/// session's last `SessionSaved` (or `SessionLoaded`/`SessionCreated`), so
+/// anything after it is work a crash kept out of the file.
+pub fn recover(session: &mut SessionState, entries: &[JournalEntry]) -> usize {
+    let snapshot = entries.iter().rposition(|e| match &e.event {
+        WSEvent::SessionSaved { session_id, .. }
+        | WSEvent::SessionLoaded { session_id, .. }
+        | WSEvent::SessionCreated { session_id, .. } => session_id == &session.id,
+        _ => false,
+    });
+    let after = snapshot.map_or(entries, |index| &entries[index + 1..]);
+    after.iter().filter(|entry| apply(session, entry)).count()
+}
+
+/// Apply a single journaled event to a session, returning whether it
+/// changed anything
+fn apply(session: &mut SessionState, entry: &JournalEntry) -> bool {
+    let at = entry.timestamp;
+    
+    match &entry.event {
+        WSEvent::TaskQueued { task } => {
+            if !session.task_queue.iter().any(|t| t.id == task.id) {
+                session.task_queue.push_back(task.clone());
+            }
+        }
+        WSEvent::TaskStarted { task_id, started_at } => {
+            if let Some(task) = session.task_queue.iter_mut().find(|t| &t.id == task_id) {
+                task.status = TaskStatus::Running;
+                task.started_at = Some(*started_at);
+            }
+        }
+        WSEvent::TaskCompleted { task_id, result } => {
+            if let Some(task) = session.task_queue.iter_mut().find(|t| &t.id == task_id) {
+                task.status = if result.success { TaskStatus::Completed } else { TaskStatus::Failed };
+                task.completed_at = Some(at);
+            }
+        }
+        WSEvent::TaskFailed { task_id, .. } => {
+            if let Some(task) = session.task_queue.iter_mut().find(|t| &t.id == task_id) {
+                task.status = TaskStatus::Failed;
+                task.completed_at = Some(at);
+            }
+        }
+        WSEvent::TaskCancelled { task_id, .. } => {
+            if let Some(task) = session.task_queue.iter_mut().find(|t| &t.id == task_id) {
+                task.status = TaskStatus::Cancelled;
+                task.completed_at = Some(at);
+            }
+        }
+        WSEvent::TasksInterrupted { tasks, .. } => {
+            for interrupted in tasks {
+                if let Some(task) = session.task_queue.iter_mut().find(|t| t.id == interrupted.task_id) {
+                    task.status = TaskStatus::Interrupted;
+                    task.completed_at = Some(at);
+                }
+            }
+        }
+        WSEvent::MispEventPushed { event_id, .. } => {
+            session.metadata.insert(misp::EVENT_ID_KEY.to_string(), event_id.clone());
+        }
+        WSEvent::TicketCreated { finding_id, key, .. } => {
+            if let Some(finding) = session.findings.iter_mut().find(|f| &f.id == finding_id) {
+                finding.ticket = Some(key.clone());
+            }
+        }
+        WSEvent::DefectDojoPushed { engagement_id, test_id, .. } => {
+            session.metadata.insert(defectdojo::ENGAGEMENT_ID_KEY.to_string(), engagement_id.to_string());
+            session.metadata.insert(defectdojo::TEST_ID_KEY.to_string(), test_id.to_string());
+        }
+        WSEvent::ApprovalRequired { approval } | WSEvent::ApprovalEscalated { approval } => {
+            match session.approval_queue.iter_mut().find(|a| a.id == approval.id) {
+                Some(existing) => *existing = approval.clone(),
+                None => session.approval_queue.push_back(approval.clone()),
+            }
+        }
+        WSEvent::ApprovalGranted { approval_id, .. } => {
+            if let Some(approval) = session.approval_queue.iter_mut().find(|a| &a.id == approval_id) {
+                approval.status = ApprovalStatus::Approved;
+            }
+            // Release the task the approval was holding
+            if let Some(task) = session.task_queue.iter_mut()
+                .find(|t| t.approval_id.as_ref() == Some(approval_id) && t.status == TaskStatus::AwaitingApproval)
+            {
+                task.status = TaskStatus::Queued;
+            }
+        }
+        WSEvent::ApprovalDenied { approval_id, .. } => {
+            if let Some(approval) = session.approval_queue.iter_mut().find(|a| &a.id == approval_id) {
+                approval.status = ApprovalStatus::Denied;
+            }
+        }
+        WSEvent::FindingUpdated { finding } | WSEvent::FindingEnriched { finding, .. } => {
+            match session.findings.iter_mut().find(|f| f.id == finding.id) {
+                Some(existing) => *existing = finding.clone(),
+                None => session.findings.push(finding.clone()),
+            }
+        }
+        WSEvent::EvidenceAttached { finding_id, evidence, .. } => {
+            if let Some(finding) = session.findings.iter_mut().find(|f| &f.id == finding_id) {
+                match finding.evidence.iter_mut().find(|e| e.artifact_id == evidence.artifact_id) {
+                    Some(existing) => *existing = evidence.clone(),
+                    None => finding.evidence.push(evidence.clone()),
+                }
+            }
+        }
+        WSEvent::TargetAdded { target, .. } => {
+            session.targets.retain(|t| !t.value.eq_ignore_ascii_case(&target.value));
+            session.targets.push(target.clone());
+        }
+        WSEvent::TargetRemoved { target, .. } => {
+            session.targets.retain(|t| !t.value.eq_ignore_ascii_case(target));
+        }
+        WSEvent::AssetDiscovered { asset, .. } | WSEvent::AssetUpdated { asset, .. } => {
+            match session.assets.iter_mut().find(|a| a.id == asset.id) {
+                Some(existing) => *existing = asset.clone(),
+                None => session.assets.push(asset.clone()),
+            }
+        }
+        WSEvent::ArtifactCreated { artifact, .. } => {
+            if !session.artifacts.iter().any(|a| a.id == artifact.id) {
+                session.artifacts.push(artifact.clone());
+            }
+        }
+        WSEvent::ArtifactDeleted { artifact_id, .. } => {
+            session.remove_artifact(artifact_id);
+        }
+        WSEvent::ApprovalExpired { approval_id, .. } => {
+            if let Some(approval) = session.approval_queue.iter_mut().find(|a| &a.id == approval_id) {
+                approval.status = ApprovalStatus::Expired;
+            }
+        }
+        WSEvent::FindingDiscovered { finding } => {
+            if !session.findings.iter().any(|f| f.id == finding.id) {
+                session.findings.push(finding.clone());
+            }
+        }
+        WSEvent::ScopeUpdated { scope, .. } => {
+            session.scope = scope.clone();
+        }
+        WSEvent::RulesOfEngagementUpdated { roe, .. } => {
+            session.roe = roe.clone();
+        }
+        WSEvent::AgentStatusChanged { agent, status } => {
+            session.agent_states.insert(*agent, status.clone());
+        }
+        _ => return false,
+    }
+    
+    session.updated_at = at;
+    true
+}
@@ -3,16 +3,84 @@ pub mod session;
 pub mod websocket;
 pub mod python_bridge;
 pub mod security;
+pub mod notifications;
+pub mod metrics;
+pub mod telemetry;
+pub mod journal;
+pub mod executor;
+pub mod enrichment;
+pub mod report;
+pub mod parsers;
+pub mod tools;
+pub mod ai;
+pub mod agents;
+pub mod config;
+pub mod cli;
+#[cfg(unix)]
+pub mod daemon;
+pub mod health;
+pub mod tor;
+pub mod proxy;
+pub mod dns;
+pub mod defectdojo;
+pub mod jira;
+pub mod misp;
+pub mod scanner;
+pub mod prober;
+pub mod artifacts;
+#[cfg(feature = "tui")]
+pub mod tui;
 
 use anyhow::Result;
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::path::PathBuf;
-use parking_lot::RwLock;
-use crate::state::{SessionState, OperationalMode, AgentType, AgentState};
-use crate::session::SessionManager;
-use crate::websocket::{WebSocketServer, events::WSEvent};
-use crate::python_bridge::PythonBridge;
+use std::time::Duration;
+use parking_lot::{Mutex, RwLock};
+use tokio::sync::{oneshot, Notify};
+use tokio::task::AbortHandle;
+use crate::state::progress::{self, RunProgress};
+use crate::state::{SessionState, OperationalMode, AgentPhase, AgentType, AgentState, AgentStatus, Actor, Action, ActionType, ApprovalRequest, ApprovalStatus, Artifact, ArtifactType, AssetObservation, AssetUpsert, asset::{normalize_hostname, HttpInfo, Service}, FindingUpsert, NewFinding, Task, TaskStatus};
+use crate::metrics::METRICS;
+use crate::telemetry::TraceContext;
+use crate::journal::EventJournal;
+use crate::security::audit::AuditLog;
+use crate::security::rbac::{AccessControl, Permission};
+use crate::security::api_keys::ApiKeyStore;
+use crate::security::vault::SecretsVault;
+use crate::security::scope::EngagementScope;
+use crate::security::roe::RulesOfEngagement;
+use crate::security::approval::{self, ApprovalPolicy, Decision};
+use crate::security::risk;
+use crate::websocket::{ClientCommand, ClientFrame, ClientInfo, Transport};
+use crate::session::{SessionManager, ExportFormat, siem::SiemFormat};
+use crate::websocket::{WebSocketServer, events::{InterruptedTask, RebuildReason, TaskResult, WSEvent}};
+use crate::python_bridge::{BridgeAuth, BridgeConfig, BridgeState, PythonBridge};
+use crate::executor::native::Backend;
+use crate::report::{ReportFormat, ReportGenerator};
+use crate::artifacts::{ArtifactStore, IntegrityStatus, StoredFile};
+use crate::artifacts::retention::RetentionPolicy;
+use crate::artifacts::transfer::{self, Progress, TransferDirection, Transfers, Upload};
+use crate::parsers::ParserRegistry;
+use crate::tools::ToolRegistry;
+use crate::health::disk::DiskLevel;
+use crate::tor::{TorConfig, TorService};
+use crate::proxy::ProxyConfig;
+use crate::ai::{AiConfig, Message, ModelManager, Role};
+use crate::ai::prompts::PromptTemplates;
+use crate::agents::bus::AgentBus;
+use crate::config::CoreConfig;
+use crate::defectdojo::{DefectDojoClient, DefectDojoConfig};
+use crate::jira::{JiraClient, JiraConfig};
+use crate::misp::{MispClient, MispConfig, MispMatch};
+use crate::notifications::{NotificationConfig, chat::ChatNotifier, siem::SiemForwarder, syslog::SyslogSink, webhook::WebhookDispatcher};
+
+/// Prior chat turns sent to the model as context
+const CHAT_CONTEXT_MESSAGES: usize = 20;
+
+/// How often shutdown checks whether running tasks have finished
+const DRAIN_POLL: Duration = Duration::from_millis(250);
 
 /// Core orchestrator for NeuroRift
 pub struct NeuroRiftCore {
@@ -28,27 +96,213 @@ pub struct NeuroRiftCore {
     /// Python bridge
     python_bridge: Arc<PythonBridge>,
     
+    /// System Tor for dark-web traffic
+    tor: Arc<TorService>,
+    
+    /// Egress proxy for engagement traffic
+    proxy: ProxyConfig,
+    
     /// Current active session ID
     active_session: Arc<RwLock<Option<String>>>,
+    
+    /// Outbound webhook notifications
+    webhooks: Arc<WebhookDispatcher>,
+    
+    /// Slack/Discord notifications
+    chat_notifier: Arc<ChatNotifier>,
+    
+    /// Audit records and errors for central logging
+    syslog: Arc<SyslogSink>,
+    
+    /// Findings and audit records streamed to a SIEM
+    siem: Arc<SiemForwarder>,
+    
+    /// Threat-intel sharing with a MISP instance
+    misp: MispClient,
+    
+    /// Remediation tracking in a DefectDojo instance
+    defectdojo: DefectDojoClient,
+    
+    /// Remediation tickets in a Jira project
+    jira: JiraClient,
+    
+    /// Findings a Jira issue is being created for
+    ticketing: DashSet<String>,
+    
+    /// Append-only event journal
+    journal: Arc<EventJournal>,
+    
+    /// Tamper-evident audit log of client commands
+    audit: Arc<AuditLog>,
+    
+    /// Role-based command authorization
+    access: Arc<AccessControl>,
+    
+    /// Scoped API keys
+    api_keys: Arc<ApiKeyStore>,
+    
+    /// Encrypted tool credentials
+    vault: Arc<SecretsVault>,
+    
+    /// Wakes the executor when a task is queued
+    task_notify: Arc<Notify>,
+    
+    /// Queued tasks held back by rules of engagement, with the reason
+    deferred: DashMap<String, String>,
+    
+    /// Expiry rules for pending approvals
+    approval_policy: ApprovalPolicy,
+    
+    /// In-flight tasks by ID, with their session
+    running: DashMap<String, (String, AbortHandle)>,
+    
+    /// Agent orchestration runs by session
+    agent_runs: DashMap<String, AbortHandle>,
+    
+    /// Phase of the latest agent run on each session
+    run_progress: DashMap<String, RunProgress>,
+    
+    /// Progress last broadcast for each session
+    progress: DashMap<String, (u8, Option<AgentPhase>)>,
+    
+    /// Set while the kill switch is engaged; the executor starts nothing
+    halted: AtomicBool,
+    
+    /// Set once shutdown begins; commands are refused and nothing new starts
+    draining: AtomicBool,
+    
+    /// Free space under the base directory, as last checked
+    disk_level: Mutex<DiskLevel>,
+    
+    /// Renders engagement reports
+    reports: ReportGenerator,
+    
+    /// Content-addressed storage for artifact files
+    artifacts: Arc<ArtifactStore>,
+    
+    /// Artifact uploads and downloads in flight
+    transfers: Arc<Transfers>,
+    
+    /// How long artifacts are kept, by type
+    retention: RetentionPolicy,
+    
+    /// Deletion times announced for expiring artifacts, by artifact ID
+    expiry_notices: DashMap<String, chrono::DateTime<chrono::Utc>>,
+    
+    /// Turns raw tool output into assets and findings
+    parsers: Arc<ParserRegistry>,
+    
+    /// Adapters describing how to build, parse and weigh each tool
+    tools: Arc<ToolRegistry>,
+    
+    /// Routes chat and AI requests to model backends
+    models: Arc<ModelManager>,
+    
+    /// Per-agent system prompts
+    prompts: Arc<PromptTemplates>,
+    
+    /// Structured messages between agents
+    agent_bus: Arc<AgentBus>,
+    
+    /// Current settings; the reloadable ones change on `reload_config`
+    config: RwLock<CoreConfig>,
 }
 
 impl NeuroRiftCore {
     /// Create a new NeuroRift core
-    pub fn new(
-        base_dir: PathBuf,
-        ws_addr: std::net::SocketAddr,
-        python_bridge_url: String,
-    ) -> Result<Self> {
+    pub fn new(config: CoreConfig) -> Result<Self> {
+        let base_dir = config.base_dir.clone();
         let session_manager = Arc::new(SessionManager::new(&base_dir)?);
-        let ws_server = Arc::new(WebSocketServer::new(ws_addr));
-        let python_bridge = Arc::new(PythonBridge::new(python_bridge_url));
+        let journal = Arc::new(EventJournal::new(&base_dir)?);
+        let audit = Arc::new(AuditLog::new(&base_dir)?);
+        let access = Arc::new(AccessControl::load(&base_dir)?);
+        let api_keys = Arc::new(ApiKeyStore::load(&base_dir)?);
+        let vault = Arc::new(SecretsVault::open(&base_dir)?);
+        let approval_policy = ApprovalPolicy::load(&base_dir)?;
+        let retention = RetentionPolicy::load(&base_dir)?;
+        let tools = Arc::new(ToolRegistry::load(&config)?);
+        if access.require_api_key() && !api_keys.has_active_keys() {
+            tracing::warn!("API keys are required but none are active; only local clients can connect");
+        }
+        
+        let mut ws_server = WebSocketServer::new(config.ws_addr)
+            .with_api_keys(api_keys.clone(), access.require_api_key())
+            .with_allowed_origins(access.allowed_origins());
+        if let Some(path) = config.ws_socket.clone() {
+            ws_server = ws_server.with_unix_socket(path);
+        }
+        let ws_server = Arc::new(ws_server);
+        let tor = Arc::new(TorService::new(TorConfig::load(&base_dir)?, ws_server.clone()));
+        let proxy = ProxyConfig::load(&base_dir)?;
+        if let Some(url) = &proxy.url {
+            tracing::info!("🌐 Engagement traffic leaves through {}", url);
+        }
+        let python_bridge = Arc::new(
+            PythonBridge::new(config.python_bridge_url.clone())
+                .with_config(BridgeConfig::load(&base_dir)?)?
+                .with_auth(BridgeAuth::load_or_create(&base_dir)?)
+                .with_events(ws_server.clone())
+                .with_tor(tor.clone())
+                .with_proxy(proxy.clone())?
+        );
+        let models = Arc::new(ModelManager::new(AiConfig::load(&base_dir)?, python_bridge.clone(), vault.clone())?);
+        let prompts = Arc::new(PromptTemplates::load(&base_dir)?);
+        let agent_bus = Arc::new(AgentBus::new(ws_server.clone()));
+        let notifications = NotificationConfig::load(&base_dir)?;
+        let webhooks = Arc::new(WebhookDispatcher::new(notifications.webhooks));
+        let chat_notifier = Arc::new(ChatNotifier::new(
+            notifications.channels,
+            notifications.routes,
+            notifications.stale_approval_minutes,
+        ));
+        let syslog = Arc::new(SyslogSink::new(notifications.syslog));
+        let siem = Arc::new(SiemForwarder::new(notifications.siem));
+        let misp = MispClient::new(MispConfig::load(&base_dir)?, vault.clone());
+        let defectdojo = DefectDojoClient::new(DefectDojoConfig::load(&base_dir)?, vault.clone());
+        let jira = JiraClient::new(JiraConfig::load(&base_dir)?, vault.clone());
         
         Ok(Self {
             sessions: Arc::new(DashMap::new()),
             session_manager,
             ws_server,
             python_bridge,
+            tor,
+            proxy,
             active_session: Arc::new(RwLock::new(None)),
+            webhooks,
+            chat_notifier,
+            syslog,
+            siem,
+            misp,
+            defectdojo,
+            jira,
+            ticketing: DashSet::new(),
+            journal,
+            audit,
+            access,
+            api_keys,
+            vault,
+            task_notify: Arc::new(Notify::new()),
+            deferred: DashMap::new(),
+            approval_policy,
+            running: DashMap::new(),
+            agent_runs: DashMap::new(),
+            run_progress: DashMap::new(),
+            progress: DashMap::new(),
+            halted: AtomicBool::new(false),
+            draining: AtomicBool::new(false),
+            disk_level: Mutex::new(DiskLevel::Ok),
+            reports: ReportGenerator::new(&base_dir),
+            artifacts: Arc::new(ArtifactStore::new(&base_dir)?),
+            transfers: Arc::new(Transfers::default()),
+            retention,
+            expiry_notices: DashMap::new(),
+            parsers: Arc::new(ParserRegistry::with_builtin()),
+            tools,
+            models,
+            prompts,
+            agent_bus,
+            config: RwLock::new(config),
         })
     }
     
@@ -57,6 +311,212 @@ impl NeuroRiftCore {
         self.ws_server.clone()
     }
     
+    /// Get webhook dispatcher
+    pub fn webhooks(&self) -> Arc<WebhookDispatcher> {
+        self.webhooks.clone()
+    }
+    
+    /// Get the on-disk session store
+    pub fn session_manager(&self) -> Arc<SessionManager> {
+        self.session_manager.clone()
+    }
+    
+    /// Get event journal
+    pub fn journal(&self) -> Arc<EventJournal> {
+        self.journal.clone()
+    }
+    
+    /// Get audit log
+    pub fn audit(&self) -> Arc<AuditLog> {
+        self.audit.clone()
+    }
+    
+    /// Get secrets vault
+    pub fn vault(&self) -> Arc<SecretsVault> {
+        self.vault.clone()
+    }
+    
+    /// Get the artifact store
+    pub fn artifact_store(&self) -> Arc<ArtifactStore> {
+        self.artifacts.clone()
+    }
+    
+    /// Get tool output parsers
+    pub fn parsers(&self) -> Arc<ParserRegistry> {
+        self.parsers.clone()
+    }
+    
+    /// Get tool adapters
+    pub fn tools(&self) -> Arc<ToolRegistry> {
+        self.tools.clone()
+    }
+    
+    /// Get AI model manager
+    pub fn models(&self) -> Arc<ModelManager> {
+        self.models.clone()
+    }
+    
+    /// Current settings
+    pub fn config(&self) -> CoreConfig {
+        self.config.read().clone()
+    }
+    
+    /// Re-read `config.toml` and the notification, MISP, DefectDojo and Jira
+    /// configs, applying what can change without a restart; running scans
+    /// are left alone
+    pub fn reload_config(&self, actor: Actor) -> Result<()> {
+        let fresh = CoreConfig::load()?;
+        let notifications = NotificationConfig::load(&self.config.read().base_dir)?;
+        let misp = MispConfig::load(&self.config.read().base_dir)?;
+        let defectdojo = DefectDojoConfig::load(&self.config.read().base_dir)?;
+        let jira = JiraConfig::load(&self.config.read().base_dir)?;
+        
+        let (mut changed, restart_required) = {
+            let mut config = self.config.write();
+            (config.apply_reloadable(&fresh), config.restart_required(&fresh))
+        };
+        // Non-short-circuiting `|`, so every part is applied
+        let notifications_changed = self.webhooks.reload(notifications.webhooks)
+            | self.chat_notifier.reload(notifications.channels, notifications.routes, notifications.stale_approval_minutes)
+            | self.syslog.reload(notifications.syslog)
+            | self.siem.reload(notifications.siem);
+        for (name, differs) in [
+            ("notifications", notifications_changed),
+            ("misp", self.misp.reload(misp)),
+            ("defectdojo", self.defectdojo.reload(defectdojo)),
+            ("jira", self.jira.reload(jira)),
+        ] {
+            if differs {
+                changed.push(name);
+            }
+        }
+        
+        tracing::info!("🔄 Configuration reloaded by {}: {}", actor, changed.join(", "));
+        if !restart_required.is_empty() {
+            tracing::warn!("Restart the core to apply: {}", restart_required.join(", "));
+        }
+        self.ws_server.broadcast(WSEvent::ConfigReloaded {
+            reloaded_by: actor,
+            changed: changed.into_iter().map(str::to_string).collect(),
+            restart_required: restart_required.into_iter().map(str::to_string).collect(),
+            timestamp: chrono::Utc::now(),
+        });
+        Ok(())
+    }
+    
+    /// Get the bus agents exchange messages on
+    pub fn agent_bus(&self) -> Arc<AgentBus> {
+        self.agent_bus.clone()
+    }
+    
+    /// An agent's system prompt, filled in with a session's context
+    pub fn agent_prompt(&self, agent: AgentType, session_id: Option<&str>) -> Result<Message> {
+        let session = session_id
+            .map(|id| self.sessions.get(id).map(|s| s.clone()).ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", id)))
+            .transpose()?;
+        let session = session.as_ref().map(|s| s.read());
+        Ok(Message::system(self.prompts.render(agent, session.as_deref(), &self.tools.list())))
+    }
+    
+    /// Notifier signalled whenever a task is queued
+    pub fn task_notify(&self) -> Arc<Notify> {
+        self.task_notify.clone()
+    }
+    
+    /// Get Slack/Discord notifier
+    pub fn chat_notifier(&self) -> Arc<ChatNotifier> {
+        self.chat_notifier.clone()
+    }
+    
+    /// Get syslog sink
+    pub fn syslog(&self) -> Arc<SyslogSink> {
+        self.syslog.clone()
+    }
+    
+    /// Get SIEM forwarder
+    pub fn siem(&self) -> Arc<SiemForwarder> {
+        self.siem.clone()
+    }
+    
+    /// Authorize and audit a client command before it is processed.
+    ///
+    /// Permitted commands are echoed to all clients; denied ones are
+    /// answered with `PermissionDenied` to the sender only.
+    pub fn admit_command(&self, command: &ClientCommand) -> bool {
+        let verdict = if self.draining.load(Ordering::SeqCst) {
+            Err("Core is shutting down".to_string())
+        } else {
+            self.access.authorize(&command.client, &command.event)
+        };
+        let outcome = if verdict.is_ok() { "accepted" } else { "denied" };
+        
+        if let Err(e) = self.audit.record_command(command, outcome) {
+            tracing::error!("Failed to write audit record: {}", e);
+        }
+        
+        match verdict {
+            Ok(()) => {
+                // Never echo plaintext secrets to other clients
+                if !command.event.is_sensitive() {
+                    self.ws_server.broadcast(command.event.clone());
+                }
+                true
+            }
+            Err(reason) => {
+                tracing::warn!("Denied {} from {}: {}", command.event.event_type(), command.client.client_id, reason);
+                self.ws_server.send_to(&command.client.client_id, WSEvent::PermissionDenied {
+                    command: command.event.event_type(),
+                    reason,
+                });
+                false
+            }
+        }
+    }
+    
+    /// Create an API key and send the secret to the requesting client only
+    pub fn create_api_key(&self, client_id: &str, name: String, scopes: Vec<Permission>) -> Result<()> {
+        let (key, secret) = self.api_keys.create(name, scopes)?;
+        self.ws_server.send_to(client_id, WSEvent::ApiKeyCreated { key, secret });
+        Ok(())
+    }
+    
+    /// Revoke an API key
+    pub fn revoke_api_key(&self, client_id: &str, key_id: &str) -> Result<()> {
+        let key = self.api_keys.revoke(key_id)?;
+        self.ws_server.send_to(client_id, WSEvent::ApiKeyRevoked { key });
+        Ok(())
+    }
+    
+    /// Send the API key list to a client
+    pub fn list_api_keys(&self, client_id: &str) {
+        self.ws_server.send_to(client_id, WSEvent::ApiKeyList {
+            keys: self.api_keys.list(),
+        });
+    }
+    
+    /// Store a secret and send the updated name list to the requesting client
+    pub fn set_secret(&self, client_id: &str, name: &str, value: &str) -> Result<()> {
+        self.vault.set(name, value)?;
+        self.list_secrets(client_id);
+        Ok(())
+    }
+    
+    /// Delete a secret and send the updated name list to the requesting client
+    pub fn delete_secret(&self, client_id: &str, name: &str) -> Result<()> {
+        if !self.vault.delete(name)? {
+            anyhow::bail!("Unknown secret: {}", name);
+        }
+        self.list_secrets(client_id);
+        Ok(())
+    }
+    
+    /// Send the names of stored secrets to a client
+    pub fn list_secrets(&self, client_id: &str) {
+        self.ws_server.send_to(client_id, WSEvent::SecretList {
+            names: self.vault.list(),
+        });
+    }
+    
     /// Create a new session
     pub fn create_session(&self, name: String, mode: OperationalMode, metadata: Option<std::collections::HashMap<String, String>>) -> Result<String> {
         let mut session = SessionState::new(name.clone(), mode);
@@ -74,12 +534,13 @@ impl NeuroRiftCore {
         self.ws_server.broadcast(WSEvent::SessionCreated {
             session_id: session_id.clone(),
             name,
+            mode,
         });
         
         tracing::info!("Session created: {}", session_id);
         Ok(session_id)
     }
-
+    
     /// Delete a session
     pub fn delete_session(&self, session_id: &str) -> Result<()> {
         // Remove from memory
@@ -104,17 +565,485 @@ impl NeuroRiftCore {
         tracing::info!("Session deleted: {}", session_id);
         Ok(())
     }
-
-    /// Export session to file
-    pub fn export_session(&self, session_id: &str) -> Result<PathBuf> {
+    
+    /// Export session to file; archives need a password
+    pub fn export_session(&self, session_id: &str, format: ExportFormat, password: Option<&str>) -> Result<PathBuf> {
         // Ensure latest state is saved
         self.save_session(session_id)?;
         
-        let path = self.session_manager.export_session_auto(session_id)?;
+        let path = match format {
+            ExportFormat::Nrs => self.session_manager.export_session_auto(session_id)?,
+            ExportFormat::Jsonl => {
+                let session = self.session_manager.load_session(session_id)?;
+                self.session_manager.export_session_jsonl(&session)?
+            }
+            ExportFormat::Archive => {
+                let password = password.ok_or_else(|| anyhow::anyhow!("An archive export needs a password"))?;
+                let session = self.session_manager.load_session(session_id)?;
+                self.session_manager.export_session_archive(&session, &self.journal.journal_path(session_id)?, password)?
+            }
+            ExportFormat::Cef => self.export_siem(session_id, SiemFormat::Cef)?,
+            ExportFormat::Leef => self.export_siem(session_id, SiemFormat::Leef)?,
+            ExportFormat::Stix => {
+                let session = self.session_manager.load_session(session_id)?;
+                self.session_manager.export_session_stix(&session)?
+            }
+        };
         tracing::info!("Session exported to: {:?}", path);
         Ok(path)
     }
     
+    /// Export findings and the audit records that name the session
+    fn export_siem(&self, session_id: &str, format: SiemFormat) -> Result<PathBuf> {
+        let session = self.session_manager.load_session(session_id)?;
+        let audit: Vec<_> = self.audit.records()?
+            .into_iter()
+            .filter(|r| r.target.as_deref() == Some(session_id))
+            .collect();
+        self.session_manager.export_session_siem(&session, format, &audit)
+    }
+    
+    /// Render an engagement report and register it as a session artifact
+    pub fn generate_report(&self, session_id: &str, format: ReportFormat, template: Option<&str>) -> Result<PathBuf> {
+        let session = self.sessions.get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?;
+        let snapshot = session.read().clone();
+        let path = self.reports.generate(&snapshot, format, template)?;
+        
+        let mut metadata = std::collections::HashMap::from([
+            ("format".to_string(), format.extension().to_string()),
+            ("findings".to_string(), snapshot.findings.len().to_string()),
+        ]);
+        if let Some(template) = template {
+            metadata.insert("template".to_string(), template.to_string());
+        }
+        self.store_artifact(session_id, &path, Some(ArtifactType::Report), metadata)?;
+        
+        tracing::info!("Report for {} written to {:?}", session_id, path);
+        Ok(path)
+    }
+    
+    /// Copy a file into the artifact store and register it with a session.
+    /// Content the session already holds returns the existing artifact.
+    /// Only for files the core produced; client files arrive by upload.
+    fn store_artifact(
+        &self,
+        session_id: &str,
+        source: &std::path::Path,
+        artifact_type: Option<ArtifactType>,
+        metadata: std::collections::HashMap<String, String>,
+    ) -> Result<Artifact> {
+        if !self.sessions.contains_key(session_id) {
+            anyhow::bail!("Session not loaded: {}", session_id);
+        }
+        let stored = self.artifacts.ingest(source)?;
+        let name = source.file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.register_stored_artifact(session_id, name, artifact_type, &stored, metadata)
+    }
+    
+    /// Register content already in the artifact store with a session
+    fn register_stored_artifact(
+        &self,
+        session_id: &str,
+        name: String,
+        artifact_type: Option<ArtifactType>,
+        stored: &StoredFile,
+        metadata: std::collections::HashMap<String, String>,
+    ) -> Result<Artifact> {
+        let session = self.sessions.get(session_id)
+            .map(|s| s.clone())
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?;
+        let artifact_type = artifact_type.unwrap_or_else(|| ArtifactType::from_path(std::path::Path::new(&name)));
+        let (artifact, created) = session.write().add_stored_artifact(artifact_type, name, stored, metadata);
+        
+        if created {
+            tracing::info!("Artifact {} stored for {} ({} bytes)", artifact.id, session_id, stored.size);
+            self.ws_server.broadcast(WSEvent::ArtifactCreated {
+                session_id: session_id.to_string(),
+                artifact: artifact.clone(),
+            });
+        }
+        Ok(artifact)
+    }
+    
+    /// Register images a task or browser action captured as screenshot
+    /// artifacts, tagged with `metadata` plus the page URL when known
+    pub fn record_captures(&self, session_id: &str, captures: Vec<artifacts::capture::Capture>, metadata: std::collections::HashMap<String, String>) {
+        for capture in captures {
+            let mut metadata = metadata.clone();
+            if let Some(url) = &capture.url {
+                metadata.insert("url".to_string(), url.clone());
+            }
+            let stored = capture.store(&self.artifacts)
+                .and_then(|stored| self.register_stored_artifact(session_id, capture.name(), Some(ArtifactType::Screenshot), &stored, metadata));
+            if let Err(e) = stored {
+                tracing::warn!("Failed to store capture for {}: {:#}", session_id, e);
+            }
+        }
+    }
+    
+    /// Run a browser automation action through the bridge, keeping any
+    /// screenshots it takes as artifacts of the session
+    pub async fn browser_action(&self, session_id: &str, action: &str, params: serde_json::Value, task_id: Option<&str>) -> Result<serde_json::Value> {
+        let response = self.python_bridge.browser_action(action, params).await?;
+        if let Some(data) = response.get("data") {
+            let mut metadata = std::collections::HashMap::from([("action".to_string(), action.to_string())]);
+            if let Some(task_id) = task_id {
+                metadata.insert("task_id".to_string(), task_id.to_string());
+            }
+            self.record_captures(session_id, artifacts::capture::captures(data), metadata);
+        }
+        Ok(response)
+    }
+    
+    /// Stream an artifact file to a client as binary frames
+    pub fn download_artifact(&self, client: &ClientInfo, session_id: &str, artifact_id: &str) -> Result<()> {
+        if client.transport == Transport::Stdio {
+            anyhow::bail!("Artifact transfer needs a WebSocket connection");
+        }
+        let session = self.sessions.get(session_id)
+            .map(|s| s.clone())
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?;
+        let artifact = session.read().artifacts.iter()
+            .find(|a| a.id == artifact_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Artifact not found in session {}: {}", session_id, artifact_id))?;
+        let path = PathBuf::from(&artifact.path);
+        let size = std::fs::metadata(&path)
+            .map_err(|e| anyhow::anyhow!("Artifact file {} is not readable: {}", artifact.path, e))?
+            .len();
+        
+        let transfer_id = transfer::new_id();
+        self.ws_server.send_to(&client.client_id, WSEvent::TransferStarted {
+            transfer_id: transfer_id.clone(),
+            direction: TransferDirection::Download,
+            session_id: session_id.to_string(),
+            artifact_id: Some(artifact.id.clone()),
+            name: artifact.name.clone(),
+            size,
+            chunk_size: transfer::CHUNK_SIZE,
+        });
+        tracing::info!("Sending artifact {} ({} bytes) to {}", artifact.id, size, client.client_id);
+        
+        let ws = self.ws_server.clone();
+        let transfers = self.transfers.clone();
+        let client_id = client.client_id.clone();
+        let id = transfer_id.clone();
+        // Hold the transfer until it is registered, so a fast finish cannot
+        // remove its entry before it is inserted
+        let (registered_tx, registered_rx) = oneshot::channel::<()>();
+        let task = tokio::spawn(async move {
+            if registered_rx.await.is_err() {
+                return;
+            }
+            let result = transfer::send_file(&ws, &client_id, &id, &path, size).await;
+            transfers.downloads.remove(&id);
+            let event = match result {
+                Ok(()) => WSEvent::TransferCompleted { transfer_id: id, artifact },
+                Err(e) => WSEvent::TransferFailed { transfer_id: id, reason: format!("{:#}", e) },
+            };
+            ws.send_to(&client_id, event);
+        });
+        self.transfers.downloads.insert(transfer_id, (client.client_id.clone(), task.abort_handle()));
+        let _ = registered_tx.send(());
+        Ok(())
+    }
+    
+    /// Accept an upload announced by a client; its content arrives as
+    /// binary frames through `receive_frame`
+    pub fn upload_artifact(
+        &self,
+        client: &ClientInfo,
+        session_id: &str,
+        name: &str,
+        size: u64,
+        sha256: Option<String>,
+        artifact_type: Option<ArtifactType>,
+    ) -> Result<()> {
+        if client.transport == Transport::Stdio {
+            anyhow::bail!("Artifact transfer needs a WebSocket connection");
+        }
+        if !self.sessions.contains_key(session_id) {
+            anyhow::bail!("Session not loaded: {}", session_id);
+        }
+        if size > transfer::MAX_UPLOAD_SIZE {
+            anyhow::bail!("Upload of {} bytes exceeds the {} byte limit", size, transfer::MAX_UPLOAD_SIZE);
+        }
+        let sha256 = sha256.map(|h| h.trim().to_ascii_lowercase());
+        if sha256.as_ref().is_some_and(|h| h.len() != 64 || !h.chars().all(|c| c.is_ascii_hexdigit())) {
+            anyhow::bail!("sha256 must be 64 hex digits");
+        }
+        // Only the file name is kept; where it is stored is up to the store
+        let name = std::path::Path::new(name).file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .filter(|n| !n.trim().is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Invalid artifact name: {:?}", name))?;
+        let writer = self.artifacts.writer(artifacts::extension(std::path::Path::new(&name)).as_deref())?;
+        
+        let transfer_id = transfer::new_id();
+        self.transfers.uploads.insert(transfer_id.clone(), Upload {
+            client_id: client.client_id.clone(),
+            session_id: session_id.to_string(),
+            name: name.clone(),
+            artifact_type,
+            size,
+            sha256,
+            uploaded_by: client.identity.to_string(),
+            writer,
+            progress: Progress::new(size),
+            last_activity: std::time::Instant::now(),
+        });
+        self.ws_server.send_to(&client.client_id, WSEvent::TransferStarted {
+            transfer_id: transfer_id.clone(),
+            direction: TransferDirection::Upload,
+            session_id: session_id.to_string(),
+            artifact_id: None,
+            name,
+            size,
+            chunk_size: transfer::CHUNK_SIZE,
+        });
+        tracing::info!("Receiving {} bytes from {} as transfer {}", size, client.client_id, transfer_id);
+        
+        if size == 0 {
+            self.finish_upload(&transfer_id);
+        }
+        Ok(())
+    }
+    
+    /// Add a binary frame from a client to its upload
+    pub fn receive_frame(&self, frame: ClientFrame) {
+        let Some((transfer_id, payload)) = transfer::split_frame(&frame.data) else {
+            tracing::debug!("Ignoring malformed binary frame from {}", frame.client_id);
+            return;
+        };
+        let outcome = {
+            let Some(mut upload) = self.transfers.uploads.get_mut(&transfer_id) else {
+                tracing::debug!("Ignoring frame for unknown transfer {}", transfer_id);
+                return;
+            };
+            if upload.client_id != frame.client_id {
+                tracing::warn!("Client {} sent a frame for another client's transfer {}", frame.client_id, transfer_id);
+                return;
+            }
+            upload.last_activity = std::time::Instant::now();
+            let received = upload.writer.size() + payload.len() as u64;
+            if received > upload.size {
+                Some(Err(anyhow::anyhow!("More data than the announced {} bytes", upload.size)))
+            } else if let Err(e) = upload.writer.write(payload) {
+                Some(Err(e))
+            } else {
+                if upload.progress.due(received) {
+                    self.ws_server.send_to(&frame.client_id, WSEvent::TransferProgress {
+                        transfer_id: transfer_id.clone(),
+                        bytes: received,
+                        total: upload.size,
+                    });
+                }
+                (received == upload.size).then_some(Ok(()))
+            }
+        };
+        match outcome {
+            Some(Ok(())) => self.finish_upload(&transfer_id),
+            Some(Err(e)) => self.fail_upload(&transfer_id, e),
+            None => {}
+        }
+    }
+    
+    /// Store a fully received upload and register it with its session
+    fn finish_upload(&self, transfer_id: &str) {
+        let Some((_, upload)) = self.transfers.uploads.remove(transfer_id) else {
+            return;
+        };
+        let client_id = upload.client_id.clone();
+        let result = (|| {
+            if let Some(expected) = &upload.sha256 {
+                let actual = upload.writer.sha256();
+                if &actual != expected {
+                    anyhow::bail!("Content hash {} does not match the announced {}", actual, expected);
+                }
+            }
+            let stored = upload.writer.finish()?;
+            let metadata = std::collections::HashMap::from([("uploaded_by".to_string(), upload.uploaded_by)]);
+            self.register_stored_artifact(&upload.session_id, upload.name, upload.artifact_type, &stored, metadata)
+        })();
+        let event = match result {
+            Ok(artifact) => WSEvent::TransferCompleted { transfer_id: transfer_id.to_string(), artifact },
+            Err(e) => {
+                tracing::warn!("Upload {} failed: {:#}", transfer_id, e);
+                WSEvent::TransferFailed { transfer_id: transfer_id.to_string(), reason: format!("{:#}", e) }
+            }
+        };
+        self.ws_server.send_to(&client_id, event);
+    }
+    
+    /// Drop an upload, discarding what arrived, and tell its client why
+    fn fail_upload(&self, transfer_id: &str, reason: anyhow::Error) {
+        if let Some((_, upload)) = self.transfers.uploads.remove(transfer_id) {
+            tracing::warn!("Upload {} failed: {:#}", transfer_id, reason);
+            self.ws_server.send_to(&upload.client_id, WSEvent::TransferFailed {
+                transfer_id: transfer_id.to_string(),
+                reason: format!("{:#}", reason),
+            });
+        }
+    }
+    
+    /// Stop one of a client's transfers
+    pub fn cancel_transfer(&self, client_id: &str, transfer_id: &str) -> Result<()> {
+        if self.transfers.uploads.remove_if(transfer_id, |_, upload| upload.client_id == client_id).is_none() {
+            let (_, (_, task)) = self.transfers.downloads.remove_if(transfer_id, |_, (owner, _)| owner == client_id)
+                .ok_or_else(|| anyhow::anyhow!("No transfer {} for this client", transfer_id))?;
+            task.abort();
+        }
+        self.ws_server.send_to(client_id, WSEvent::TransferFailed {
+            transfer_id: transfer_id.to_string(),
+            reason: "Cancelled".to_string(),
+        });
+        Ok(())
+    }
+    
+    /// Abandon uploads whose client stopped sending
+    pub fn sweep_transfers(&self) {
+        let idle: Vec<String> = self.transfers.uploads.iter()
+            .filter(|upload| upload.last_activity.elapsed() > transfer::IDLE_TIMEOUT)
+            .map(|upload| upload.key().clone())
+            .collect();
+        for transfer_id in idle {
+            let reason = anyhow::anyhow!("No data for {} seconds", transfer::IDLE_TIMEOUT.as_secs());
+            self.fail_upload(&transfer_id, reason);
+        }
+    }
+    
+    /// Re-hash every artifact in a session and broadcast the results, so
+    /// the check itself lands in the journal
+    pub fn verify_artifacts(&self, session_id: &str) -> Result<Vec<artifacts::ArtifactCheck>> {
+        let artifacts = self.sessions.get(session_id)
+            .map(|s| s.read().artifacts.clone())
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?;
+        let checks: Vec<artifacts::ArtifactCheck> = artifacts.iter().map(artifacts::verify).collect();
+        
+        for check in checks.iter().filter(|c| matches!(c.status, IntegrityStatus::Missing | IntegrityStatus::Tampered)) {
+            tracing::warn!("Artifact {} ({}) in {} is {:?}", check.artifact_id, check.name, session_id, check.status);
+        }
+        tracing::info!("Verified {} artifacts in {}", checks.len(), session_id);
+        self.ws_server.broadcast(WSEvent::ArtifactsVerified {
+            session_id: session_id.to_string(),
+            verified_at: chrono::Utc::now(),
+            checks: checks.clone(),
+        });
+        Ok(checks)
+    }
+    
+    /// Apply the retention policy to loaded sessions: warn about artifacts
+    /// nearing expiry, then delete them once the warned time has passed.
+    /// Stored content is removed when no saved session refers to it.
+    pub fn sweep_artifacts(&self) {
+        let now = chrono::Utc::now();
+        let notice = self.retention.notice();
+        let mut warnings = Vec::new();
+        let mut doomed = Vec::new();
+        
+        for entry in self.sessions.iter() {
+            let session = entry.value().read();
+            let evidence: std::collections::HashSet<&str> = session.findings.iter()
+                .flat_map(|f| f.evidence.iter().map(|e| e.artifact_id.as_str()))
+                .collect();
+            for artifact in &session.artifacts {
+                if self.retention.keep_evidence && evidence.contains(artifact.id.as_str()) {
+                    continue;
+                }
+                let Some(expiry) = self.retention.expiry(artifact) else {
+                    continue;
+                };
+                if now < expiry - notice {
+                    continue;
+                }
+                // Deletion always follows a warning by the full notice, even
+                // when the core was down as the artifact expired
+                match self.expiry_notices.get(&artifact.id).map(|d| *d) {
+                    Some(deletes_at) if now >= deletes_at => doomed.push((entry.key().clone(), artifact.id.clone())),
+                    Some(_) => {}
+                    None => {
+                        let deletes_at = expiry.max(now + notice);
+                        self.expiry_notices.insert(artifact.id.clone(), deletes_at);
+                        warnings.push((entry.key().clone(), artifact.clone(), deletes_at));
+                    }
+                }
+            }
+        }
+        
+        for (session_id, artifact, deletes_at) in warnings {
+            tracing::info!("Artifact {} in {} expires at {}", artifact.id, session_id, deletes_at);
+            self.ws_server.broadcast(WSEvent::ArtifactExpiring { session_id, artifact, deletes_at });
+        }
+        
+        let mut removed = Vec::new();
+        for (session_id, artifact_id) in doomed {
+            self.expiry_notices.remove(&artifact_id);
+            let Some(session) = self.sessions.get(&session_id).map(|s| s.clone()) else {
+                continue;
+            };
+            let Some(artifact) = session.write().remove_artifact(&artifact_id) else {
+                continue;
+            };
+            tracing::info!("Artifact {} deleted from {} by retention", artifact_id, session_id);
+            self.ws_server.broadcast(WSEvent::ArtifactDeleted {
+                session_id,
+                artifact_id,
+                reason: format!("Retention period of {} days elapsed", self.retention.rules.get(&artifact.artifact_type).copied().unwrap_or_default()),
+            });
+            removed.push(artifact);
+        }
+        if removed.is_empty() {
+            return;
+        }
+        
+        // Deduplicated content may still back artifacts in other sessions
+        let in_use = match self.stored_paths_in_use() {
+            Ok(paths) => paths,
+            Err(e) => {
+                tracing::error!("Not deleting expired artifact files: {:#}", e);
+                return;
+            }
+        };
+        for artifact in removed {
+            let path = PathBuf::from(&artifact.path);
+            if self.artifacts.holds(&path) && !in_use.contains(&artifact.path) {
+                if let Err(e) = self.artifacts.remove(&path) {
+                    tracing::error!("{:#}", e);
+                }
+            }
+        }
+    }
+    
+    /// Artifact paths referenced by any loaded or saved session
+    fn stored_paths_in_use(&self) -> Result<std::collections::HashSet<String>> {
+        let mut paths: std::collections::HashSet<String> = self.sessions.iter()
+            .flat_map(|entry| entry.value().read().artifacts.iter().map(|a| a.path.clone()).collect::<Vec<_>>())
+            .collect();
+        for saved in self.session_manager.list_sessions()? {
+            if self.sessions.contains_key(&saved.id) {
+                continue;
+            }
+            let session = self.session_manager.load_session(&saved.id)?;
+            paths.extend(session.artifacts.into_iter().map(|a| a.path));
+        }
+        Ok(paths)
+    }
+    
+    /// Send the available report templates to one client
+    pub fn list_report_templates(&self, client_id: &str) -> Result<()> {
+        let templates = self.reports.templates().list()?;
+        self.ws_server.send_to(client_id, WSEvent::ReportTemplateList { templates });
+        Ok(())
+    }
+    
+    /// Send the tool catalog to one client
+    pub fn list_tool_catalog(&self, client_id: &str) {
+        self.ws_server.send_to(client_id, WSEvent::ToolCatalog { tools: self.tools.catalog() });
+    }
+    
     /// List all sessions
     pub fn list_sessions(&self) -> Result<()> {
         let sessions = self.session_manager.list_sessions()?;
@@ -125,25 +1054,118 @@ impl NeuroRiftCore {
         
         Ok(())
     }
-
-    /// Load a session from disk
+    
+    /// Load a session from disk, replaying changes journaled since it was
+    /// last saved, or rebuilding it from the journal if the .nrs file is
+    /// missing or corrupt
     pub fn load_session(&self, session_id: &str) -> Result<()> {
-        let session = self.session_manager.load_session(session_id)?;
+        // Only a missing or unparseable file is rebuilt from the journal;
+        // other errors (permissions, I/O) leave the file alone
+        let mut session = match self.session_manager.load_session(session_id) {
+            Ok(session) => session,
+            Err(e) if e.downcast_ref::<serde_json::Error>().is_some() => {
+                tracing::warn!("Session file of {} is corrupt ({:#}), rebuilding from journal", session_id, e);
+                let corrupt_copy = self.session_manager.set_aside(session_id)?;
+                return self.rebuild_from_journal(session_id, RebuildReason::CorruptFile, corrupt_copy);
+            }
+            Err(e) if e.downcast_ref::<std::io::Error>().is_some_and(|e| e.kind() == std::io::ErrorKind::NotFound) => {
+                tracing::warn!("Session file of {} is missing, rebuilding from journal", session_id);
+                return self.rebuild_from_journal(session_id, RebuildReason::MissingFile, None);
+            }
+            Err(e) => return Err(e),
+        };
+        
+        // Changes journaled after the file was last written, lost in a crash
+        let recovered = match self.journal.journal_path(session_id)?.exists() {
+            true => match self.journal.read(session_id) {
+                Ok(entries) => crate::journal::replay::recover(&mut session, &entries),
+                Err(e) => {
+                    tracing::warn!("Could not read the journal of {} to recover unsaved changes: {:#}", session_id, e);
+                    0
+                }
+            },
+            false => 0,
+        };
+        
+        self.activate_loaded(session);
+        if recovered > 0 {
+            tracing::warn!("Session {}: recovered {} unsaved changes from the journal", session_id, recovered);
+            self.save_session(session_id)?;
+        }
+        Ok(())
+    }
+    
+    /// Reconstruct a session by replaying its event journal, then persist it
+    pub fn rebuild_session(&self, session_id: &str) -> Result<()> {
+        self.rebuild_from_journal(session_id, RebuildReason::Requested, None)
+    }
+    
+    fn rebuild_from_journal(&self, session_id: &str, reason: RebuildReason, corrupt_copy: Option<PathBuf>) -> Result<()> {
+        if !crate::state::is_valid_session_id(session_id) {
+            anyhow::bail!("Invalid session ID {:?}", session_id);
+        }
+        let entries = self.journal.read(session_id)?;
+        let session = crate::journal::replay::replay(session_id, &entries)?;
+        tracing::info!("Session {} rebuilt from {} journal entries", session_id, entries.len());
+        
+        self.session_manager.save_session(&session)?;
+        self.activate_loaded(session);
+        self.ws_server.broadcast(WSEvent::SessionRebuilt {
+            session_id: session_id.to_string(),
+            reason,
+            entries: entries.len(),
+            corrupt_copy: corrupt_copy.map(|path| path.display().to_string()),
+        });
+        Ok(())
+    }
+    
+    /// Make a loaded session active and announce it, recovering tasks a
+    /// stopped core left running
+    fn activate_loaded(&self, mut session: SessionState) {
         let id = session.id.clone();
+        let interrupted = session.interrupt_stale_tasks(|task_id| self.running.contains_key(task_id));
         
         self.sessions.insert(id.clone(), Arc::new(RwLock::new(session.clone())));
         *self.active_session.write() = Some(id.clone());
         
         // Broadcast event
         self.ws_server.broadcast(WSEvent::SessionLoaded {
-            session_id: id,
-            state: session,
+            session_id: id.clone(),
+            state: Box::new(session),
         });
         
-        Ok(())
+        if !interrupted.is_empty() {
+            self.recover_interrupted(&id, interrupted);
+        }
+    }
+    
+    /// Report interrupted tasks, queueing them again if configured to
+    fn recover_interrupted(&self, session_id: &str, interrupted: Vec<Task>) {
+        let requeue = self.config.read().requeue_interrupted;
+        let tasks: Vec<InterruptedTask> = interrupted.into_iter()
+            .map(|task| {
+                let requeued_as = requeue.then(|| {
+                    let args = serde_json::to_value(&task.args).unwrap_or_default();
+                    let created_by = task.created_by.clone().unwrap_or(Actor::System);
+                    self.queue_task_in(session_id, task.tool_name.clone(), task.target.clone(), args, task.proxy.clone(), created_by)
+                        .inspect_err(|e| tracing::warn!("Failed to requeue interrupted task {}: {:#}", task.id, e))
+                        .ok()
+                }).flatten();
+                InterruptedTask { task_id: task.id, tool_name: task.tool_name, target: task.target, requeued_as }
+            })
+            .collect();
+        
+        let requeued = tasks.iter().filter(|t| t.requeued_as.is_some()).count();
+        tracing::warn!("Session {}: {} tasks were interrupted by a restart, {} requeued", session_id, tasks.len(), requeued);
+        self.ws_server.broadcast(WSEvent::TasksInterrupted {
+            session_id: session_id.to_string(),
+            tasks,
+        });
+        self.update_progress(session_id);
     }
     
     /// Save current session
+    #[tracing::instrument(skip(self))]
     pub fn save_session(&self, session_id: &str) -> Result<()> {
         if let Some(session_ref) = self.sessions.get(session_id) {
             let session = session_ref.read().clone();
@@ -159,78 +1181,1193 @@ impl NeuroRiftCore {
         Ok(())
     }
     
-    /// Get active session
-    pub fn get_active_session(&self) -> Option<Arc<RwLock<SessionState>>> {
-        let active_id = self.active_session.read().clone()?;
-        self.sessions.get(&active_id).map(|r| r.value().clone())
-    }
-    
-    /// Queue a task in the active session
-    pub fn queue_task(&self, tool_name: String, target: String, args: serde_json::Value) -> Result<()> {
-        if let Some(session) = self.get_active_session() {
+    /// Replace a session's engagement scope
+    pub fn set_scope(&self, session_id: &str, scope: EngagementScope) -> Result<()> {
+        let session = self.sessions.get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?;
+        {
             let mut session = session.write();
-            let args_map = args.as_object()
-                .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
-                .unwrap_or_default();
+            session.scope = scope.clone();
+            session.touch();
+        }
+        
+        self.ws_server.broadcast(WSEvent::ScopeUpdated {
+            session_id: session_id.to_string(),
+            scope,
+        });
+        Ok(())
+    }
+    
+    /// Replace a session's rules of engagement
+    pub fn set_rules_of_engagement(&self, session_id: &str, roe: RulesOfEngagement) -> Result<()> {
+        let session = self.sessions.get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?;
+        {
+            let mut session = session.write();
+            session.roe = roe.clone();
+            session.touch();
+        }
+        
+        self.ws_server.broadcast(WSEvent::RulesOfEngagementUpdated {
+            session_id: session_id.to_string(),
+            roe,
+        });
+        self.task_notify.notify_one();
+        Ok(())
+    }
+    
+    /// Register a target on a session
+    pub fn add_target(&self, session_id: &str, value: String, in_scope: bool, note: Option<String>, added_by: Actor) -> Result<()> {
+        let session = self.sessions.get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?;
+        if value.trim().is_empty() {
+            anyhow::bail!("Target must not be empty");
+        }
+        let target = session.write().add_target(value, in_scope, note, added_by);
+        
+        self.ws_server.broadcast(WSEvent::TargetAdded {
+            session_id: session_id.to_string(),
+            target,
+        });
+        Ok(())
+    }
+    
+    /// Unregister a target from a session
+    pub fn remove_target(&self, session_id: &str, value: &str) -> Result<()> {
+        let session = self.sessions.get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?;
+        let removed = session.write().remove_target(value)
+            .ok_or_else(|| anyhow::anyhow!("Target not registered: {}", value))?;
+        
+        self.ws_server.broadcast(WSEvent::TargetRemoved {
+            session_id: session_id.to_string(),
+            target: removed.value,
+        });
+        Ok(())
+    }
+    
+    /// Send a session's registered targets to one client
+    pub fn list_targets(&self, client_id: &str, session_id: &str) -> Result<()> {
+        let session = self.sessions.get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?;
+        let targets = session.read().targets.clone();
+        
+        self.ws_server.send_to(client_id, WSEvent::TargetList {
+            session_id: session_id.to_string(),
+            targets,
+        });
+        Ok(())
+    }
+    
+    /// Send a digest of a session's progress to one client
+    pub fn get_session_summary(&self, client_id: &str, session_id: &str) -> Result<()> {
+        let session = self.sessions.get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?;
+        let summary = crate::state::summary::SessionSummary::of(&session.read());
+        
+        self.ws_server.send_to(client_id, WSEvent::SessionSummary { summary });
+        Ok(())
+    }
+    
+    /// Send counts and a discovery series over a session's findings to
+    /// one client
+    pub fn get_findings_stats(&self, client_id: &str, session_id: &str) -> Result<()> {
+        let session = self.sessions.get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?;
+        let stats = crate::state::stats::FindingsStats::of(&session.read());
+        
+        self.ws_server.send_to(client_id, WSEvent::FindingsStats {
+            session_id: session_id.to_string(),
+            stats,
+        });
+        Ok(())
+    }
+    
+    /// Send a session's agent timeline to one client, optionally for one
+    /// agent or from a point in time
+    pub fn get_timeline(&self, client_id: &str, session_id: &str, agent: Option<AgentType>, since: Option<chrono::DateTime<chrono::Utc>>) -> Result<()> {
+        let session = self.sessions.get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?;
+        let entries = session.read().timeline.iter()
+            .filter(|e| agent.is_none_or(|a| e.agent == a))
+            .filter(|e| since.is_none_or(|t| e.timestamp >= t))
+            .cloned()
+            .collect();
+        
+        self.ws_server.send_to(client_id, WSEvent::Timeline {
+            session_id: session_id.to_string(),
+            entries,
+        });
+        Ok(())
+    }
+    
+    /// Check a task against its session's scope before dispatch.
+    ///
+    /// Tasks whose out-of-scope override was approved are let through.
+    pub fn check_scope(&self, session_id: &str, task: &Task) -> Result<()> {
+        let verdict = match self.sessions.get(session_id) {
+            Some(session) => {
+                let session = session.read();
+                let overridden = task.approval_id.as_ref().is_some_and(|id| {
+                    session.approval_queue.iter().any(|a| &a.id == id && a.status == ApprovalStatus::Approved)
+                });
+                if overridden { Ok(()) } else { session.check_target(&task.target) }
+            }
+            None => Err(format!("Session not loaded: {}", session_id)),
+        };
+        
+        verdict.map_err(|reason| {
+            self.report_scope_violation(session_id, &task.tool_name, &task.target, Some(&task.id), &reason);
+            anyhow::anyhow!(reason)
+        })
+    }
+    
+    /// Announce a target that falls outside the engagement scope
+    fn report_scope_violation(&self, session_id: &str, tool_name: &str, target: &str, task_id: Option<&str>, reason: &str) {
+        tracing::warn!("Scope violation in {}: {} -> {} ({})", session_id, tool_name, target, reason);
+        self.ws_server.broadcast(WSEvent::ScopeViolation {
+            session_id: session_id.to_string(),
+            task_id: task_id.map(str::to_string),
+            tool_name: tool_name.to_string(),
+            target: target.to_string(),
+            reason: reason.to_string(),
+        });
+    }
+    
+    /// Get active session ID
+    pub fn active_session_id(&self) -> Option<String> {
+        self.active_session.read().clone()
+    }
+    
+    /// Get active session
+    pub fn get_active_session(&self) -> Option<Arc<RwLock<SessionState>>> {
+        let active_id = self.active_session.read().clone()?;
+        self.sessions.get(&active_id).map(|r| r.value().clone())
+    }
+    
+    /// Get a loaded session
+    pub fn session(&self, session_id: &str) -> Option<Arc<RwLock<SessionState>>> {
+        self.sessions.get(session_id).map(|r| r.value().clone())
+    }
+    
+    /// Pending approvals across all in-memory sessions
+    pub fn pending_approvals(&self) -> Vec<ApprovalRequest> {
+        self.sessions.iter()
+            .flat_map(|entry| {
+                entry.value().read().approval_queue.iter()
+                    .filter(|a| a.status == ApprovalStatus::Pending)
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+    
+    /// Approve or deny a pending approval, releasing or cancelling its task
+    pub fn decide_approval(&self, approval_id: &str, decision: Decision, decided_by: Actor) -> Result<()> {
+        let session = self.sessions.iter()
+            .find(|entry| entry.value().read().approval_queue.iter().any(|a| a.id == approval_id))
+            .map(|entry| entry.value().clone())
+            .ok_or_else(|| anyhow::anyhow!("Unknown approval: {}", approval_id))?;
+        
+        let outcome = approval::decide(&mut session.write(), approval_id, &decision, decided_by.clone())?;
+        self.publish_progress(&session.read());
+        tracing::info!("Approval {} {:?} by {}", approval_id, outcome.approval.status, decided_by);
+        
+        match decision {
+            Decision::Approve => {
+                self.ws_server.broadcast(WSEvent::ApprovalGranted {
+                    approval_id: approval_id.to_string(),
+                    granted_at: outcome.decided_at,
+                });
+                if outcome.gated_task.is_some() {
+                    self.task_notify.notify_one();
+                }
+            }
+            Decision::Deny { reason } => {
+                self.ws_server.broadcast(WSEvent::ApprovalDenied {
+                    approval_id: approval_id.to_string(),
+                    denied_at: outcome.decided_at,
+                    reason: reason.clone(),
+                });
+                if let Some(task_id) = outcome.gated_task {
+                    self.ws_server.broadcast(WSEvent::TaskCancelled {
+                        task_id,
+                        reason: reason.unwrap_or_else(|| "Approval denied".to_string()),
+                    });
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Escalate long-pending approvals and deny-by-default expired ones
+    pub fn sweep_approvals(&self) {
+        let now = chrono::Utc::now();
+        
+        for entry in self.sessions.iter() {
+            let (escalated, expired) = {
+                let mut session = entry.value().write();
+                let expired = approval::expire(&mut session, &self.approval_policy, now);
+                if !expired.is_empty() {
+                    self.publish_progress(&session);
+                }
+                (approval::escalate(&mut session, &self.approval_policy, now), expired)
+            };
             
-            session.queue_task(tool_name.clone(), target.clone(), args_map);
+            for approval in escalated {
+                tracing::warn!("Approval {} escalated to level {}", approval.id, approval.escalation_level);
+                self.ws_server.broadcast(WSEvent::ApprovalEscalated { approval: approval.clone() });
+                self.ws_server.broadcast(WSEvent::ApprovalRequired { approval });
+            }
             
-            // Get the task that was just added
-            if let Some(task) = session.task_queue.back() {
-                self.ws_server.broadcast(WSEvent::TaskQueued {
-                    task: task.clone(),
+            for (approval, gated_task) in expired {
+                tracing::warn!("Approval {} expired without a decision", approval.id);
+                self.ws_server.broadcast(WSEvent::ApprovalExpired {
+                    approval_id: approval.id.clone(),
+                    expired_at: now,
                 });
+                if let Some(task_id) = gated_task {
+                    self.ws_server.broadcast(WSEvent::TaskFailed {
+                        task_id,
+                        error: format!("Approval {} expired", approval.id),
+                    });
+                }
+            }
+        }
+    }
+    
+    /// Update gauges derived from in-memory session state
+    pub fn refresh_metrics(&self) {
+        let mut counts = [0i64; 7];
+        for entry in self.sessions.iter() {
+            for task in &entry.value().read().task_queue {
+                let index = match task.status {
+                    TaskStatus::Queued => 0,
+                    TaskStatus::Running => 1,
+                    TaskStatus::Completed => 2,
+                    TaskStatus::Failed => 3,
+                    TaskStatus::Cancelled => 4,
+                    TaskStatus::AwaitingApproval => 5,
+                    TaskStatus::Interrupted => 6,
+                };
+                counts[index] += 1;
             }
         }
         
+        for (status, count) in ["queued", "running", "completed", "failed", "cancelled", "awaiting_approval", "interrupted"].iter().zip(counts) {
+            METRICS.tasks.with_label_values(&[status]).set(count);
+        }
+        METRICS.sessions.set(self.sessions.len() as i64);
+    }
+    
+    /// Queue a task in the active session
+    ///
+    /// Out-of-scope targets are not rejected outright: the task is held
+    /// behind a critical-risk approval so an operator can authorize the
+    /// exception explicitly.
+    #[tracing::instrument(skip(self, args, created_by), fields(trace_id))]
+    pub fn queue_task(&self, tool_name: String, target: String, args: serde_json::Value, proxy: Option<String>, created_by: Actor) -> Result<()> {
+        if let Some(session_id) = self.active_session_id() {
+            self.queue_task_in(&session_id, tool_name, target, args, proxy, created_by)?;
+        }
         Ok(())
     }
     
-    /// Update agent status
-    pub fn update_agent_status(&self, agent: AgentType, state: AgentState, current_task: Option<String>) {
-        if let Some(session) = self.get_active_session() {
+    /// Queue a task in a specific session, returning its ID
+    pub fn queue_task_in(&self, session_id: &str, tool_name: String, target: String, args: serde_json::Value, proxy: Option<String>, created_by: Actor) -> Result<String> {
+        if let Some(proxy) = &proxy {
+            crate::proxy::validate(proxy)?;
+        }
+        let session = self.sessions.get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?;
+        let mut session = session.write();
+        let args_map = args.as_object()
+            .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default();
+        let violation = session.check_target(&target).err();
+        
+        session.queue_task(tool_name.clone(), target.clone(), args_map, proxy, created_by.clone());
+        let task_id = session.task_queue.back().map(|t| t.id.clone()).unwrap_or_default();
+        
+        let approval = violation.map(|reason| {
+            self.report_scope_violation(&session.id, &tool_name, &target, Some(&task_id), &reason);
+            let args = session.task_queue.back().map(|t| t.args.clone()).unwrap_or_default();
+            let tool_risk = self.tools.estimate_risk(&tool_name, &args);
+            let assessment = risk::assess(&ActionType::ToolExecution, &tool_name, &args, &target, &session, tool_risk);
+            let action = Action {
+                action_type: ActionType::ToolExecution,
+                description: format!("Run {} against out-of-scope target {}", tool_name, target),
+                risk_level: assessment.level.clone(),
+                details: serde_json::json!({
+                    "task_id": task_id,
+                    "tool_name": tool_name,
+                    "target": target,
+                    "scope_violation": reason,
+                }),
+            };
+            let approval_id = session.request_approval(action, reason, created_by);
+            if let Some(approval) = session.approval_queue.back_mut() {
+                approval.risk = Some(assessment);
+                approval.expires_at = Some(self.approval_policy.deadline(approval));
+            }
+            if let Some(task) = session.task_queue.back_mut() {
+                task.status = TaskStatus::AwaitingApproval;
+                task.approval_id = Some(approval_id.clone());
+            }
+            approval_id
+        });
+        
+        if let Some(task) = session.task_queue.back() {
+            tracing::Span::current().record("trace_id", task.trace_id.as_deref());
+        }
+        
+        METRICS.tasks_queued_total.inc();
+        
+        // Get the task that was just added
+        if let Some(task) = session.task_queue.back() {
+            self.ws_server.broadcast(WSEvent::TaskQueued {
+                task: task.clone(),
+            });
+        }
+        
+        match approval.and_then(|id| session.approval_queue.iter().find(|a| a.id == id).cloned()) {
+            Some(approval) => self.ws_server.broadcast(WSEvent::ApprovalRequired { approval }),
+            None => self.task_notify.notify_one(),
+        }
+        self.publish_progress(&session);
+        
+        Ok(task_id)
+    }
+    
+    /// Mark the oldest queued task (across in-memory sessions) as running.
+    ///
+    /// Each session's rules of engagement are consulted first: tasks using
+    /// a forbidden technique fail, and sessions outside their allowed
+    /// window or over their rate limit are skipped for now.
+    pub fn claim_next_task(&self) -> Option<(String, Task)> {
+        let now = chrono::Utc::now();
+        
+        for entry in self.sessions.iter() {
+            let mut session = entry.value().write();
+            
+            let forbidden: Vec<(String, String)> = session.task_queue.iter()
+                .filter(|t| t.status == TaskStatus::Queued)
+                .filter_map(|t| session.roe.forbids(&t.tool_name, &t.args).map(|reason| (t.id.clone(), reason)))
+                .collect();
+            for (task_id, reason) in forbidden {
+                if let Some(task) = session.task_queue.iter_mut().find(|t| t.id == task_id) {
+                    task.status = TaskStatus::Failed;
+                    task.completed_at = Some(now);
+                }
+                tracing::warn!("Task {} blocked: {}", task_id, reason);
+                self.ws_server.broadcast(WSEvent::TaskFailed { task_id, error: reason });
+            }
+            self.publish_progress(&session);
+            
+            let Some(first) = session.task_queue.iter().position(|t| t.status == TaskStatus::Queued) else {
+                continue;
+            };
+            
+            let starts_last_minute = session.task_queue.iter()
+                .filter(|t| t.started_at.is_some_and(|at| now - at < chrono::Duration::minutes(1)))
+                .count();
+            if let Some(reason) = session.roe.defer_reason(now, starts_last_minute) {
+                self.announce_deferral(&session.task_queue[first].id, reason);
+                continue;
+            }
+            
+            // Tasks that need the bridge wait for it; native ones go ahead
+            let bridge_state = self.python_bridge.status().state;
+            let disk_critical = self.disk_level() == DiskLevel::Critical;
+            let mut next = None;
+            for (index, task) in session.task_queue.iter().enumerate().skip(first) {
+                if task.status != TaskStatus::Queued {
+                    continue;
+                }
+                if disk_critical && self.is_artifact_heavy(&task.tool_name) {
+                    self.announce_deferral(&task.id, "Disk space under the base directory is critically low".to_string());
+                    continue;
+                }
+                if bridge_state == BridgeState::Healthy || !self.needs_bridge(&task.tool_name) {
+                    next = Some(index);
+                    break;
+                }
+                let state = if bridge_state == BridgeState::Degraded { "degraded" } else { "unreachable" };
+                self.announce_deferral(&task.id, format!("Waiting for the Python bridge, which is {}", state));
+            }
+            let Some(index) = next else {
+                continue;
+            };
+            
+            let task = &mut session.task_queue[index];
+            task.status = TaskStatus::Running;
+            task.started_at = Some(now);
+            let task = task.clone();
+            session.touch();
+            self.deferred.remove(&task.id);
+            
+            self.ws_server.broadcast(WSEvent::TaskStarted {
+                task_id: task.id.clone(),
+                started_at: now,
+            });
+            return Some((entry.key().clone(), task));
+        }
+        None
+    }
+    
+    /// Announce a task deferral once rather than on every poll
+    fn announce_deferral(&self, task_id: &str, reason: String) {
+        if self.deferred.insert(task_id.to_string(), reason.clone()).as_ref() != Some(&reason) {
+            tracing::info!("Task {} deferred: {}", task_id, reason);
+            self.ws_server.broadcast(WSEvent::TaskDeferred { task_id: task_id.to_string(), reason });
+        }
+    }
+    
+    /// Whether a tool runs through the Python bridge rather than natively
+    fn needs_bridge(&self, tool_name: &str) -> bool {
+        let backend = Backend::from_env();
+        self.tools.get(tool_name).is_none_or(|adapter| !backend.runs_natively(adapter.describe()))
+    }
+    
+    /// Whether a tool may write large artifacts; undescribed tools are assumed to
+    fn is_artifact_heavy(&self, tool_name: &str) -> bool {
+        self.tools.describe(tool_name).is_none_or(|d| d.artifact_heavy)
+    }
+    
+    /// Free space under the base directory, as last checked
+    pub fn disk_level(&self) -> DiskLevel {
+        *self.disk_level.lock()
+    }
+    
+    /// Record a disk check, returning the previous level
+    pub fn set_disk_level(&self, level: DiskLevel) -> DiskLevel {
+        let previous = std::mem::replace(&mut *self.disk_level.lock(), level);
+        if previous == DiskLevel::Critical && level != DiskLevel::Critical {
+            self.task_notify.notify_one();
+        }
+        previous
+    }
+    
+    /// Track an in-flight task so the kill switch can abort it
+    pub fn track_running(&self, session_id: &str, task_id: &str, handle: AbortHandle) {
+        self.running.insert(task_id.to_string(), (session_id.to_string(), handle));
+    }
+    
+    /// Whether nothing new may start: the kill switch is engaged or the
+    /// core is shutting down
+    pub fn is_halted(&self) -> bool {
+        self.halted.load(Ordering::SeqCst) || self.draining.load(Ordering::SeqCst)
+    }
+    
+    /// Stop everything: pause the executor and abort every running task
+    pub fn engage_kill_switch(&self, engaged_by: Actor) -> Vec<String> {
+        self.halted.store(true, Ordering::SeqCst);
+        
+        self.stop_all_agents(&engaged_by);
+        
+        let cancelled = self.cancel_running("Kill switch engaged");
+        tracing::warn!("🛑 Kill switch engaged by {}: {} tasks cancelled", engaged_by, cancelled.len());
+        self.ws_server.broadcast(WSEvent::KillSwitchEngaged {
+            engaged_by,
+            cancelled: cancelled.clone(),
+            timestamp: chrono::Utc::now(),
+        });
+        cancelled
+    }
+    
+    fn stop_all_agents(&self, stopped_by: &Actor) {
+        let runs: Vec<String> = self.agent_runs.iter().map(|e| e.key().clone()).collect();
+        for session_id in runs {
+            if let Err(e) = self.stop_agents(&session_id, stopped_by.clone()) {
+                tracing::debug!("Agent run on {} already ended: {}", session_id, e);
+            }
+        }
+    }
+    
+    /// Abort every running task, marking it cancelled
+    fn cancel_running(&self, reason: &str) -> Vec<String> {
+        let task_ids: Vec<String> = self.running.iter().map(|e| e.key().clone()).collect();
+        let mut cancelled = Vec::new();
+        for task_id in task_ids {
+            let Some((_, (session_id, handle))) = self.running.remove(&task_id) else {
+                continue;
+            };
+            handle.abort();
+            
+            if let Some(session) = self.sessions.get(&session_id) {
+                let mut session = session.write();
+                if let Some(task) = session.task_queue.iter_mut().find(|t| t.id == task_id && t.status == TaskStatus::Running) {
+                    task.status = TaskStatus::Cancelled;
+                    task.completed_at = Some(chrono::Utc::now());
+                    cancelled.push(task_id.clone());
+                }
+                session.touch();
+                self.publish_progress(&session);
+            }
+        }
+        
+        for task_id in &cancelled {
+            self.ws_server.broadcast(WSEvent::TaskCancelled {
+                task_id: task_id.clone(),
+                reason: reason.to_string(),
+            });
+        }
+        cancelled
+    }
+    
+    /// Stop for good: refuse commands, stop agent runs, give running tasks
+    /// up to `drain` to finish, cancel what is left and save every loaded
+    /// session
+    pub async fn shutdown(&self, drain: Duration) {
+        self.draining.store(true, Ordering::SeqCst);
+        
+        self.stop_all_agents(&Actor::System);
+        
+        let deadline = tokio::time::Instant::now() + drain;
+        if !self.running.is_empty() {
+            tracing::info!("Waiting up to {}s for {} running tasks", drain.as_secs(), self.running.len());
+        }
+        while !self.running.is_empty() && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(DRAIN_POLL).await;
+        }
+        let cancelled = self.cancel_running("Core shutting down");
+        if !cancelled.is_empty() {
+            tracing::warn!("Cancelled {} tasks still running at shutdown", cancelled.len());
+        }
+        
+        let session_ids: Vec<String> = self.sessions.iter().map(|e| e.key().clone()).collect();
+        for session_id in session_ids {
+            if let Err(e) = self.save_session(&session_id) {
+                tracing::error!("Failed to save session {} on shutdown: {}", session_id, e);
+            }
+        }
+    }
+    
+    /// Let the executor resume starting queued tasks
+    pub fn release_kill_switch(&self, released_by: Actor) {
+        if !self.halted.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        
+        tracing::warn!("Kill switch released by {}", released_by);
+        self.ws_server.broadcast(WSEvent::KillSwitchReleased {
+            released_by,
+            timestamp: chrono::Utc::now(),
+        });
+        self.task_notify.notify_one();
+    }
+    
+    /// Record the outcome of a dispatched task
+    pub fn finish_task(&self, session_id: &str, task_id: &str, outcome: Result<TaskResult>) {
+        self.running.remove(task_id);
+        let mut tool_name = None;
+        if let Some(session) = self.sessions.get(session_id) {
             let mut session = session.write();
+            if let Some(task) = session.task_queue.iter_mut().find(|t| t.id == task_id) {
+                task.status = if outcome.is_ok() { TaskStatus::Completed } else { TaskStatus::Failed };
+                task.completed_at = Some(chrono::Utc::now());
+                tool_name = Some(task.tool_name.clone());
+            }
+            session.touch();
+            self.publish_progress(&session);
+        }
+        
+        // Parse what we recognize in the raw output, through the tool's
+        // adapter when it has one; hosts a tool reports under
+        // `structured_data.assets` take precedence
+        if let (Ok(result), Some(tool_name)) = (&outcome, tool_name) {
+            let adapted = self.tools.get(&tool_name).and_then(|adapter| {
+                adapter.parse_output(&result.output)
+                    .inspect_err(|e| tracing::warn!("{} adapter failed to parse output: {:#}", tool_name, e))
+                    .ok()
+                    .filter(|parsed| !parsed.is_empty())
+            });
+            let mut parsed = adapted.unwrap_or_else(|| self.parsers.parse(&tool_name, &result.output));
+            if let Some(assets) = result.structured_data.as_ref()
+                .and_then(|data| data.get("assets"))
+                .and_then(|assets| serde_json::from_value::<Vec<AssetObservation>>(assets.clone()).ok())
+            {
+                parsed.assets = assets;
+            }
+            if let Some(subdomains) = result.structured_data.as_ref()
+                .and_then(|data| data.get("subdomains"))
+                .and_then(|names| serde_json::from_value::<Vec<String>>(names.clone()).ok())
+            {
+                parsed.subdomains.extend(subdomains);
+            }
+            
+            if !parsed.assets.is_empty() {
+                self.record_assets(session_id, parsed.assets, &tool_name);
+            }
+            if !parsed.subdomains.is_empty() {
+                self.record_subdomains(session_id, parsed.subdomains, &tool_name);
+            }
+            if let Some(data) = &result.structured_data {
+                let metadata = std::collections::HashMap::from([
+                    ("task_id".to_string(), task_id.to_string()),
+                    ("tool".to_string(), tool_name.clone()),
+                ]);
+                self.record_captures(session_id, artifacts::capture::captures(data), metadata);
+            }
+            for finding in parsed.findings {
+                if let Err(e) = self.add_finding_to(session_id, finding, Actor::Agent(AgentType::Operator)) {
+                    tracing::warn!("Failed to record finding from {}: {:#}", tool_name, e);
+                }
+            }
+        }
+        
+        match outcome {
+            Ok(result) => {
+                tracing::info!("Task {} completed in {}ms", task_id, result.duration_ms);
+                self.ws_server.broadcast(WSEvent::TaskCompleted {
+                    task_id: task_id.to_string(),
+                    result,
+                });
+            }
+            Err(e) => {
+                tracing::warn!("Task {} failed: {:#}", task_id, e);
+                self.ws_server.broadcast(WSEvent::TaskFailed {
+                    task_id: task_id.to_string(),
+                    error: format!("{:#}", e),
+                });
+            }
+        }
+    }
+    
+    /// Merge host observations into a session's asset inventory
+    pub fn record_assets(&self, session_id: &str, observations: Vec<AssetObservation>, source: &str) {
+        let Some(session) = self.sessions.get(session_id) else {
+            return;
+        };
+        let mut session = session.write();
+        
+        for observation in observations {
+            let known: Vec<Service> = session.assets.iter()
+                .find(|a| a.matches(&observation))
+                .map(|a| a.services.clone())
+                .unwrap_or_default();
+            let Some(upsert) = session.upsert_asset(observation, source) else {
+                continue;
+            };
+            let (AssetUpsert::Added(id) | AssetUpsert::Updated(id) | AssetUpsert::Unchanged(id)) = &upsert;
+            let Some(asset) = session.assets.iter().find(|a| &a.id == id).cloned() else {
+                continue;
+            };
             
-            if let Some(agent_status) = session.agent_states.get_mut(&agent) {
-                agent_status.state = state;
-                agent_status.current_task = current_task;
-                agent_status.last_update = chrono::Utc::now();
-                
-                // Broadcast event
-                self.ws_server.broadcast(WSEvent::AgentStatusChanged {
-                    agent,
-                    status: agent_status.clone(),
+            let new_services: Vec<Service> = asset.services.iter()
+                .filter(|s| !known.iter().any(|k| k.same_port(s)))
+                .cloned()
+                .collect();
+            let probed: Vec<(u16, HttpInfo)> = asset.services.iter()
+                .filter(|s| !known.iter().any(|k| k.same_port(s) && k.http == s.http))
+                .filter_map(|s| Some((s.port, s.http.clone()?)))
+                .collect();
+            let asset_id = asset.id.clone();
+            match upsert {
+                AssetUpsert::Added(_) => self.ws_server.broadcast(WSEvent::AssetDiscovered { session_id: session_id.to_string(), asset }),
+                AssetUpsert::Updated(_) => self.ws_server.broadcast(WSEvent::AssetUpdated { session_id: session_id.to_string(), asset }),
+                AssetUpsert::Unchanged(_) => {}
+            }
+            for service in new_services {
+                tracing::info!("New service on {}: {}/{:?}", asset_id, service.port, service.protocol);
+                self.ws_server.broadcast(WSEvent::ServiceDiscovered {
+                    session_id: session_id.to_string(),
+                    asset_id: asset_id.clone(),
+                    service,
+                });
+            }
+            for (port, http) in probed {
+                tracing::info!("Probed {} port {}: {} {}", asset_id, port, http.status, http.technologies.join(", "));
+                self.ws_server.broadcast(WSEvent::WebServiceProbed {
+                    session_id: session_id.to_string(),
+                    asset_id: asset_id.clone(),
+                    port,
+                    http,
                 });
             }
         }
     }
     
-    /// Handle chat message
-    pub async fn chat(&self, message: String, model: Option<String>) -> Result<()> {
-        // Forward to Python bridge
-        let cmd = serde_json::json!({
-            "type": "ai_generate",
-            "prompt": message,
-            "model": model
+    /// Announce hostnames a recon tool enumerated, minus duplicates and names
+    /// already in the inventory, so the DNS service resolves them
+    pub fn record_subdomains(&self, session_id: &str, hostnames: Vec<String>, source: &str) {
+        let Some(session) = self.sessions.get(session_id) else {
+            return;
+        };
+        let session = session.read();
+        let mut fresh: Vec<String> = hostnames.iter()
+            .map(|name| normalize_hostname(name))
+            .filter(|name| !name.is_empty() && !session.assets.iter().any(|a| a.is_host(name)))
+            .collect();
+        fresh.sort();
+        fresh.dedup();
+        if fresh.is_empty() {
+            return;
+        }
+        
+        tracing::info!("{} new subdomain(s) from {} in {}", fresh.len(), source, session_id);
+        self.ws_server.broadcast(WSEvent::SubdomainsDiscovered {
+            session_id: session_id.to_string(),
+            source: source.to_string(),
+            hostnames: fresh,
         });
+    }
+    
+    /// Record a finding in the active session, merging re-discoveries
+    pub fn add_finding(&self, new: NewFinding, added_by: Actor) -> Result<FindingUpsert> {
+        let session_id = self.active_session_id()
+            .ok_or_else(|| anyhow::anyhow!("No active session"))?;
+        self.add_finding_to(&session_id, new, added_by)
+    }
+    
+    /// Record a finding in a specific session, merging re-discoveries
+    pub fn add_finding_to(&self, session_id: &str, new: NewFinding, added_by: Actor) -> Result<FindingUpsert> {
+        let session = self.sessions.get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?;
+        let mut session = session.write();
         
-        let data = self.python_bridge.execute(cmd).await?;
+        let upsert = session.add_finding(new, added_by);
+        let (FindingUpsert::Added(id) | FindingUpsert::Merged(id)) = &upsert;
+        if let Some(finding) = session.findings.iter().find(|f| &f.id == id).cloned() {
+            let event = match upsert {
+                FindingUpsert::Added(_) => WSEvent::FindingDiscovered { finding },
+                FindingUpsert::Merged(_) => WSEvent::FindingUpdated { finding },
+            };
+            self.ws_server.broadcast(event);
+        }
+        
+        Ok(upsert)
+    }
+    
+    /// Share findings with MISP: the listed ones, or every one at or above
+    /// the configured severity. A session pushed before adds to its event.
+    pub async fn push_to_misp(&self, session_id: &str, finding_ids: Option<Vec<String>>) -> Result<()> {
+        let config = self.misp.config()
+            .ok_or_else(|| anyhow::anyhow!("MISP is not configured"))?;
+        let snapshot = self.sessions.get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?
+            .read()
+            .clone();
+        let findings = match &finding_ids {
+            Some(ids) => snapshot.findings.iter().filter(|f| ids.contains(&f.id)).collect(),
+            None => misp::default_selection(&snapshot, &config),
+        };
+        if findings.is_empty() {
+            anyhow::bail!("No findings in {} to push to MISP", session_id);
+        }
+        
+        let existing = snapshot.metadata.get(misp::EVENT_ID_KEY).cloned();
+        let (event_id, attributes) = self.misp.push(&snapshot, &findings, existing.as_deref()).await?;
+        if let Some(session) = self.sessions.get(session_id) {
+            let mut session = session.write();
+            session.metadata.insert(misp::EVENT_ID_KEY.to_string(), event_id.clone());
+            session.touch();
+        }
+        
+        tracing::info!("Pushed {} findings from {} to MISP event {}", findings.len(), session_id, event_id);
+        self.ws_server.broadcast(WSEvent::MispEventPushed {
+            session_id: session_id.to_string(),
+            event_id,
+            findings: findings.len(),
+            attributes,
+        });
+        Ok(())
+    }
+    
+    /// Search MISP for the session's CVEs, targets and assets, attaching
+    /// matches to the findings they concern
+    pub async fn pull_from_misp(&self, session_id: &str) -> Result<()> {
+        let snapshot = self.sessions.get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?
+            .read()
+            .clone();
+        let mut values: Vec<String> = snapshot.findings.iter().flat_map(misp::finding_values).collect();
+        for asset in &snapshot.assets {
+            values.extend(asset.addresses.iter().map(|ip| ip.to_string()));
+            values.extend(asset.hostnames.iter().cloned());
+        }
+        values.sort();
+        values.dedup();
+        
+        let own_event = snapshot.metadata.get(misp::EVENT_ID_KEY).map(String::as_str);
+        let matches = self.misp.search(&values, own_event).await?;
+        
+        let mut enriched = Vec::new();
+        if let Some(session) = self.sessions.get(session_id) {
+            let mut session = session.write();
+            for finding in session.findings.iter_mut() {
+                let values = misp::finding_values(finding);
+                let hits: Vec<&MispMatch> = matches.iter()
+                    .filter(|m| values.iter().any(|v| v.eq_ignore_ascii_case(&m.value)))
+                    .collect();
+                if hits.is_empty() {
+                    continue;
+                }
+                if !finding.details.is_object() {
+                    finding.details = serde_json::json!({ "raw": finding.details.take() });
+                }
+                finding.details["misp"] = serde_json::to_value(hits).unwrap_or_default();
+                enriched.push(finding.clone());
+            }
+            if !enriched.is_empty() {
+                session.touch();
+            }
+        }
+        
+        tracing::info!("MISP returned {} matches for {}, {} findings enriched", matches.len(), session_id, enriched.len());
+        let finding_ids = enriched.iter().map(|f| f.id.clone()).collect();
+        for finding in enriched {
+            self.ws_server.broadcast(WSEvent::FindingUpdated { finding });
+        }
+        self.ws_server.broadcast(WSEvent::MispEnriched {
+            session_id: session_id.to_string(),
+            matches,
+            findings: finding_ids,
+        });
+        Ok(())
+    }
+    
+    /// Upload findings to DefectDojo: the listed ones, or every one at or
+    /// above the configured severity. Findings uploaded before are updated.
+    pub async fn push_to_defectdojo(&self, session_id: &str, finding_ids: Option<Vec<String>>) -> Result<()> {
+        let config = self.defectdojo.config()
+            .ok_or_else(|| anyhow::anyhow!("DefectDojo is not configured"))?;
+        let snapshot = self.sessions.get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", session_id))?
+            .read()
+            .clone();
+        let findings = match &finding_ids {
+            Some(ids) => snapshot.findings.iter().filter(|f| ids.contains(&f.id)).collect(),
+            None => defectdojo::default_selection(&snapshot, &config),
+        };
+        if findings.is_empty() {
+            anyhow::bail!("No findings in {} to upload to DefectDojo", session_id);
+        }
+        
+        let upload = self.defectdojo.upload(&snapshot, &findings).await?;
+        if let Some(session) = self.sessions.get(session_id) {
+            let mut session = session.write();
+            session.metadata.insert(defectdojo::ENGAGEMENT_ID_KEY.to_string(), upload.engagement_id.to_string());
+            session.metadata.insert(defectdojo::TEST_ID_KEY.to_string(), upload.test_id.to_string());
+            session.touch();
+        }
+        
+        tracing::info!(
+            "Uploaded {} findings from {} to DefectDojo engagement {} ({} new, {} updated)",
+            findings.len(), session_id, upload.engagement_id, upload.created, upload.updated,
+        );
+        self.ws_server.broadcast(WSEvent::DefectDojoPushed {
+            session_id: session_id.to_string(),
+            engagement_id: upload.engagement_id,
+            test_id: upload.test_id,
+            created: upload.created,
+            updated: upload.updated,
+        });
+        Ok(())
+    }
+    
+    /// Open a Jira issue for a finding in whichever session holds it and
+    /// record its key on the finding
+    pub async fn create_ticket(&self, finding_id: &str) -> Result<()> {
+        if !self.ticketing.insert(finding_id.to_string()) {
+            anyhow::bail!("A ticket for {} is already being created", finding_id);
+        }
+        let result = self.open_ticket(finding_id).await;
+        self.ticketing.remove(finding_id);
+        result
+    }
+    
+    async fn open_ticket(&self, finding_id: &str) -> Result<()> {
+        let (session_id, snapshot) = self.sessions.iter()
+            .find(|entry| entry.value().read().findings.iter().any(|f| f.id == finding_id))
+            .map(|entry| (entry.key().clone(), entry.value().read().clone()))
+            .ok_or_else(|| anyhow::anyhow!("Finding not found in any loaded session: {}", finding_id))?;
+        let finding = snapshot.findings.iter()
+            .find(|f| f.id == finding_id)
+            .ok_or_else(|| anyhow::anyhow!("Finding not found: {}", finding_id))?;
+        if let Some(ticket) = &finding.ticket {
+            anyhow::bail!("Finding {} is already tracked in {}", finding_id, ticket);
+        }
         
-        if let Some(text) = data.get("response").and_then(|v| v.as_str()) {
-            let model = data.get("model").and_then(|v| v.as_str()).unwrap_or("unknown").to_string();
+        let (key, url) = self.jira.create_issue(&snapshot, finding).await?;
+        if let Some(session) = self.sessions.get(&session_id) {
+            let mut session = session.write();
+            if let Some(finding) = session.findings.iter_mut().find(|f| f.id == finding_id) {
+                finding.ticket = Some(key.clone());
+            }
+            session.touch();
+        }
+        
+        tracing::info!("Created Jira issue {} for finding {}", key, finding_id);
+        self.ws_server.broadcast(WSEvent::TicketCreated {
+            session_id,
+            finding_id: finding_id.to_string(),
+            key,
+            url,
+        });
+        Ok(())
+    }
+    
+    /// Attach NVD records to a finding in whichever session holds it
+    pub fn enrich_finding(&self, finding_id: &str, records: std::collections::BTreeMap<String, serde_json::Value>) {
+        for entry in self.sessions.iter() {
+            let mut session = entry.value().write();
+            let Some(finding) = session.findings.iter_mut().find(|f| f.id == finding_id) else {
+                continue;
+            };
             
-            // Broadcast response
-            self.ws_server.broadcast(WSEvent::ChatResponse {
-                response: text.to_string(),
-                model,
+            if !finding.details.is_object() {
+                finding.details = serde_json::json!({ "raw": finding.details.take() });
+            }
+            let cves: Vec<String> = records.keys().cloned().collect();
+            finding.details["nvd"] = serde_json::to_value(records).unwrap_or_default();
+            let finding = finding.clone();
+            session.touch();
+            
+            tracing::info!("Finding {} enriched with {}", finding_id, cves.join(", "));
+            self.ws_server.broadcast(WSEvent::FindingEnriched { finding, cves });
+            return;
+        }
+    }
+    
+    /// Attach an artifact of the finding's session to the finding
+    pub fn attach_evidence(&self, finding_id: &str, artifact_id: &str, caption: Option<String>, attached_by: Actor) -> Result<()> {
+        let (session_id, session) = self.sessions.iter()
+            .find(|entry| entry.value().read().findings.iter().any(|f| f.id == finding_id))
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .ok_or_else(|| anyhow::anyhow!("Finding not found in any loaded session: {}", finding_id))?;
+        if !session.read().artifacts.iter().any(|a| a.id == artifact_id) {
+            anyhow::bail!("Artifact not found in session {}: {}", session_id, artifact_id);
+        }
+        
+        let evidence = session.write().attach_evidence(finding_id, artifact_id, caption, attached_by)
+            .ok_or_else(|| anyhow::anyhow!("Finding not found: {}", finding_id))?;
+        
+        tracing::info!("Evidence {} attached to finding {}", artifact_id, finding_id);
+        self.ws_server.broadcast(WSEvent::EvidenceAttached {
+            session_id,
+            finding_id: finding_id.to_string(),
+            evidence,
+        });
+        Ok(())
+    }
+    
+    /// Update agent status
+    pub fn update_agent_status(&self, agent: AgentType, state: AgentState, current_task: Option<String>) {
+        if let Some(session_id) = self.active_session_id() {
+            self.update_agent_status_in(&session_id, agent, state, current_task);
+        }
+    }
+    
+    /// Update agent status in a specific session
+    pub fn update_agent_status_in(&self, session_id: &str, agent: AgentType, state: AgentState, current_task: Option<String>) {
+        if let Some(session) = self.session(session_id) {
+            if let Some(status) = session.write().set_agent_state(agent, state, current_task) {
+                self.ws_server.broadcast(WSEvent::AgentStatusChanged { agent, status });
+            }
+        }
+    }
+    
+    /// Note that an agent is still making progress, without changing its state
+    pub fn agent_heartbeat(&self, session_id: &str, agent: AgentType) {
+        if let Some(session) = self.session(session_id) {
+            if let Some(status) = session.write().agent_states.get_mut(&agent) {
+                status.last_update = chrono::Utc::now();
+            }
+        }
+    }
+    
+    /// Flip busy agents that have not reported for longer than `threshold`
+    /// to `Error` and raise an alert for each, returning how many stalled
+    pub fn sweep_stalled_agents(&self, threshold: std::time::Duration) -> usize {
+        let now = chrono::Utc::now();
+        let threshold = chrono::Duration::from_std(threshold).unwrap_or(chrono::Duration::MAX);
+        let mut stalled = Vec::new();
+        
+        for entry in self.sessions.iter() {
+            let mut session = entry.value().write();
+            let stuck: Vec<AgentStatus> = session.agent_states.values()
+                .filter(|s| matches!(s.state, AgentState::Planning | AgentState::Executing | AgentState::Analyzing | AgentState::Writing))
+                .filter(|s| now - s.last_update >= threshold)
+                .cloned()
+                .collect();
+            
+            for status in stuck {
+                tracing::warn!("⏱️ Agent {:?} stalled in {}: no progress since {}", status.agent, entry.key(), status.last_update);
+                let alert = WSEvent::AgentStalled {
+                    session_id: entry.key().clone(),
+                    agent: status.agent,
+                    state: status.state,
+                    current_task: status.current_task.clone(),
+                    last_update: status.last_update,
+                };
+                if let Some(status) = session.set_agent_state(status.agent, AgentState::Error, status.current_task) {
+                    stalled.push((status, alert));
+                }
+            }
+        }
+        
+        for (status, alert) in &stalled {
+            self.ws_server.broadcast(WSEvent::AgentStatusChanged {
+                agent: status.agent,
+                status: status.clone(),
+            });
+            self.ws_server.broadcast(alert.clone());
+        }
+        stalled.len()
+    }
+    
+    /// Register a session's agent run; a session runs one at a time
+    pub fn track_agent_run(&self, session_id: &str, handle: AbortHandle) -> Result<()> {
+        match self.agent_runs.entry(session_id.to_string()) {
+            dashmap::mapref::entry::Entry::Occupied(_) => {
+                handle.abort();
+                anyhow::bail!("Agents are already running on {}", session_id)
+            }
+            dashmap::mapref::entry::Entry::Vacant(entry) => {
+                entry.insert(handle);
+                Ok(())
+            }
+        }
+    }
+    
+    /// Forget a session's agent run once it has ended
+    pub fn agent_run_finished(&self, session_id: &str) {
+        self.agent_runs.remove(session_id);
+    }
+    
+    /// Record an agent run entering a phase and publish the session's
+    /// progress
+    pub fn set_run_phase(&self, session_id: &str, phase: AgentPhase, cycle: u32, max_cycles: u32) {
+        self.run_progress.insert(session_id.to_string(), RunProgress {
+            phase,
+            cycle,
+            max_cycles,
+            since: chrono::Utc::now(),
+        });
+        self.update_progress(session_id);
+    }
+    
+    /// Broadcast `SessionProgress` if a session's progress has changed
+    pub fn update_progress(&self, session_id: &str) {
+        if let Some(session) = self.sessions.get(session_id).map(|s| s.clone()) {
+            self.publish_progress(&session.read());
+        }
+    }
+    
+    /// As `update_progress`, for a session already locked by the caller
+    fn publish_progress(&self, session: &SessionState) {
+        let run = self.run_progress.get(&session.id).map(|r| *r);
+        let percent = progress::percent(session, run.as_ref());
+        let current = (percent, run.map(|r| r.phase));
+        if self.progress.insert(session.id.clone(), current) == Some(current) {
+            return;
+        }
+        self.ws_server.broadcast(WSEvent::SessionProgress {
+            session_id: session.id.clone(),
+            percent,
+            phase: current.1,
+        });
+    }
+    
+    /// Abort a session's agent run; tasks it already queued keep running
+    pub fn stop_agents(&self, session_id: &str, stopped_by: Actor) -> Result<()> {
+        let (_, handle) = self.agent_runs.remove(session_id)
+            .ok_or_else(|| anyhow::anyhow!("No agents running on {}", session_id))?;
+        handle.abort();
+        
+        tracing::info!("Agents on {} stopped by {}", session_id, stopped_by);
+        for agent in [AgentType::Planner, AgentType::Operator, AgentType::Analyst, AgentType::Scribe] {
+            self.update_agent_status_in(session_id, agent, AgentState::Idle, None);
+        }
+        self.ws_server.broadcast(WSEvent::AgentRunPhase {
+            session_id: session_id.to_string(),
+            run_id: None,
+            phase: AgentPhase::Stopped,
+            cycle: 0,
+            detail: Some(format!("Stopped by {}", stopped_by)),
+        });
+        if let Some(mut run) = self.run_progress.get_mut(session_id) {
+            run.phase = AgentPhase::Stopped;
+        }
+        self.update_progress(session_id);
+        Ok(())
+    }
+    
+    /// Handle chat message, in the context of a session's conversation if
+    /// given and speaking as `agent` if given, streaming the reply to
+    /// clients as it is written
+    #[tracing::instrument(skip(self, message), fields(trace_id))]
+    pub async fn chat(
+        &self,
+        message: String,
+        model: Option<String>,
+        session_id: Option<String>,
+        request_id: Option<String>,
+        agent: Option<AgentType>,
+    ) -> Result<()> {
+        let trace = TraceContext::new_root();
+        tracing::Span::current().record("trace_id", trace.trace_id.as_str());
+        
+        let session = session_id.as_deref()
+            .map(|id| self.sessions.get(id).map(|s| s.clone()).ok_or_else(|| anyhow::anyhow!("Session not loaded: {}", id)))
+            .transpose()?;
+        
+        let mut messages = Vec::new();
+        if let Some(agent) = agent {
+            messages.push(self.agent_prompt(agent, session_id.as_deref())?);
+        }
+        if let Some(session) = &session {
+            let mut session = session.write();
+            let history = &session.chat_history;
+            messages.extend(history[history.len().saturating_sub(CHAT_CONTEXT_MESSAGES)..].iter().map(|m| Message {
+                role: m.role,
+                content: m.content.clone(),
+            }));
+            session.add_chat_message(Role::User, message.clone(), None);
+        }
+        messages.push(Message::user(message));
+        
+        let request_id = request_id
+            .unwrap_or_else(|| format!("chat_{}", &uuid::Uuid::new_v4().to_string().replace("-", "")[..8]));
+        let mut on_delta = |delta: &str| {
+            self.ws_server.broadcast(WSEvent::ChatResponseChunk {
+                request_id: request_id.clone(),
+                delta: delta.to_string(),
             });
+        };
+        let generation = self.models
+            .generate_streaming(&messages, model.as_deref(), Some(&trace), &mut on_delta)
+            .await?;
+        tracing::debug!("Chat answered by {} ({})", generation.backend, generation.model);
+        
+        if let Some(session) = &session {
+            session.write().add_chat_message(Role::Assistant, generation.text.clone(), Some(generation.model.clone()));
         }
         
+        self.ws_server.broadcast(WSEvent::ChatResponse {
+            request_id,
+            response: generation.text,
+            model: generation.model,
+            session_id,
+        });
+        
         Ok(())
     }
-
+    
     /// Get Python bridge
     pub fn python_bridge(&self) -> Arc<PythonBridge> {
         self.python_bridge.clone()
     }
+    
+    /// Get the Tor service
+    pub fn tor(&self) -> Arc<TorService> {
+        self.tor.clone()
+    }
+    
+    /// Get the egress proxy configuration
+    pub fn proxy(&self) -> &ProxyConfig {
+        &self.proxy
+    }
 }
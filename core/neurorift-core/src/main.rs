@@ -1,55 +1,304 @@
 use anyhow::Result;
+use clap::Parser;
+use neurorift_core::cli::Cli;
 use neurorift_core::NeuroRiftCore;
-use std::path::PathBuf;
+use neurorift_core::config::CoreConfig;
+#[cfg(unix)]
+use neurorift_core::daemon;
+use neurorift_core::security::approval::Decision;
+use neurorift_core::websocket::LogFilter;
+use std::io::IsTerminal;
+use std::process::ExitCode;
 use std::sync::Arc;
-use tracing_subscriber;
+
+/// How often a disabled auto-save checks whether a reload enabled it
+const AUTOSAVE_RECHECK: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Grace after the shutdown saves for the journal writer to catch up
+const JOURNAL_FLUSH: std::time::Duration = std::time::Duration::from_millis(200);
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
+    let Some(mode) = cli.serve_mode() else {
+        init_cli_logging();
+        return neurorift_core::cli::run(cli.command.expect("serve_mode covers the no-command case")).await;
+    };
+    let stdio = mode.stdio;
+    
+    // Start a detached copy of ourselves and leave it running
+    #[cfg(not(unix))]
+    if mode.daemon {
+        anyhow::bail!("serve --daemon is only supported on Unix");
+    }
+    #[cfg(unix)]
+    if mode.daemon {
+        init_cli_logging();
+        let base_dir = CoreConfig::load()?.base_dir;
+        let pid = daemon::spawn_detached(&base_dir)?;
+        println!("Started (pid {}), logging to {}", pid, base_dir.join(daemon::LOG_FILE).display());
+        return Ok(ExitCode::SUCCESS);
+    }
+    
+    // In stdio mode stdout carries the protocol, so logs go to stderr
     // Initialize logging
-    tracing_subscriber::fmt()
+    let subscriber = tracing_subscriber::fmt()
         .with_target(false)
         .with_thread_ids(true)
-        .with_level(true)
-        .init();
+        .with_level(true);
+    if stdio {
+        subscriber.with_writer(std::io::stderr).init();
+    } else {
+        // A detached core logs to a file, which should not get colour codes
+        subscriber.with_ansi(std::io::stdout().is_terminal()).init();
+    }
     
     tracing::info!("🧠 NeuroRift Core starting...");
     
     // Configuration
-    let base_dir = PathBuf::from(std::env::var("NEURORIFT_HOME")
-        .unwrap_or_else(|_| {
-            let home = std::env::var("HOME").unwrap();
-            format!("{}/.neurorift", home)
-        }));
+    let config = CoreConfig::load()?;
+    let base_dir = config.base_dir.clone();
+    let ws_addr = config.ws_addr;
+    let python_bridge_url = config.python_bridge_url.clone();
+    let metrics_addr = config.metrics_addr;
     
-    let ws_addr = "127.0.0.1:8765".parse()?;
-    let python_bridge_url = "http://127.0.0.1:8766".to_string();
+    // One serving core per base directory; stdio cores are per-client and exempt
+    #[cfg(unix)]
+    let _pid_file = if stdio { None } else { Some(daemon::PidFile::create(&base_dir)?) };
     
     // Create core
-    let core = Arc::new(NeuroRiftCore::new(
-        base_dir,
-        ws_addr,
-        python_bridge_url.clone(),
-    )?);
+    let core = Arc::new(NeuroRiftCore::new(config)?);
     
     tracing::info!("✅ NeuroRift Core initialized");
-    tracing::info!("📡 WebSocket server: ws://{}", ws_addr);
+    
+    // Surface any tampering with the audit trail at startup
+    match core.audit().verify() {
+        Ok(count) => tracing::info!("🔏 Audit log verified ({} records)", count),
+        Err(e) => tracing::error!("🚨 Audit log verification failed: {}", e),
+    }
+    if stdio {
+        tracing::info!("📡 Serving JSON-RPC over stdio");
+    } else {
+        tracing::info!("📡 WebSocket server: ws://{}", ws_addr);
+    }
+    if let Some(path) = core.ws_server().unix_socket() {
+        tracing::info!("📡 WebSocket socket: unix:{}", path.display());
+    }
     tracing::info!("🐍 Python bridge: {}", python_bridge_url);
     
-    // Start WebSocket server
+    // Start event journal writer (subscribed before anything is broadcast)
+    let journal = core.journal();
+    let core_journal = core.clone();
+    let mut journal_rx = core.ws_server().get_sender().subscribe();
+    tokio::spawn(async move {
+        use tokio::sync::broadcast::error::RecvError;
+        loop {
+            match journal_rx.recv().await {
+                Ok(event) if event.is_transient() => {}
+                Ok(event) => {
+                    let active = core_journal.active_session_id();
+                    if let Err(e) = journal.append(&event, active.as_deref()) {
+                        tracing::error!("Failed to journal event: {}", e);
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::error!("Event journal lagged, {} events not recorded", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+    
+    // Start WebSocket server (or the stdio transport in its place)
     let ws_server = core.ws_server();
-    let ws_task = tokio::spawn(async move {
-        if let Err(e) = ws_server.run().await {
-            tracing::error!("WebSocket server error: {}", e);
+    let ws_task = if stdio {
+        tokio::spawn(async move {
+            if let Err(e) = neurorift_core::websocket::stdio::serve_stdio(ws_server).await {
+                tracing::error!("Stdio transport error: {}", e);
+            }
+        })
+    } else {
+        tokio::spawn(async move {
+            if let Err(e) = ws_server.run().await {
+                tracing::error!("WebSocket server error: {}", e);
+            }
+        })
+    };
+    
+    // Start Unix socket listener
+    #[cfg(unix)]
+    let unix_task = {
+        let ws_server = core.ws_server();
+        tokio::spawn(async move {
+            if let Err(e) = ws_server.run_unix().await {
+                tracing::error!("Unix socket server error: {}", e);
+            }
+        })
+    };
+    
+    // Start metrics endpoint (stdio mode opens no network ports)
+    if !stdio {
+        let core_metrics = core.clone();
+        tokio::spawn(async move {
+            let refresh = Arc::new(move || core_metrics.refresh_metrics());
+            if let Err(e) = neurorift_core::metrics::serve(metrics_addr, refresh).await {
+                tracing::error!("Metrics endpoint error: {}", e);
+            }
+        });
+    }
+    
+    // Start webhook dispatcher, Slack/Discord notifier, syslog sink and SIEM
+    // forwarder; all run with no targets configured so a reload can add some
+    let webhooks = core.webhooks();
+    tokio::spawn(webhooks.run(core.ws_server().get_sender().subscribe()));
+    
+    let chat_notifier = core.chat_notifier();
+    tokio::spawn(chat_notifier.clone().run(core.ws_server().get_sender().subscribe()));
+    
+    tokio::spawn(core.syslog().run(core.ws_server().get_sender().subscribe(), core.audit().subscribe()));
+    tokio::spawn(core.siem().run(core.ws_server().get_sender().subscribe(), core.audit().subscribe()));
+    
+    // Report approvals left waiting too long
+    let core_notify = core.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            let pending = core_notify.pending_approvals();
+            chat_notifier.check_stale_approvals(&pending, chrono::Utc::now());
         }
     });
     
-    // Start auto-save task
+    // Start NVD enrichment of findings that reference CVEs
+    if neurorift_core::enrichment::NvdClient::is_enabled() {
+        match neurorift_core::enrichment::EnrichmentService::new(core.clone(), &base_dir) {
+            Ok(service) => {
+                let rx = core.ws_server().get_sender().subscribe();
+                tokio::spawn(service.run(rx));
+            }
+            Err(e) => tracing::error!("Failed to start NVD enrichment: {}", e),
+        }
+    }
+    
+    // Resolve discovered hostnames and turn enumerated subdomains into assets
+    match neurorift_core::dns::DnsConfig::load(&base_dir) {
+        Ok(config) if !config.enabled => {}
+        Ok(config) => match neurorift_core::dns::DnsService::new(core.clone(), &config) {
+            Ok(service) => {
+                let rx = core.ws_server().get_sender().subscribe();
+                tokio::spawn(service.run(rx));
+            }
+            Err(e) => tracing::error!("Failed to start DNS resolution: {:#}", e),
+        },
+        Err(e) => tracing::error!("Failed to load DNS config: {:#}", e),
+    }
+    
+    // Follow the system Tor's circuits when dark-web traffic is routed through it
+    tokio::spawn(core.tor().run());
+    
+    // Probe the Python bridge so tasks that need it wait until it is up
+    tokio::spawn(core.python_bridge().run_health_checks());
+    
+    // Report host load and what running tools are using
+    tokio::spawn(neurorift_core::health::run(core.clone()));
+    
+    // Warn before scans fill the disk, and hold artifact-heavy tasks when it is nearly full
+    tokio::spawn(neurorift_core::health::disk::run(core.clone()));
+    
+    // Start task executor
+    tokio::spawn(neurorift_core::executor::TaskExecutor::new(core.clone()).run());
+    
+    // Escalate stuck approvals and expire abandoned ones so gated tasks do not wait forever
+    let core_expiry = core.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            core_expiry.sweep_approvals();
+        }
+    });
+    
+    // Feed binary frames from clients into artifact uploads
+    if let Some(mut frames) = core.ws_server().take_frames() {
+        let core_frames = core.clone();
+        tokio::spawn(async move {
+            while let Some(frame) = frames.recv().await {
+                core_frames.receive_frame(frame);
+            }
+        });
+    }
+    
+    // Abandon uploads whose client went quiet
+    let core_transfers = core.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            core_transfers.sweep_transfers();
+        }
+    });
+    
+    // Warn about and delete artifacts past their retention period
+    let core_retention = core.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(600));
+        loop {
+            interval.tick().await;
+            core_retention.sweep_artifacts();
+        }
+    });
+    
+    // Surface agents that silently stopped making progress
+    let core_watchdog = core.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            core_watchdog.sweep_stalled_agents(core_watchdog.config().agent_stall());
+        }
+    });
+    
+    // SIGUSR1 engages the kill switch for operators without a client at hand
+    #[cfg(unix)]
+    {
+        let core_signal = core.clone();
+        let mut usr1 = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::user_defined1())?;
+        tokio::spawn(async move {
+            while usr1.recv().await.is_some() {
+                let actor = neurorift_core::state::Actor::System;
+                let cancelled = core_signal.engage_kill_switch(actor.clone());
+                let details = serde_json::json!({ "source": "SIGUSR1", "cancelled": cancelled });
+                if let Err(e) = core_signal.audit().record(actor, None, "kill_switch", None, details, "accepted") {
+                    tracing::error!("Failed to write audit record: {}", e);
+                }
+            }
+        });
+    }
+    
+    // SIGHUP reloads the configuration without touching running scans
+    #[cfg(unix)]
+    {
+        let core_signal = core.clone();
+        let mut hup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+        tokio::spawn(async move {
+            while hup.recv().await.is_some() {
+                if let Err(e) = core_signal.reload_config(neurorift_core::state::Actor::System) {
+                    tracing::error!("Failed to reload configuration: {}", e);
+                }
+            }
+        });
+    }
+    
+    // Start auto-save task; the interval is re-read after each save so a reload applies
     let core_clone = core.clone();
     let autosave_task = tokio::spawn(async move {
-        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(300)); // 5 minutes
         loop {
-            interval.tick().await;
+            let Some(period) = core_clone.config().autosave_interval() else {
+                // Disabled; check again later in case a reload enables it
+                tokio::time::sleep(AUTOSAVE_RECHECK).await;
+                continue;
+            };
+            tokio::time::sleep(period).await;
             
             if let Some(session) = core_clone.get_active_session() {
                 let session_id = session.read().id.clone();
@@ -65,12 +314,17 @@ async fn main() -> Result<()> {
     // Start command listener
     let core_cmd = core.clone();
     let cmd_task = tokio::spawn(async move {
-        let mut rx = core_cmd.ws_server().get_sender().subscribe();
+        let mut rx = core_cmd.ws_server().subscribe_commands();
         
-        while let Ok(event) = rx.recv().await {
-            use neurorift_core::websocket::events::WSEvent::*;
+        while let Ok(command) = rx.recv().await {
+            use neurorift_core::websocket::events::WSEvent::{self, *};
             
-            match event {
+            if !core_cmd.admit_command(&command) {
+                continue;
+            }
+            
+            let client = command.client;
+            match command.event {
                 CreateSession { name, mode, metadata } => {
                     tracing::info!("Received CreateSession: {}", name);
                     if let Err(e) = core_cmd.create_session(name, mode, metadata) {
@@ -95,29 +349,248 @@ async fn main() -> Result<()> {
                         tracing::error!("Failed to delete session: {}", e);
                     }
                 }
-                ExportSession { session_id } => {
-                    tracing::info!("Received ExportSession: {}", session_id);
-                    if let Err(e) = core_cmd.export_session(&session_id) {
-                        tracing::error!("Failed to export session: {}", e);
+                ExportSession { session_id, format, password } => {
+                    tracing::info!("Received ExportSession: {} ({:?})", session_id, format);
+                    // Archives hash a deliberately slow key and copy every artifact
+                    let core_export = core_cmd.clone();
+                    tokio::task::spawn_blocking(move || {
+                        if let Err(e) = core_export.export_session(&session_id, format, password.as_deref()) {
+                            tracing::error!("Failed to export session: {}", e);
+                        }
+                    });
+                }
+                GenerateReport { session_id, format, template } => {
+                    tracing::info!("Received GenerateReport: {} ({:?})", session_id, format);
+                    // PDF rendering shells out to a browser; keep it off the command loop
+                    let core_report = core_cmd.clone();
+                    tokio::task::spawn_blocking(move || {
+                        if let Err(e) = core_report.generate_report(&session_id, format, template.as_deref()) {
+                            tracing::error!("Failed to generate report: {}", e);
+                        }
+                    });
+                }
+                ListReportTemplates => {
+                    if let Err(e) = core_cmd.list_report_templates(&client.client_id) {
+                        tracing::error!("Failed to list report templates: {}", e);
+                    }
+                }
+                AttachEvidence { finding_id, artifact_id, caption } => {
+                    tracing::info!("Received AttachEvidence for {}", finding_id);
+                    if let Err(e) = core_cmd.attach_evidence(&finding_id, &artifact_id, caption, client.identity) {
+                        tracing::error!("Failed to attach evidence: {}", e);
+                    }
+                }
+                DownloadArtifact { session_id, artifact_id } => {
+                    tracing::info!("Received DownloadArtifact: {} from {}", artifact_id, session_id);
+                    if let Err(e) = core_cmd.download_artifact(&client, &session_id, &artifact_id) {
+                        tracing::error!("Failed to start download: {}", e);
+                        core_cmd.ws_server().send_to(&client.client_id, WSEvent::error("Download failed", Some(e.to_string())));
+                    }
+                }
+                UploadArtifact { session_id, name, size, sha256, artifact_type } => {
+                    tracing::info!("Received UploadArtifact for {}: {} ({} bytes)", session_id, name, size);
+                    if let Err(e) = core_cmd.upload_artifact(&client, &session_id, &name, size, sha256, artifact_type) {
+                        tracing::error!("Failed to start upload: {}", e);
+                        core_cmd.ws_server().send_to(&client.client_id, WSEvent::error("Upload failed", Some(e.to_string())));
+                    }
+                }
+                VerifyArtifacts { session_id } => {
+                    tracing::info!("Received VerifyArtifacts: {}", session_id);
+                    // Hashing large captures takes a while; keep it off the command loop
+                    let core_verify = core_cmd.clone();
+                    tokio::task::spawn_blocking(move || {
+                        if let Err(e) = core_verify.verify_artifacts(&session_id) {
+                            tracing::error!("Failed to verify artifacts: {}", e);
+                        }
+                    });
+                }
+                CancelTransfer { transfer_id } => {
+                    if let Err(e) = core_cmd.cancel_transfer(&client.client_id, &transfer_id) {
+                        tracing::error!("Failed to cancel transfer: {}", e);
+                    }
+                }
+                RebuildSession { session_id } => {
+                    tracing::info!("Received RebuildSession: {}", session_id);
+                    if let Err(e) = core_cmd.rebuild_session(&session_id) {
+                        tracing::error!("Failed to rebuild session: {}", e);
+                    }
+                }
+                SetScope { session_id, scope } => {
+                    tracing::info!("Received SetScope: {}", session_id);
+                    if let Err(e) = core_cmd.set_scope(&session_id, scope) {
+                        tracing::error!("Failed to set scope: {}", e);
+                    }
+                }
+                SetRulesOfEngagement { session_id, roe } => {
+                    tracing::info!("Received SetRulesOfEngagement: {}", session_id);
+                    if let Err(e) = core_cmd.set_rules_of_engagement(&session_id, roe) {
+                        tracing::error!("Failed to set rules of engagement: {}", e);
+                    }
+                }
+                AddTarget { session_id, target, in_scope, note } => {
+                    tracing::info!("Received AddTarget: {} {} (in scope: {})", session_id, target, in_scope);
+                    if let Err(e) = core_cmd.add_target(&session_id, target, in_scope, note, client.identity) {
+                        tracing::error!("Failed to add target: {}", e);
+                    }
+                }
+                RemoveTarget { session_id, target } => {
+                    tracing::info!("Received RemoveTarget: {} {}", session_id, target);
+                    if let Err(e) = core_cmd.remove_target(&session_id, &target) {
+                        tracing::error!("Failed to remove target: {}", e);
+                    }
+                }
+                GetTimeline { session_id, agent, since } => {
+                    if let Err(e) = core_cmd.get_timeline(&client.client_id, &session_id, agent, since) {
+                        tracing::error!("Failed to get timeline: {}", e);
+                    }
+                }
+                GetSessionSummary { session_id } => {
+                    if let Err(e) = core_cmd.get_session_summary(&client.client_id, &session_id) {
+                        tracing::error!("Failed to get session summary: {}", e);
+                    }
+                }
+                PushToMisp { session_id, finding_ids } => {
+                    tracing::info!("Received PushToMisp from {}: {}", client.identity, session_id);
+                    let core_misp = core_cmd.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = core_misp.push_to_misp(&session_id, finding_ids).await {
+                            tracing::error!("Failed to push to MISP: {:#}", e);
+                        }
+                    });
+                }
+                PullFromMisp { session_id } => {
+                    let core_misp = core_cmd.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = core_misp.pull_from_misp(&session_id).await {
+                            tracing::error!("Failed to pull from MISP: {:#}", e);
+                        }
+                    });
+                }
+                CreateTicket { finding_id } => {
+                    tracing::info!("Received CreateTicket from {}: {}", client.identity, finding_id);
+                    let core_jira = core_cmd.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = core_jira.create_ticket(&finding_id).await {
+                            tracing::error!("Failed to create Jira ticket: {:#}", e);
+                        }
+                    });
+                }
+                PushToDefectDojo { session_id, finding_ids } => {
+                    tracing::info!("Received PushToDefectDojo from {}: {}", client.identity, session_id);
+                    let core_dojo = core_cmd.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = core_dojo.push_to_defectdojo(&session_id, finding_ids).await {
+                            tracing::error!("Failed to upload to DefectDojo: {:#}", e);
+                        }
+                    });
+                }
+                SubscribeLogs { min_level, agent } => {
+                    core_cmd.ws_server().filter_logs(&client.client_id, LogFilter { min_level, agent });
+                }
+                GetFindingsStats { session_id } => {
+                    if let Err(e) = core_cmd.get_findings_stats(&client.client_id, &session_id) {
+                        tracing::error!("Failed to get findings stats: {}", e);
+                    }
+                }
+                ListTargets { session_id } => {
+                    if let Err(e) = core_cmd.list_targets(&client.client_id, &session_id) {
+                        tracing::error!("Failed to list targets: {}", e);
+                    }
+                }
+                ApproveAction { approval_id } => {
+                    tracing::info!("Received ApproveAction from {}: {}", client.identity, approval_id);
+                    if let Err(e) = core_cmd.decide_approval(&approval_id, Decision::Approve, client.identity) {
+                        tracing::error!("Failed to approve action: {}", e);
+                    }
+                }
+                DenyAction { approval_id, reason } => {
+                    tracing::info!("Received DenyAction from {}: {}", client.identity, approval_id);
+                    if let Err(e) = core_cmd.decide_approval(&approval_id, Decision::Deny { reason }, client.identity) {
+                        tracing::error!("Failed to deny action: {}", e);
+                    }
+                }
+                KillSwitch => {
+                    core_cmd.engage_kill_switch(client.identity);
+                }
+                ReleaseKillSwitch => {
+                    core_cmd.release_kill_switch(client.identity);
+                }
+                ReloadConfig => {
+                    if let Err(e) = core_cmd.reload_config(client.identity) {
+                        tracing::error!("Failed to reload configuration: {}", e);
                     }
                 }
+                StartAgents { session_id, goal, max_cycles } => {
+                    tracing::info!("Received StartAgents from {}: {}", client.identity, session_id);
+                    match neurorift_core::agents::start(core_cmd.clone(), session_id, goal, max_cycles) {
+                        Ok(run_id) => tracing::info!("Started agent run {}", run_id),
+                        Err(e) => tracing::error!("Failed to start agents: {}", e),
+                    }
+                }
+                StopAgents { session_id } => {
+                    if let Err(e) = core_cmd.stop_agents(&session_id, client.identity) {
+                        tracing::error!("Failed to stop agents: {}", e);
+                    }
+                }
+                CreateApiKey { name, scopes } => {
+                    tracing::info!("Received CreateApiKey: {}", name);
+                    if let Err(e) = core_cmd.create_api_key(&client.client_id, name, scopes) {
+                        tracing::error!("Failed to create API key: {}", e);
+                    }
+                }
+                RevokeApiKey { key_id } => {
+                    tracing::info!("Received RevokeApiKey: {}", key_id);
+                    if let Err(e) = core_cmd.revoke_api_key(&client.client_id, &key_id) {
+                        tracing::error!("Failed to revoke API key: {}", e);
+                    }
+                }
+                ListApiKeys => {
+                    core_cmd.list_api_keys(&client.client_id);
+                }
+                SetSecret { name, value } => {
+                    tracing::info!("Received SetSecret: {}", name);
+                    if let Err(e) = core_cmd.set_secret(&client.client_id, &name, &value) {
+                        tracing::error!("Failed to store secret: {}", e);
+                    }
+                }
+                DeleteSecret { name } => {
+                    tracing::info!("Received DeleteSecret: {}", name);
+                    if let Err(e) = core_cmd.delete_secret(&client.client_id, &name) {
+                        tracing::error!("Failed to delete secret: {}", e);
+                    }
+                }
+                ListSecrets => {
+                    core_cmd.list_secrets(&client.client_id);
+                }
                 GetSessionList => {
                     tracing::info!("Received GetSessionList");
                     if let Err(e) = core_cmd.list_sessions() {
                         tracing::error!("Failed to list sessions: {}", e);
                     }
                 }
-                QueueTask { tool_name, target, args } => {
-                    tracing::info!("Received QueueTask: {} -> {}", tool_name, target);
-                    if let Err(e) = core_cmd.queue_task(tool_name, target, args) {
+                GetToolCatalog => {
+                    core_cmd.list_tool_catalog(&client.client_id);
+                }
+                GetTorStatus => {
+                    core_cmd.ws_server().send_to(&client.client_id, core_cmd.tor().status_event());
+                }
+                RotateTorCircuit => {
+                    tracing::info!("Received RotateTorCircuit from {}", client.identity);
+                    if let Err(e) = core_cmd.tor().request_rotation(format!("requested by {}", client.identity)) {
+                        tracing::error!("Failed to rotate Tor circuit: {}", e);
+                    }
+                }
+                QueueTask { tool_name, target, args, proxy } => {
+                    tracing::info!("Received QueueTask from {}: {} -> {}", client.identity, tool_name, target);
+                    if let Err(e) = core_cmd.queue_task(tool_name, target, args, proxy, client.identity) {
                         tracing::error!("Failed to queue task: {}", e);
                     }
                 }
-                Chat { message, model } => {
+                Chat { message, model, session_id, request_id, agent } => {
                      tracing::info!("Received Chat message");
                      let core_chat = core_cmd.clone();
                      tokio::spawn(async move {
-                         if let Err(e) = core_chat.chat(message, model).await {
+                         if let Err(e) = core_chat.chat(message, model, session_id, request_id, agent).await {
                              tracing::error!("Chat failed: {}", e);
                          }
                      });
@@ -126,13 +599,13 @@ async fn main() -> Result<()> {
             }
         }
     });
-
+    
     tracing::info!("🚀 NeuroRift Core ready!");
     
     // Wait for tasks
     tokio::select! {
         _ = ws_task => {
-            tracing::info!("WebSocket server stopped");
+            tracing::info!("Transport stopped");
         }
         _ = cmd_task => {
             tracing::info!("Command listener stopped");
@@ -140,11 +613,63 @@ async fn main() -> Result<()> {
         _ = autosave_task => {
             tracing::info!("Auto-save task stopped");
         }
-        _ = tokio::signal::ctrl_c() => {
-            tracing::info!("Received Ctrl+C, shutting down...");
+        signal = shutdown_signal() => {
+            tracing::info!("Received {}, shutting down...", signal);
+        }
+    }
+    
+    // A second signal skips the drain
+    let drain = core.config().shutdown_drain();
+    tokio::select! {
+        _ = core.shutdown(drain) => {
+            // Let the journal writer record the final saves
+            tokio::time::sleep(JOURNAL_FLUSH).await;
+        }
+        signal = shutdown_signal() => {
+            tracing::warn!("Received {} while draining, exiting without saving", signal);
+        }
+    }
+    
+    #[cfg(unix)]
+    {
+        unix_task.abort();
+        if let Some(path) = core.ws_server().unix_socket() {
+            let _ = std::fs::remove_file(path);
         }
     }
     
     tracing::info!("👋 NeuroRift Core stopped");
-    Ok(())
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Management commands print results on stdout; only problems are logged
+fn init_cli_logging() {
+    tracing_subscriber::fmt()
+        .with_target(false)
+        .with_max_level(tracing::Level::WARN)
+        .with_writer(std::io::stderr)
+        .init();
+}
+
+/// Wait for Ctrl+C, or SIGTERM as sent by `neurorift stop` and service managers
+#[cfg(unix)]
+async fn shutdown_signal() -> &'static str {
+    let mut term = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+        Ok(term) => term,
+        Err(e) => {
+            tracing::error!("Failed to listen for SIGTERM: {}", e);
+            let _ = tokio::signal::ctrl_c().await;
+            return "Ctrl+C";
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => "Ctrl+C",
+        _ = term.recv() => "SIGTERM",
+    }
+}
+
+#[cfg(not(unix))]
+async fn shutdown_signal() -> &'static str {
+    let _ = tokio::signal::ctrl_c().await;
+    "Ctrl+C"
 }
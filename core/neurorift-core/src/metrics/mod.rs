@@ -0,0 +1,127 @@
+use anyhow::Result;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry, TextEncoder,
+};
+use std::net::SocketAddr;
+use std::sync::{Arc, LazyLock};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Process-wide metrics
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// Prometheus metrics exposed on `/metrics`
+pub struct Metrics {
+    registry: Registry,
+    pub ws_clients: IntGauge,
+    pub events_broadcast: IntCounter,
+    pub tasks_queued_total: IntCounter,
+    pub tasks: IntGaugeVec,
+    pub sessions: IntGauge,
+    pub bridge_requests: IntCounterVec,
+    pub bridge_latency: HistogramVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("neurorift".to_string()), None).unwrap();
+        
+        let ws_clients = IntGauge::new("ws_clients", "Connected WebSocket clients").unwrap();
+        let events_broadcast = IntCounter::new("events_broadcast_total", "Events broadcast to clients").unwrap();
+        let tasks_queued_total = IntCounter::new("tasks_queued_total", "Tasks queued since start").unwrap();
+        let tasks = IntGaugeVec::new(
+            Opts::new("tasks", "Tasks in in-memory sessions by status"),
+            &["status"],
+        ).unwrap();
+        let sessions = IntGauge::new("sessions", "Sessions held in memory").unwrap();
+        let bridge_requests = IntCounterVec::new(
+            Opts::new("bridge_requests_total", "Python bridge requests by command type and outcome"),
+            &["command", "outcome"],
+        ).unwrap();
+        let bridge_latency = HistogramVec::new(
+            HistogramOpts::new("bridge_request_duration_seconds", "Python bridge request latency")
+                .buckets(vec![0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 30.0, 120.0, 300.0]),
+            &["command"],
+        ).unwrap();
+        
+        registry.register(Box::new(ws_clients.clone())).unwrap();
+        registry.register(Box::new(events_broadcast.clone())).unwrap();
+        registry.register(Box::new(tasks_queued_total.clone())).unwrap();
+        registry.register(Box::new(tasks.clone())).unwrap();
+        registry.register(Box::new(sessions.clone())).unwrap();
+        registry.register(Box::new(bridge_requests.clone())).unwrap();
+        registry.register(Box::new(bridge_latency.clone())).unwrap();
+        
+        Self {
+            registry,
+            ws_clients,
+            events_broadcast,
+            tasks_queued_total,
+            tasks,
+            sessions,
+            bridge_requests,
+            bridge_latency,
+        }
+    }
+    
+    /// Render all metrics in the Prometheus text format
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        let encoder = TextEncoder::new();
+        if let Err(e) = encoder.encode(&self.registry.gather(), &mut buffer) {
+            tracing::error!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+/// Serve `/metrics` over plain HTTP.
+///
+/// `refresh` runs before each scrape so gauges derived from session
+/// state are current.
+pub async fn serve(addr: SocketAddr, refresh: Arc<dyn Fn() + Send + Sync>) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    tracing::info!("Metrics endpoint listening on http://{}/metrics", addr);
+    
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let refresh = refresh.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_scrape(stream, refresh).await {
+                        tracing::debug!("Metrics request error: {}", e);
+                    }
+                });
+            }
+            Err(e) => {
+                tracing::error!("Metrics accept error: {}", e);
+            }
+        }
+    }
+}
+
+/// Answer a single HTTP request
+async fn handle_scrape(mut stream: TcpStream, refresh: Arc<dyn Fn() + Send + Sync>) -> Result<()> {
+    let mut buf = [0u8; 1024];
+    let n = stream.read(&mut buf).await?;
+    let request = String::from_utf8_lossy(&buf[..n]);
+    let request_line = request.lines().next().unwrap_or_default();
+    
+    let (status, body) = if request_line.starts_with("GET /metrics") {
+        refresh();
+        ("200 OK", METRICS.render())
+    } else {
+        ("404 Not Found", "not found\n".to_string())
+    };
+    
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
@@ -0,0 +1,289 @@
+use anyhow::{bail, Context, Result};
+use parking_lot::RwLock;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::enrichment::extract_cves;
+use crate::security::vault::SecretsVault;
+use crate::state::{Finding, SessionState, Severity};
+
+/// MISP configuration file name under the base directory
+const MISP_FILE: &str = "misp.json";
+
+/// Session metadata key holding the MISP event a session was pushed to
+pub const EVENT_ID_KEY: &str = "misp_event_id";
+
+/// Most attributes asked for in one search
+const SEARCH_LIMIT: usize = 500;
+
+/// A MISP instance to share findings with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MispConfig {
+    /// Base URL, e.g. `https://misp.example.org`
+    pub url: String,
+    /// Automation key, which may reference the vault as `{{secret:name}}`
+    pub api_key: String,
+    #[serde(default = "default_verify_tls")]
+    pub verify_tls: bool,
+    /// Distribution of pushed events; 0 keeps them in your organisation
+    #[serde(default)]
+    pub distribution: u8,
+    /// Least severe finding pushed when none are picked
+    #[serde(default = "default_min_severity")]
+    pub min_severity: Severity,
+    /// Tags put on pushed events
+    #[serde(default = "default_tags")]
+    pub tags: Vec<String>,
+}
+
+fn default_verify_tls() -> bool {
+    true
+}
+
+fn default_min_severity() -> Severity {
+    Severity::Medium
+}
+
+fn default_tags() -> Vec<String> {
+    vec!["tlp:amber".to_string()]
+}
+
+impl MispConfig {
+    /// Load the MISP config from the base directory; `None` when absent
+    pub fn load(base_dir: impl AsRef<Path>) -> Result<Option<Self>> {
+        let path = base_dir.as_ref().join(MISP_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        
+        let json = fs::read_to_string(&path)
+            .context("Failed to read MISP config")?;
+        let config = serde_json::from_str(&json)
+            .context("Failed to parse MISP config")?;
+        Ok(Some(config))
+    }
+}
+
+/// An attribute another MISP event holds for a value seen in the session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MispMatch {
+    pub value: String,
+    #[serde(rename = "type")]
+    pub attribute_type: String,
+    pub category: String,
+    pub event_id: String,
+    /// Title of the event holding the attribute
+    pub event_info: Option<String>,
+    pub comment: Option<String>,
+    /// Whether MISP marks it for detection
+    pub to_ids: bool,
+    pub tags: Vec<String>,
+}
+
+/// Pushes findings to MISP as events and searches it for what a session saw
+pub struct MispClient {
+    config: RwLock<Option<MispConfig>>,
+    vault: Arc<SecretsVault>,
+}
+
+impl MispClient {
+    pub fn new(config: Option<MispConfig>, vault: Arc<SecretsVault>) -> Self {
+        Self { config: RwLock::new(config), vault }
+    }
+    
+    /// Replace the instance, returning whether it changed; requests under
+    /// way finish against the old one
+    pub fn reload(&self, config: Option<MispConfig>) -> bool {
+        let mut current = self.config.write();
+        let changed = *current != config;
+        *current = config;
+        changed
+    }
+    
+    pub fn config(&self) -> Option<MispConfig> {
+        self.config.read().clone()
+    }
+    
+    /// Create an event for `findings`, or add them to `existing`, returning
+    /// the event ID and the number of attributes sent
+    pub async fn push(&self, session: &SessionState, findings: &[&Finding], existing: Option<&str>) -> Result<(String, usize)> {
+        let (client, config, key) = self.connect()?;
+        let event = event(session, findings, &config);
+        let attributes = event["Attribute"].as_array().map_or(0, Vec::len);
+        let url = match existing {
+            Some(id) => format!("{}/events/edit/{}", config.url.trim_end_matches('/'), id),
+            None => format!("{}/events/add", config.url.trim_end_matches('/')),
+        };
+        
+        let response = client.post(&url)
+            .header(reqwest::header::AUTHORIZATION, key)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .json(&json!({ "Event": event }))
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach MISP at {}", config.url))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            bail!("MISP returned HTTP {}: {}", status, body.chars().take(200).collect::<String>());
+        }
+        let body: Value = response.json().await.context("MISP returned invalid JSON")?;
+        let event_id = match &body["Event"]["id"] {
+            Value::String(id) => id.clone(),
+            Value::Number(id) => id.to_string(),
+            _ => bail!("MISP response has no event ID"),
+        };
+        Ok((event_id, attributes))
+    }
+    
+    /// Attributes in other events matching any of `values`
+    pub async fn search(&self, values: &[String], own_event: Option<&str>) -> Result<Vec<MispMatch>> {
+        if values.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (client, config, key) = self.connect()?;
+        let response = client.post(format!("{}/attributes/restSearch", config.url.trim_end_matches('/')))
+            .header(reqwest::header::AUTHORIZATION, key)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .json(&json!({
+                "returnFormat": "json",
+                "value": values,
+                "limit": SEARCH_LIMIT,
+                "includeEventTags": true,
+            }))
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach MISP at {}", config.url))?;
+        if !response.status().is_success() {
+            bail!("MISP search returned HTTP {}", response.status());
+        }
+        let body: Value = response.json().await.context("MISP returned invalid JSON")?;
+        
+        let attributes = body["response"]["Attribute"].as_array().cloned().unwrap_or_default();
+        Ok(attributes.iter()
+            .filter_map(parse_match)
+            .filter(|m| own_event != Some(m.event_id.as_str()))
+            .collect())
+    }
+    
+    fn connect(&self) -> Result<(Client, MispConfig, String)> {
+        let Some(config) = self.config() else {
+            bail!("MISP is not configured; add {} to the base directory", MISP_FILE);
+        };
+        let key = self.vault.resolve_str(&config.api_key)?;
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .danger_accept_invalid_certs(!config.verify_tls)
+            .build()?;
+        Ok((client, config, key))
+    }
+}
+
+/// Findings worth pushing when none are picked
+pub fn default_selection<'a>(session: &'a SessionState, config: &MispConfig) -> Vec<&'a Finding> {
+    session.findings.iter()
+        .filter(|f| f.severity >= config.min_severity)
+        .collect()
+}
+
+/// Values in a finding that MISP may know about: its CVEs, target and host
+pub fn finding_values(finding: &Finding) -> Vec<String> {
+    let mut values = extract_cves(finding);
+    if let Some(target) = &finding.target {
+        values.push(target.clone());
+        let host = host_of(target);
+        if host != target {
+            values.push(host.to_string());
+        }
+    }
+    values
+}
+
+/// MISP event body for a session's findings
+fn event(session: &SessionState, findings: &[&Finding], config: &MispConfig) -> Value {
+    let mut attributes: Vec<Value> = Vec::new();
+    let mut add = |attribute_type: &str, category: &str, value: String, comment: &str| {
+        let duplicate = attributes.iter().any(|a| a["type"] == attribute_type && a["value"] == value.as_str());
+        if !duplicate {
+            attributes.push(json!({
+                "type": attribute_type,
+                "category": category,
+                "value": value,
+                "comment": comment,
+                "to_ids": false,
+            }));
+        }
+    };
+    for finding in findings {
+        let comment = format!("[{}] {}", format!("{:?}", finding.severity).to_uppercase(), finding.title);
+        let cves = extract_cves(finding);
+        for cve in &cves {
+            add("vulnerability", "External analysis", cve.clone(), &comment);
+        }
+        match &finding.target {
+            Some(target) if target.contains("://") => add("url", "Network activity", target.clone(), &comment),
+            Some(target) if target.parse::<IpAddr>().is_ok() => add("ip-dst", "Network activity", target.clone(), &comment),
+            Some(target) => add("hostname", "Network activity", host_of(target).to_string(), &comment),
+            None if cves.is_empty() => add("text", "Other", finding.title.clone(), &comment),
+            None => {}
+        }
+    }
+    
+    let highest = findings.iter().map(|f| f.severity.clone()).max();
+    let threat_level = match highest {
+        Some(Severity::Critical | Severity::High) => 1,
+        Some(Severity::Medium) => 2,
+        Some(Severity::Low) => 3,
+        _ => 4,
+    };
+    json!({
+        "info": format!("NeuroRift: {}", session.name),
+        "date": session.created_at.format("%Y-%m-%d").to_string(),
+        "distribution": config.distribution,
+        "threat_level_id": threat_level,
+        "analysis": 1,
+        "Attribute": attributes,
+        "Tag": config.tags.iter().map(|name| json!({ "name": name })).collect::<Vec<_>>(),
+    })
+}
+
+fn parse_match(attribute: &Value) -> Option<MispMatch> {
+    let text = |value: &Value| match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    };
+    Some(MispMatch {
+        value: text(&attribute["value"])?,
+        attribute_type: text(&attribute["type"])?,
+        category: text(&attribute["category"]).unwrap_or_default(),
+        event_id: text(&attribute["event_id"])?,
+        event_info: text(&attribute["Event"]["info"]),
+        comment: text(&attribute["comment"]).filter(|c| !c.is_empty()),
+        to_ids: attribute["to_ids"].as_bool().unwrap_or(false),
+        tags: attribute["Tag"].as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|tag| tag["name"].as_str().map(str::to_string))
+            .collect(),
+    })
+}
+
+/// Host part of a URL or `host:port` target
+fn host_of(target: &str) -> &str {
+    let rest = target.split_once("://").map_or(target, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+    let authority = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    if let Some(v6) = authority.strip_prefix('[') {
+        return v6.split(']').next().unwrap_or(v6);
+    }
+    match authority.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) && !host.contains(':') => host,
+        _ => authority,
+    }
+}
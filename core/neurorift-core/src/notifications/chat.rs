@@ -0,0 +1,273 @@
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::{Mutex, RwLock};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use crate::state::{ApprovalRequest, Severity};
+use crate::websocket::events::WSEvent;
+
+/// Chat platform of an incoming-webhook channel
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatPlatform {
+    Slack,
+    Discord,
+}
+
+/// A Slack or Discord incoming webhook
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatChannelConfig {
+    pub name: String,
+    pub platform: ChatPlatform,
+    pub webhook_url: String,
+}
+
+/// Event kinds that produce chat notifications
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    CriticalFinding,
+    TaskFailed,
+    StaleApproval,
+    ApprovalEscalated,
+    AgentStalled,
+    DiskSpaceLow,
+}
+
+/// A formatted notification, rendered per platform on delivery
+#[derive(Debug, Clone)]
+pub struct ChatMessage {
+    pub kind: NotificationKind,
+    pub title: String,
+    pub fields: Vec<(String, String)>,
+}
+
+impl ChatMessage {
+    /// Render the platform-specific webhook payload
+    fn payload(&self, platform: ChatPlatform) -> serde_json::Value {
+        match platform {
+            ChatPlatform::Slack => {
+                let mut text = format!("*{}*", self.title);
+                for (key, value) in &self.fields {
+                    text.push_str(&format!("\n• *{}:* {}", key, value));
+                }
+                serde_json::json!({ "text": text })
+            }
+            ChatPlatform::Discord => {
+                let mut content = format!("**{}**", self.title);
+                for (key, value) in &self.fields {
+                    content.push_str(&format!("\n> **{}:** {}", key, value));
+                }
+                serde_json::json!({ "content": content })
+            }
+        }
+    }
+}
+
+/// Posts critical events to Slack/Discord channels
+pub struct ChatNotifier {
+    client: Client,
+    targets: RwLock<ChatTargets>,
+    notified_approvals: Mutex<HashSet<String>>,
+}
+
+/// Where chat notifications go; replaced as a whole on reload
+#[derive(PartialEq)]
+struct ChatTargets {
+    channels: Vec<ChatChannelConfig>,
+    routes: HashMap<NotificationKind, Vec<String>>,
+    stale_after: chrono::Duration,
+}
+
+impl ChatNotifier {
+    /// Create a new notifier
+    pub fn new(
+        channels: Vec<ChatChannelConfig>,
+        routes: HashMap<NotificationKind, Vec<String>>,
+        stale_after_minutes: u64,
+    ) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap();
+        
+        Self {
+            client,
+            targets: RwLock::new(ChatTargets {
+                channels,
+                routes,
+                stale_after: chrono::Duration::minutes(stale_after_minutes as i64),
+            }),
+            notified_approvals: Mutex::new(HashSet::new()),
+        }
+    }
+    
+    /// Whether any channels are configured
+    pub fn is_enabled(&self) -> bool {
+        !self.targets.read().channels.is_empty()
+    }
+    
+    /// Age after which a pending approval is reported
+    pub fn stale_after(&self) -> chrono::Duration {
+        self.targets.read().stale_after
+    }
+    
+    /// Replace the channels, routes and stale-approval age, true if any
+    /// of them differ
+    pub fn reload(
+        &self,
+        channels: Vec<ChatChannelConfig>,
+        routes: HashMap<NotificationKind, Vec<String>>,
+        stale_after_minutes: u64,
+    ) -> bool {
+        let targets = ChatTargets {
+            channels,
+            routes,
+            stale_after: chrono::Duration::minutes(stale_after_minutes as i64),
+        };
+        let mut current = self.targets.write();
+        let changed = *current != targets;
+        *current = targets;
+        changed
+    }
+    
+    /// Forward critical broadcast events until the channel closes
+    pub async fn run(self: Arc<Self>, mut rx: broadcast::Receiver<WSEvent>) {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    if let Some(message) = message_for(&event) {
+                        self.send(message);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Chat notifier lagged, skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+    
+    /// Report pending approvals that have waited too long (once each)
+    pub fn check_stale_approvals(self: &Arc<Self>, approvals: &[ApprovalRequest], now: DateTime<Utc>) {
+        // Without channels nothing would be sent, so leave them unmarked
+        if !self.is_enabled() {
+            return;
+        }
+        let stale_after = self.stale_after();
+        for approval in approvals {
+            if now - approval.created_at < stale_after {
+                continue;
+            }
+            if !self.notified_approvals.lock().insert(approval.id.clone()) {
+                continue;
+            }
+            
+            let waiting = (now - approval.created_at).num_minutes();
+            self.send(ChatMessage {
+                kind: NotificationKind::StaleApproval,
+                title: format!("⏳ Approval pending for {} minutes", waiting),
+                fields: vec![
+                    ("Approval".to_string(), approval.id.clone()),
+                    ("Action".to_string(), approval.action.description.clone()),
+                    ("Risk".to_string(), format!("{:?}", approval.action.risk_level)),
+                    ("Reason".to_string(), approval.reason.clone()),
+                ],
+            });
+        }
+    }
+    
+    /// Deliver a message to every channel routed for its kind
+    pub fn send(self: &Arc<Self>, message: ChatMessage) {
+        for channel in self.channels_for(message.kind) {
+            let notifier = self.clone();
+            let message = message.clone();
+            tokio::spawn(async move {
+                if let Err(e) = notifier.post(&channel, &message).await {
+                    tracing::error!("Chat notification to {} failed: {}", channel.name, e);
+                }
+            });
+        }
+    }
+    
+    /// Channels routed for a kind; unrouted kinds go to every channel
+    fn channels_for(&self, kind: NotificationKind) -> Vec<ChatChannelConfig> {
+        let targets = self.targets.read();
+        match targets.routes.get(&kind) {
+            Some(names) => targets.channels.iter()
+                .filter(|c| names.contains(&c.name))
+                .cloned()
+                .collect(),
+            None => targets.channels.clone(),
+        }
+    }
+    
+    /// POST a message to one channel
+    async fn post(&self, channel: &ChatChannelConfig, message: &ChatMessage) -> Result<()> {
+        let response = self.client
+            .post(&channel.webhook_url)
+            .json(&message.payload(channel.platform))
+            .send()
+            .await?;
+        
+        if !response.status().is_success() {
+            bail!("HTTP {}", response.status());
+        }
+        Ok(())
+    }
+}
+
+/// Build a chat message for events worth paging someone about
+fn message_for(event: &WSEvent) -> Option<ChatMessage> {
+    match event {
+        WSEvent::FindingDiscovered { finding } if finding.severity == Severity::Critical => Some(ChatMessage {
+            kind: NotificationKind::CriticalFinding,
+            title: format!("🚨 Critical finding: {}", finding.title),
+            fields: vec![
+                ("Source".to_string(), finding.tool_source.clone()),
+                ("Description".to_string(), finding.description.clone()),
+            ],
+        }),
+        WSEvent::ApprovalEscalated { approval } => Some(ChatMessage {
+            kind: NotificationKind::ApprovalEscalated,
+            title: format!("📟 Approval escalated (level {}): {}", approval.escalation_level, approval.action.description),
+            fields: vec![
+                ("Approval".to_string(), approval.id.clone()),
+                ("Risk".to_string(), format!("{:?}", approval.action.risk_level)),
+                ("Reason".to_string(), approval.reason.clone()),
+                ("Waiting since".to_string(), approval.created_at.to_rfc3339()),
+            ],
+        }),
+        WSEvent::AgentStalled { session_id, agent, state, current_task, last_update } => Some(ChatMessage {
+            kind: NotificationKind::AgentStalled,
+            title: format!("⏱️ Agent {:?} stalled while {:?}", agent, state),
+            fields: vec![
+                ("Session".to_string(), session_id.clone()),
+                ("Task".to_string(), current_task.clone().unwrap_or_else(|| "-".to_string())),
+                ("Last progress".to_string(), last_update.to_rfc3339()),
+            ],
+        }),
+        WSEvent::DiskSpaceLow { path, free_bytes, threshold_bytes, critical, .. } => Some(ChatMessage {
+            kind: NotificationKind::DiskSpaceLow,
+            title: match critical {
+                true => "💾 Disk space critically low; artifact-heavy tasks paused".to_string(),
+                false => "💾 Disk space low".to_string(),
+            },
+            fields: vec![
+                ("Path".to_string(), path.clone()),
+                ("Free".to_string(), format!("{} MB", free_bytes / (1024 * 1024))),
+                ("Threshold".to_string(), format!("{} MB", threshold_bytes / (1024 * 1024))),
+            ],
+        }),
+        WSEvent::TaskFailed { task_id, error } => Some(ChatMessage {
+            kind: NotificationKind::TaskFailed,
+            title: format!("❌ Task failed: {}", task_id),
+            fields: vec![("Error".to_string(), error.clone())],
+        }),
+        _ => None,
+    }
+}
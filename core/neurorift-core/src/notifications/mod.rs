@@ -0,0 +1,73 @@
+pub mod chat;
+pub mod siem;
+pub mod syslog;
+pub mod webhook;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::fs;
+use crate::notifications::chat::{ChatChannelConfig, NotificationKind};
+use crate::notifications::siem::SiemConfig;
+use crate::notifications::syslog::SyslogConfig;
+use crate::notifications::webhook::WebhookConfig;
+
+/// Notification configuration file name under the base directory
+const NOTIFICATIONS_FILE: &str = "notifications.json";
+
+/// Outbound notification configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationConfig {
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+    /// Slack/Discord channels
+    #[serde(default)]
+    pub channels: Vec<ChatChannelConfig>,
+    /// Channel names per notification kind (unrouted kinds go everywhere)
+    #[serde(default)]
+    pub routes: HashMap<NotificationKind, Vec<String>>,
+    /// Minutes before a pending approval is reported to chat
+    #[serde(default = "default_stale_approval_minutes")]
+    pub stale_approval_minutes: u64,
+    /// RFC 5424 collector for audit records and errors
+    #[serde(default)]
+    pub syslog: Option<SyslogConfig>,
+    /// CEF or LEEF stream of findings and audit records
+    #[serde(default)]
+    pub siem: Option<SiemConfig>,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            webhooks: Vec::new(),
+            channels: Vec::new(),
+            routes: HashMap::new(),
+            stale_approval_minutes: default_stale_approval_minutes(),
+            syslog: None,
+            siem: None,
+        }
+    }
+}
+
+fn default_stale_approval_minutes() -> u64 {
+    15
+}
+
+impl NotificationConfig {
+    /// Load notification config from the base directory (empty if absent)
+    pub fn load(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let path = base_dir.as_ref().join(NOTIFICATIONS_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let json = fs::read_to_string(&path)
+            .context("Failed to read notification config")?;
+        let config = serde_json::from_str(&json)
+            .context("Failed to parse notification config")?;
+        
+        Ok(config)
+    }
+}
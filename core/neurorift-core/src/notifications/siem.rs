@@ -0,0 +1,138 @@
+use anyhow::{Context, Result};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::sync::broadcast;
+use crate::security::audit::AuditRecord;
+use crate::session::siem::{self, SiemFormat};
+use crate::websocket::events::WSEvent;
+
+/// Where to stream CEF or LEEF lines as they happen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SiemConfig {
+    /// `tcp://host:port` of a SIEM listener, or a file a log shipper tails
+    pub address: String,
+    #[serde(default)]
+    pub format: SiemFormat,
+    /// Send newly discovered findings
+    #[serde(default = "default_true")]
+    pub findings: bool,
+    /// Send audit records
+    #[serde(default = "default_true")]
+    pub audit: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Streams findings and audit records to a SIEM, one line each
+pub struct SiemForwarder {
+    config: RwLock<Option<SiemConfig>>,
+}
+
+impl SiemForwarder {
+    /// Create a forwarder; with no config it sends nothing until a reload adds one
+    pub fn new(config: Option<SiemConfig>) -> Self {
+        Self { config: RwLock::new(config) }
+    }
+    
+    /// Replace the destination, true if it differs; the next line opens
+    /// the new one
+    pub fn reload(&self, config: Option<SiemConfig>) -> bool {
+        let mut current = self.config.write();
+        let changed = *current != config;
+        *current = config;
+        changed
+    }
+    
+    /// Forward findings and audit records until both channels close
+    pub async fn run(self: Arc<Self>, mut events: broadcast::Receiver<WSEvent>, mut audit: broadcast::Receiver<AuditRecord>) {
+        let mut output: Option<(String, Output)> = None;
+        loop {
+            let line = tokio::select! {
+                record = audit.recv() => match record {
+                    Ok(record) => self.config.read().as_ref()
+                        .filter(|config| config.audit)
+                        .map(|config| siem::audit_line(config.format, &record)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("SIEM forwarder lagged, skipped {} audit records", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                event = events.recv() => match event {
+                    Ok(WSEvent::FindingDiscovered { finding }) => self.config.read().as_ref()
+                        .filter(|config| config.findings)
+                        .map(|config| siem::finding_line(config.format, None, &finding)),
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("SIEM forwarder lagged, skipped {} events", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+            let Some(line) = line else {
+                continue;
+            };
+            let Some(address) = self.config.read().as_ref().map(|config| config.address.clone()) else {
+                continue;
+            };
+            if let Err(e) = send(&mut output, &address, &line).await {
+                // Reopen on the next line
+                output = None;
+                tracing::warn!("SIEM delivery to {} failed: {}", address, e);
+            }
+        }
+    }
+}
+
+/// Write one line, opening `address` first if it is not already open
+async fn send(output: &mut Option<(String, Output)>, address: &str, line: &str) -> Result<()> {
+    let open = match output.take() {
+        Some((to, open)) if to == address => open,
+        _ => Output::open(address).await?,
+    };
+    let (_, open) = output.insert((address.to_string(), open));
+    open.write_line(line).await
+}
+
+enum Output {
+    Tcp(TcpStream),
+    File(File),
+}
+
+impl Output {
+    async fn open(address: &str) -> Result<Self> {
+        if let Some(host) = address.strip_prefix("tcp://") {
+            let stream = TcpStream::connect(host).await
+                .with_context(|| format!("Failed to connect to {}", host))?;
+            return Ok(Self::Tcp(stream));
+        }
+        let path = PathBuf::from(address);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        Ok(Self::File(file))
+    }
+    
+    async fn write_line(&mut self, line: &str) -> Result<()> {
+        let line = format!("{}\n", line);
+        match self {
+            Self::Tcp(stream) => stream.write_all(line.as_bytes()).await?,
+            Self::File(file) => {
+                file.write_all(line.as_bytes()).await?;
+                file.flush().await?;
+            }
+        }
+        Ok(())
+    }
+}
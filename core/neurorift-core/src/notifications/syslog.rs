@@ -0,0 +1,288 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpStream, UdpSocket};
+#[cfg(unix)]
+use tokio::net::UnixDatagram;
+use tokio::sync::broadcast;
+use crate::security::audit::AuditRecord;
+use crate::websocket::events::{LogLevel, WSEvent};
+
+/// Enterprise number in structured data IDs: the documentation number
+/// from RFC 5612, as NeuroRift has none registered
+const ENTERPRISE_ID: u32 = 32473;
+
+/// Longest message sent in one datagram; longer ones are truncated
+const MAX_DATAGRAM: usize = 2048;
+
+/// Severities from RFC 5424 §6.2.1
+const ERROR: u8 = 3;
+const WARNING: u8 = 4;
+const NOTICE: u8 = 5;
+
+/// A syslog collector and what to send it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyslogConfig {
+    /// `udp://host:port`, `tcp://host:port`, or the path of a local
+    /// datagram socket such as `/dev/log`
+    pub address: String,
+    /// Facility code; 13 is log audit, 16 to 23 are local0 to local7
+    #[serde(default = "default_facility")]
+    pub facility: u8,
+    #[serde(default = "default_app_name")]
+    pub app_name: String,
+    /// Send audit records
+    #[serde(default = "default_true")]
+    pub audit: bool,
+    /// Send errors, error-level log entries and failed tasks
+    #[serde(default = "default_true")]
+    pub errors: bool,
+}
+
+fn default_facility() -> u8 {
+    16
+}
+
+fn default_app_name() -> String {
+    "neurorift".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// One message before it is framed for a collector
+struct Entry {
+    timestamp: DateTime<Utc>,
+    severity: u8,
+    msg_id: &'static str,
+    params: Vec<(&'static str, String)>,
+    text: String,
+}
+
+/// Forwards audit records and errors to syslog in RFC 5424 format
+pub struct SyslogSink {
+    config: RwLock<Option<SyslogConfig>>,
+    hostname: String,
+}
+
+impl SyslogSink {
+    /// Create a sink; with no config it sends nothing until a reload adds one
+    pub fn new(config: Option<SyslogConfig>) -> Self {
+        let hostname = sysinfo::System::host_name()
+            .map(|name| name.chars().filter(|c| c.is_ascii_graphic()).collect::<String>())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "-".to_string());
+        Self { config: RwLock::new(config), hostname }
+    }
+    
+    /// Replace the collector, true if it differs; the next message
+    /// connects to the new one
+    pub fn reload(&self, config: Option<SyslogConfig>) -> bool {
+        let mut current = self.config.write();
+        let changed = *current != config;
+        *current = config;
+        changed
+    }
+    
+    /// Forward matching events and every audit record until both channels close
+    pub async fn run(self: Arc<Self>, mut events: broadcast::Receiver<WSEvent>, mut audit: broadcast::Receiver<AuditRecord>) {
+        let mut connection: Option<(String, Connection)> = None;
+        loop {
+            let entry = tokio::select! {
+                record = audit.recv() => match record {
+                    Ok(record) => audit_entry(&record),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Syslog sink lagged, skipped {} audit records", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                event = events.recv() => match event {
+                    Ok(event) => match error_entry(&event) {
+                        Some(entry) => entry,
+                        None => continue,
+                    },
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Syslog sink lagged, skipped {} events", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+            
+            let Some(config) = self.config.read().clone() else {
+                continue;
+            };
+            let wanted = if entry.msg_id == "audit" { config.audit } else { config.errors };
+            if !wanted {
+                continue;
+            }
+            let message = self.format(&config, &entry);
+            if let Err(e) = send(&mut connection, &config.address, &message).await {
+                // Reconnect on the next message
+                connection = None;
+                tracing::warn!("Syslog delivery to {} failed: {}", config.address, e);
+            }
+        }
+    }
+    
+    /// RFC 5424 line: `<PRI>1 TIMESTAMP HOST APP PROCID MSGID [SD] MSG`
+    fn format(&self, config: &SyslogConfig, entry: &Entry) -> String {
+        let priority = u16::from(config.facility.min(23)) * 8 + u16::from(entry.severity);
+        let data = if entry.params.is_empty() {
+            "-".to_string()
+        } else {
+            let params: Vec<String> = entry.params.iter()
+                .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+                .collect();
+            format!("[{}@{} {}]", entry.msg_id, ENTERPRISE_ID, params.join(" "))
+        };
+        format!(
+            "<{}>1 {} {} {} {} {} {} {}",
+            priority,
+            entry.timestamp.to_rfc3339_opts(SecondsFormat::Micros, true),
+            self.hostname,
+            config.app_name,
+            std::process::id(),
+            entry.msg_id,
+            data,
+            entry.text,
+        )
+    }
+}
+
+fn audit_entry(record: &AuditRecord) -> Entry {
+    let mut params = vec![
+        ("seq", record.seq.to_string()),
+        ("actor", record.actor.to_string()),
+        ("command", record.command.clone()),
+        ("outcome", record.outcome.clone()),
+        ("hash", record.hash.clone()),
+    ];
+    if let Some(target) = &record.target {
+        params.push(("target", target.clone()));
+    }
+    if let Some(client_id) = &record.client_id {
+        params.push(("client", client_id.clone()));
+    }
+    let text = match &record.target {
+        Some(target) => format!("{} {} on {}: {}", record.actor, record.command, target, record.outcome),
+        None => format!("{} {}: {}", record.actor, record.command, record.outcome),
+    };
+    Entry {
+        timestamp: record.timestamp,
+        severity: if record.outcome == "denied" { WARNING } else { NOTICE },
+        msg_id: "audit",
+        params,
+        text,
+    }
+}
+
+/// Entry for events reporting a failure
+fn error_entry(event: &WSEvent) -> Option<Entry> {
+    let (msg_id, params, text) = match event {
+        WSEvent::Error { message, details } => {
+            let text = match details {
+                Some(details) => format!("{}: {}", message, details),
+                None => message.clone(),
+            };
+            ("error", Vec::new(), text)
+        }
+        WSEvent::LogEntry { level: LogLevel::Error, agent, message, .. } => {
+            let params = agent.map(|agent| vec![("agent", format!("{:?}", agent))]).unwrap_or_default();
+            ("log", params, message.clone())
+        }
+        WSEvent::TaskFailed { task_id, error } => ("task_failed", vec![("task", task_id.clone())], error.clone()),
+        _ => return None,
+    };
+    Some(Entry { timestamp: Utc::now(), severity: ERROR, msg_id, params, text })
+}
+
+/// Escape a structured data value (RFC 5424 §6.3.3)
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Send one message, connecting first if there is no connection to `address`
+async fn send(connection: &mut Option<(String, Connection)>, address: &str, message: &str) -> Result<()> {
+    let open = match connection.take() {
+        Some((to, open)) if to == address => open,
+        _ => Connection::open(address).await?,
+    };
+    let (_, open) = connection.insert((address.to_string(), open));
+    open.send(message).await
+}
+
+enum Connection {
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixDatagram),
+}
+
+impl Connection {
+    async fn open(address: &str) -> Result<Self> {
+        if let Some(host) = address.strip_prefix("udp://") {
+            let peer = tokio::net::lookup_host(host).await?
+                .next()
+                .with_context(|| format!("{} did not resolve", host))?;
+            let local = if peer.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+            let socket = UdpSocket::bind(local).await?;
+            socket.connect(peer).await?;
+            return Ok(Self::Udp(socket));
+        }
+        if let Some(host) = address.strip_prefix("tcp://") {
+            let stream = TcpStream::connect(host).await
+                .with_context(|| format!("Failed to connect to {}", host))?;
+            return Ok(Self::Tcp(stream));
+        }
+        #[cfg(unix)]
+        if address.starts_with('/') {
+            let socket = UnixDatagram::unbound()?;
+            socket.connect(address)
+                .with_context(|| format!("Failed to connect to {}", address))?;
+            return Ok(Self::Unix(socket));
+        }
+        bail!("Unsupported syslog address {}: use udp://, tcp:// or a socket path", address)
+    }
+    
+    async fn send(&mut self, message: &str) -> Result<()> {
+        match self {
+            Self::Udp(socket) => {
+                socket.send(truncate(message).as_bytes()).await?;
+            }
+            // Octet-counting framing (RFC 6587 §3.4.1)
+            Self::Tcp(stream) => {
+                stream.write_all(format!("{} {}", message.len(), message).as_bytes()).await?;
+            }
+            #[cfg(unix)]
+            Self::Unix(socket) => {
+                socket.send(truncate(message).as_bytes()).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Cut a message to fit one datagram, on a character boundary
+fn truncate(message: &str) -> &str {
+    if message.len() <= MAX_DATAGRAM {
+        return message;
+    }
+    let mut end = MAX_DATAGRAM;
+    while !message.is_char_boundary(end) {
+        end -= 1;
+    }
+    &message[..end]
+}
@@ -0,0 +1,175 @@
+use anyhow::{bail, Result};
+use hmac::{Hmac, Mac};
+use parking_lot::RwLock;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use crate::state::Severity;
+use crate::websocket::events::WSEvent;
+
+/// Header carrying the HMAC-SHA256 signature of the request body
+const SIGNATURE_HEADER: &str = "X-NeuroRift-Signature";
+
+/// Header carrying the event type
+const EVENT_HEADER: &str = "X-NeuroRift-Event";
+
+/// A single webhook endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Shared secret used to sign payloads
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Minimum severity for `FindingDiscovered` deliveries
+    #[serde(default = "default_min_severity")]
+    pub min_severity: Severity,
+    /// Attempts after the first failure
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+}
+
+fn default_min_severity() -> Severity {
+    Severity::High
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+/// Dispatches selected events to configured webhooks
+pub struct WebhookDispatcher {
+    client: Client,
+    webhooks: RwLock<Vec<WebhookConfig>>,
+}
+
+impl WebhookDispatcher {
+    /// Create a new dispatcher
+    pub fn new(webhooks: Vec<WebhookConfig>) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap();
+        
+        Self { client, webhooks: RwLock::new(webhooks) }
+    }
+    
+    /// Whether any webhooks are configured
+    pub fn is_enabled(&self) -> bool {
+        !self.webhooks.read().is_empty()
+    }
+    
+    /// Replace the endpoints, true if any were added, removed or edited;
+    /// deliveries already under way finish as they were
+    pub fn reload(&self, webhooks: Vec<WebhookConfig>) -> bool {
+        let mut current = self.webhooks.write();
+        let changed = *current != webhooks;
+        *current = webhooks;
+        changed
+    }
+    
+    /// Forward matching broadcast events until the channel closes
+    pub async fn run(self: Arc<Self>, mut rx: broadcast::Receiver<WSEvent>) {
+        loop {
+            match rx.recv().await {
+                Ok(event) => self.dispatch(&event),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Webhook dispatcher lagged, skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+    
+    /// Deliver an event to every webhook interested in it
+    pub fn dispatch(self: &Arc<Self>, event: &WSEvent) {
+        let Some(event_type) = event_type(event) else {
+            return;
+        };
+        
+        let webhooks = self.webhooks.read().clone();
+        for webhook in webhooks {
+            if !wants(&webhook, event) {
+                continue;
+            }
+            
+            let payload = serde_json::json!({
+                "event_type": event_type,
+                "sent_at": chrono::Utc::now(),
+                "event": event,
+            });
+            
+            let dispatcher = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = dispatcher.deliver(&webhook, event_type, &payload).await {
+                    tracing::error!("Webhook delivery to {} failed: {}", webhook.url, e);
+                }
+            });
+        }
+    }
+    
+    /// POST a payload with exponential backoff between attempts
+    async fn deliver(&self, webhook: &WebhookConfig, event_type: &str, payload: &serde_json::Value) -> Result<()> {
+        let body = serde_json::to_vec(payload)?;
+        let signature = webhook.secret.as_deref().map(|secret| sign(secret, &body));
+        
+        let mut delay = Duration::from_secs(1);
+        let mut attempt = 0;
+        loop {
+            let mut request = self.client
+                .post(&webhook.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(EVENT_HEADER, event_type)
+                .body(body.clone());
+            if let Some(signature) = &signature {
+                request = request.header(SIGNATURE_HEADER, format!("sha256={}", signature));
+            }
+            
+            let error = match request.send().await {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => format!("HTTP {}", response.status()),
+                Err(e) => e.to_string(),
+            };
+            
+            if attempt >= webhook.max_retries {
+                bail!("giving up after {} attempts: {}", attempt + 1, error);
+            }
+            
+            tracing::warn!("Webhook {} attempt {} failed: {}", webhook.url, attempt + 1, error);
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+            attempt += 1;
+        }
+    }
+}
+
+/// Event type name for events that trigger webhooks
+fn event_type(event: &WSEvent) -> Option<&'static str> {
+    match event {
+        WSEvent::FindingDiscovered { .. } => Some("finding_discovered"),
+        WSEvent::ApprovalRequired { .. } => Some("approval_required"),
+        WSEvent::ApprovalEscalated { .. } => Some("approval_escalated"),
+        WSEvent::DiskSpaceLow { .. } => Some("disk_space_low"),
+        _ => None,
+    }
+}
+
+/// Whether a webhook should receive an event
+fn wants(webhook: &WebhookConfig, event: &WSEvent) -> bool {
+    match event {
+        WSEvent::FindingDiscovered { finding } => finding.severity >= webhook.min_severity,
+        // Escalations are delivered as `approval_escalated` instead
+        WSEvent::ApprovalRequired { approval } => approval.escalation_level == 0,
+        _ => true,
+    }
+}
+
+/// Hex-encoded HMAC-SHA256 of a payload
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
@@ -0,0 +1,94 @@
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use crate::parsers::{ParsedOutput, ToolOutputParser};
+use crate::state::{NewFinding, Severity};
+
+/// One match from `ffuf -json` (JSON lines) or the `results` of `-of json`
+#[derive(Debug, Deserialize)]
+struct FfufResult {
+    url: String,
+    status: u16,
+    #[serde(default)]
+    length: u64,
+    #[serde(default)]
+    words: u64,
+    #[serde(default, rename = "redirectlocation")]
+    redirect_location: String,
+    #[serde(default)]
+    host: String,
+}
+
+/// Whole-run report written by `-of json`
+#[derive(Debug, Deserialize)]
+struct FfufReport {
+    results: Vec<FfufResult>,
+}
+
+/// ffuf with `-json` or `-of json`
+pub struct FfufParser;
+
+impl ToolOutputParser for FfufParser {
+    fn name(&self) -> &str {
+        "ffuf"
+    }
+    
+    fn tools(&self) -> &[&str] {
+        &["ffuf"]
+    }
+    
+    fn detect(&self, output: &str) -> bool {
+        let output = output.trim_start();
+        output.starts_with('{') && output.contains("\"redirectlocation\"") && output.contains("\"position\"")
+    }
+    
+    /// Matches are grouped into one Info finding per host listing the
+    /// discovered paths, so a wordlist run does not produce hundreds of findings
+    fn parse(&self, output: &str) -> Result<ParsedOutput> {
+        let results: Vec<FfufResult> = match serde_json::from_str::<FfufReport>(output.trim()) {
+            Ok(report) => report.results,
+            Err(_) => output.lines()
+                .filter_map(|line| serde_json::from_str(line.trim()).ok())
+                .collect(),
+        };
+        
+        let mut by_host: BTreeMap<String, Vec<FfufResult>> = BTreeMap::new();
+        for result in results {
+            let host = match result.host.is_empty() {
+                true => crate::security::scope::target_host(&result.url).unwrap_or_default(),
+                false => result.host.clone(),
+            };
+            by_host.entry(host).or_default().push(result);
+        }
+        
+        let findings = by_host.into_iter()
+            .map(|(host, mut results)| {
+                results.sort_by(|a, b| a.url.cmp(&b.url));
+                let paths: Vec<serde_json::Value> = results.iter()
+                    .map(|r| json!({
+                        "url": r.url,
+                        "status": r.status,
+                        "length": r.length,
+                        "words": r.words,
+                        "redirect": (!r.redirect_location.is_empty()).then_some(&r.redirect_location),
+                    }))
+                    .collect();
+                let listing: Vec<String> = results.iter().map(|r| format!("{} [{}]", r.url, r.status)).collect();
+                
+                NewFinding {
+                    title: format!("Content discovered on {}", host),
+                    severity: Severity::Info,
+                    description: format!("ffuf found {} resources:\n{}", results.len(), listing.join("\n")),
+                    tool_source: "ffuf".to_string(),
+                    target: Some(host),
+                    details: json!({ "paths": paths }),
+                    cvss_vector: None,
+                    asset_id: None,
+                }
+            })
+            .collect();
+        
+        Ok(ParsedOutput { findings, ..Default::default() })
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::net::IpAddr;
+use crate::parsers::{ParsedOutput, ToolOutputParser};
+use crate::state::AssetObservation;
+use crate::state::asset::{HttpInfo, Protocol, Service};
+
+/// One line of `httpx -json`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+struct HttpxResult {
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    host: Option<String>,
+    #[serde(default)]
+    input: Option<String>,
+    #[serde(default)]
+    port: Option<String>,
+    #[serde(default)]
+    scheme: Option<String>,
+    #[serde(default)]
+    webserver: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    status_code: Option<u16>,
+    #[serde(default)]
+    tech: Vec<String>,
+    #[serde(default)]
+    content_type: Option<String>,
+    #[serde(default)]
+    location: Option<String>,
+    /// Resolved addresses
+    #[serde(default)]
+    a: Vec<String>,
+}
+
+/// httpx with `-json`
+pub struct HttpxParser;
+
+impl ToolOutputParser for HttpxParser {
+    fn name(&self) -> &str {
+        "httpx"
+    }
+    
+    fn tools(&self) -> &[&str] {
+        &["httpx"]
+    }
+    
+    fn detect(&self, output: &str) -> bool {
+        output.lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+            .is_some_and(|l| l.starts_with('{') && l.contains("\"status_code\"") && l.contains("\"scheme\""))
+    }
+    
+    /// Each live URL becomes an HTTP service on its host
+    fn parse(&self, output: &str) -> Result<ParsedOutput> {
+        let mut parsed = ParsedOutput::default();
+        
+        for line in output.lines().map(str::trim).filter(|l| l.starts_with('{')) {
+            let Ok(result) = serde_json::from_str::<HttpxResult>(line) else {
+                continue;
+            };
+            let Some(port) = result.port.as_deref().and_then(|p| p.parse::<u16>().ok()) else {
+                continue;
+            };
+            
+            let host = result.input.as_deref()
+                .or(result.host.as_deref())
+                .and_then(crate::security::scope::target_host)
+                .or_else(|| crate::security::scope::target_host(&result.url));
+            let address = result.a.iter().find_map(|a| a.parse::<IpAddr>().ok())
+                .or_else(|| host.as_deref().and_then(|h| h.parse().ok()));
+            let hostname = host.filter(|h| h.parse::<IpAddr>().is_err());
+            
+            let mut service = Service::new(port, Protocol::Tcp);
+            service.name = result.scheme.clone();
+            if let Some(server) = &result.webserver {
+                let (product, version) = match server.split_once('/') {
+                    Some((product, version)) => (product, Some(version)),
+                    None => (server.as_str(), None),
+                };
+                service.product = Some(product.to_string());
+                service.version = version.map(str::to_string);
+            }
+            let mut banner: Vec<String> = Vec::new();
+            banner.extend(result.status_code.map(|s| s.to_string()));
+            banner.extend(result.webserver.clone());
+            banner.extend(result.title.clone().map(|t| format!("\"{}\"", t)));
+            if !result.tech.is_empty() {
+                banner.push(format!("[{}]", result.tech.join(", ")));
+            }
+            if !banner.is_empty() {
+                service.banner = Some(banner.join(" "));
+            }
+            service.http = result.status_code.map(|status| HttpInfo {
+                url: result.url.clone(),
+                status,
+                title: result.title.clone(),
+                server: result.webserver.clone(),
+                content_type: result.content_type.clone(),
+                location: result.location.clone(),
+                technologies: result.tech.clone(),
+                tls: None,
+            });
+            
+            parsed.assets.push(AssetObservation {
+                address,
+                hostname,
+                os_guess: None,
+                services: vec![service],
+                dns: Vec::new(),
+            });
+        }
+        Ok(parsed)
+    }
+}
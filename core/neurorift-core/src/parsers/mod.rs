@@ -0,0 +1,104 @@
+pub mod ffuf;
+pub mod httpx;
+pub mod nmap;
+pub mod nuclei;
+pub mod subdomains;
+
+use anyhow::Result;
+use parking_lot::RwLock;
+use std::sync::Arc;
+use crate::state::{AssetObservation, NewFinding};
+
+/// Hosts, services and findings extracted from a tool's raw output
+#[derive(Debug, Clone, Default)]
+pub struct ParsedOutput {
+    pub assets: Vec<AssetObservation>,
+    pub findings: Vec<NewFinding>,
+    /// Hostnames to resolve before they are recorded as assets
+    pub subdomains: Vec<String>,
+}
+
+impl ParsedOutput {
+    /// Whether nothing was recognized
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty() && self.findings.is_empty() && self.subdomains.is_empty()
+    }
+}
+
+/// Turns one tool's raw output into assets, services and findings
+pub trait ToolOutputParser: Send + Sync {
+    /// Short name for logs
+    fn name(&self) -> &str;
+    
+    /// Tool names whose output this parser handles
+    fn tools(&self) -> &[&str];
+    
+    /// Whether output from an unregistered tool looks like this parser's format
+    fn detect(&self, output: &str) -> bool;
+    
+    /// Parse raw output
+    fn parse(&self, output: &str) -> Result<ParsedOutput>;
+}
+
+/// Parsers consulted when a task finishes.
+///
+/// A parser registered for the task's tool is used first; otherwise the
+/// first parser that recognizes the output format is used, so wrappers
+/// around known tools (e.g. a script that shells out to nmap) still work.
+pub struct ParserRegistry {
+    parsers: RwLock<Vec<Arc<dyn ToolOutputParser>>>,
+}
+
+impl ParserRegistry {
+    /// Empty registry
+    pub fn new() -> Self {
+        Self { parsers: RwLock::new(Vec::new()) }
+    }
+    
+    /// Registry with the built-in parsers
+    pub fn with_builtin() -> Self {
+        let registry = Self::new();
+        // Registered first so its loose format check is tried last
+        registry.register(Arc::new(subdomains::SubdomainParser));
+        registry.register(Arc::new(nmap::NmapParser));
+        registry.register(Arc::new(nuclei::NucleiParser));
+        registry.register(Arc::new(ffuf::FfufParser));
+        registry.register(Arc::new(httpx::HttpxParser));
+        registry
+    }
+    
+    /// Add a parser; later registrations win for the same tool
+    pub fn register(&self, parser: Arc<dyn ToolOutputParser>) {
+        tracing::debug!("Registered output parser {} for {:?}", parser.name(), parser.tools());
+        self.parsers.write().insert(0, parser);
+    }
+    
+    /// Parser for a tool, falling back to format detection
+    pub fn find(&self, tool_name: &str, output: &str) -> Option<Arc<dyn ToolOutputParser>> {
+        let parsers = self.parsers.read();
+        parsers.iter()
+            .find(|p| p.tools().iter().any(|t| t.eq_ignore_ascii_case(tool_name)))
+            .or_else(|| parsers.iter().find(|p| p.detect(output)))
+            .cloned()
+    }
+    
+    /// Parse a tool's output, yielding nothing if no parser applies or parsing fails
+    pub fn parse(&self, tool_name: &str, output: &str) -> ParsedOutput {
+        let Some(parser) = self.find(tool_name, output) else {
+            return ParsedOutput::default();
+        };
+        match parser.parse(output) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                tracing::warn!("{} parser failed on {} output: {:#}", parser.name(), tool_name, e);
+                ParsedOutput::default()
+            }
+        }
+    }
+}
+
+impl Default for ParserRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,349 @@
+use anyhow::{Context, Result};
+use roxmltree::{Document, Node};
+use std::net::IpAddr;
+use crate::parsers::{ParsedOutput, ToolOutputParser};
+use crate::state::{AssetObservation, NewFinding, Severity};
+use crate::state::asset::{Protocol, Service};
+
+/// nmap in XML (`-oX`), normal or grepable (`-oG`) output
+pub struct NmapParser;
+
+impl ToolOutputParser for NmapParser {
+    fn name(&self) -> &str {
+        "nmap"
+    }
+    
+    fn tools(&self) -> &[&str] {
+        &["nmap"]
+    }
+    
+    fn detect(&self, output: &str) -> bool {
+        output.contains("<nmaprun")
+            || output.contains("Nmap scan report for")
+            || output.lines().any(|l| l.starts_with("Host: ") && l.contains("Ports: "))
+    }
+    
+    fn parse(&self, output: &str) -> Result<ParsedOutput> {
+        if output.contains("<nmaprun") {
+            parse_xml(output)
+        } else if output.contains("Nmap scan report for") {
+            Ok(ParsedOutput { assets: parse_normal(output), ..Default::default() })
+        } else {
+            Ok(ParsedOutput { assets: parse_grepable(output), ..Default::default() })
+        }
+    }
+}
+
+/// Parse nmap XML (`-oX`) output.
+///
+/// Every host becomes an asset with its open ports as services. NSE
+/// script results become findings by their reported state: `VULNERABLE`
+/// is High, `LIKELY VULNERABLE` Medium, and everything else (including
+/// `NOT VULNERABLE`) Info.
+pub fn parse_xml(output: &str) -> Result<ParsedOutput> {
+    // nmap writes a DOCTYPE that roxmltree refuses by default
+    let options = roxmltree::ParsingOptions { allow_dtd: true, ..Default::default() };
+    let doc = Document::parse_with_options(output.trim_start(), options).context("Invalid nmap XML")?;
+    let mut parsed = ParsedOutput::default();
+    
+    for host in doc.descendants().filter(|n| n.has_tag_name("host")) {
+        let is_up = child(host, "status").and_then(|s| s.attribute("state")).is_none_or(|s| s == "up");
+        if !is_up {
+            continue;
+        }
+        
+        let mut observation = AssetObservation::default();
+        for address in children(host, "address") {
+            if matches!(address.attribute("addrtype"), Some("ipv4" | "ipv6")) {
+                observation.address = observation.address.or_else(|| address.attribute("addr")?.parse().ok());
+            }
+        }
+        observation.hostname = child(host, "hostnames")
+            .and_then(|h| children(h, "hostname").find_map(|n| n.attribute("name")))
+            .map(str::to_string);
+        // osmatch elements are ordered by accuracy
+        observation.os_guess = child(host, "os")
+            .and_then(|os| child(os, "osmatch"))
+            .and_then(|m| m.attribute("name"))
+            .map(str::to_string);
+        
+        let host_label = observation.address.map(|a| a.to_string())
+            .or_else(|| observation.hostname.clone())
+            .unwrap_or_default();
+        
+        for port in child(host, "ports").into_iter().flat_map(|p| children(p, "port")) {
+            let Some(service) = xml_port(port) else {
+                continue;
+            };
+            let target = format!("{}:{}", host_label, service.port);
+            for script in children(port, "script") {
+                parsed.findings.push(script_finding(script, &target, Some(&service)));
+            }
+            observation.services.push(service);
+        }
+        for script in child(host, "hostscript").into_iter().flat_map(|h| children(h, "script")) {
+            parsed.findings.push(script_finding(script, &host_label, None));
+        }
+        
+        if observation.address.is_some() || observation.hostname.is_some() {
+            parsed.assets.push(observation);
+        }
+    }
+    Ok(parsed)
+}
+
+/// An open `<port>` as a service
+fn xml_port(port: Node) -> Option<Service> {
+    if child(port, "state")?.attribute("state")? != "open" {
+        return None;
+    }
+    let protocol = match port.attribute("protocol")? {
+        "udp" => Protocol::Udp,
+        _ => Protocol::Tcp,
+    };
+    
+    let mut service = Service::new(port.attribute("portid")?.parse().ok()?, protocol);
+    if let Some(info) = child(port, "service") {
+        service.name = info.attribute("name").filter(|n| *n != "unknown").map(str::to_string);
+        service.product = info.attribute("product").map(str::to_string);
+        service.version = info.attribute("version").map(str::to_string);
+        let banner: Vec<&str> = ["product", "version", "extrainfo"].iter()
+            .filter_map(|a| info.attribute(*a))
+            .collect();
+        if !banner.is_empty() {
+            service.banner = Some(banner.join(" "));
+        }
+    }
+    Some(service)
+}
+
+/// Turn an NSE `<script>` result into a finding
+fn script_finding(script: Node, target: &str, service: Option<&Service>) -> NewFinding {
+    let id = script.attribute("id").unwrap_or("script");
+    let output = script.attribute("output").unwrap_or_default().trim().to_string();
+    
+    // vuln scripts report a table per issue with title and state elements
+    let vuln_table = script.descendants()
+        .filter(|n| n.has_tag_name("table"))
+        .find(|t| children(*t, "elem").any(|e| e.attribute("key") == Some("state")));
+    let elem = |key: &str| vuln_table.and_then(|t| {
+        children(t, "elem").find(|e| e.attribute("key") == Some(key)).and_then(|e| e.text()).map(str::trim)
+    });
+    let state = elem("state").or_else(|| output_state(&output)).unwrap_or_default().to_string();
+    
+    let severity = if state.starts_with("LIKELY") {
+        Severity::Medium
+    } else if state.starts_with("VULNERABLE") {
+        Severity::High
+    } else {
+        Severity::Info
+    };
+    let title = match elem("title") {
+        Some(title) => title.to_string(),
+        None => format!("nmap {} on {}", id, target),
+    };
+    
+    NewFinding {
+        title,
+        severity,
+        description: output.clone(),
+        tool_source: "nmap".to_string(),
+        target: Some(target.to_string()),
+        details: serde_json::json!({
+            "script_id": id,
+            "state": (!state.is_empty()).then_some(state),
+            "port": service.map(|s| s.port),
+            "service": service.and_then(|s| s.name.clone()),
+            "output": output,
+            "table_key": vuln_table.and_then(|t| t.attribute("key")),
+        }),
+        cvss_vector: None,
+        asset_id: None,
+    }
+}
+
+/// The `State:` line vuln scripts print, for results without a table
+fn output_state(output: &str) -> Option<&str> {
+    output.lines().find_map(|line| line.trim().strip_prefix("State:")).map(str::trim)
+}
+
+fn child<'a, 'i>(node: Node<'a, 'i>, tag: &str) -> Option<Node<'a, 'i>> {
+    node.children().find(|n| n.has_tag_name(tag))
+}
+
+fn children<'a, 'i: 'a>(node: Node<'a, 'i>, tag: &'a str) -> impl Iterator<Item = Node<'a, 'i>> + 'a {
+    node.children().filter(move |n| n.has_tag_name(tag))
+}
+
+/// Parse nmap's normal (human-readable) output
+pub fn parse_normal(output: &str) -> Vec<AssetObservation> {
+    let mut hosts = Vec::new();
+    let mut current: Option<AssetObservation> = None;
+    
+    for line in output.lines() {
+        if let Some(rest) = line.strip_prefix("Nmap scan report for ") {
+            hosts.extend(current.take());
+            current = Some(host_header(rest.trim()));
+            continue;
+        }
+        let Some(host) = current.as_mut() else {
+            continue;
+        };
+        
+        if let Some(os) = line.strip_prefix("OS details: ").or_else(|| line.strip_prefix("Running: ")) {
+            host.os_guess.get_or_insert_with(|| os.trim().to_string());
+        } else if let Some(service) = port_line(line) {
+            host.services.push(service);
+        }
+    }
+    hosts.extend(current);
+    hosts
+}
+
+/// Parse nmap's grepable (`-oG`) output
+pub fn parse_grepable(output: &str) -> Vec<AssetObservation> {
+    output.lines()
+        .filter_map(|line| {
+            let rest = line.strip_prefix("Host: ")?;
+            let (host, fields) = rest.split_once('\t')?;
+            let mut observation = host_header(host.trim());
+            
+            for field in fields.split('\t') {
+                if let Some(ports) = field.strip_prefix("Ports: ") {
+                    observation.services = ports.split(", ").filter_map(grepable_port).collect();
+                } else if let Some(os) = field.strip_prefix("OS: ") {
+                    observation.os_guess = Some(os.trim().to_string());
+                }
+            }
+            Some(observation)
+        })
+        .filter(|o| !o.services.is_empty() || o.os_guess.is_some())
+        .collect()
+}
+
+/// Parse `host (1.2.3.4)`, `1.2.3.4 (host)` or a bare address/hostname
+fn host_header(text: &str) -> AssetObservation {
+    let mut observation = AssetObservation::default();
+    let (first, second) = match text.split_once(" (") {
+        Some((first, second)) => (first.trim(), Some(second.trim_end_matches(')').trim())),
+        None => (text.trim(), None),
+    };
+    
+    for part in [Some(first), second].into_iter().flatten().filter(|p| !p.is_empty()) {
+        match part.parse::<IpAddr>() {
+            Ok(ip) => observation.address = Some(ip),
+            Err(_) => observation.hostname = Some(part.to_string()),
+        }
+    }
+    observation
+}
+
+/// Parse a `PORT STATE SERVICE [VERSION]` row, keeping open ports only
+fn port_line(line: &str) -> Option<Service> {
+    let mut columns = line.split_whitespace();
+    let (port, protocol) = port_protocol(columns.next()?)?;
+    if columns.next()? != "open" {
+        return None;
+    }
+    
+    let mut service = Service::new(port, protocol);
+    service.name = columns.next().filter(|n| *n != "unknown").map(str::to_string);
+    let banner = columns.collect::<Vec<_>>().join(" ");
+    set_banner(&mut service, &banner);
+    Some(service)
+}
+
+/// Parse a grepable port entry: `22/open/tcp//ssh//OpenSSH 8.9p1/`
+fn grepable_port(entry: &str) -> Option<Service> {
+    let fields: Vec<&str> = entry.trim().split('/').collect();
+    let port = fields.first()?.parse().ok()?;
+    if *fields.get(1)? != "open" {
+        return None;
+    }
+    let protocol = match *fields.get(2)? {
+        "udp" => Protocol::Udp,
+        _ => Protocol::Tcp,
+    };
+    
+    let mut service = Service::new(port, protocol);
+    service.name = fields.get(4).filter(|n| !n.is_empty()).map(|n| n.to_string());
+    set_banner(&mut service, fields.get(6).copied().unwrap_or_default());
+    Some(service)
+}
+
+/// Parse `22/tcp`
+fn port_protocol(text: &str) -> Option<(u16, Protocol)> {
+    let (port, protocol) = text.split_once('/')?;
+    let protocol = match protocol {
+        "tcp" => Protocol::Tcp,
+        "udp" => Protocol::Udp,
+        _ => return None,
+    };
+    Some((port.parse().ok()?, protocol))
+}
+
+/// Record a version-detection string and split it into product and version.
+///
+/// nmap prints the product, then the version (the first word starting with
+/// a digit), then extra info in parentheses: `OpenSSH 8.9p1 Ubuntu 3 (protocol 2.0)`.
+fn set_banner(service: &mut Service, banner: &str) {
+    let banner = banner.trim();
+    if banner.is_empty() {
+        return;
+    }
+    service.banner = Some(banner.to_string());
+    
+    let words: Vec<&str> = banner.split_whitespace().take_while(|w| !w.starts_with('(')).collect();
+    let version_at = words.iter().position(|w| w.starts_with(|c: char| c.is_ascii_digit()));
+    let product = &words[..version_at.unwrap_or(words.len())];
+    if !product.is_empty() {
+        service.product = Some(product.join(" "));
+    }
+    service.version = version_at.map(|i| words[i].to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const VULN_SCAN: &str = include_str!("fixtures/nmap_vuln.xml");
+    
+    fn finding<'a>(parsed: &'a ParsedOutput, script_id: &str) -> &'a NewFinding {
+        parsed.findings.iter().find(|f| f.details["script_id"] == script_id).unwrap()
+    }
+    
+    #[test]
+    fn parses_hosts_and_open_ports() {
+        let parsed = parse_xml(VULN_SCAN).unwrap();
+        assert_eq!(parsed.assets.len(), 1);
+        let host = &parsed.assets[0];
+        assert_eq!(host.address, Some("192.0.2.10".parse().unwrap()));
+        assert_eq!(host.hostname.as_deref(), Some("files.example.test"));
+        let ports: Vec<u16> = host.services.iter().map(|s| s.port).collect();
+        assert_eq!(ports, [22, 443, 445]);
+        assert_eq!(host.services[0].product.as_deref(), Some("OpenSSH"));
+        assert_eq!(host.services[0].version.as_deref(), Some("8.9p1 Ubuntu 3ubuntu0.10"));
+    }
+    
+    #[test]
+    fn severity_follows_the_reported_state() {
+        let parsed = parse_xml(VULN_SCAN).unwrap();
+        
+        let ms17 = finding(&parsed, "smb-vuln-ms17-010");
+        assert_eq!(ms17.severity, Severity::High);
+        assert_eq!(ms17.title, "Remote Code Execution vulnerability in Microsoft SMBv1 servers (ms17-010)");
+        assert_eq!(ms17.target.as_deref(), Some("192.0.2.10:445"));
+        
+        assert_eq!(finding(&parsed, "smb-vuln-cve-2017-7494").severity, Severity::Medium);
+        assert_eq!(finding(&parsed, "smb-vuln-ms10-054").severity, Severity::Info);
+        assert_eq!(finding(&parsed, "ssh-hostkey").severity, Severity::Info);
+    }
+    
+    #[test]
+    fn not_vulnerable_output_is_not_a_high_finding() {
+        let parsed = parse_xml(VULN_SCAN).unwrap();
+        let cve = finding(&parsed, "http-vuln-cve2017-5638");
+        assert_eq!(cve.severity, Severity::Info);
+        assert_eq!(cve.details["state"], "NOT VULNERABLE");
+    }
+}
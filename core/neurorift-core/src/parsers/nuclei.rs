@@ -0,0 +1,207 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::net::IpAddr;
+use anyhow::Result;
+use crate::parsers::{ParsedOutput, ToolOutputParser};
+use crate::state::{AssetObservation, NewFinding, Severity};
+
+/// One result line of `nuclei -jsonl`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct NucleiResult {
+    template_id: String,
+    #[serde(default)]
+    info: NucleiInfo,
+    #[serde(rename = "type", default)]
+    protocol: Option<String>,
+    #[serde(default)]
+    host: Option<String>,
+    #[serde(default)]
+    matched_at: Option<String>,
+    #[serde(default)]
+    ip: Option<String>,
+    #[serde(default)]
+    matcher_name: Option<String>,
+    #[serde(default)]
+    extracted_results: Vec<String>,
+    #[serde(default)]
+    timestamp: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct NucleiInfo {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    severity: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    /// A list in current nuclei, a single string in some older templates
+    #[serde(default)]
+    reference: Option<Value>,
+    #[serde(default)]
+    tags: Option<Value>,
+    #[serde(default)]
+    classification: Option<NucleiClassification>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct NucleiClassification {
+    #[serde(default)]
+    cve_id: Option<Value>,
+    #[serde(default)]
+    cwe_id: Option<Value>,
+    #[serde(default)]
+    cvss_metrics: Option<String>,
+}
+
+/// nuclei with `-jsonl`
+pub struct NucleiParser;
+
+impl ToolOutputParser for NucleiParser {
+    fn name(&self) -> &str {
+        "nuclei"
+    }
+    
+    fn tools(&self) -> &[&str] {
+        &["nuclei"]
+    }
+    
+    fn detect(&self, output: &str) -> bool {
+        is_jsonl(output)
+    }
+    
+    fn parse(&self, output: &str) -> Result<ParsedOutput> {
+        Ok(parse_jsonl(output))
+    }
+}
+
+/// Whether output looks like nuclei JSON lines
+pub fn is_jsonl(output: &str) -> bool {
+    output.lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .is_some_and(|l| l.starts_with('{') && l.contains("\"template-id\""))
+}
+
+/// Parse nuclei's JSON-lines output.
+///
+/// Each result becomes a finding keyed on template and matched-at URL,
+/// with the template ID recorded in `details.template_id`. Lines that are
+/// not results (progress, stats) are skipped.
+pub fn parse_jsonl(output: &str) -> ParsedOutput {
+    let mut parsed = ParsedOutput::default();
+    
+    for line in output.lines().map(str::trim).filter(|l| l.starts_with('{')) {
+        let result: NucleiResult = match serde_json::from_str(line) {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::debug!("Skipping nuclei line: {}", e);
+                continue;
+            }
+        };
+        
+        if let Some(observation) = asset(&result) {
+            if !parsed.assets.iter().any(|a| a.address == observation.address && a.hostname == observation.hostname) {
+                parsed.assets.push(observation);
+            }
+        }
+        parsed.findings.push(finding(result));
+    }
+    parsed
+}
+
+/// Map nuclei's template severity
+fn severity(value: Option<&str>) -> Severity {
+    match value.map(str::to_ascii_lowercase).as_deref() {
+        Some("critical") => Severity::Critical,
+        Some("high") => Severity::High,
+        Some("medium") => Severity::Medium,
+        Some("low") => Severity::Low,
+        _ => Severity::Info,
+    }
+}
+
+fn finding(result: NucleiResult) -> NewFinding {
+    let info = result.info;
+    let classification = info.classification.unwrap_or_default();
+    let target = result.matched_at.clone().or_else(|| result.host.clone());
+    let name = info.name.unwrap_or_else(|| result.template_id.clone());
+    
+    NewFinding {
+        title: name.clone(),
+        severity: severity(info.severity.as_deref()),
+        description: info.description.map(|d| d.trim().to_string()).filter(|d| !d.is_empty()).unwrap_or(name),
+        tool_source: "nuclei".to_string(),
+        target,
+        details: json!({
+            "template_id": result.template_id,
+            "protocol": result.protocol,
+            "matched_at": result.matched_at,
+            "host": result.host,
+            "matcher_name": result.matcher_name,
+            "extracted_results": result.extracted_results,
+            "cve_id": classification.cve_id,
+            "cwe_id": classification.cwe_id,
+            "reference": info.reference,
+            "tags": info.tags,
+            "timestamp": result.timestamp,
+        }),
+        cvss_vector: classification.cvss_metrics,
+        asset_id: None,
+    }
+}
+
+/// Host the result was found on, from the resolved IP and the URL's host
+fn asset(result: &NucleiResult) -> Option<AssetObservation> {
+    let address = result.ip.as_deref().and_then(|ip| ip.parse::<IpAddr>().ok());
+    let hostname = result.host.as_deref()
+        .or(result.matched_at.as_deref())
+        .and_then(crate::security::scope::target_host)
+        .filter(|host| host.parse::<IpAddr>().is_err());
+    
+    if address.is_none() && hostname.is_none() {
+        return None;
+    }
+    Some(AssetObservation {
+        address,
+        hostname,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const SCAN: &str = include_str!("fixtures/nuclei.jsonl");
+    
+    #[test]
+    fn parses_results_and_skips_other_lines() {
+        assert!(is_jsonl(SCAN));
+        let parsed = parse_jsonl(SCAN);
+        assert_eq!(parsed.findings.len(), 3);
+        
+        let cve = &parsed.findings[0];
+        assert_eq!(cve.title, "Apache HTTP Server 2.4.49 - Path Traversal");
+        assert_eq!(cve.severity, Severity::High);
+        assert_eq!(cve.target.as_deref(), Some("https://www.example.test/cgi-bin/.%2e/.%2e/etc/passwd"));
+        assert_eq!(cve.cvss_vector.as_deref(), Some("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:N/A:N"));
+        assert_eq!(cve.details["cve_id"][0], "CVE-2021-41773");
+        
+        assert_eq!(parsed.findings[1].severity, Severity::Info);
+        // Unknown severities are Info rather than dropped
+        assert_eq!(parsed.findings[2].severity, Severity::Info);
+        assert_eq!(parsed.findings[2].title, "custom-check");
+    }
+    
+    #[test]
+    fn records_each_host_once() {
+        let parsed = parse_jsonl(SCAN);
+        assert_eq!(parsed.assets.len(), 1);
+        assert_eq!(parsed.assets[0].address, Some("198.51.100.7".parse().unwrap()));
+        assert_eq!(parsed.assets[0].hostname.as_deref(), Some("www.example.test"));
+    }
+}
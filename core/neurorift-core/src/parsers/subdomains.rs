@@ -0,0 +1,48 @@
+use anyhow::Result;
+use std::collections::BTreeSet;
+use crate::parsers::{ParsedOutput, ToolOutputParser};
+use crate::state::asset::normalize_hostname;
+
+/// Subdomain enumerators that print one name per line (amass also prints
+/// `name (FQDN) --> ...` graph lines, whose first word is the name)
+pub struct SubdomainParser;
+
+impl ToolOutputParser for SubdomainParser {
+    fn name(&self) -> &str {
+        "subdomains"
+    }
+    
+    fn tools(&self) -> &[&str] {
+        &["subfinder", "amass", "assetfinder", "findomain", "sublist3r"]
+    }
+    
+    fn detect(&self, output: &str) -> bool {
+        let mut lines = output.lines().map(str::trim).filter(|l| !l.is_empty()).peekable();
+        lines.peek().is_some() && lines.all(is_hostname)
+    }
+    
+    /// Names are only listed here; they become assets once they resolve
+    fn parse(&self, output: &str) -> Result<ParsedOutput> {
+        let names: BTreeSet<String> = output.lines()
+            .filter_map(|line| line.split_whitespace().next())
+            .filter(|word| is_hostname(word))
+            .map(normalize_hostname)
+            .collect();
+        Ok(ParsedOutput { subdomains: names.into_iter().collect(), ..Default::default() })
+    }
+}
+
+/// A dotted DNS name with letters in its last label, so not an address
+pub fn is_hostname(name: &str) -> bool {
+    let name = name.trim_end_matches('.');
+    let labels: Vec<&str> = name.split('.').collect();
+    name.len() <= 253
+        && labels.len() >= 2
+        && labels.iter().all(|label| {
+            (1..=63).contains(&label.len())
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+        })
+        && labels.last().is_some_and(|tld| tld.chars().any(|c| c.is_ascii_alphabetic()))
+}
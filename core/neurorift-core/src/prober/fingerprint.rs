@@ -0,0 +1,89 @@
+use reqwest::header::{HeaderMap, SET_COOKIE};
+
+/// Evidence that a technology is in use
+enum Signal {
+    /// Header present whose value contains the text (lowercase; empty
+    /// matches any value)
+    Header(&'static str, &'static str),
+    /// Cookie set with this name
+    Cookie(&'static str),
+    /// Text in the page (lowercase)
+    Body(&'static str),
+}
+
+use Signal::{Body, Cookie, Header};
+
+/// Recognizable technologies, checked in order
+const SIGNATURES: &[(&str, Signal)] = &[
+    ("nginx", Header("server", "nginx")),
+    ("Apache", Header("server", "apache")),
+    ("Microsoft IIS", Header("server", "microsoft-iis")),
+    ("LiteSpeed", Header("server", "litespeed")),
+    ("Caddy", Header("server", "caddy")),
+    ("Envoy", Header("server", "envoy")),
+    ("Cloudflare", Header("cf-ray", "")),
+    ("Amazon CloudFront", Header("x-amz-cf-id", "")),
+    ("Varnish", Header("x-varnish", "")),
+    ("PHP", Header("x-powered-by", "php")),
+    ("PHP", Cookie("PHPSESSID")),
+    ("ASP.NET", Header("x-powered-by", "asp.net")),
+    ("ASP.NET", Header("x-aspnet-version", "")),
+    ("ASP.NET", Cookie("ASP.NET_SessionId")),
+    ("Express", Header("x-powered-by", "express")),
+    ("Java", Cookie("JSESSIONID")),
+    ("Laravel", Cookie("laravel_session")),
+    ("Django", Cookie("csrftoken")),
+    ("Jenkins", Header("x-jenkins", "")),
+    ("WordPress", Body("/wp-content/")),
+    ("WordPress", Body("/wp-includes/")),
+    ("Drupal", Header("x-drupal-cache", "")),
+    ("Drupal", Body("drupal-settings-json")),
+    ("Joomla", Body("/media/jui/")),
+    ("Grafana", Body("grafana-app")),
+    ("GitLab", Body("gon.gitlab_url")),
+    ("Next.js", Header("x-powered-by", "next.js")),
+    ("Next.js", Body("__next_data__")),
+    ("Nuxt.js", Body("__nuxt__")),
+    ("Angular", Body("ng-version=")),
+    ("React", Body("data-reactroot")),
+    ("jQuery", Body("jquery")),
+    ("Bootstrap", Body("bootstrap.min.css")),
+];
+
+/// Technologies evident from a response's headers and body, plus whatever
+/// a `<meta name="generator">` tag claims
+pub fn detect(headers: &HeaderMap, body: &str) -> Vec<String> {
+    let lower = body.to_ascii_lowercase();
+    let mut found: Vec<String> = Vec::new();
+    for (name, signal) in SIGNATURES {
+        let matched = match signal {
+            Header(header, text) => headers.get_all(*header).iter()
+                .filter_map(|v| v.to_str().ok())
+                .any(|v| v.to_ascii_lowercase().contains(text)),
+            Cookie(cookie) => headers.get_all(SET_COOKIE).iter()
+                .filter_map(|v| v.to_str().ok())
+                .any(|v| v.trim_start().strip_prefix(cookie).is_some_and(|rest| rest.starts_with('='))),
+            Body(text) => lower.contains(text),
+        };
+        if matched && !found.iter().any(|f| f == name) {
+            found.push(name.to_string());
+        }
+    }
+    if let Some(generator) = generator(body, &lower) {
+        if !found.iter().any(|f| generator.to_ascii_lowercase().starts_with(&f.to_ascii_lowercase())) {
+            found.push(generator);
+        }
+    }
+    found
+}
+
+/// Content of the generator meta tag; `lower` is the page lowercased,
+/// which keeps byte offsets
+fn generator(body: &str, lower: &str) -> Option<String> {
+    let name = lower.find("name=\"generator\"")?;
+    let start = lower[..name].rfind('<')?;
+    let end = start + lower[start..].find('>')?;
+    let content = start + lower[start..end].find("content=\"")? + "content=\"".len();
+    let value = body[content..end].split('"').next()?.trim();
+    Some(value.to_string()).filter(|v| !v.is_empty())
+}
@@ -0,0 +1,320 @@
+pub mod fingerprint;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use futures_util::stream::{self, StreamExt};
+use openssl::asn1::{Asn1Time, Asn1TimeRef};
+use openssl::x509::{X509NameRef, X509};
+use reqwest::{redirect, Client, Url};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+use crate::executor::stream::OutputStream;
+use crate::security::scope::target_host;
+use crate::state::asset::{HttpInfo, Protocol, Service, TlsInfo};
+use crate::state::AssetObservation;
+use crate::websocket::events::TaskResult;
+
+/// Name the prober is registered under
+pub const TOOL_NAME: &str = "httpprobe";
+
+/// Most of a response body read for the title and fingerprints
+const BODY_LIMIT: usize = 512 * 1024;
+
+/// Longest title kept
+const MAX_TITLE_LEN: usize = 200;
+
+/// Redirects followed when following is enabled
+const MAX_REDIRECTS: usize = 5;
+
+/// What to probe and how
+#[derive(Debug, Clone)]
+pub struct ProbeOptions {
+    /// Ports tried on a bare host; empty means the default HTTP and HTTPS ports
+    pub ports: Vec<u16>,
+    pub timeout: Duration,
+    pub follow_redirects: bool,
+    /// Requests in flight at once
+    pub concurrency: usize,
+}
+
+impl Default for ProbeOptions {
+    fn default() -> Self {
+        Self {
+            ports: Vec::new(),
+            timeout: Duration::from_secs(10),
+            follow_redirects: false,
+            concurrency: 16,
+        }
+    }
+}
+
+/// One web service that answered, as printed by the prober
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProbeResult {
+    pub host: String,
+    pub port: u16,
+    pub http: HttpInfo,
+}
+
+impl ProbeResult {
+    /// Asset observation carrying the probed service
+    pub fn observation(&self) -> AssetObservation {
+        let (address, hostname) = match self.host.parse::<IpAddr>() {
+            Ok(address) => (Some(address), None),
+            Err(_) => (None, Some(self.host.clone())),
+        };
+        AssetObservation {
+            address,
+            hostname,
+            services: vec![service(self.port, &self.http)],
+            ..Default::default()
+        }
+    }
+}
+
+/// Service for a probed port, named by scheme with the product taken from
+/// the `Server` header
+pub fn service(port: u16, http: &HttpInfo) -> Service {
+    let mut service = Service::new(port, Protocol::Tcp);
+    service.name = Some(if http.tls.is_some() || http.url.starts_with("https:") { "https" } else { "http" }.to_string());
+    if let Some(server) = &http.server {
+        let (product, version) = match server.split_once('/') {
+            Some((product, version)) => (product, Some(version.split_whitespace().next().unwrap_or(version))),
+            None => (server.as_str(), None),
+        };
+        service.product = Some(product.to_string());
+        service.version = version.map(str::to_string);
+    }
+    service.http = Some(http.clone());
+    service
+}
+
+/// URLs to try for a target, grouped per endpoint in the order they are
+/// tried: a URL is probed as given, a bare host on each port over HTTPS
+/// and then plain HTTP
+pub fn endpoints(target: &str, ports: &[u16]) -> Result<Vec<Vec<Url>>> {
+    let target = target.trim();
+    if target.contains("://") {
+        let url = Url::parse(target).with_context(|| format!("Invalid URL '{}'", target))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            bail!("Cannot probe {} URLs", url.scheme());
+        }
+        return Ok(vec![vec![url]]);
+    }
+    
+    let host = target_host(target).with_context(|| format!("No host in target '{}'", target))?;
+    let host = if host.contains(':') { format!("[{}]", host) } else { host };
+    if ports.is_empty() {
+        return Ok(vec![
+            vec![Url::parse(&format!("https://{}", host))?],
+            vec![Url::parse(&format!("http://{}", host))?],
+        ]);
+    }
+    ports.iter()
+        .map(|port| match port {
+            443 => Ok(vec![Url::parse(&format!("https://{}", host))?]),
+            80 => Ok(vec![Url::parse(&format!("http://{}", host))?]),
+            port => Ok(vec![
+                Url::parse(&format!("https://{}:{}", host, port))?,
+                Url::parse(&format!("http://{}:{}", host, port))?,
+            ]),
+        })
+        .collect()
+}
+
+/// Client for probing: certificates are not checked so self-signed
+/// services still answer, and the certificate is kept for inspection
+pub fn client(options: &ProbeOptions, proxy: Option<&str>) -> Result<Client> {
+    let redirects = match options.follow_redirects {
+        true => redirect::Policy::limited(MAX_REDIRECTS),
+        false => redirect::Policy::none(),
+    };
+    let mut builder = Client::builder()
+        .danger_accept_invalid_certs(true)
+        .tls_info(true)
+        .timeout(options.timeout)
+        .redirect(redirects)
+        .user_agent(concat!("NeuroRift/", env!("CARGO_PKG_VERSION")));
+    builder = match proxy {
+        Some(proxy) => builder.proxy(reqwest::Proxy::all(proxy).with_context(|| format!("Invalid proxy {}", proxy))?),
+        None => builder.no_proxy(),
+    };
+    builder.build().context("Failed to build HTTP client")
+}
+
+/// Request one URL and describe the response
+pub async fn probe(client: &Client, url: Url) -> Result<HttpInfo> {
+    let mut response = client.get(url.clone()).send().await
+        .with_context(|| format!("No HTTP response from {}", url))?;
+    let header = |name: reqwest::header::HeaderName| {
+        response.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string)
+    };
+    let server = header(reqwest::header::SERVER);
+    let content_type = header(reqwest::header::CONTENT_TYPE);
+    let location = header(reqwest::header::LOCATION);
+    let status = response.status().as_u16();
+    let headers = response.headers().clone();
+    let tls = response.extensions()
+        .get::<reqwest::tls::TlsInfo>()
+        .and_then(|info| info.peer_certificate())
+        .and_then(|der| certificate(der).inspect_err(|e| tracing::debug!("{}: {:#}", url, e)).ok());
+    
+    let mut body = Vec::new();
+    while body.len() < BODY_LIMIT {
+        match response.chunk().await {
+            Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+            Ok(None) => break,
+            // Headers already tell most of the story
+            Err(e) => {
+                tracing::debug!("Reading body from {} failed: {}", url, e);
+                break;
+            }
+        }
+    }
+    body.truncate(BODY_LIMIT);
+    let body = String::from_utf8_lossy(&body);
+    
+    Ok(HttpInfo {
+        url: url.to_string(),
+        status,
+        title: title(&body),
+        server,
+        content_type,
+        location,
+        technologies: fingerprint::detect(&headers, &body),
+        tls,
+    })
+}
+
+/// Probe a target and report every endpoint that answered
+pub async fn probe_target(target: &str, options: &ProbeOptions, proxy: Option<&str>) -> Result<Vec<ProbeResult>> {
+    let client = client(options, proxy)?;
+    let endpoints = endpoints(target, &options.ports)?;
+    let attempts: Vec<Result<ProbeResult>> = stream::iter(endpoints)
+        .map(|urls| {
+            let client = &client;
+            async move {
+                let mut last_error = None;
+                for url in urls {
+                    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+                        continue;
+                    };
+                    let host = host.trim_start_matches('[').trim_end_matches(']').to_string();
+                    match probe(client, url).await {
+                        Ok(http) => return Ok(ProbeResult { host, port, http }),
+                        Err(e) => last_error = Some(e),
+                    }
+                }
+                Err(last_error.unwrap_or_else(|| anyhow::anyhow!("Nothing to probe")))
+            }
+        })
+        .buffer_unordered(options.concurrency.max(1))
+        .collect()
+        .await;
+    
+    let mut results = Vec::new();
+    let mut first_error = None;
+    for attempt in attempts {
+        match attempt {
+            Ok(result) => results.push(result),
+            Err(e) => {
+                tracing::debug!("{:#}", e);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    if let (true, Some(e)) = (results.is_empty(), first_error) {
+        return Err(e);
+    }
+    results.sort_by_key(|r| r.port);
+    Ok(results)
+}
+
+/// Run the prober as a task: one JSON line per live endpoint, returned as
+/// `structured_data.assets`
+pub async fn run_task(target: &str, options: &ProbeOptions, proxy: Option<&str>, stream: Option<&mut OutputStream>) -> Result<TaskResult> {
+    let started = Instant::now();
+    let results = probe_target(target, options, proxy).await?;
+    
+    let mut output = String::new();
+    for result in &results {
+        output.push_str(&serde_json::to_string(result)?);
+        output.push('\n');
+    }
+    if let Some(stream) = stream {
+        for line in output.lines() {
+            stream.push(line);
+        }
+        stream.flush();
+    }
+    
+    let assets: Vec<AssetObservation> = results.iter().map(ProbeResult::observation).collect();
+    Ok(TaskResult {
+        success: true,
+        output,
+        structured_data: Some(serde_json::json!({ "assets": assets })),
+        duration_ms: started.elapsed().as_millis() as u64,
+        exit_code: None,
+        stderr: None,
+    })
+}
+
+/// Probed services back from the prober's printed output
+pub fn parse_output(output: &str) -> Vec<AssetObservation> {
+    output.lines()
+        .filter_map(|line| serde_json::from_str::<ProbeResult>(line.trim()).ok())
+        .map(|result| result.observation())
+        .collect()
+}
+
+/// Page title, whitespace collapsed and common entities decoded
+fn title(body: &str) -> Option<String> {
+    let lower = body.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let title = body[start..end].split_whitespace().collect::<Vec<_>>().join(" ");
+    let title = [("&amp;", "&"), ("&lt;", "<"), ("&gt;", ">"), ("&quot;", "\""), ("&#39;", "'"), ("&nbsp;", " ")]
+        .iter()
+        .fold(title, |title, (entity, text)| title.replace(entity, text));
+    let title: String = title.chars().take(MAX_TITLE_LEN).collect();
+    Some(title).filter(|t| !t.is_empty())
+}
+
+/// Subject, issuer, names and validity of a DER certificate
+fn certificate(der: &[u8]) -> Result<TlsInfo> {
+    let cert = X509::from_der(der).context("Malformed certificate")?;
+    let names = cert.subject_alt_names()
+        .map(|names| names.iter().filter_map(|n| n.dnsname().map(str::to_string)).collect())
+        .unwrap_or_default();
+    let self_signed = cert.public_key()
+        .and_then(|key| cert.verify(&key))
+        .unwrap_or(false);
+    Ok(TlsInfo {
+        subject: distinguished_name(cert.subject_name()),
+        issuer: distinguished_name(cert.issuer_name()),
+        names,
+        not_before: timestamp(cert.not_before())?,
+        not_after: timestamp(cert.not_after())?,
+        self_signed,
+    })
+}
+
+/// `CN=example.com, O=Example` style rendering
+fn distinguished_name(name: &X509NameRef) -> String {
+    name.entries()
+        .filter_map(|entry| {
+            let key = entry.object().nid().short_name().ok()?;
+            let value = entry.data().as_utf8().ok()?;
+            Some(format!("{}={}", key, value))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn timestamp(time: &Asn1TimeRef) -> Result<DateTime<Utc>> {
+    let diff = Asn1Time::from_unix(0)?.diff(time)?;
+    let seconds = i64::from(diff.days) * 86_400 + i64::from(diff.secs);
+    DateTime::from_timestamp(seconds, 0).context("Certificate time out of range")
+}
@@ -0,0 +1,103 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Egress proxy configuration file name under the base directory
+const PROXY_FILE: &str = "proxy.json";
+
+/// Overrides the configured proxy URL; empty disables it
+const PROXY_ENV: &str = "NEURORIFT_PROXY";
+
+/// Schemes understood by both reqwest and common tools
+const SCHEMES: &[&str] = &["http", "https", "socks5", "socks5h"];
+
+/// Egress point that engagement traffic is forced through
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    /// `http://`, `https://`, `socks5://` or `socks5h://` URL; unset means
+    /// traffic leaves directly unless a task names its own proxy
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Hosts reached directly, such as a local Python bridge
+    #[serde(default = "default_no_proxy")]
+    pub no_proxy: Vec<String>,
+}
+
+impl Default for ProxyConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            no_proxy: default_no_proxy(),
+        }
+    }
+}
+
+fn default_no_proxy() -> Vec<String> {
+    ["localhost", "127.0.0.1", "::1"].into_iter().map(String::from).collect()
+}
+
+impl ProxyConfig {
+    /// Load proxy config from the base directory, then `NEURORIFT_PROXY`
+    pub fn load(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let path = base_dir.as_ref().join(PROXY_FILE);
+        let mut config: Self = if path.exists() {
+            let json = fs::read_to_string(&path)
+                .context("Failed to read proxy config")?;
+            serde_json::from_str(&json)
+                .context("Failed to parse proxy config")?
+        } else {
+            Self::default()
+        };
+        
+        if let Ok(url) = std::env::var(PROXY_ENV) {
+            config.url = Some(url).filter(|url| !url.is_empty());
+        }
+        if let Some(url) = &config.url {
+            validate(url).with_context(|| format!("Invalid proxy in {} or {}", PROXY_FILE, PROXY_ENV))?;
+        }
+        Ok(config)
+    }
+    
+    /// The proxy a task's traffic goes through: its own, else the global one
+    pub fn resolve(&self, task_proxy: Option<&str>) -> Option<String> {
+        task_proxy.map(String::from).or_else(|| self.url.clone())
+    }
+    
+    /// The global proxy for a reqwest client, bypassed for `no_proxy` hosts
+    pub fn reqwest_proxy(&self) -> Result<Option<reqwest::Proxy>> {
+        let Some(url) = &self.url else {
+            return Ok(None);
+        };
+        let proxy = reqwest::Proxy::all(url.as_str())
+            .with_context(|| format!("Invalid proxy {}", url))?
+            .no_proxy(reqwest::NoProxy::from_string(&self.no_proxy.join(",")));
+        Ok(Some(proxy))
+    }
+    
+    /// Environment for a child process whose traffic should use `proxy`.
+    /// Only tools that honour the usual proxy variables are covered.
+    pub fn env(&self, proxy: &str) -> Vec<(String, String)> {
+        let no_proxy = self.no_proxy.join(",");
+        ["ALL_PROXY", "HTTP_PROXY", "HTTPS_PROXY"].into_iter()
+            .map(|name| (name, proxy))
+            .chain([("NO_PROXY", no_proxy.as_str())])
+            .flat_map(|(name, value)| [
+                (name.to_string(), value.to_string()),
+                (name.to_lowercase(), value.to_string()),
+            ])
+            .collect()
+    }
+}
+
+/// Check that a proxy URL has a supported scheme and a host
+pub fn validate(url: &str) -> Result<()> {
+    let parsed = reqwest::Url::parse(url).with_context(|| format!("Malformed proxy URL {}", url))?;
+    if !SCHEMES.contains(&parsed.scheme()) {
+        bail!("Unsupported proxy scheme {} (expected one of {})", parsed.scheme(), SCHEMES.join(", "));
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        bail!("Proxy URL {} has no host", url);
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use anyhow::{bail, Context, Result};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use crate::security::vault::restrict_permissions;
+
+/// Environment variable holding the shared bridge secret
+const BRIDGE_SECRET_ENV: &str = "NEURORIFT_BRIDGE_SECRET";
+
+/// Shared secret file under the base directory, read by the bridge too
+const BRIDGE_KEY_FILE: &str = "bridge.key";
+
+/// Request header carrying the signing time (Unix seconds)
+pub const TIMESTAMP_HEADER: &str = "x-neurorift-timestamp";
+
+/// Request and response header carrying the hex HMAC-SHA256 signature
+pub const SIGNATURE_HEADER: &str = "x-neurorift-signature";
+
+/// Shared-secret signing of bridge traffic.
+///
+/// Requests are signed over `timestamp \n method \n path \n body`.
+/// Responses are signed over `request signature \n body`, which ties each
+/// response to the request it answers, so a process squatting on the
+/// bridge port cannot produce results the core will accept.
+#[derive(Clone)]
+pub struct BridgeAuth {
+    secret: Arc<Vec<u8>>,
+}
+
+impl BridgeAuth {
+    /// Use a known secret
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Self { secret: Arc::new(secret.into()) }
+    }
+    
+    /// Secret from the environment, the key file, or freshly generated
+    pub fn load_or_create(base_dir: impl AsRef<Path>) -> Result<Self> {
+        if let Ok(secret) = std::env::var(BRIDGE_SECRET_ENV) {
+            if secret.trim().is_empty() {
+                bail!("{} is empty", BRIDGE_SECRET_ENV);
+            }
+            return Ok(Self::new(secret.trim()));
+        }
+        
+        let key_path = base_dir.as_ref().join(BRIDGE_KEY_FILE);
+        if key_path.exists() {
+            let secret = fs::read_to_string(&key_path).context("Failed to read bridge key")?;
+            if secret.trim().is_empty() {
+                bail!("Bridge key file {} is empty", key_path.display());
+            }
+            return Ok(Self::new(secret.trim()));
+        }
+        
+        fs::create_dir_all(base_dir.as_ref())?;
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let secret = hex::encode(bytes);
+        fs::write(&key_path, &secret).context("Failed to write bridge key")?;
+        restrict_permissions(&key_path)?;
+        tracing::info!("Generated new bridge key: {}", key_path.display());
+        Ok(Self::new(secret))
+    }
+    
+    /// Signature for a request sent at `timestamp`
+    pub fn sign_request(&self, timestamp: i64, method: &str, path: &str, body: &[u8]) -> String {
+        let mut mac = self.mac();
+        mac.update(format!("{}\n{}\n{}\n", timestamp, method, path).as_bytes());
+        mac.update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+    
+    /// Check a response's signature against the request it answers
+    pub fn verify_response(&self, request_signature: &str, body: &[u8], signature: Option<&str>) -> Result<()> {
+        let signature = signature.context("Python bridge response is not signed")?;
+        let expected = hex::decode(signature).context("Malformed bridge response signature")?;
+        
+        let mut mac = self.mac();
+        mac.update(request_signature.as_bytes());
+        mac.update(b"\n");
+        mac.update(body);
+        mac.verify_slice(&expected)
+            .map_err(|_| anyhow::anyhow!("Python bridge response signature does not match"))
+    }
+    
+    fn mac(&self) -> Hmac<Sha256> {
+        Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts keys of any length")
+    }
+}
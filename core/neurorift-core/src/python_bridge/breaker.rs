@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// Whether the bridge is taking calls
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BridgeState {
+    Healthy,
+    /// Too many consecutive failures; calls fail fast until `retry_at`
+    Degraded,
+    /// The health probe fails or the bridge refuses connections; tasks
+    /// that need it are held
+    Unreachable,
+}
+
+/// Bridge health as reported to clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BridgeStatus {
+    pub state: BridgeState,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    /// When the next trial call is let through while degraded
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+/// Consecutive-failure circuit breaker.
+///
+/// Opens after `threshold` failed calls in a row. While open, calls are
+/// refused until the cooldown passes; then one trial call goes through
+/// and the cooldown restarts, so a success closes the circuit and
+/// anything else keeps it open.
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: chrono::Duration,
+    inner: Mutex<BreakerState>,
+}
+
+struct BreakerState {
+    failures: u32,
+    last_error: Option<String>,
+    open_until: Option<DateTime<Utc>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, cooldown: std::time::Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown: chrono::Duration::from_std(cooldown).unwrap_or(chrono::Duration::seconds(30)),
+            inner: Mutex::new(BreakerState {
+                failures: 0,
+                last_error: None,
+                open_until: None,
+            }),
+        }
+    }
+    
+    /// Whether a call may proceed; the reason it may not otherwise
+    pub fn admit(&self) -> Result<(), String> {
+        let mut inner = self.inner.lock();
+        let Some(open_until) = inner.open_until else {
+            return Ok(());
+        };
+        let now = Utc::now();
+        if now < open_until {
+            return Err(format!(
+                "Python bridge degraded after {} consecutive failures (last: {})",
+                inner.failures,
+                inner.last_error.as_deref().unwrap_or("unknown error"),
+            ));
+        }
+        inner.open_until = Some(now + self.cooldown);
+        Ok(())
+    }
+    
+    /// Record a successful call, closing the circuit
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock();
+        inner.failures = 0;
+        inner.last_error = None;
+        inner.open_until = None;
+    }
+    
+    /// Record a failed call, opening the circuit at the threshold and
+    /// restarting the cooldown while open
+    pub fn record_failure(&self, error: &str) {
+        let mut inner = self.inner.lock();
+        inner.failures += 1;
+        inner.last_error = Some(error.to_string());
+        if inner.open_until.is_some() || inner.failures >= self.threshold {
+            inner.open_until = Some(Utc::now() + self.cooldown);
+        }
+    }
+    
+    /// Close an open circuit whose cooldown has passed; a passing health
+    /// probe stands in for the trial call
+    pub fn close_if_cooled(&self) {
+        let mut inner = self.inner.lock();
+        if inner.open_until.is_some_and(|until| Utc::now() >= until) {
+            inner.failures = 0;
+            inner.last_error = None;
+            inner.open_until = None;
+        }
+    }
+    
+    /// Current status
+    pub fn status(&self) -> BridgeStatus {
+        status_of(&self.inner.lock())
+    }
+}
+
+fn status_of(inner: &BreakerState) -> BridgeStatus {
+    BridgeStatus {
+        state: if inner.open_until.is_some() { BridgeState::Degraded } else { BridgeState::Healthy },
+        consecutive_failures: inner.failures,
+        last_error: inner.last_error.clone(),
+        retry_at: inner.open_until,
+    }
+}
@@ -0,0 +1,189 @@
+use anyhow::{anyhow, bail, Context, Result};
+use hyperlocal::UnixClientExt;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::Duration;
+use crate::python_bridge::auth::{BridgeAuth, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use crate::python_bridge::TRACEPARENT_HEADER;
+use crate::telemetry::TraceContext;
+
+/// HTTP client for the bridge that signs requests and verifies responses
+/// when a shared secret is configured
+#[derive(Clone)]
+pub struct BridgeClient {
+    http: Client,
+    base_url: String,
+    auth: Option<BridgeAuth>,
+    /// Talk HTTP over this Unix socket instead of TCP to `base_url`
+    socket: Option<UnixSocket>,
+}
+
+/// HTTP over a Unix domain socket
+#[derive(Clone)]
+struct UnixSocket {
+    path: PathBuf,
+    client: hyper::Client<hyperlocal::UnixConnector>,
+    /// Applied when a request sets no timeout of its own
+    default_timeout: Duration,
+}
+
+/// A bridge response whose signature checked out
+pub struct BridgeResponse {
+    pub status: StatusCode,
+    pub body: Vec<u8>,
+}
+
+impl BridgeResponse {
+    /// Decode the body
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_slice(&self.body).context("Invalid JSON from Python bridge")
+    }
+    
+    /// Fail on any non-2xx status
+    pub fn error_for_status(self) -> Result<Self> {
+        if !self.status.is_success() {
+            bail!("Python bridge returned HTTP {}", self.status);
+        }
+        Ok(self)
+    }
+}
+
+impl BridgeClient {
+    pub fn new(http: Client, base_url: impl Into<String>) -> Self {
+        Self {
+            http,
+            base_url: base_url.into(),
+            auth: None,
+            socket: None,
+        }
+    }
+    
+    /// Replace the underlying HTTP client, e.g. after pool settings changed
+    pub fn set_http(&mut self, http: Client) {
+        self.http = http;
+    }
+    
+    /// Send requests over a Unix domain socket instead of TCP, or back over TCP with `None`
+    pub fn set_socket(&mut self, path: Option<PathBuf>, default_timeout: Duration) {
+        self.socket = path.map(|path| UnixSocket {
+            path,
+            client: hyper::Client::unix(),
+            default_timeout,
+        });
+    }
+    
+    /// Shared secret in use, if any
+    pub fn auth(&self) -> Option<&BridgeAuth> {
+        self.auth.as_ref()
+    }
+    
+    /// Sign requests and require signed responses
+    pub fn set_auth(&mut self, auth: BridgeAuth) {
+        self.auth = Some(auth);
+    }
+    
+    /// Send one request to a bridge path such as `/jobs`; `timeout` overrides
+    /// the client's default request timeout
+    pub async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&Value>,
+        trace: Option<&TraceContext>,
+        timeout: Option<Duration>,
+    ) -> Result<BridgeResponse> {
+        let body = body.map(serde_json::to_vec).transpose()?.unwrap_or_default();
+        
+        let mut headers = Vec::new();
+        if !body.is_empty() {
+            headers.push((CONTENT_TYPE.as_str(), "application/json".to_string()));
+        }
+        if let Some(trace) = trace {
+            headers.push((TRACEPARENT_HEADER, trace.traceparent()));
+        }
+        let mut signature = None;
+        if let Some(auth) = &self.auth {
+            let timestamp = chrono::Utc::now().timestamp();
+            let signed = auth.sign_request(timestamp, method.as_str(), path, &body);
+            headers.push((TIMESTAMP_HEADER, timestamp.to_string()));
+            headers.push((SIGNATURE_HEADER, signed.clone()));
+            signature = Some(signed);
+        }
+        
+        let (status, response_signature, response_body) = match &self.socket {
+            Some(socket) => socket.send(&method, path, headers, body, timeout).await?,
+            None => self.send_tcp(&method, path, headers, body, timeout).await?,
+        };
+        
+        if let (Some(auth), Some(signature)) = (&self.auth, &signature) {
+            auth.verify_response(signature, &response_body, response_signature.as_deref())
+                .with_context(|| format!("Rejected {} {} response (HTTP {})", method, path, status))?;
+        }
+        Ok(BridgeResponse { status, body: response_body })
+    }
+    
+    async fn send_tcp(
+        &self,
+        method: &Method,
+        path: &str,
+        headers: Vec<(&str, String)>,
+        body: Vec<u8>,
+        timeout: Option<Duration>,
+    ) -> Result<(StatusCode, Option<String>, Vec<u8>)> {
+        let mut request = self.http.request(method.clone(), format!("{}{}", self.base_url, path));
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        if !body.is_empty() {
+            request = request.body(body);
+        }
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+        
+        let response = request.send().await?;
+        let status = response.status();
+        let signature = response.headers()
+            .get(SIGNATURE_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        Ok((status, signature, response.bytes().await?.to_vec()))
+    }
+}
+
+impl UnixSocket {
+    async fn send(
+        &self,
+        method: &Method,
+        path: &str,
+        headers: Vec<(&str, String)>,
+        body: Vec<u8>,
+        timeout: Option<Duration>,
+    ) -> Result<(StatusCode, Option<String>, Vec<u8>)> {
+        let mut request = hyper::Request::builder()
+            .method(method.as_str())
+            .uri(hyperlocal::Uri::new(&self.path, path));
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let request = request.body(hyper::Body::from(body))?;
+        
+        let timeout = timeout.unwrap_or(self.default_timeout);
+        let exchange = async {
+            let response = self.client.request(request).await?;
+            let status = response.status();
+            let signature = response.headers()
+                .get(SIGNATURE_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            let body = hyper::body::to_bytes(response.into_body()).await?;
+            anyhow::Ok((status, signature, body.to_vec()))
+        };
+        tokio::time::timeout(timeout, exchange)
+            .await
+            .map_err(|_| anyhow!("Bridge request over {} timed out after {}s", self.path.display(), timeout.as_secs()))?
+    }
+}
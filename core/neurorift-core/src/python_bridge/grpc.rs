@@ -0,0 +1,286 @@
+use anyhow::{anyhow, bail, Context, Result};
+use prost::Message;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::net::UnixStream;
+use tonic::transport::Uri;
+use tower::service_fn;
+use tonic::metadata::{MetadataMap, MetadataValue};
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Code, Request, Status};
+use crate::executor::stream::OutputStream;
+use crate::python_bridge::auth::{BridgeAuth, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use crate::python_bridge::{BridgeConfig, TRACEPARENT_HEADER};
+use crate::telemetry::TraceContext;
+
+/// Generated from `proto/neurorift/bridge/v1/bridge.proto`
+pub mod proto {
+    tonic::include_proto!("neurorift.bridge.v1");
+}
+
+use proto::bridge_client::BridgeClient as RpcClient;
+use proto::tool_event::Event;
+
+/// Contract version this core speaks; the bridge reports its own from `Health`
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Fully qualified service name, used in signed request paths
+const SERVICE: &str = "neurorift.bridge.v1.Bridge";
+
+/// A non-OK gRPC status from the bridge
+#[derive(Debug, thiserror::Error)]
+#[error("Python bridge returned {code:?}: {message}")]
+pub struct RpcError {
+    pub code: Code,
+    pub message: String,
+}
+
+impl RpcError {
+    /// Whether the call never reached the bridge, so it is safe to resend
+    pub fn is_unavailable(&self) -> bool {
+        self.code == Code::Unavailable
+    }
+}
+
+impl From<Status> for RpcError {
+    fn from(status: Status) -> Self {
+        Self {
+            code: status.code(),
+            message: status.message().to_string(),
+        }
+    }
+}
+
+/// gRPC client for the bridge's `neurorift.bridge.v1.Bridge` service
+#[derive(Clone)]
+pub struct GrpcTransport {
+    client: RpcClient<Channel>,
+    auth: Option<BridgeAuth>,
+}
+
+impl GrpcTransport {
+    /// Connects lazily, so the bridge may start after the core; over
+    /// `socket_path` when set, otherwise to `url`
+    pub fn new(url: &str, config: &BridgeConfig) -> Result<Self> {
+        let keepalive = (config.tcp_keepalive_secs > 0).then(|| Duration::from_secs(config.tcp_keepalive_secs));
+        let mut endpoint = Endpoint::from_shared(url.to_string())
+            .with_context(|| format!("Invalid gRPC bridge URL: {}", url))?
+            .connect_timeout(Duration::from_secs(config.connect_timeout_secs.max(1)))
+            .tcp_keepalive(keepalive);
+        if let Some(interval) = keepalive {
+            endpoint = endpoint.http2_keep_alive_interval(interval).keep_alive_while_idle(true);
+        }
+        
+        let channel = match &config.socket_path {
+            // The URL only fills in the `:authority`; every connection goes to the socket
+            Some(socket) => {
+                let socket = socket.clone();
+                endpoint.connect_with_connector_lazy(service_fn(move |_: Uri| UnixStream::connect(socket.clone())))
+            }
+            None => endpoint.connect_lazy(),
+        };
+        
+        Ok(Self {
+            client: RpcClient::new(channel),
+            auth: None,
+        })
+    }
+    
+    /// Sign calls and require signed replies
+    pub fn set_auth(&mut self, auth: BridgeAuth) {
+        self.auth = Some(auth);
+    }
+    
+    /// Call `Health`, checking the bridge speaks our protocol version
+    pub async fn health(&self, timeout: Duration) -> Result<Value> {
+        let (request, signature) = self.request("Health", proto::HealthRequest {}, None, timeout)?;
+        let response = self.client.clone().health(request).await.map_err(RpcError::from)?;
+        let (metadata, health, _) = response.into_parts();
+        self.verify(signature.as_deref(), &health.encode_to_vec(), &metadata)?;
+        
+        if health.protocol_version != PROTOCOL_VERSION {
+            bail!(
+                "Python bridge speaks protocol v{}, this core needs v{}",
+                health.protocol_version,
+                PROTOCOL_VERSION,
+            );
+        }
+        Ok(json!({ "status": health.status, "protocol_version": health.protocol_version }))
+    }
+    
+    /// Run a command in the same JSON form the HTTP bridge takes, returning
+    /// the same `{success, data, error}` response.
+    ///
+    /// Output lines of streaming tools go to `output` as they arrive; the
+    /// result itself is only returned once the reply signature checks out.
+    pub async fn call(
+        &self,
+        command: &Value,
+        trace: &TraceContext,
+        timeout: Duration,
+        output: Option<&mut OutputStream>,
+    ) -> Result<Value> {
+        let command_type = command.get("type").and_then(|t| t.as_str()).unwrap_or("unknown");
+        let call = async {
+            match command_type {
+                "tool_execute" => self.execute_tool(command, trace, timeout, output).await,
+                "ai_generate" => self.generate(command, trace, timeout).await,
+                "browser_action" => {
+                    let message = proto::BrowserActionRequest {
+                        action: str_field(command, "action"),
+                        params_json: json_field(command, "params"),
+                    };
+                    let (request, signature) = self.request("BrowserAction", message, Some(trace), timeout)?;
+                    let response = self.client.clone().browser_action(request).await.map_err(RpcError::from)?;
+                    self.unary_result(signature, response)
+                }
+                "robin_search" => {
+                    let message = proto::RobinSearchRequest {
+                        query: str_field(command, "query"),
+                        proxy: opt_str_field(command, "proxy"),
+                    };
+                    let (request, signature) = self.request("RobinSearch", message, Some(trace), timeout)?;
+                    let response = self.client.clone().robin_search(request).await.map_err(RpcError::from)?;
+                    self.unary_result(signature, response)
+                }
+                other => bail!("Command type {} has no gRPC method", other),
+            }
+        };
+        
+        tokio::time::timeout(timeout, call)
+            .await
+            .map_err(|_| anyhow!("Bridge {} timed out after {}s", command_type, timeout.as_secs()))?
+    }
+    
+    async fn execute_tool(
+        &self,
+        command: &Value,
+        trace: &TraceContext,
+        timeout: Duration,
+        mut output: Option<&mut OutputStream>,
+    ) -> Result<Value> {
+        let message = proto::ExecuteToolRequest {
+            tool: str_field(command, "tool"),
+            target: str_field(command, "target"),
+            args_json: json_field(command, "args"),
+            proxy: opt_str_field(command, "proxy"),
+        };
+        let (request, signature) = self.request("ExecuteTool", message, Some(trace), timeout)?;
+        let response = self.client.clone().execute_tool(request).await.map_err(RpcError::from)?;
+        let headers = response.metadata().clone();
+        let mut stream = response.into_inner();
+        
+        let mut received = Vec::new();
+        let mut result = None;
+        while let Some(event) = stream.message().await.map_err(RpcError::from)? {
+            received.extend(event.encode_to_vec());
+            match event.event {
+                Some(Event::OutputLine(line)) => {
+                    if let Some(output) = output.as_deref_mut() {
+                        output.push(&line);
+                    }
+                }
+                Some(Event::Result(r)) => result = Some(r),
+                None => {}
+            }
+        }
+        let trailers = stream.trailers().await.map_err(RpcError::from)?
+            .filter(|t| t.contains_key(SIGNATURE_HEADER))
+            .unwrap_or(headers);
+        self.verify(signature.as_deref(), &received, &trailers)?;
+        
+        command_result(result.context("Python bridge ended ExecuteTool without a result")?)
+    }
+    
+    async fn generate(&self, command: &Value, trace: &TraceContext, timeout: Duration) -> Result<Value> {
+        let message = proto::GenerateRequest {
+            prompt: str_field(command, "prompt"),
+            model: command.get("model").and_then(|m| m.as_str()).map(str::to_string),
+        };
+        let (request, signature) = self.request("Generate", message, Some(trace), timeout)?;
+        let response = self.client.clone().generate(request).await.map_err(RpcError::from)?;
+        let headers = response.metadata().clone();
+        let mut stream = response.into_inner();
+        
+        let mut received = Vec::new();
+        let (mut text, mut model) = (String::new(), String::new());
+        while let Some(chunk) = stream.message().await.map_err(RpcError::from)? {
+            received.extend(chunk.encode_to_vec());
+            text.push_str(&chunk.text);
+            if !chunk.model.is_empty() {
+                model = chunk.model;
+            }
+        }
+        let trailers = stream.trailers().await.map_err(RpcError::from)?
+            .filter(|t| t.contains_key(SIGNATURE_HEADER))
+            .unwrap_or(headers);
+        self.verify(signature.as_deref(), &received, &trailers)?;
+        
+        Ok(json!({ "success": true, "data": { "response": text, "model": model }, "error": null }))
+    }
+    
+    /// Wrap a message in a request with trace, deadline and signature metadata
+    fn request<T: Message>(
+        &self,
+        method: &str,
+        message: T,
+        trace: Option<&TraceContext>,
+        timeout: Duration,
+    ) -> Result<(Request<T>, Option<String>)> {
+        let signature = self.auth.as_ref().map(|auth| {
+            let timestamp = chrono::Utc::now().timestamp();
+            let path = format!("/{}/{}", SERVICE, method);
+            (timestamp, auth.sign_request(timestamp, "POST", &path, &message.encode_to_vec()))
+        });
+        
+        let mut request = Request::new(message);
+        request.set_timeout(timeout);
+        let metadata = request.metadata_mut();
+        if let Some(trace) = trace {
+            metadata.insert(TRACEPARENT_HEADER, MetadataValue::try_from(trace.traceparent())?);
+        }
+        if let Some((timestamp, signature)) = &signature {
+            metadata.insert(TIMESTAMP_HEADER, MetadataValue::from(*timestamp));
+            metadata.insert(SIGNATURE_HEADER, MetadataValue::try_from(signature.as_str())?);
+        }
+        Ok((request, signature.map(|(_, s)| s)))
+    }
+    
+    /// Check the reply signature over everything received
+    fn verify(&self, request_signature: Option<&str>, received: &[u8], metadata: &MetadataMap) -> Result<()> {
+        if let (Some(auth), Some(request_signature)) = (&self.auth, request_signature) {
+            let signature = metadata.get(SIGNATURE_HEADER).and_then(|v| v.to_str().ok());
+            auth.verify_response(request_signature, received, signature)?;
+        }
+        Ok(())
+    }
+    
+    fn unary_result(&self, signature: Option<String>, response: tonic::Response<proto::CommandResult>) -> Result<Value> {
+        let (metadata, result, _) = response.into_parts();
+        self.verify(signature.as_deref(), &result.encode_to_vec(), &metadata)?;
+        command_result(result)
+    }
+}
+
+/// Convert a `CommandResult` into the HTTP bridge's response shape
+fn command_result(result: proto::CommandResult) -> Result<Value> {
+    let data = if result.data_json.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_str(&result.data_json).context("Invalid data_json from Python bridge")?
+    };
+    let error = (!result.error.is_empty()).then_some(result.error);
+    Ok(json!({ "success": result.success, "data": data, "error": error }))
+}
+
+fn str_field(command: &Value, key: &str) -> String {
+    command.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string()
+}
+
+fn opt_str_field(command: &Value, key: &str) -> Option<String> {
+    command.get(key).and_then(|v| v.as_str()).map(str::to_string)
+}
+
+fn json_field(command: &Value, key: &str) -> String {
+    command.get(key).filter(|v| !v.is_null()).map(Value::to_string).unwrap_or_default()
+}
@@ -0,0 +1,125 @@
+use anyhow::{anyhow, bail, Result};
+use reqwest::{Method, StatusCode};
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+use crate::python_bridge::PythonBridge;
+use crate::python_bridge::client::BridgeClient;
+use crate::telemetry::TraceContext;
+
+/// First delay between status polls; doubles up to `MAX_POLL_INTERVAL`
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(200);
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Consecutive failed polls before a job is given up on
+const MAX_POLL_FAILURES: u32 = 5;
+
+/// Job as reported by `/jobs`
+#[derive(Debug, Deserialize)]
+struct JobStatus {
+    job_id: String,
+    /// `running`, `completed`, `failed` or `cancelled`
+    status: String,
+    /// The same response `/execute` returns, once finished
+    result: Option<Value>,
+}
+
+/// Cancels a bridge job when dropped before it finished, e.g. because the
+/// task was aborted or timed out
+struct JobGuard {
+    client: BridgeClient,
+    path: String,
+    finished: bool,
+}
+
+impl Drop for JobGuard {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let (client, path) = (self.client.clone(), self.path.clone());
+        runtime.spawn(async move {
+            match client.send(Method::DELETE, &path, None, None, None).await {
+                Ok(_) => tracing::info!("Cancelled bridge job {}", path),
+                Err(e) => tracing::warn!("Failed to cancel bridge job {}: {:#}", path, e),
+            }
+        });
+    }
+}
+
+impl PythonBridge {
+    /// Run a command as a bridge job, or through `/execute` if the bridge
+    /// has no job API, giving up (and cancelling the job) after `timeout`
+    pub(super) async fn run_job(&self, command: &Value, trace: &TraceContext, timeout: Duration) -> Result<Value> {
+        if self.jobs_supported.load(Ordering::Relaxed) {
+            match self.submit_job(command, trace).await? {
+                Some(job_id) => return self.await_job(&job_id, timeout).await,
+                None => {
+                    tracing::info!("Python bridge has no job API, using /execute");
+                    self.jobs_supported.store(false, Ordering::Relaxed);
+                }
+            }
+        }
+        self.post_execute(command, trace, timeout).await
+    }
+    
+    /// Submit a command; `None` if the bridge predates the job API
+    async fn submit_job(&self, command: &Value, trace: &TraceContext) -> Result<Option<String>> {
+        let response = self.client
+            .send(Method::POST, "/jobs", Some(command), Some(trace), None)
+            .await?;
+        if matches!(response.status, StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED) {
+            return Ok(None);
+        }
+        
+        let job: JobStatus = response.error_for_status()?.json()?;
+        tracing::debug!("Submitted bridge job {}", job.job_id);
+        Ok(Some(job.job_id))
+    }
+    
+    /// Poll a job until it finishes, cancelling it if this future is dropped
+    async fn await_job(&self, job_id: &str, timeout: Duration) -> Result<Value> {
+        let path = format!("/jobs/{}", job_id);
+        let mut guard = JobGuard { client: self.client.clone(), path: path.clone(), finished: false };
+        let deadline = Instant::now() + timeout;
+        let mut interval = MIN_POLL_INTERVAL;
+        let mut failures = 0;
+        
+        loop {
+            tokio::time::sleep(interval).await;
+            interval = (interval * 2).min(MAX_POLL_INTERVAL);
+            if Instant::now() >= deadline {
+                bail!("Bridge job {} timed out after {}s", job_id, timeout.as_secs());
+            }
+            
+            match self.poll_job(&path).await {
+                Ok(job) if job.status == "running" => failures = 0,
+                Ok(job) => {
+                    guard.finished = true;
+                    return job.result
+                        .ok_or_else(|| anyhow!("Bridge job {} {} without a result", job_id, job.status));
+                }
+                Err(e) => {
+                    failures += 1;
+                    if failures >= MAX_POLL_FAILURES {
+                        // Not a connection error any more: resubmitting could run the job twice
+                        return Err(anyhow!("Lost track of bridge job {}: {:#}", job_id, e));
+                    }
+                    tracing::debug!("Polling bridge job {} failed: {:#}", job_id, e);
+                }
+            }
+        }
+    }
+    
+    async fn poll_job(&self, path: &str) -> Result<JobStatus> {
+        self.client
+            .send(Method::GET, path, None, None, None)
+            .await?
+            .error_for_status()?
+            .json()
+    }
+}
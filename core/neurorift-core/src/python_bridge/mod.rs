@@ -1,78 +1,604 @@
-use anyhow::Result;
-use reqwest::Client;
+pub mod auth;
+pub mod breaker;
+pub mod client;
+pub mod grpc;
+pub mod jobs;
+
+use anyhow::{anyhow, bail, Context, Result};
+use rand::Rng;
+use reqwest::{Client, Method};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use crate::executor::stream::OutputStream;
+use crate::metrics::METRICS;
+use crate::proxy::ProxyConfig;
+use crate::telemetry::TraceContext;
+use crate::tor::TorService;
+use crate::websocket::WebSocketServer;
+use crate::websocket::events::WSEvent;
+
+pub use auth::BridgeAuth;
+pub use breaker::{BridgeState, BridgeStatus, CircuitBreaker};
+use client::BridgeClient;
+use grpc::{GrpcTransport, RpcError};
+
+/// Header propagating the W3C trace context
+const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Bridge settings file name under the base directory
+const BRIDGE_FILE: &str = "bridge.json";
+
+/// Environment variable naming a Unix socket to reach the bridge on;
+/// overrides `socket_path` and is read by the Python bridge too
+const BRIDGE_SOCKET_ENV: &str = "NEURORIFT_BRIDGE_SOCKET";
+
+/// Longest a health probe may take
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Built-in timeouts (seconds) by command type; long scans get hours, chat
+/// gets minutes. `BridgeConfig::timeouts` overrides these.
+const DEFAULT_COMMAND_TIMEOUTS: &[(&str, u64)] = &[
+    ("ai_generate", 120),
+    ("browser_action", 120),
+    ("robin_search", 600),
+    ("tool_execute", 4 * 60 * 60),
+];
+
+/// Commands without side effects, safe to resend after any failure;
+/// everything else is only retried when the connection was refused
+const IDEMPOTENT_COMMANDS: &[&str] = &["ai_generate", "robin_search"];
+
+/// How the core talks to the bridge
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BridgeTransport {
+    /// JSON over HTTP (`/jobs`, falling back to `/execute`)
+    #[default]
+    Http,
+    /// The `neurorift.bridge.v1` gRPC contract
+    Grpc,
+}
+
+/// Retry, circuit breaker, timeout and connection settings for the bridge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeConfig {
+    #[serde(default)]
+    pub transport: BridgeTransport,
+    /// Address of the gRPC bridge when `transport` is `grpc`
+    #[serde(default = "default_grpc_url")]
+    pub grpc_url: String,
+    /// Unix domain socket to reach the bridge on instead of localhost TCP
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub socket_path: Option<PathBuf>,
+    /// Retries after the first attempt
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled (with jitter) each time
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    /// Consecutive failed calls that mark the bridge degraded
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// Seconds a degraded bridge refuses calls before a trial call
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+    /// Seconds between `/health` probes
+    #[serde(default = "default_health_interval_secs")]
+    pub health_interval_secs: u64,
+    /// Seconds a command may run, by command type (`tool_execute`, `ai_generate`, ...)
+    #[serde(default)]
+    pub timeouts: HashMap<String, u64>,
+    /// Timeout for command types without a specific one
+    #[serde(default = "default_command_timeout_secs")]
+    pub default_timeout_secs: u64,
+    /// Timeout for short control requests (job submit, poll, cancel)
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+    /// Idle keep-alive connections kept open to the bridge
+    #[serde(default = "default_pool_max_idle")]
+    pub pool_max_idle: usize,
+    /// Seconds an idle pooled connection is kept before closing
+    #[serde(default = "default_pool_idle_timeout_secs")]
+    pub pool_idle_timeout_secs: u64,
+    /// TCP keep-alive probe interval; 0 disables it
+    #[serde(default = "default_tcp_keepalive_secs")]
+    pub tcp_keepalive_secs: u64,
+}
+
+impl Default for BridgeConfig {
+    fn default() -> Self {
+        Self {
+            transport: BridgeTransport::default(),
+            grpc_url: default_grpc_url(),
+            socket_path: None,
+            max_retries: default_max_retries(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            failure_threshold: default_failure_threshold(),
+            cooldown_secs: default_cooldown_secs(),
+            health_interval_secs: default_health_interval_secs(),
+            timeouts: HashMap::new(),
+            default_timeout_secs: default_command_timeout_secs(),
+            request_timeout_secs: default_request_timeout_secs(),
+            connect_timeout_secs: default_connect_timeout_secs(),
+            pool_max_idle: default_pool_max_idle(),
+            pool_idle_timeout_secs: default_pool_idle_timeout_secs(),
+            tcp_keepalive_secs: default_tcp_keepalive_secs(),
+        }
+    }
+}
+
+fn default_grpc_url() -> String {
+    "http://127.0.0.1:8767".to_string()
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    250
+}
+
+fn default_max_backoff_ms() -> u64 {
+    5_000
+}
+
+fn default_failure_threshold() -> u32 {
+    5
+}
+
+fn default_cooldown_secs() -> u64 {
+    30
+}
+
+fn default_health_interval_secs() -> u64 {
+    10
+}
+
+fn default_command_timeout_secs() -> u64 {
+    300
+}
+
+fn default_request_timeout_secs() -> u64 {
+    30
+}
+
+fn default_connect_timeout_secs() -> u64 {
+    5
+}
+
+fn default_pool_max_idle() -> usize {
+    8
+}
+
+fn default_pool_idle_timeout_secs() -> u64 {
+    90
+}
+
+fn default_tcp_keepalive_secs() -> u64 {
+    60
+}
+
+impl BridgeConfig {
+    /// Load bridge settings from the base directory (defaults if absent)
+    pub fn load(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let path = base_dir.as_ref().join(BRIDGE_FILE);
+        let mut config: Self = if path.exists() {
+            let json = fs::read_to_string(&path)
+                .context("Failed to read bridge config")?;
+            serde_json::from_str(&json)
+                .context("Failed to parse bridge config")?
+        } else {
+            Self::default()
+        };
+        
+        if let Some(socket) = std::env::var_os(BRIDGE_SOCKET_ENV).filter(|s| !s.is_empty()) {
+            config.socket_path = Some(PathBuf::from(socket));
+        }
+        Ok(config)
+    }
+    
+    /// How long a command of this type may run
+    pub fn timeout_for(&self, command_type: &str) -> Duration {
+        let secs = self.timeouts.get(command_type).copied()
+            .or_else(|| DEFAULT_COMMAND_TIMEOUTS.iter().find(|(t, _)| *t == command_type).map(|(_, s)| *s))
+            .unwrap_or(self.default_timeout_secs);
+        Duration::from_secs(secs.max(1))
+    }
+    
+    /// HTTP client with this config's pool and keep-alive settings, going
+    /// through the egress proxy when one is configured
+    fn http_client(&self, proxy: &ProxyConfig) -> Result<Client> {
+        let keepalive = (self.tcp_keepalive_secs > 0).then(|| Duration::from_secs(self.tcp_keepalive_secs));
+        let mut builder = Client::builder()
+            .timeout(Duration::from_secs(self.request_timeout_secs.max(1)))
+            .connect_timeout(Duration::from_secs(self.connect_timeout_secs.max(1)))
+            .pool_max_idle_per_host(self.pool_max_idle)
+            .pool_idle_timeout(Duration::from_secs(self.pool_idle_timeout_secs))
+            .tcp_keepalive(keepalive);
+        if let Some(proxy) = proxy.reqwest_proxy()? {
+            builder = builder.proxy(proxy);
+        }
+        builder.build().context("Failed to build the bridge HTTP client")
+    }
+}
 
 /// Python bridge for calling Python tools and AI
 pub struct PythonBridge {
-    client: Client,
-    base_url: String,
+    client: BridgeClient,
+    /// Used instead of `client` when the gRPC transport is configured
+    grpc: Option<GrpcTransport>,
+    config: BridgeConfig,
+    breaker: CircuitBreaker,
+    /// Whether the bridge answered its last health probe or call
+    reachable: AtomicBool,
+    /// Why the bridge is unreachable
+    unreachable_reason: Mutex<Option<String>>,
+    /// State last announced to clients
+    announced: Mutex<Option<BridgeState>>,
+    /// Cleared once the bridge turns out to predate the job API
+    jobs_supported: AtomicBool,
+    /// Where `BridgeStatus` changes are announced
+    events: Option<Arc<WebSocketServer>>,
+    /// Routes dark-web searches
+    tor: Option<Arc<TorService>>,
+    /// Egress proxy for the bridge's own HTTP traffic
+    proxy: ProxyConfig,
 }
 
 impl PythonBridge {
     /// Create a new Python bridge
     pub fn new(base_url: impl Into<String>) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(300)) // 5 minutes for long-running tools
-            .build()
-            .unwrap();
-        
+        let config = BridgeConfig::default();
         Self {
-            client,
-            base_url: base_url.into(),
+            client: BridgeClient::new(config.http_client(&ProxyConfig::default()).expect("default HTTP client settings are valid"), base_url),
+            grpc: None,
+            breaker: CircuitBreaker::new(config.failure_threshold, Duration::from_secs(config.cooldown_secs)),
+            config,
+            reachable: AtomicBool::new(false),
+            unreachable_reason: Mutex::new(Some("not probed yet".to_string())),
+            announced: Mutex::new(None),
+            jobs_supported: AtomicBool::new(true),
+            events: None,
+            tor: None,
+            proxy: ProxyConfig::default(),
+        }
+    }
+    
+    /// Use retry, circuit breaker, timeout, connection and transport settings
+    pub fn with_config(mut self, config: BridgeConfig) -> Result<Self> {
+        self.breaker = CircuitBreaker::new(config.failure_threshold, Duration::from_secs(config.cooldown_secs));
+        self.client.set_http(config.http_client(&self.proxy)?);
+        self.client.set_socket(config.socket_path.clone(), Duration::from_secs(config.request_timeout_secs.max(1)));
+        self.grpc = match config.transport {
+            BridgeTransport::Http => None,
+            BridgeTransport::Grpc => {
+                let mut grpc = GrpcTransport::new(&config.grpc_url, &config)?;
+                if let Some(auth) = self.client.auth() {
+                    grpc.set_auth(auth.clone());
+                }
+                Some(grpc)
+            }
+        };
+        match (&config.socket_path, config.transport) {
+            (Some(socket), transport) => tracing::info!("Using {:?} bridge on socket {}", transport, socket.display()),
+            (None, BridgeTransport::Grpc) => tracing::info!("Using gRPC bridge at {}", config.grpc_url),
+            (None, BridgeTransport::Http) => {}
+        }
+        self.config = config;
+        Ok(self)
+    }
+    
+    /// Override how long commands of one type may run
+    pub fn with_timeout(mut self, command_type: impl Into<String>, timeout: Duration) -> Self {
+        self.config.timeouts.insert(command_type.into(), timeout.as_secs());
+        self
+    }
+    
+    /// Sign every request with a shared secret and reject unsigned responses
+    pub fn with_auth(mut self, auth: BridgeAuth) -> Self {
+        if let Some(grpc) = &mut self.grpc {
+            grpc.set_auth(auth.clone());
+        }
+        self.client.set_auth(auth);
+        self
+    }
+    
+    /// Broadcast bridge status changes to clients
+    pub fn with_events(mut self, ws_server: Arc<WebSocketServer>) -> Self {
+        self.events = Some(ws_server);
+        self
+    }
+    
+    /// Send dark-web searches through Tor when it is configured
+    pub fn with_tor(mut self, tor: Arc<TorService>) -> Self {
+        self.tor = Some(tor);
+        self
+    }
+    
+    /// Send the bridge's HTTP traffic through an egress proxy, except to
+    /// `no_proxy` hosts such as a local bridge
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Result<Self> {
+        self.client.set_http(self.config.http_client(&proxy)?);
+        self.proxy = proxy;
+        Ok(self)
+    }
+    
+    /// Current health of the bridge
+    pub fn status(&self) -> BridgeStatus {
+        let status = self.breaker.status();
+        if self.is_reachable() {
+            return status;
+        }
+        BridgeStatus {
+            state: BridgeState::Unreachable,
+            last_error: self.unreachable_reason.lock().clone().or(status.last_error),
+            ..status
+        }
+    }
+    
+    /// Whether calls are expected to succeed; tasks needing the bridge wait otherwise
+    pub fn is_ready(&self) -> bool {
+        self.status().state == BridgeState::Healthy
+    }
+    
+    fn is_reachable(&self) -> bool {
+        self.reachable.load(Ordering::SeqCst)
+    }
+    
+    fn set_reachable(&self, reachable: bool, reason: Option<String>) {
+        self.reachable.store(reachable, Ordering::SeqCst);
+        *self.unreachable_reason.lock() = reason;
+    }
+    
+    /// Probe `GET /health`, or the `Health` RPC
+    pub async fn health(&self) -> Result<Value> {
+        if let Some(grpc) = &self.grpc {
+            return grpc.health(HEALTH_TIMEOUT).await;
+        }
+        self.client
+            .send(Method::GET, "/health", None, None, Some(HEALTH_TIMEOUT))
+            .await?
+            .error_for_status()?
+            .json()
+    }
+    
+    /// Probe once, updating and announcing the bridge status
+    pub async fn check_health(&self) -> BridgeStatus {
+        match self.health().await {
+            Ok(_) => {
+                self.set_reachable(true, None);
+                self.breaker.close_if_cooled();
+            }
+            Err(e) => self.set_reachable(false, Some(format!("health check failed: {:#}", e))),
+        }
+        self.publish_status();
+        self.status()
+    }
+    
+    /// Probe on startup and then periodically, until the process exits
+    pub async fn run_health_checks(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(Duration::from_secs(self.config.health_interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            self.check_health().await;
+        }
+    }
+    
+    /// Log and broadcast the status if its state changed since last announced
+    fn publish_status(&self) {
+        let status = self.status();
+        {
+            let mut announced = self.announced.lock();
+            if *announced == Some(status.state) {
+                return;
+            }
+            *announced = Some(status.state);
+        }
+        
+        match status.state {
+            BridgeState::Healthy => tracing::info!("Python bridge ready"),
+            BridgeState::Degraded => tracing::warn!(
+                "Python bridge degraded after {} consecutive failures",
+                status.consecutive_failures,
+            ),
+            BridgeState::Unreachable => tracing::warn!(
+                "Python bridge unreachable: {}",
+                status.last_error.as_deref().unwrap_or("unknown error"),
+            ),
+        }
+        if let Some(events) = &self.events {
+            events.broadcast(WSEvent::BridgeStatus { status });
         }
     }
     
     /// Execute a Python command
+    ///
+    /// The command runs as a bridge job (or gRPC call) that is cancelled if the
+    /// returned future is dropped. A `traceparent` already present in the command continues that trace;
+    /// otherwise a new trace is started. Either way the bridge receives a
+    /// child span in both the JSON body and the `traceparent` header.
     pub async fn execute(&self, command: Value) -> Result<Value> {
-        let url = format!("{}/execute", self.base_url);
+        self.execute_streaming(command, None).await
+    }
+    
+    /// Execute a command, forwarding tool output lines to `output` when the
+    /// transport streams them
+    #[tracing::instrument(skip(self, command, output), fields(command_type, trace_id))]
+    async fn execute_streaming(&self, mut command: Value, mut output: Option<&mut OutputStream>) -> Result<Value> {
+        let command_type = command.get("type")
+            .and_then(|t| t.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+        
+        let trace = command.get("traceparent")
+            .and_then(|t| t.as_str())
+            .and_then(TraceContext::parse_traceparent)
+            .map(|parent| parent.child())
+            .unwrap_or_else(TraceContext::new_root);
+        if let Some(obj) = command.as_object_mut() {
+            obj.insert("traceparent".to_string(), Value::String(trace.traceparent()));
+            obj.insert("trace_id".to_string(), Value::String(trace.trace_id.clone()));
+        }
+        
+        let span = tracing::Span::current();
+        span.record("command_type", command_type.as_str());
+        span.record("trace_id", trace.trace_id.as_str());
         
+        let started = Instant::now();
+        let result = self.send_with_retry(&command, &command_type, &trace, output.as_deref_mut()).await;
+        
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        METRICS.bridge_latency
+            .with_label_values(&[&command_type])
+            .observe(started.elapsed().as_secs_f64());
+        METRICS.bridge_requests
+            .with_label_values(&[&command_type, outcome])
+            .inc();
+        
+        result
+    }
+    
+    /// Send through the circuit breaker, retrying with jittered backoff.
+    ///
+    /// Refused connections are retried for every command, since nothing
+    /// reached the bridge; other failures only for idempotent commands.
+    async fn send_with_retry(
+        &self,
+        command: &Value,
+        command_type: &str,
+        trace: &TraceContext,
+        mut output: Option<&mut OutputStream>,
+    ) -> Result<Value> {
+        self.breaker.admit().map_err(|reason| anyhow!(reason))?;
+        
+        let idempotent = IDEMPOTENT_COMMANDS.contains(&command_type);
+        let timeout = self.config.timeout_for(command_type);
+        let mut delay = Duration::from_millis(self.config.initial_backoff_ms);
+        let mut attempt = 0;
+        let result = loop {
+            let attempt_result = match &self.grpc {
+                Some(grpc) => grpc.call(command, trace, timeout, output.as_deref_mut()).await,
+                None => self.run_job(command, trace, timeout).await,
+            };
+            match attempt_result {
+                Ok(value) => break Ok(value),
+                Err(e) if attempt < self.config.max_retries && (idempotent || is_refused(&e)) => {
+                    let wait = jitter(delay);
+                    tracing::warn!("Bridge {} attempt {} failed: {:#}; retrying in {}ms", command_type, attempt + 1, e, wait.as_millis());
+                    tokio::time::sleep(wait).await;
+                    delay = (delay * 2).min(Duration::from_millis(self.config.max_backoff_ms));
+                    attempt += 1;
+                }
+                Err(e) => break Err(e),
+            }
+        };
+        
+        match &result {
+            Ok(_) => {
+                self.set_reachable(true, None);
+                self.breaker.record_success();
+            }
+            Err(e) => {
+                if is_refused(e) {
+                    self.set_reachable(false, Some(format!("{:#}", e)));
+                }
+                self.breaker.record_failure(&format!("{:#}", e));
+            }
+        }
+        self.publish_status();
+        result
+    }
+    
+    /// POST a command to `/execute` and wait up to `timeout` for the JSON response
+    async fn post_execute(&self, command: &Value, trace: &TraceContext, timeout: Duration) -> Result<Value> {
         let response = self.client
-            .post(&url)
-            .json(&command)
-            .send()
+            .send(Method::POST, "/execute", Some(command), Some(trace), Some(timeout))
             .await?;
+        if response.status.is_server_error() {
+            bail!("Python bridge returned HTTP {}", response.status);
+        }
         
-        let result = response.json::<Value>().await?;
-        Ok(result)
+        response.json()
     }
     
-    /// Execute a tool
-    pub async fn execute_tool(&self, tool_name: &str, target: &str, args: Value) -> Result<Value> {
-        let command = serde_json::json!({
+    /// Execute a tool, continuing the caller's trace if given and streaming
+    /// its output to `output` where the transport supports it. With a
+    /// `proxy`, the tool's traffic must go through it.
+    pub async fn execute_tool(
+        &self,
+        tool_name: &str,
+        target: &str,
+        args: Value,
+        proxy: Option<&str>,
+        trace: Option<&TraceContext>,
+        output: Option<&mut OutputStream>,
+    ) -> Result<Value> {
+        let mut command = serde_json::json!({
             "type": "tool_execute",
             "tool": tool_name,
             "target": target,
             "args": args,
+            "proxy": proxy,
         });
+        if let Some(trace) = trace {
+            command["traceparent"] = Value::String(trace.traceparent());
+        }
         
-        self.execute(command).await
+        self.execute_streaming(command, output).await
     }
     
-    /// Generate AI response
-    pub async fn ai_generate(&self, prompt: &str, model: Option<&str>) -> Result<String> {
-        let command = serde_json::json!({
+    /// Generate an AI response, returning it with the model the bridge reports
+    pub async fn ai_generate(&self, prompt: &str, model: Option<&str>, trace: Option<&TraceContext>) -> Result<(String, Option<String>)> {
+        let mut command = serde_json::json!({
             "type": "ai_generate",
             "prompt": prompt,
             "model": model,
         });
+        if let Some(trace) = trace {
+            command["traceparent"] = Value::String(trace.traceparent());
+        }
         
         let result = self.execute(command).await?;
+        if !result["success"].as_bool().unwrap_or(false) {
+            bail!("{}", result["error"].as_str().unwrap_or("AI generation failed"));
+        }
         
-        Ok(result["response"]
-            .as_str()
-            .unwrap_or("")
-            .to_string())
+        let data = &result["data"];
+        let response = data["response"].as_str()
+            .ok_or_else(|| anyhow!("Python bridge returned no response"))?;
+        Ok((response.to_string(), data["model"].as_str().map(str::to_string)))
     }
     
-    /// Robin dark web search
+    /// Robin dark web search, through Tor when it is configured
     pub async fn robin_search(&self, query: &str) -> Result<Value> {
+        let proxy = match &self.tor {
+            Some(tor) => tor.route()?,
+            None => None,
+        };
         let command = serde_json::json!({
             "type": "robin_search",
             "query": query,
+            "proxy": proxy,
         });
         
-        self.execute(command).await
+        let result = self.execute(command).await;
+        if let (Some(tor), Some(_)) = (&self.tor, &proxy) {
+            tor.observe(&result);
+        }
+        result
     }
     
     /// Browser automation action
@@ -86,3 +612,16 @@ impl PythonBridge {
         self.execute(command).await
     }
 }
+
+/// Whether a request failed before reaching the bridge
+fn is_refused(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_connect())
+        || error.downcast_ref::<hyper::Error>().is_some_and(|e| e.is_connect())
+        || error.downcast_ref::<RpcError>().is_some_and(RpcError::is_unavailable)
+}
+
+/// Random delay between half and all of `delay`
+fn jitter(delay: Duration) -> Duration {
+    let millis = delay.as_millis() as u64;
+    Duration::from_millis(rand::thread_rng().gen_range(millis / 2..=millis))
+}
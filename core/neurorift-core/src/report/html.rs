@@ -0,0 +1,235 @@
+use std::fmt::Write;
+use crate::report::{utc_offset, Attachment, ReportContext};
+use crate::state::{ArtifactType, Severity};
+
+/// Inline stylesheet so the report is a single self-contained file
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; color: #1f2328; max-width: 960px; margin: 2rem auto; padding: 0 1.5rem; line-height: 1.5; }
+h1 { border-bottom: 3px solid #6e40c9; padding-bottom: .4rem; }
+h2 { margin-top: 2.2rem; border-bottom: 1px solid #d0d7de; padding-bottom: .3rem; }
+table { border-collapse: collapse; width: 100%; margin: 1rem 0; }
+th, td { border: 1px solid #d0d7de; padding: .4rem .6rem; text-align: left; vertical-align: top; }
+th { background: #f6f8fa; }
+code { background: #f6f8fa; padding: .1rem .3rem; border-radius: 4px; font-size: .9em; }
+.finding { border: 1px solid #d0d7de; border-left-width: 6px; border-radius: 6px; padding: .6rem 1rem; margin: 1rem 0; }
+.badge { display: inline-block; color: #fff; border-radius: 10px; padding: 0 .6rem; font-size: .8em; font-weight: 600; }
+.meta { color: #57606a; font-size: .9em; }
+figure { margin: .8rem 0; } figure img { max-width: 100%; border: 1px solid #d0d7de; } figcaption { color: #57606a; font-size: .85em; }
+.critical { border-left-color: #8b0000; } .badge.critical { background: #8b0000; }
+.high { border-left-color: #d1242f; } .badge.high { background: #d1242f; }
+.medium { border-left-color: #bf8700; } .badge.medium { background: #bf8700; }
+.low { border-left-color: #0969da; } .badge.low { background: #0969da; }
+.info { border-left-color: #57606a; } .badge.info { background: #57606a; }
+"#;
+
+/// Render a report as a styled, self-contained HTML document
+pub fn render(ctx: &ReportContext) -> String {
+    let mut out = String::new();
+    
+    let _ = write!(out, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n", escape(&ctx.title), STYLE);
+    let _ = writeln!(out, "<h1>{}</h1>", escape(&ctx.title));
+    let _ = writeln!(out, "<p class=\"meta\">Session <code>{}</code> · {:?} mode · {} — {} · generated {}</p>",
+        escape(&ctx.session_id),
+        ctx.mode,
+        ctx.started_at.format("%Y-%m-%d %H:%M UTC"),
+        ctx.last_activity.format("%Y-%m-%d %H:%M UTC"),
+        ctx.generated_at.format("%Y-%m-%d %H:%M UTC"),
+    );
+    
+    // Executive summary
+    let _ = writeln!(out, "<h2>Executive Summary</h2>\n<p>{}</p>", escape(&ctx.summary.narrative));
+    out.push_str("<table><tr><th>Severity</th><th>Count</th></tr>\n");
+    for count in &ctx.summary.by_severity {
+        let _ = writeln!(out, "<tr><td><span class=\"badge {}\">{:?}</span></td><td>{}</td></tr>", class(&count.severity), count.severity, count.count);
+    }
+    out.push_str("</table>\n");
+    
+    // Scope and rules of engagement
+    out.push_str("<h2>Scope</h2>\n");
+    if ctx.scope.is_empty() && ctx.targets.is_empty() {
+        out.push_str("<p>No scope restrictions were defined for this engagement.</p>\n");
+    } else {
+        out.push_str("<ul>\n");
+        item(&mut out, "Networks", ctx.scope.cidrs.iter().map(|c| c.to_string()));
+        item(&mut out, "Domains", ctx.scope.domains.iter().cloned());
+        item(&mut out, "URL patterns", ctx.scope.url_patterns.iter().cloned());
+        item(&mut out, "Exclusions", ctx.scope.exclusions.iter().cloned());
+        out.push_str("</ul>\n");
+    }
+    let mut roe = Vec::new();
+    for (label, in_scope) in [("Targets", true), ("Out-of-scope targets", false)] {
+        let targets: Vec<String> = ctx.targets.iter().filter(|t| t.in_scope == in_scope).map(|t| escape(&t.value)).collect();
+        if !targets.is_empty() {
+            roe.push(format!("<li><strong>{}:</strong> {}</li>", label, targets.join(", ")));
+        }
+    }
+    if let Some(window) = &ctx.roe.allowed_hours {
+        roe.push(format!("<li><strong>Testing window:</strong> {}–{} (UTC{})</li>", window.start.format("%H:%M"), window.end.format("%H:%M"), utc_offset(window.utc_offset_minutes)));
+    }
+    if let Some(rate) = ctx.roe.max_requests_per_minute {
+        roe.push(format!("<li><strong>Rate limit:</strong> {} tasks per minute</li>", rate));
+    }
+    if !ctx.roe.forbidden_techniques.is_empty() {
+        let techniques: Vec<String> = ctx.roe.forbidden_techniques.iter().map(|t| format!("{:?}", t)).collect();
+        roe.push(format!("<li><strong>Forbidden techniques:</strong> {}</li>", techniques.join(", ")));
+    }
+    if !roe.is_empty() {
+        let _ = writeln!(out, "<ul>\n{}\n</ul>", roe.join("\n"));
+    }
+    
+    // Methodology
+    out.push_str("<h2>Methodology</h2>\n");
+    if ctx.tools.is_empty() {
+        out.push_str("<p>No tools were run.</p>\n");
+    } else {
+        out.push_str("<table><tr><th>Tool</th><th>Runs</th><th>Completed</th><th>Failed</th><th>Targets</th></tr>\n");
+        for tool in &ctx.tools {
+            let _ = writeln!(out, "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape(&tool.tool), tool.runs, tool.completed, tool.failed, escape(&tool.targets.join(", ")));
+        }
+        out.push_str("</table>\n");
+    }
+    
+    // Assets
+    if !ctx.assets.is_empty() {
+        out.push_str("<h2>Assets</h2>\n<table><tr><th>Address</th><th>Hostnames</th><th>OS</th><th>Services</th></tr>\n");
+        for asset in &ctx.assets {
+            let addresses: Vec<String> = asset.addresses.iter().map(|a| a.to_string()).collect();
+            let services: Vec<String> = asset.services.iter().map(|s| escape(&s.label())).collect();
+            let _ = writeln!(out, "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                addresses.join(", "), escape(&asset.hostnames.join(", ")), escape(asset.os_guess.as_deref().unwrap_or("—")), services.join("<br>"));
+        }
+        out.push_str("</table>\n");
+    }
+    
+    // Findings
+    out.push_str("<h2>Findings</h2>\n");
+    if ctx.finding_groups.is_empty() {
+        out.push_str("<p>No findings were identified.</p>\n");
+    }
+    for group in &ctx.finding_groups {
+        let _ = writeln!(out, "<h3>{:?} ({})</h3>", group.severity, group.findings.len());
+        for entry in &group.findings {
+            let finding = &entry.finding;
+            let _ = writeln!(out, "<div class=\"finding {}\">", class(&finding.severity));
+            let _ = writeln!(out, "<h4><span class=\"badge {}\">{:?}</span> {}</h4>", class(&finding.severity), finding.severity, escape(&finding.title));
+            
+            let mut meta = vec![format!("Source: {}", escape(&finding.tool_source))];
+            if let Some(target) = &finding.target {
+                meta.push(format!("Target: {}", escape(target)));
+            }
+            if let (Some(vector), Some(score)) = (&finding.cvss_vector, finding.cvss_score) {
+                meta.push(format!("CVSS {:.1} <code>{}</code>", score, escape(vector)));
+            }
+            meta.push(format!("First seen {}", finding.discovered_at.format("%Y-%m-%d %H:%M UTC")));
+            if finding.seen_count > 1 {
+                meta.push(format!("Seen {} times", finding.seen_count));
+            }
+            let _ = writeln!(out, "<p class=\"meta\">{}</p>", meta.join(" · "));
+            let _ = writeln!(out, "<p>{}</p>", escape(&finding.description));
+            
+            if let Some(nvd) = finding.details.get("nvd").and_then(|v| v.as_object()) {
+                let links: Vec<String> = nvd.keys()
+                    .map(|cve| format!("<a href=\"https://nvd.nist.gov/vuln/detail/{0}\">{0}</a>", escape(cve)))
+                    .collect();
+                let _ = writeln!(out, "<p class=\"meta\">References: {}</p>", links.join(", "));
+            }
+            
+            for attachment in &entry.attachments {
+                evidence(&mut out, attachment);
+            }
+            out.push_str("</div>\n");
+        }
+    }
+    
+    // Artifacts
+    out.push_str("<h2>Artifacts</h2>\n");
+    if ctx.artifacts.is_empty() {
+        out.push_str("<p>No artifacts were collected.</p>\n");
+    } else {
+        out.push_str("<table><tr><th>Name</th><th>Type</th><th>Path</th><th>Created</th></tr>\n");
+        for artifact in &ctx.artifacts {
+            let _ = writeln!(out, "<tr><td>{}</td><td>{:?}</td><td><code>{}</code></td><td>{}</td></tr>",
+                escape(&artifact.name), artifact.artifact_type, escape(&artifact.path), artifact.created_at.format("%Y-%m-%d %H:%M"));
+        }
+        out.push_str("</table>\n");
+    }
+    
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Render one evidence attachment; screenshots are embedded so the report stays self-contained
+fn evidence(out: &mut String, attachment: &Attachment) {
+    let artifact = &attachment.artifact;
+    let caption = escape(attachment.caption.as_deref().unwrap_or(&artifact.name));
+    
+    let embedded = match artifact.artifact_type {
+        ArtifactType::Screenshot => image_data_uri(&artifact.path),
+        _ => None,
+    };
+    match embedded {
+        Some(uri) => {
+            let _ = writeln!(out, "<figure><img src=\"{}\" alt=\"{}\"><figcaption>{}</figcaption></figure>", uri, caption, caption);
+        }
+        None => {
+            let _ = writeln!(out, "<p class=\"meta\">Evidence: {} <code>{}</code></p>", caption, escape(&artifact.path));
+        }
+    }
+}
+
+/// Screenshots larger than this are linked rather than embedded
+const MAX_EMBED_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Read an image into a `data:` URI, if it exists and is small enough
+fn image_data_uri(path: &str) -> Option<String> {
+    use base64::Engine;
+    
+    if std::fs::metadata(path).ok()?.len() > MAX_EMBED_BYTES {
+        return None;
+    }
+    let mime = match std::path::Path::new(path).extension()?.to_string_lossy().to_lowercase().as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        _ => return None,
+    };
+    let bytes = std::fs::read(path).ok()?;
+    Some(format!("data:{};base64,{}", mime, base64::engine::general_purpose::STANDARD.encode(bytes)))
+}
+
+/// CSS class for a severity
+fn class(severity: &Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "critical",
+        Severity::High => "high",
+        Severity::Medium => "medium",
+        Severity::Low => "low",
+        Severity::Info => "info",
+    }
+}
+
+/// List item with a comma-separated list, skipped when empty
+fn item(out: &mut String, label: &str, items: impl Iterator<Item = String>) {
+    let items: Vec<String> = items.map(|i| escape(&i)).collect();
+    if !items.is_empty() {
+        let _ = writeln!(out, "<li><strong>{}:</strong> {}</li>", label, items.join(", "));
+    }
+}
+
+/// Escape text for HTML
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
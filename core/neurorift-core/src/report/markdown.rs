@@ -0,0 +1,138 @@
+use std::fmt::Write;
+use crate::report::{utc_offset, ReportContext};
+use crate::state::ArtifactType;
+
+/// Render a report as Markdown
+pub fn render(ctx: &ReportContext) -> String {
+    let mut out = String::new();
+    
+    let _ = writeln!(out, "# {}\n", ctx.title);
+    let _ = writeln!(out, "| | |\n|---|---|");
+    let _ = writeln!(out, "| Session | `{}` |", ctx.session_id);
+    let _ = writeln!(out, "| Mode | {:?} |", ctx.mode);
+    let _ = writeln!(out, "| Period | {} — {} |", ctx.started_at.format("%Y-%m-%d %H:%M UTC"), ctx.last_activity.format("%Y-%m-%d %H:%M UTC"));
+    let _ = writeln!(out, "| Generated | {} |\n", ctx.generated_at.format("%Y-%m-%d %H:%M UTC"));
+    
+    // Executive summary
+    let _ = writeln!(out, "## Executive Summary\n\n{}\n", ctx.summary.narrative);
+    let _ = writeln!(out, "| Severity | Count |\n|---|---|");
+    for count in &ctx.summary.by_severity {
+        let _ = writeln!(out, "| {:?} | {} |", count.severity, count.count);
+    }
+    out.push('\n');
+    
+    // Scope and rules of engagement
+    let _ = writeln!(out, "## Scope\n");
+    if ctx.scope.is_empty() && ctx.targets.is_empty() {
+        let _ = writeln!(out, "No scope restrictions were defined for this engagement.\n");
+    } else {
+        list(&mut out, "Networks", ctx.scope.cidrs.iter().map(|c| c.to_string()));
+        list(&mut out, "Domains", ctx.scope.domains.iter().cloned());
+        list(&mut out, "URL patterns", ctx.scope.url_patterns.iter().cloned());
+        list(&mut out, "Exclusions", ctx.scope.exclusions.iter().cloned());
+    }
+    list(&mut out, "Targets", ctx.targets.iter().filter(|t| t.in_scope).map(|t| t.value.clone()));
+    list(&mut out, "Out-of-scope targets", ctx.targets.iter().filter(|t| !t.in_scope).map(|t| t.value.clone()));
+    if let Some(window) = &ctx.roe.allowed_hours {
+        let _ = writeln!(out, "- **Testing window:** {}–{} (UTC{})", window.start.format("%H:%M"), window.end.format("%H:%M"), utc_offset(window.utc_offset_minutes));
+    }
+    if let Some(rate) = ctx.roe.max_requests_per_minute {
+        let _ = writeln!(out, "- **Rate limit:** {} tasks per minute", rate);
+    }
+    if !ctx.roe.forbidden_techniques.is_empty() {
+        let techniques: Vec<String> = ctx.roe.forbidden_techniques.iter().map(|t| format!("{:?}", t)).collect();
+        let _ = writeln!(out, "- **Forbidden techniques:** {}", techniques.join(", "));
+    }
+    out.push('\n');
+    
+    // Methodology
+    let _ = writeln!(out, "## Methodology\n");
+    if ctx.tools.is_empty() {
+        let _ = writeln!(out, "No tools were run.\n");
+    } else {
+        let _ = writeln!(out, "| Tool | Runs | Completed | Failed | Targets |\n|---|---|---|---|---|");
+        for tool in &ctx.tools {
+            let _ = writeln!(out, "| {} | {} | {} | {} | {} |", tool.tool, tool.runs, tool.completed, tool.failed, tool.targets.join(", "));
+        }
+        out.push('\n');
+    }
+    
+    // Assets
+    if !ctx.assets.is_empty() {
+        let _ = writeln!(out, "## Assets\n");
+        let _ = writeln!(out, "| Address | Hostnames | OS | Services |\n|---|---|---|---|");
+        for asset in &ctx.assets {
+            let addresses: Vec<String> = asset.addresses.iter().map(|a| a.to_string()).collect();
+            let services: Vec<String> = asset.services.iter().map(|s| s.label()).collect();
+            let _ = writeln!(out, "| {} | {} | {} | {} |", addresses.join(", "), asset.hostnames.join(", "), asset.os_guess.as_deref().unwrap_or("—"), services.join("<br>"));
+        }
+        out.push('\n');
+    }
+    
+    // Findings
+    let _ = writeln!(out, "## Findings\n");
+    if ctx.finding_groups.is_empty() {
+        let _ = writeln!(out, "No findings were identified.\n");
+    }
+    for group in &ctx.finding_groups {
+        let _ = writeln!(out, "### {:?} ({})\n", group.severity, group.findings.len());
+        for entry in &group.findings {
+            let finding = &entry.finding;
+            let _ = writeln!(out, "#### {}\n", finding.title);
+            if let Some(target) = &finding.target {
+                let _ = writeln!(out, "- **Target:** {}", target);
+            }
+            let _ = writeln!(out, "- **Source:** {}", finding.tool_source);
+            if let (Some(vector), Some(score)) = (&finding.cvss_vector, finding.cvss_score) {
+                let _ = writeln!(out, "- **CVSS:** {:.1} (`{}`)", score, vector);
+            }
+            let _ = writeln!(out, "- **First seen:** {}", finding.discovered_at.format("%Y-%m-%d %H:%M UTC"));
+            if finding.seen_count > 1 {
+                let _ = writeln!(out, "- **Seen:** {} times", finding.seen_count);
+            }
+            if let Some(nvd) = finding.details.get("nvd").and_then(|v| v.as_object()) {
+                let cves: Vec<&String> = nvd.keys().collect();
+                let _ = writeln!(out, "- **References:** {}", cves.iter().map(|c| c.as_str()).collect::<Vec<_>>().join(", "));
+            }
+            let _ = writeln!(out, "\n{}\n", finding.description);
+            
+            if !entry.attachments.is_empty() {
+                let _ = writeln!(out, "**Evidence**\n");
+                for attachment in &entry.attachments {
+                    let artifact = &attachment.artifact;
+                    let caption = attachment.caption.as_deref().unwrap_or(&artifact.name);
+                    match artifact.artifact_type {
+                        ArtifactType::Screenshot => {
+                            let _ = writeln!(out, "![{}]({})\n", caption, artifact.path);
+                        }
+                        _ => {
+                            let _ = writeln!(out, "- {} (`{}`)", caption, artifact.path);
+                        }
+                    }
+                }
+                out.push('\n');
+            }
+        }
+    }
+    
+    // Artifacts
+    let _ = writeln!(out, "## Artifacts\n");
+    if ctx.artifacts.is_empty() {
+        let _ = writeln!(out, "No artifacts were collected.");
+    } else {
+        let _ = writeln!(out, "| Name | Type | Path | Created |\n|---|---|---|---|");
+        for artifact in &ctx.artifacts {
+            let _ = writeln!(out, "| {} | {:?} | `{}` | {} |", artifact.name, artifact.artifact_type, artifact.path, artifact.created_at.format("%Y-%m-%d %H:%M"));
+        }
+    }
+    
+    out
+}
+
+/// Bullet with a comma-separated list, skipped when empty
+fn list(out: &mut String, label: &str, items: impl Iterator<Item = String>) {
+    let items: Vec<String> = items.collect();
+    if !items.is_empty() {
+        let _ = writeln!(out, "- **{}:** {}", label, items.join(", "));
+    }
+}
@@ -0,0 +1,300 @@
+pub mod html;
+pub mod markdown;
+pub mod pdf;
+pub mod template;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::report::template::TemplateLibrary;
+use crate::security::roe::RulesOfEngagement;
+use crate::security::scope::EngagementScope;
+use crate::state::{Artifact, Asset, Finding, OperationalMode, SessionState, SessionStatus, Severity, Target, TaskStatus};
+
+/// Report output format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Html,
+    Pdf,
+}
+
+impl ReportFormat {
+    /// File extension for the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+            ReportFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// Everything a report renders, derived from a session
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportContext {
+    pub title: String,
+    pub session_id: String,
+    pub session_name: String,
+    pub mode: OperationalMode,
+    pub status: SessionStatus,
+    pub generated_at: DateTime<Utc>,
+    pub started_at: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
+    pub summary: Summary,
+    pub scope: EngagementScope,
+    pub targets: Vec<Target>,
+    pub roe: RulesOfEngagement,
+    pub tools: Vec<ToolUsage>,
+    pub assets: Vec<Asset>,
+    /// Findings grouped by severity, most severe first; empty groups omitted
+    pub finding_groups: Vec<FindingGroup>,
+    pub artifacts: Vec<Artifact>,
+}
+
+/// Executive summary figures
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    pub total_findings: usize,
+    pub by_severity: Vec<SeverityCount>,
+    pub highest_severity: Option<Severity>,
+    pub tasks_total: usize,
+    pub tasks_completed: usize,
+    pub tasks_failed: usize,
+    /// One-paragraph plain-language overview
+    pub narrative: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeverityCount {
+    pub severity: Severity,
+    pub count: usize,
+}
+
+/// How a tool was used during the engagement
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolUsage {
+    pub tool: String,
+    pub runs: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub targets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FindingGroup {
+    pub severity: Severity,
+    pub findings: Vec<ReportFinding>,
+}
+
+/// A finding with its evidence resolved to artifacts
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportFinding {
+    #[serde(flatten)]
+    pub finding: Finding,
+    pub attachments: Vec<Attachment>,
+}
+
+/// An evidence artifact and what it shows
+#[derive(Debug, Clone, Serialize)]
+pub struct Attachment {
+    pub caption: Option<String>,
+    pub artifact: Artifact,
+}
+
+/// Session metadata key holding a written executive summary, used in
+/// place of the generated narrative
+pub const EXECUTIVE_SUMMARY_KEY: &str = "executive_summary";
+
+/// Severities from most to least severe
+const SEVERITY_ORDER: [Severity; 5] = [Severity::Critical, Severity::High, Severity::Medium, Severity::Low, Severity::Info];
+
+impl ReportContext {
+    /// Build the report context for a session
+    pub fn from_session(session: &SessionState) -> Self {
+        let finding_groups: Vec<FindingGroup> = SEVERITY_ORDER.iter()
+            .map(|severity| {
+                let mut findings: Vec<ReportFinding> = session.findings.iter()
+                    .filter(|f| &f.severity == severity)
+                    .map(|f| ReportFinding {
+                        attachments: attachments(session, f),
+                        finding: f.clone(),
+                    })
+                    .collect();
+                // Highest CVSS first within a severity band
+                findings.sort_by(|a, b| {
+                    b.finding.cvss_score.unwrap_or(0.0).total_cmp(&a.finding.cvss_score.unwrap_or(0.0))
+                });
+                FindingGroup { severity: severity.clone(), findings }
+            })
+            .filter(|group| !group.findings.is_empty())
+            .collect();
+        
+        let by_severity: Vec<SeverityCount> = SEVERITY_ORDER.iter()
+            .map(|severity| SeverityCount {
+                severity: severity.clone(),
+                count: session.findings.iter().filter(|f| &f.severity == severity).count(),
+            })
+            .collect();
+        
+        let mut tools: BTreeMap<String, ToolUsage> = BTreeMap::new();
+        for task in &session.task_queue {
+            let usage = tools.entry(task.tool_name.clone()).or_insert_with(|| ToolUsage {
+                tool: task.tool_name.clone(),
+                runs: 0,
+                completed: 0,
+                failed: 0,
+                targets: Vec::new(),
+            });
+            usage.runs += 1;
+            match task.status {
+                TaskStatus::Completed => usage.completed += 1,
+                TaskStatus::Failed => usage.failed += 1,
+                _ => {}
+            }
+            if !usage.targets.contains(&task.target) {
+                usage.targets.push(task.target.clone());
+            }
+        }
+        
+        let count = |status: TaskStatus| session.task_queue.iter().filter(|t| t.status == status).count();
+        let mut summary = Summary {
+            total_findings: session.findings.len(),
+            highest_severity: finding_groups.first().map(|g| g.severity.clone()),
+            by_severity,
+            tasks_total: session.task_queue.len(),
+            tasks_completed: count(TaskStatus::Completed),
+            tasks_failed: count(TaskStatus::Failed),
+            narrative: String::new(),
+        };
+        summary.narrative = session.metadata.get(EXECUTIVE_SUMMARY_KEY)
+            .cloned()
+            .unwrap_or_else(|| narrative(session, &summary, tools.len()));
+        
+        Self {
+            title: format!("{} — Engagement Report", session.name),
+            session_id: session.id.clone(),
+            session_name: session.name.clone(),
+            mode: session.mode,
+            status: session.status,
+            generated_at: Utc::now(),
+            started_at: session.created_at,
+            last_activity: session.updated_at,
+            summary,
+            scope: session.scope.clone(),
+            targets: session.targets.clone(),
+            roe: session.roe.clone(),
+            tools: tools.into_values().collect(),
+            assets: session.assets.clone(),
+            finding_groups,
+            artifacts: session.artifacts.clone(),
+        }
+    }
+}
+
+/// Resolve a finding's evidence references, skipping artifacts that no longer exist
+fn attachments(session: &SessionState, finding: &Finding) -> Vec<Attachment> {
+    finding.evidence.iter()
+        .filter_map(|evidence| {
+            let artifact = session.artifacts.iter().find(|a| a.id == evidence.artifact_id)?;
+            Some(Attachment {
+                caption: evidence.caption.clone(),
+                artifact: artifact.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Plain-language overview for the executive summary
+fn narrative(session: &SessionState, summary: &Summary, tool_count: usize) -> String {
+    let mut text = format!(
+        "Between {} and {}, {} tasks were run using {} tools. ",
+        session.created_at.format("%Y-%m-%d"),
+        session.updated_at.format("%Y-%m-%d"),
+        summary.tasks_total,
+        tool_count,
+    );
+    
+    match &summary.highest_severity {
+        None => text.push_str("No findings were identified."),
+        Some(highest) => {
+            let notable: Vec<String> = summary.by_severity.iter()
+                .filter(|c| c.count > 0)
+                .map(|c| format!("{} {:?}", c.count, c.severity))
+                .collect();
+            text.push_str(&format!(
+                "The assessment identified {} findings ({}); the highest severity observed was {:?}.",
+                summary.total_findings,
+                notable.join(", "),
+                highest,
+            ));
+        }
+    }
+    text
+}
+
+/// Format a UTC offset in minutes as `+HH:MM`
+fn utc_offset(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    format!("{}{:02}:{:02}", sign, minutes.abs() / 60, minutes.abs() % 60)
+}
+
+/// Render a session report as text; PDF reports render as the HTML they are printed from
+pub fn render(session: &SessionState, format: ReportFormat) -> String {
+    let context = ReportContext::from_session(session);
+    match format {
+        ReportFormat::Markdown => markdown::render(&context),
+        ReportFormat::Html | ReportFormat::Pdf => html::render(&context),
+    }
+}
+
+/// Writes reports under `<base>/reports/<session_id>/`
+pub struct ReportGenerator {
+    reports_dir: PathBuf,
+    templates: TemplateLibrary,
+}
+
+impl ReportGenerator {
+    /// Report generator using the reports and templates directories under `base_dir`
+    pub fn new(base_dir: &Path) -> Self {
+        Self {
+            reports_dir: base_dir.join("reports"),
+            templates: TemplateLibrary::new(base_dir.join("templates")),
+        }
+    }
+    
+    /// User-supplied report templates
+    pub fn templates(&self) -> &TemplateLibrary {
+        &self.templates
+    }
+    
+    /// Render a report, optionally through a user template, and write it to disk
+    pub fn generate(&self, session: &SessionState, format: ReportFormat, template: Option<&str>) -> Result<PathBuf> {
+        let body = match template {
+            Some(name) => {
+                let html = matches!(format, ReportFormat::Html | ReportFormat::Pdf);
+                self.templates.render(name, &ReportContext::from_session(session), html)?
+            }
+            None => render(session, format),
+        };
+        
+        let dir = self.reports_dir.join(&session.id);
+        fs::create_dir_all(&dir).context("Failed to create reports directory")?;
+        let path = dir.join(format!(
+            "report_{}.{}",
+            Utc::now().format("%Y%m%d_%H%M%S"),
+            format.extension(),
+        ));
+        
+        match format {
+            ReportFormat::Pdf => pdf::write(&body, &path)?,
+            _ => fs::write(&path, body).context("Failed to write report")?,
+        }
+        Ok(path)
+    }
+}
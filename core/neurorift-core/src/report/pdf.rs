@@ -0,0 +1,127 @@
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use crate::tools::which;
+
+/// Environment variable naming the browser or converter binary to use
+const RENDERER_ENV: &str = "NEURORIFT_PDF_RENDERER";
+
+/// Headless browsers searched for on `PATH`, in order of preference
+const BROWSERS: [&str; 5] = ["chromium", "chromium-browser", "google-chrome", "google-chrome-stable", "microsoft-edge"];
+
+/// Give up on a renderer that has not finished after this long
+const RENDER_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A program that can print HTML to PDF
+#[derive(Debug, Clone)]
+enum Renderer {
+    /// Chromium-family browser in headless mode
+    Browser(PathBuf),
+    /// wkhtmltopdf
+    Wkhtmltopdf(PathBuf),
+}
+
+impl Renderer {
+    /// Pick the renderer from the environment override or `PATH`
+    fn detect() -> Option<Self> {
+        if let Ok(path) = std::env::var(RENDERER_ENV) {
+            let path = PathBuf::from(path);
+            let is_wkhtml = path.file_name()
+                .map(|n| n.to_string_lossy().starts_with("wkhtmltopdf"))
+                .unwrap_or(false);
+            return Some(if is_wkhtml { Renderer::Wkhtmltopdf(path) } else { Renderer::Browser(path) });
+        }
+        
+        BROWSERS.iter()
+            .find_map(|name| which(name).map(Renderer::Browser))
+            .or_else(|| which("wkhtmltopdf").map(Renderer::Wkhtmltopdf))
+    }
+    
+    /// Command that converts `html` into `pdf`
+    fn command(&self, html: &Path, pdf: &Path) -> Command {
+        match self {
+            Renderer::Browser(path) => {
+                let mut cmd = Command::new(path);
+                cmd.arg("--headless").arg("--disable-gpu");
+                // Chromium refuses to start sandboxed as root
+                if running_as_root() {
+                    cmd.arg("--no-sandbox");
+                }
+                cmd.arg("--no-pdf-header-footer")
+                    .arg(format!("--print-to-pdf={}", pdf.display()))
+                    .arg(format!("file://{}", html.display()));
+                cmd
+            }
+            Renderer::Wkhtmltopdf(path) => {
+                let mut cmd = Command::new(path);
+                // Report content must not pull in other local files
+                cmd.arg("--quiet")
+                    .arg("--disable-local-file-access")
+                    .arg(html)
+                    .arg(pdf);
+                cmd
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn running_as_root() -> bool {
+    // SAFETY: geteuid cannot fail
+    unsafe { libc::geteuid() == 0 }
+}
+
+#[cfg(not(unix))]
+fn running_as_root() -> bool {
+    false
+}
+
+/// Print an HTML report to `pdf` with a headless browser.
+///
+/// The HTML is staged next to the output so relative links resolve and
+/// is removed afterwards.
+pub fn write(html: &str, pdf: &Path) -> Result<()> {
+    let Some(renderer) = Renderer::detect() else {
+        bail!("No PDF renderer found; install Chromium or wkhtmltopdf, or set {}", RENDERER_ENV);
+    };
+    
+    let staged = pdf.with_extension("pdf.html");
+    std::fs::write(&staged, html).context("Failed to stage report HTML")?;
+    let staged = staged.canonicalize()?;
+    let result = run(&renderer, &staged, pdf);
+    let _ = std::fs::remove_file(&staged);
+    result?;
+    
+    if !pdf.exists() {
+        bail!("PDF renderer exited without producing {:?}", pdf);
+    }
+    Ok(())
+}
+
+/// Run the renderer, killing it if it exceeds the timeout
+fn run(renderer: &Renderer, html: &Path, pdf: &Path) -> Result<()> {
+    tracing::debug!("Rendering PDF with {:?}", renderer);
+    let mut child = renderer.command(html, pdf)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to start PDF renderer {:?}", renderer))?;
+    
+    let started = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            if !status.success() {
+                bail!("PDF renderer failed with {}", status);
+            }
+            return Ok(());
+        }
+        if started.elapsed() > RENDER_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("PDF renderer timed out after {}s", RENDER_TIMEOUT.as_secs());
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
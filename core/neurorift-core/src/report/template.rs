@@ -0,0 +1,93 @@
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+use tera::Tera;
+use crate::report::ReportContext;
+
+/// User-supplied Tera report templates, loaded from `<base>/templates`.
+///
+/// Templates are re-read on every render so new or edited files take
+/// effect without a restart. Each template receives the full
+/// `ReportContext`; files ending in `.html`, and every template rendered
+/// to HTML or PDF, are auto-escaped.
+pub struct TemplateLibrary {
+    dir: PathBuf,
+}
+
+impl TemplateLibrary {
+    /// Template library rooted at `dir`
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+    
+    /// Directory templates are loaded from
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+    
+    /// Names of the available templates, relative to the template directory
+    pub fn list(&self) -> Result<Vec<String>> {
+        let tera = self.load()?;
+        let mut names: Vec<String> = tera.get_template_names().map(str::to_string).collect();
+        names.sort();
+        Ok(names)
+    }
+    
+    /// Render the named template against a report context, escaping
+    /// whatever it interpolates when `html` is set
+    pub fn render(&self, name: &str, context: &ReportContext, html: bool) -> Result<String> {
+        let mut tera = self.load()?;
+        if html {
+            // Every name ends in the empty suffix
+            tera.autoescape_on(vec![""]);
+        }
+        if !tera.get_template_names().any(|n| n == name) {
+            return Err(anyhow!("Report template '{}' not found in {:?}", name, self.dir));
+        }
+        
+        let context = tera::Context::from_serialize(context)?;
+        tera.render(name, &context)
+            .map_err(|e| anyhow!(error_chain(&e)))
+    }
+    
+    /// Parse every template in the directory
+    fn load(&self) -> Result<Tera> {
+        if !self.dir.is_dir() {
+            return Ok(Tera::default());
+        }
+        
+        let glob = format!("{}/**/*", self.dir.display());
+        Tera::new(&glob)
+            .map_err(|e| anyhow!(error_chain(&e)))
+            .with_context(|| format!("Failed to load report templates from {:?}", self.dir))
+    }
+}
+
+/// Tera hides the useful detail (line, column, filter) in the error source chain
+fn error_chain(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{OperationalMode, SessionState};
+    
+    #[test]
+    fn html_output_is_escaped_whatever_the_template_name() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("report.md"), "{{ session_name }}").unwrap();
+        let library = TemplateLibrary::new(dir.path());
+        let session = SessionState::new("<img src=x onerror=alert(1)>".to_string(), OperationalMode::Offensive);
+        let context = ReportContext::from_session(&session);
+        
+        assert_eq!(library.render("report.md", &context, false).unwrap(), session.name);
+        assert_eq!(library.render("report.md", &context, true).unwrap(), "&lt;img src=x onerror=alert(1)&gt;");
+    }
+}
@@ -0,0 +1,246 @@
+pub mod syn;
+
+use anyhow::{bail, Context, Result};
+use futures_util::stream::{self, StreamExt};
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use crate::executor::stream::OutputStream;
+use crate::security::scope::target_host;
+use crate::state::asset::{Protocol, Service};
+use crate::state::AssetObservation;
+use crate::websocket::events::TaskResult;
+
+/// Name the scanner is registered under
+pub const TOOL_NAME: &str = "portscan";
+
+/// Largest network one task may sweep
+const MAX_HOSTS: usize = 1024;
+
+/// Ports scanned when a task names none, roughly the most common services
+pub const DEFAULT_PORTS: &[u16] = &[
+    21, 22, 23, 25, 53, 80, 81, 88, 110, 111, 135, 139, 143, 389, 443, 445, 465, 587, 636, 873,
+    993, 995, 1080, 1433, 1521, 1723, 2049, 2375, 2376, 3000, 3128, 3306, 3389, 4443, 5000, 5432,
+    5601, 5900, 5985, 5986, 6379, 6443, 7001, 8000, 8008, 8080, 8081, 8088, 8443, 8888, 9000,
+    9090, 9200, 9443, 10250, 11211, 27017,
+];
+
+/// How a host's ports are probed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanMethod {
+    /// Half-open with raw sockets; IPv4 only and needs `CAP_NET_RAW`
+    Syn,
+    /// Full TCP handshake, works anywhere
+    Connect,
+}
+
+impl ScanMethod {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Syn => "syn",
+            Self::Connect => "connect",
+        }
+    }
+}
+
+/// What to scan and how hard
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    pub ports: Vec<u16>,
+    /// `None` uses SYN where permitted and connect otherwise
+    pub method: Option<ScanMethod>,
+    /// Per-connection timeout, and how long SYN waits for late answers
+    pub timeout: Duration,
+    /// Connections in flight at once per host
+    pub concurrency: usize,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            ports: DEFAULT_PORTS.to_vec(),
+            method: None,
+            timeout: Duration::from_millis(1000),
+            concurrency: 256,
+        }
+    }
+}
+
+/// Open ports found on one host
+#[derive(Debug, Clone)]
+pub struct HostScan {
+    pub address: IpAddr,
+    pub hostname: Option<String>,
+    pub open: Vec<u16>,
+    pub method: ScanMethod,
+}
+
+/// Ports from a spec like `22,80,8000-8100`
+pub fn parse_ports(specs: &[String]) -> Result<Vec<u16>> {
+    let mut ports = BTreeSet::new();
+    for spec in specs.iter().flat_map(|s| s.split(',')).map(str::trim).filter(|s| !s.is_empty()) {
+        let (start, end) = spec.split_once('-').unwrap_or((spec, spec));
+        let parse = |port: &str| port.trim().parse::<u16>().ok().filter(|p| *p > 0);
+        let (Some(start), Some(end)) = (parse(start), parse(end)) else {
+            bail!("Invalid port '{}'", spec);
+        };
+        if start > end {
+            bail!("Invalid port range '{}'", spec);
+        }
+        ports.extend(start..=end);
+    }
+    Ok(ports.into_iter().collect())
+}
+
+/// Addresses behind a target: an IP, a CIDR range, or a hostname or URL
+/// resolved here
+pub async fn resolve_targets(target: &str) -> Result<Vec<(IpAddr, Option<String>)>> {
+    let target = target.trim();
+    if let Ok(net) = target.parse::<IpNet>() {
+        let hosts: Vec<IpAddr> = match net.prefix_len() == net.max_prefix_len() {
+            true => vec![net.addr()],
+            false => net.hosts().take(MAX_HOSTS + 1).collect(),
+        };
+        if hosts.len() > MAX_HOSTS {
+            bail!("{} has more than {} hosts; split it into smaller ranges", net, MAX_HOSTS);
+        }
+        return Ok(hosts.into_iter().map(|ip| (ip, None)).collect());
+    }
+    
+    let host = target_host(target).with_context(|| format!("No host in target '{}'", target))?;
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(vec![(ip, None)]);
+    }
+    let mut addresses: Vec<IpAddr> = tokio::net::lookup_host((host.as_str(), 0)).await
+        .with_context(|| format!("Failed to resolve {}", host))?
+        .map(|address| address.ip())
+        .collect();
+    addresses.sort();
+    addresses.dedup();
+    Ok(addresses.into_iter().map(|ip| (ip, Some(host.clone()))).collect())
+}
+
+/// Scan one host, falling back to connect scanning when SYN is not
+/// possible or fails
+pub async fn scan_host(address: IpAddr, options: &ScanOptions) -> Result<(Vec<u16>, ScanMethod)> {
+    let syn_allowed = matches!(address, IpAddr::V4(_)) && syn::is_permitted();
+    match (options.method, address) {
+        (Some(ScanMethod::Syn), _) if !syn_allowed => {
+            bail!("SYN scanning {} needs IPv4 and raw socket access (root or CAP_NET_RAW)", address);
+        }
+        (Some(ScanMethod::Syn) | None, IpAddr::V4(v4)) if syn_allowed => {
+            let (ports, timeout) = (options.ports.clone(), options.timeout);
+            match tokio::task::spawn_blocking(move || syn::scan(v4, &ports, timeout)).await? {
+                Ok(open) => return Ok((open, ScanMethod::Syn)),
+                Err(e) if options.method.is_none() => {
+                    tracing::warn!("SYN scan of {} failed, using connect scan: {}", address, e);
+                }
+                Err(e) => return Err(e).with_context(|| format!("SYN scan of {} failed", address)),
+            }
+        }
+        _ => {}
+    }
+    Ok((connect_scan(address, options).await, ScanMethod::Connect))
+}
+
+async fn connect_scan(address: IpAddr, options: &ScanOptions) -> Vec<u16> {
+    let mut open: Vec<u16> = stream::iter(options.ports.iter().copied())
+        .map(|port| async move {
+            let connect = TcpStream::connect(SocketAddr::new(address, port));
+            matches!(tokio::time::timeout(options.timeout, connect).await, Ok(Ok(_))).then_some(port)
+        })
+        .buffer_unordered(options.concurrency.max(1))
+        .filter_map(|port| async move { port })
+        .collect()
+        .await;
+    open.sort_unstable();
+    open
+}
+
+/// Run the scanner as a task: hosts and open ports are printed as found and
+/// returned as `structured_data.assets`
+pub async fn run_task(target: &str, options: &ScanOptions, mut stream: Option<&mut OutputStream>) -> Result<TaskResult> {
+    let started = Instant::now();
+    let hosts = resolve_targets(target).await?;
+    if hosts.is_empty() {
+        bail!("{} did not resolve to any address", target);
+    }
+    
+    let mut output = String::new();
+    let mut print = |line: String| {
+        if let Some(stream) = stream.as_deref_mut() {
+            stream.push(&line);
+        }
+        output.push_str(&line);
+        output.push('\n');
+    };
+    let sweep = hosts.len() > 1;
+    let mut scans = Vec::new();
+    for (address, hostname) in hosts {
+        let (open, method) = scan_host(address, options).await?;
+        let host = match &hostname {
+            Some(name) => format!("{} ({})", address, name),
+            None => address.to_string(),
+        };
+        // Sweeps only list hosts with something open
+        if sweep && open.is_empty() {
+            continue;
+        }
+        print(format!("Host: {} [{} scan, {} ports]", host, method.label(), options.ports.len()));
+        for port in &open {
+            print(format!("  {}/tcp open", port));
+        }
+        scans.push(HostScan { address, hostname, open, method });
+    }
+    if let Some(stream) = stream {
+        stream.flush();
+    }
+    
+    let assets: Vec<AssetObservation> = scans.iter().map(observation).collect();
+    Ok(TaskResult {
+        success: true,
+        output,
+        structured_data: Some(serde_json::json!({ "assets": assets })),
+        duration_ms: started.elapsed().as_millis() as u64,
+        exit_code: None,
+        stderr: None,
+    })
+}
+
+/// Asset observation for a scanned host
+pub fn observation(scan: &HostScan) -> AssetObservation {
+    AssetObservation {
+        address: Some(scan.address),
+        hostname: scan.hostname.clone(),
+        services: scan.open.iter().map(|port| Service::new(*port, Protocol::Tcp)).collect(),
+        ..Default::default()
+    }
+}
+
+/// Hosts and open ports back from the scanner's printed output
+pub fn parse_output(output: &str) -> Vec<AssetObservation> {
+    let mut observations: Vec<AssetObservation> = Vec::new();
+    for line in output.lines() {
+        if let Some(host) = line.strip_prefix("Host: ") {
+            let host = host.split(" [").next().unwrap_or(host);
+            let (address, hostname) = match host.split_once(" (") {
+                Some((address, name)) => (address, Some(name.trim_end_matches(')').to_string())),
+                None => (host, None),
+            };
+            observations.push(AssetObservation {
+                address: address.trim().parse().ok(),
+                hostname,
+                ..Default::default()
+            });
+        } else if let Some(port) = line.trim().strip_suffix("/tcp open").and_then(|p| p.parse().ok()) {
+            if let Some(observation) = observations.last_mut() {
+                observation.services.push(Service::new(port, Protocol::Tcp));
+            }
+        }
+    }
+    observations
+}
@@ -0,0 +1,176 @@
+use rand::Rng;
+use std::collections::BTreeSet;
+use std::io;
+use std::net::{Ipv4Addr, UdpSocket};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::time::{Duration, Instant};
+
+const TCP_HEADER_LEN: usize = 20;
+
+const SYN: u8 = 0x02;
+const RST: u8 = 0x04;
+const ACK: u8 = 0x10;
+
+/// How long each receive waits before checking the deadline again
+const RECV_POLL: Duration = Duration::from_millis(100);
+
+/// Whether this process may open raw sockets (root or `CAP_NET_RAW`)
+pub fn is_permitted() -> bool {
+    raw_socket().is_ok()
+}
+
+/// Half-open scan: send one SYN per port and collect the ports that answer
+/// SYN-ACK within `timeout` of the last probe. The kernel resets those
+/// connections itself, since no socket owns them.
+///
+/// Blocking; run it off the async runtime.
+pub fn scan(target: Ipv4Addr, ports: &[u16], timeout: Duration) -> io::Result<Vec<u16>> {
+    let socket = raw_socket()?;
+    let source = source_address(target)?;
+    let source_port: u16 = rand::thread_rng().gen_range(40000..60000);
+    set_receive_timeout(&socket, RECV_POLL)?;
+    
+    let mut open = BTreeSet::new();
+    for &port in ports {
+        let segment = syn_segment(source, target, source_port, port);
+        send_to(&socket, &segment, target)?;
+        // Drain as we go so a long port list does not overflow the buffer
+        while let Some(port) = receive(&socket, target, source_port, Duration::ZERO)? {
+            open.extend(port);
+        }
+    }
+    
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if let Some(port) = receive(&socket, target, source_port, RECV_POLL)? {
+            open.extend(port);
+        }
+    }
+    Ok(open.into_iter().collect())
+}
+
+fn raw_socket() -> io::Result<OwnedFd> {
+    // SAFETY: plain socket(2); the descriptor is owned from here on
+    let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_RAW, libc::IPPROTO_TCP) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// Local address the kernel would use to reach `target`
+fn source_address(target: Ipv4Addr) -> io::Result<Ipv4Addr> {
+    let probe = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    probe.connect((target, 9))?;
+    match probe.local_addr()?.ip() {
+        std::net::IpAddr::V4(address) => Ok(address),
+        std::net::IpAddr::V6(_) => Err(io::Error::new(io::ErrorKind::Unsupported, "no IPv4 route to target")),
+    }
+}
+
+fn set_receive_timeout(socket: &OwnedFd, timeout: Duration) -> io::Result<()> {
+    let value = libc::timeval {
+        tv_sec: timeout.as_secs() as libc::time_t,
+        tv_usec: timeout.subsec_micros() as libc::suseconds_t,
+    };
+    // SAFETY: `value` outlives the call and its size is passed alongside
+    let result = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_RCVTIMEO,
+            &value as *const libc::timeval as *const libc::c_void,
+            std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn send_to(socket: &OwnedFd, segment: &[u8], target: Ipv4Addr) -> io::Result<()> {
+    let address = libc::sockaddr_in {
+        sin_family: libc::AF_INET as libc::sa_family_t,
+        sin_port: 0,
+        sin_addr: libc::in_addr { s_addr: u32::from(target).to_be() },
+        sin_zero: [0; 8],
+    };
+    // SAFETY: buffer and address are valid for the stated lengths
+    let sent = unsafe {
+        libc::sendto(
+            socket.as_raw_fd(),
+            segment.as_ptr() as *const libc::c_void,
+            segment.len(),
+            0,
+            &address as *const libc::sockaddr_in as *const libc::sockaddr,
+            std::mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+        )
+    };
+    if sent < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Read one packet. `Some(Some(port))` is a SYN-ACK from the target to our
+/// probe port, `Some(None)` anything else, `None` nothing to read.
+fn receive(socket: &OwnedFd, target: Ipv4Addr, source_port: u16, wait: Duration) -> io::Result<Option<Option<u16>>> {
+    let flags = if wait.is_zero() { libc::MSG_DONTWAIT } else { 0 };
+    let mut buffer = [0u8; 1500];
+    // SAFETY: the buffer is valid for its length
+    let read = unsafe { libc::recv(socket.as_raw_fd(), buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), flags) };
+    if read < 0 {
+        let error = io::Error::last_os_error();
+        return match error.kind() {
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut | io::ErrorKind::Interrupted => Ok(None),
+            _ => Err(error),
+        };
+    }
+    Ok(Some(syn_ack_port(&buffer[..read as usize], target, source_port)))
+}
+
+/// Port that answered SYN-ACK, from an IPv4 packet carrying TCP
+fn syn_ack_port(packet: &[u8], target: Ipv4Addr, source_port: u16) -> Option<u16> {
+    let header_len = usize::from(packet.first()? & 0x0f) * 4;
+    if packet.get(9) != Some(&(libc::IPPROTO_TCP as u8)) || packet.get(12..16)? != target.octets() {
+        return None;
+    }
+    let tcp = packet.get(header_len..header_len + TCP_HEADER_LEN)?;
+    let port = u16::from_be_bytes([tcp[0], tcp[1]]);
+    let destination = u16::from_be_bytes([tcp[2], tcp[3]]);
+    let flags = tcp[13];
+    (destination == source_port && flags & (SYN | ACK) == SYN | ACK && flags & RST == 0).then_some(port)
+}
+
+/// A bare SYN with a valid checksum; the kernel adds the IP header
+fn syn_segment(source: Ipv4Addr, target: Ipv4Addr, source_port: u16, port: u16) -> [u8; TCP_HEADER_LEN] {
+    let mut segment = [0u8; TCP_HEADER_LEN];
+    segment[0..2].copy_from_slice(&source_port.to_be_bytes());
+    segment[2..4].copy_from_slice(&port.to_be_bytes());
+    segment[4..8].copy_from_slice(&rand::thread_rng().gen::<u32>().to_be_bytes());
+    segment[12] = ((TCP_HEADER_LEN / 4) as u8) << 4;
+    segment[13] = SYN;
+    segment[14..16].copy_from_slice(&1024u16.to_be_bytes());
+    let checksum = tcp_checksum(source, target, &segment);
+    segment[16..18].copy_from_slice(&checksum.to_be_bytes());
+    segment
+}
+
+/// One's-complement sum over the IPv4 pseudo-header and the segment
+fn tcp_checksum(source: Ipv4Addr, target: Ipv4Addr, segment: &[u8]) -> u16 {
+    let mut pseudo = Vec::with_capacity(12 + segment.len());
+    pseudo.extend_from_slice(&source.octets());
+    pseudo.extend_from_slice(&target.octets());
+    pseudo.extend_from_slice(&[0, libc::IPPROTO_TCP as u8]);
+    pseudo.extend_from_slice(&(segment.len() as u16).to_be_bytes());
+    pseudo.extend_from_slice(segment);
+    
+    let mut sum: u32 = pseudo.chunks(2)
+        .map(|pair| u32::from(u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)])))
+        .sum();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
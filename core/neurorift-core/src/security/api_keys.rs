@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use crate::security::rbac::Permission;
+
+/// API key store file name under the base directory
+const API_KEYS_FILE: &str = "api_keys.json";
+
+/// Prefix identifying NeuroRift API keys
+const KEY_PREFIX: &str = "nrk_";
+
+/// A stored API key. Only the SHA-256 of the secret is kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyRecord {
+    pub id: String,
+    pub name: String,
+    /// First characters of the key, for recognising it in listings
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<Permission>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// API key details safe to show to clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyInfo {
+    pub id: String,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<Permission>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl From<&ApiKeyRecord> for ApiKeyInfo {
+    fn from(record: &ApiKeyRecord) -> Self {
+        Self {
+            id: record.id.clone(),
+            name: record.name.clone(),
+            prefix: record.prefix.clone(),
+            scopes: record.scopes.clone(),
+            created_at: record.created_at,
+            revoked_at: record.revoked_at,
+            last_used_at: record.last_used_at,
+        }
+    }
+}
+
+/// Persistent store of scoped API keys
+pub struct ApiKeyStore {
+    path: PathBuf,
+    keys: RwLock<Vec<ApiKeyRecord>>,
+}
+
+impl ApiKeyStore {
+    /// Load the key store from the base directory
+    pub fn load(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let path = base_dir.as_ref().join(API_KEYS_FILE);
+        let keys = if path.exists() {
+            let json = fs::read_to_string(&path)
+                .context("Failed to read API key store")?;
+            serde_json::from_str(&json)
+                .context("Failed to parse API key store")?
+        } else {
+            Vec::new()
+        };
+        
+        Ok(Self {
+            path,
+            keys: RwLock::new(keys),
+        })
+    }
+    
+    /// Create a key, returning its details and the plaintext secret.
+    /// The secret is not recoverable afterwards.
+    pub fn create(&self, name: String, scopes: Vec<Permission>) -> Result<(ApiKeyInfo, String)> {
+        let secret = format!("{}{}{}", KEY_PREFIX, Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let record = ApiKeyRecord {
+            id: format!("key_{}", &Uuid::new_v4().simple().to_string()[..8]),
+            name,
+            prefix: secret[..KEY_PREFIX.len() + 6].to_string(),
+            key_hash: hash_key(&secret),
+            scopes,
+            created_at: Utc::now(),
+            revoked_at: None,
+            last_used_at: None,
+        };
+        let info = ApiKeyInfo::from(&record);
+        
+        let mut keys = self.keys.write();
+        keys.push(record);
+        self.persist(&keys)?;
+        
+        tracing::info!("API key created: {} ({})", info.id, info.name);
+        Ok((info, secret))
+    }
+    
+    /// Revoke a key by ID
+    pub fn revoke(&self, key_id: &str) -> Result<ApiKeyInfo> {
+        let mut keys = self.keys.write();
+        let record = keys.iter_mut()
+            .find(|k| k.id == key_id)
+            .with_context(|| format!("Unknown API key: {}", key_id))?;
+        record.revoked_at.get_or_insert_with(Utc::now);
+        let info = ApiKeyInfo::from(&*record);
+        self.persist(&keys)?;
+        
+        tracing::info!("API key revoked: {}", key_id);
+        Ok(info)
+    }
+    
+    /// List all keys (including revoked ones)
+    pub fn list(&self) -> Vec<ApiKeyInfo> {
+        self.keys.read().iter().map(ApiKeyInfo::from).collect()
+    }
+    
+    /// Whether any active key exists
+    pub fn has_active_keys(&self) -> bool {
+        self.keys.read().iter().any(|k| k.revoked_at.is_none())
+    }
+    
+    /// Look up an active key by its plaintext secret
+    pub fn authenticate(&self, secret: &str) -> Option<ApiKeyInfo> {
+        if !secret.starts_with(KEY_PREFIX) {
+            return None;
+        }
+        
+        let hash = hash_key(secret);
+        let mut keys = self.keys.write();
+        let record = keys.iter_mut()
+            .find(|k| k.revoked_at.is_none() && k.key_hash == hash)?;
+        record.last_used_at = Some(Utc::now());
+        let info = ApiKeyInfo::from(&*record);
+        
+        if let Err(e) = self.persist(&keys) {
+            tracing::warn!("Failed to record API key use: {}", e);
+        }
+        Some(info)
+    }
+    
+    /// Write the store with owner-only permissions
+    fn persist(&self, keys: &[ApiKeyRecord]) -> Result<()> {
+        let json = serde_json::to_string_pretty(keys)?;
+        fs::write(&self.path, json).context("Failed to write API key store")?;
+        
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&self.path, fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    }
+}
+
+/// SHA-256 of a key secret as hex
+fn hash_key(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
@@ -1,2 +1,189 @@
-// Placeholder for approval workflow logic
-// This will be expanded in future iterations
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use crate::state::{Actor, ApprovalRequest, ApprovalStatus, RiskLevel, SessionState, TaskStatus};
+
+/// Approval policy file name under the base directory
+const APPROVALS_FILE: &str = "approvals.json";
+
+/// How long approvals may stay pending before they are denied by default
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalPolicy {
+    /// Minutes before a pending approval expires
+    #[serde(default = "default_expire_after_minutes")]
+    pub expire_after_minutes: u64,
+    /// Per-risk-level overrides of `expire_after_minutes`
+    #[serde(default)]
+    pub risk_overrides: HashMap<RiskLevel, u64>,
+    /// Minutes a pending approval waits before (each further) escalation
+    #[serde(default = "default_escalate_after_minutes")]
+    pub escalate_after_minutes: u64,
+    /// Escalations per approval before paging stops
+    #[serde(default = "default_max_escalations")]
+    pub max_escalations: u32,
+}
+
+impl Default for ApprovalPolicy {
+    fn default() -> Self {
+        Self {
+            expire_after_minutes: default_expire_after_minutes(),
+            risk_overrides: HashMap::new(),
+            escalate_after_minutes: default_escalate_after_minutes(),
+            max_escalations: default_max_escalations(),
+        }
+    }
+}
+
+fn default_expire_after_minutes() -> u64 {
+    60
+}
+
+fn default_escalate_after_minutes() -> u64 {
+    15
+}
+
+fn default_max_escalations() -> u32 {
+    3
+}
+
+impl ApprovalPolicy {
+    /// Load the approval policy from the base directory (defaults if absent)
+    pub fn load(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let path = base_dir.as_ref().join(APPROVALS_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let json = fs::read_to_string(&path)
+            .context("Failed to read approval policy")?;
+        let policy = serde_json::from_str(&json)
+            .context("Failed to parse approval policy")?;
+        
+        Ok(policy)
+    }
+    
+    /// When an approval expires under this policy
+    pub fn deadline(&self, approval: &ApprovalRequest) -> DateTime<Utc> {
+        let minutes = self.risk_overrides
+            .get(&approval.action.risk_level)
+            .copied()
+            .unwrap_or(self.expire_after_minutes);
+        approval.created_at + Duration::minutes(minutes as i64)
+    }
+}
+
+/// Operator decision on a pending approval
+#[derive(Debug, Clone)]
+pub enum Decision {
+    Approve,
+    Deny { reason: Option<String> },
+}
+
+/// Result of applying a decision to a session
+#[derive(Debug, Clone)]
+pub struct DecisionOutcome {
+    pub approval: ApprovalRequest,
+    /// Task released back to the queue or cancelled by the decision
+    pub gated_task: Option<String>,
+    pub decided_at: DateTime<Utc>,
+}
+
+/// Apply a decision to a pending approval and the task it gates.
+///
+/// Approving moves a held task back to `Queued`; denying cancels it.
+pub fn decide(session: &mut SessionState, approval_id: &str, decision: &Decision, decided_by: Actor) -> Result<DecisionOutcome> {
+    let Some(approval) = session.approval_queue.iter_mut().find(|a| a.id == approval_id) else {
+        bail!("Unknown approval: {}", approval_id);
+    };
+    if approval.status != ApprovalStatus::Pending {
+        bail!("Approval {} was already decided ({:?})", approval_id, approval.status);
+    }
+    
+    let decided_at = Utc::now();
+    approval.status = match decision {
+        Decision::Approve => ApprovalStatus::Approved,
+        Decision::Deny { .. } => ApprovalStatus::Denied,
+    };
+    approval.decided_by = Some(decided_by);
+    approval.decided_at = Some(decided_at);
+    let approval = approval.clone();
+    
+    let gated_task = session.task_queue.iter_mut()
+        .find(|t| t.approval_id.as_deref() == Some(approval_id) && t.status == TaskStatus::AwaitingApproval)
+        .map(|task| {
+            match decision {
+                Decision::Approve => task.status = TaskStatus::Queued,
+                Decision::Deny { .. } => {
+                    task.status = TaskStatus::Cancelled;
+                    task.completed_at = Some(decided_at);
+                }
+            }
+            task.id.clone()
+        });
+    
+    session.touch();
+    Ok(DecisionOutcome { approval, gated_task, decided_at })
+}
+
+/// Escalate approvals that have waited too long since creation or their
+/// last escalation, returning the updated requests.
+pub fn escalate(session: &mut SessionState, policy: &ApprovalPolicy, now: DateTime<Utc>) -> Vec<ApprovalRequest> {
+    let interval = Duration::minutes(policy.escalate_after_minutes as i64);
+    let mut escalated = Vec::new();
+    
+    for approval in session.approval_queue.iter_mut() {
+        if approval.status != ApprovalStatus::Pending || approval.escalation_level >= policy.max_escalations {
+            continue;
+        }
+        let since = approval.escalated_at.unwrap_or(approval.created_at);
+        if now - since < interval {
+            continue;
+        }
+        
+        approval.escalation_level += 1;
+        approval.escalated_at = Some(now);
+        escalated.push(approval.clone());
+    }
+    
+    if !escalated.is_empty() {
+        session.touch();
+    }
+    escalated
+}
+
+/// Expire pending approvals past their deadline, failing the tasks they gate.
+///
+/// Returns each expired approval with the ID of its gated task, if any.
+pub fn expire(session: &mut SessionState, policy: &ApprovalPolicy, now: DateTime<Utc>) -> Vec<(ApprovalRequest, Option<String>)> {
+    let mut expired = Vec::new();
+    
+    for approval in session.approval_queue.iter_mut() {
+        let deadline = approval.expires_at.unwrap_or_else(|| policy.deadline(approval));
+        if approval.status == ApprovalStatus::Pending && deadline <= now {
+            approval.status = ApprovalStatus::Expired;
+            approval.decided_at = Some(now);
+            expired.push(approval.clone());
+        }
+    }
+    
+    let expired: Vec<_> = expired.into_iter()
+        .map(|approval| {
+            let gated_task = session.task_queue.iter_mut()
+                .find(|t| t.approval_id.as_ref() == Some(&approval.id) && t.status == TaskStatus::AwaitingApproval)
+                .map(|task| {
+                    task.status = TaskStatus::Failed;
+                    task.completed_at = Some(now);
+                    task.id.clone()
+                });
+            (approval, gated_task)
+        })
+        .collect();
+    
+    if !expired.is_empty() {
+        session.touch();
+    }
+    expired
+}
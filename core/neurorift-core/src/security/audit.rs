@@ -1,2 +1,328 @@
-// Placeholder for audit logging logic
-// This will be expanded in future iterations
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tokio::sync::broadcast;
+use crate::state::Actor;
+use crate::websocket::ClientCommand;
+
+/// Hash preceding the first record
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Field names whose values never reach the audit log
+const REDACTED_FIELDS: &[&str] = &["value", "secret", "password", "token", "api_key"];
+
+/// Records buffered for slow subscribers such as the syslog sink
+const RECORD_BUFFER: usize = 256;
+
+/// A single audit record.
+///
+/// Records form a hash chain: each `hash` covers the record contents and
+/// the previous record's hash, so editing or deleting any line breaks
+/// verification of everything after it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub seq: u64,
+    pub timestamp: DateTime<Utc>,
+    pub actor: Actor,
+    pub client_id: Option<String>,
+    pub command: String,
+    pub target: Option<String>,
+    pub details: Value,
+    /// Whether the action was carried out (`accepted`) or refused (`denied`)
+    pub outcome: String,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditRecord {
+    /// Hash of the record contents chained to `prev_hash`
+    fn compute_hash(&self) -> String {
+        let content = serde_json::json!({
+            "seq": self.seq,
+            "timestamp": self.timestamp,
+            "actor": self.actor,
+            "client_id": self.client_id,
+            "command": self.command,
+            "target": self.target,
+            "details": self.details,
+            "outcome": self.outcome,
+        });
+        
+        let mut hasher = Sha256::new();
+        hasher.update(self.prev_hash.as_bytes());
+        hasher.update(content.to_string().as_bytes());
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// Chain position after the last written record
+struct ChainHead {
+    file: File,
+    next_seq: u64,
+    last_hash: String,
+}
+
+/// Append-only, hash-chained audit log
+pub struct AuditLog {
+    path: PathBuf,
+    head: Mutex<ChainHead>,
+    /// Each record once written
+    records: broadcast::Sender<AuditRecord>,
+}
+
+impl AuditLog {
+    /// Open (or create) the audit log under the base directory
+    pub fn new(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let audit_dir = base_dir.as_ref().join("audit");
+        fs::create_dir_all(&audit_dir)
+            .context("Failed to create audit directory")?;
+        let path = audit_dir.join("audit.log");
+        
+        // Continue the chain from the last record on disk
+        let (next_seq, last_hash) = match recover(&path)?.last() {
+            Some(last) => (last.seq + 1, last.hash.clone()),
+            None => (0, GENESIS_HASH.to_string()),
+        };
+        
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .context("Failed to open audit log")?;
+        
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+        }
+        
+        Ok(Self {
+            path,
+            head: Mutex::new(ChainHead { file, next_seq, last_hash }),
+            records: broadcast::channel(RECORD_BUFFER).0,
+        })
+    }
+    
+    /// Record an action
+    pub fn record(
+        &self,
+        actor: Actor,
+        client_id: Option<String>,
+        command: impl Into<String>,
+        target: Option<String>,
+        details: Value,
+        outcome: impl Into<String>,
+    ) -> Result<()> {
+        let mut head = self.head.lock();
+        
+        let mut record = AuditRecord {
+            seq: head.next_seq,
+            timestamp: Utc::now(),
+            actor,
+            client_id,
+            command: command.into(),
+            target,
+            details,
+            outcome: outcome.into(),
+            prev_hash: head.last_hash.clone(),
+            hash: String::new(),
+        };
+        record.hash = record.compute_hash();
+        
+        writeln!(head.file, "{}", serde_json::to_string(&record)?)
+            .context("Failed to write audit record")?;
+        head.file.sync_data()?;
+        
+        head.next_seq += 1;
+        head.last_hash = record.hash.clone();
+        let _ = self.records.send(record);
+        Ok(())
+    }
+    
+    /// Receive every record written from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<AuditRecord> {
+        self.records.subscribe()
+    }
+    
+    /// Record a command received from a client
+    pub fn record_command(&self, command: &ClientCommand, outcome: &str) -> Result<()> {
+        let Value::Object(mut fields) = serde_json::to_value(&command.event)? else {
+            bail!("Command did not serialize to an object");
+        };
+        
+        let name = fields.remove("type")
+            .and_then(|t| t.as_str().map(str::to_string))
+            .unwrap_or_else(|| "unknown".to_string());
+        let target = ["target", "session_id", "approval_id"].iter()
+            .find_map(|key| fields.get(*key).and_then(|v| v.as_str()))
+            .map(str::to_string);
+        
+        let mut details = Value::Object(fields);
+        redact(&mut details);
+        
+        self.record(
+            command.client.identity.clone(),
+            Some(command.client.client_id.clone()),
+            name,
+            target,
+            details,
+            outcome,
+        )
+    }
+    
+    /// Every record written so far
+    pub fn records(&self) -> Result<Vec<AuditRecord>> {
+        let _head = self.head.lock();
+        read_records(&self.path)
+    }
+    
+    /// Verify the whole chain, returning the number of intact records
+    pub fn verify(&self) -> Result<u64> {
+        // Hold the lock so no record is appended mid-verification
+        let _head = self.head.lock();
+        
+        let mut prev_hash = GENESIS_HASH.to_string();
+        let mut count = 0;
+        for (expected_seq, record) in read_records(&self.path)?.into_iter().enumerate() {
+            if record.seq != expected_seq as u64 {
+                bail!("Audit log sequence gap at record {}", expected_seq);
+            }
+            if record.prev_hash != prev_hash {
+                bail!("Audit log chain broken at record {}", record.seq);
+            }
+            if record.compute_hash() != record.hash {
+                bail!("Audit log record {} was modified", record.seq);
+            }
+            prev_hash = record.hash;
+            count += 1;
+        }
+        
+        Ok(count)
+    }
+}
+
+/// Records that can be read from an audit log, after cutting off a final
+/// record torn by a crash mid-write. Corruption elsewhere is left for
+/// `verify` to report, so it never keeps the core from starting.
+fn recover(path: &Path) -> Result<Vec<AuditRecord>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    
+    let data = fs::read(path).context("Failed to read audit log")?;
+    let mut records = Vec::new();
+    let mut offset = 0;
+    // Start of the unreadable lines at the end of the file, if any
+    let mut torn_at = None;
+    for (line_no, line) in data.split_inclusive(|b| *b == b'\n').enumerate() {
+        let start = offset;
+        offset += line.len();
+        let text = String::from_utf8_lossy(line);
+        if text.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<AuditRecord>(text.trim_end()) {
+            Ok(record) => {
+                records.push(record);
+                torn_at = None;
+            }
+            Err(e) => {
+                tracing::warn!("Unreadable audit record on line {}: {}", line_no + 1, e);
+                torn_at = torn_at.or(Some(start));
+            }
+        }
+    }
+    
+    let mut file = OpenOptions::new().write(true).open(path).context("Failed to open audit log")?;
+    if let Some(torn_at) = torn_at {
+        tracing::warn!("Truncating torn audit record at byte {} of {}", torn_at, path.display());
+        file.set_len(torn_at as u64)?;
+    } else if data.last().is_some_and(|b| *b != b'\n') {
+        // The last record was written without its newline
+        file.seek(SeekFrom::End(0))?;
+        file.write_all(b"\n")?;
+    }
+    Ok(records)
+}
+
+/// Read all records from an audit log file
+fn read_records(path: &Path) -> Result<Vec<AuditRecord>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    
+    let file = File::open(path).context("Failed to open audit log")?;
+    let mut records = Vec::new();
+    for (line_no, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line)
+            .with_context(|| format!("Corrupt audit record on line {}", line_no + 1))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Blank out secret-looking fields anywhere in a JSON value
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if REDACTED_FIELDS.contains(&key.as_str()) {
+                    *field = Value::String("[REDACTED]".to_string());
+                } else {
+                    redact(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    fn log_with_records(dir: &Path, count: usize) -> PathBuf {
+        let log = AuditLog::new(dir).unwrap();
+        for i in 0..count {
+            log.record(Actor::System, None, "Test", Some(format!("t{}", i)), json!({}), "allowed").unwrap();
+        }
+        dir.join("audit").join("audit.log")
+    }
+    
+    #[test]
+    fn torn_last_record_is_truncated_on_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = log_with_records(dir.path(), 2);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"seq\":2,\"timest").unwrap();
+        
+        let log = AuditLog::new(dir.path()).unwrap();
+        log.record(Actor::System, None, "Test", None, json!({}), "allowed").unwrap();
+        assert_eq!(log.verify().unwrap(), 3);
+    }
+    
+    #[test]
+    fn corruption_mid_file_fails_verify_not_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = log_with_records(dir.path(), 3);
+        let text = fs::read_to_string(&path).unwrap();
+        let mut lines: Vec<&str> = text.lines().collect();
+        lines[1] = "garbage";
+        fs::write(&path, lines.join("\n") + "\n").unwrap();
+        
+        let log = AuditLog::new(dir.path()).unwrap();
+        assert!(log.verify().is_err());
+    }
+}
@@ -1,2 +1,8 @@
+pub mod api_keys;
 pub mod approval;
 pub mod audit;
+pub mod rbac;
+pub mod risk;
+pub mod roe;
+pub mod scope;
+pub mod vault;
@@ -0,0 +1,244 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use crate::state::Actor;
+use crate::websocket::{ClientInfo, events::WSEvent};
+
+/// Role configuration file name under the base directory
+const ROLES_FILE: &str = "roles.json";
+
+/// Access role, ordered from least to most privileged
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Viewer,
+    Analyst,
+    Operator,
+    Admin,
+}
+
+/// Command-level permission, also used as API key scope
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum Permission {
+    #[serde(rename = "sessions:read")]
+    ViewSessions,
+    #[serde(rename = "sessions:write")]
+    ManageSessions,
+    #[serde(rename = "sessions:delete")]
+    DeleteSessions,
+    #[serde(rename = "tasks:write")]
+    QueueTasks,
+    #[serde(rename = "approvals:write")]
+    DecideApprovals,
+    #[serde(rename = "chat:write")]
+    UseChat,
+    #[serde(rename = "admin")]
+    Administer,
+}
+
+impl Role {
+    /// Permissions granted to this role
+    pub fn permissions(&self) -> &'static [Permission] {
+        use Permission::*;
+        match self {
+            Role::Viewer => &[ViewSessions],
+            Role::Analyst => &[ViewSessions, UseChat],
+            Role::Operator => &[ViewSessions, UseChat, ManageSessions, QueueTasks, DecideApprovals],
+            Role::Admin => &[ViewSessions, UseChat, ManageSessions, QueueTasks, DecideApprovals, DeleteSessions, Administer],
+        }
+    }
+    
+    /// Whether this role grants a permission
+    pub fn allows(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+/// Permission required to run a client command.
+///
+/// Anything not listed here (including server-originated events sent by
+/// a client) requires `Administer`, so new commands are locked down until
+/// they are explicitly mapped.
+pub fn required_permission(event: &WSEvent) -> Permission {
+    match event {
+        WSEvent::GetSessionList
+        | WSEvent::GetAgentStatus { .. }
+        | WSEvent::GenerateReport { .. }
+        | WSEvent::ListReportTemplates
+        | WSEvent::DownloadArtifact { .. }
+        | WSEvent::CancelTransfer { .. }
+        | WSEvent::ListTargets { .. }
+        | WSEvent::GetTimeline { .. }
+        | WSEvent::GetFindingsStats { .. }
+        | WSEvent::GetSessionSummary { .. }
+        | WSEvent::SubscribeLogs { .. }
+        | WSEvent::GetToolCatalog
+        | WSEvent::GetTorStatus => Permission::ViewSessions,
+        // Loading switches the active session and may save or rebuild it;
+        // exports and artifact checks write to disk
+        WSEvent::CreateSession { .. }
+        | WSEvent::LoadSession { .. }
+        | WSEvent::ExportSession { .. }
+        | WSEvent::VerifyArtifacts { .. }
+        | WSEvent::SaveSession { .. }
+        | WSEvent::RebuildSession { .. }
+        | WSEvent::AttachEvidence { .. }
+        | WSEvent::UploadArtifact { .. }
+        | WSEvent::AddTarget { .. }
+        | WSEvent::RemoveTarget { .. }
+        | WSEvent::PushToMisp { .. }
+        | WSEvent::PullFromMisp { .. }
+        | WSEvent::PushToDefectDojo { .. }
+        | WSEvent::CreateTicket { .. } => Permission::ManageSessions,
+        WSEvent::DeleteSession { .. } => Permission::DeleteSessions,
+        // Anyone who can start tasks can stop them; resuming needs an admin
+        WSEvent::QueueTask { .. }
+        | WSEvent::KillSwitch
+        | WSEvent::StartAgents { .. }
+        | WSEvent::StopAgents { .. }
+        | WSEvent::RotateTorCircuit => Permission::QueueTasks,
+        WSEvent::ApproveAction { .. } | WSEvent::DenyAction { .. } => Permission::DecideApprovals,
+        WSEvent::Chat { .. } => Permission::UseChat,
+        _ => Permission::Administer,
+    }
+}
+
+/// Operator-to-role assignments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleConfig {
+    /// Role for API key clients without an assignment
+    #[serde(default = "default_role")]
+    pub default_role: Role,
+    /// Reject TCP clients that do not present a valid API key
+    #[serde(default)]
+    pub require_api_key: bool,
+    /// Roles by API key name
+    #[serde(default)]
+    pub operators: HashMap<String, Role>,
+    /// Browser origins allowed to open a connection; requests without an
+    /// `Origin` header (non-browser clients) are not affected
+    #[serde(default = "default_allowed_origins")]
+    pub allowed_origins: Vec<String>,
+}
+
+impl Default for RoleConfig {
+    fn default() -> Self {
+        Self {
+            default_role: default_role(),
+            require_api_key: false,
+            operators: HashMap::new(),
+            allowed_origins: default_allowed_origins(),
+        }
+    }
+}
+
+fn default_role() -> Role {
+    Role::Operator
+}
+
+/// The bundled web UI
+fn default_allowed_origins() -> Vec<String> {
+    vec!["http://localhost:3000".to_string(), "http://127.0.0.1:3000".to_string()]
+}
+
+/// Central access control for client commands
+pub struct AccessControl {
+    config: RoleConfig,
+}
+
+impl AccessControl {
+    /// Load role assignments from the base directory (defaults if absent)
+    pub fn load(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let path = base_dir.as_ref().join(ROLES_FILE);
+        let config = if path.exists() {
+            let json = fs::read_to_string(&path)
+                .context("Failed to read role config")?;
+            serde_json::from_str(&json)
+                .context("Failed to parse role config")?
+        } else {
+            RoleConfig::default()
+        };
+        
+        Ok(Self { config })
+    }
+    
+    /// Whether TCP clients must authenticate with an API key
+    pub fn require_api_key(&self) -> bool {
+        self.config.require_api_key
+    }
+    
+    /// Browser origins the WebSocket handshake accepts
+    pub fn allowed_origins(&self) -> Vec<String> {
+        self.config.allowed_origins.clone()
+    }
+    
+    /// Role of a connected client.
+    ///
+    /// Unix socket and stdio clients already passed OS-level access checks
+    /// and act as the machine owner. Remote roles follow the API key name;
+    /// a name a client merely asserts never grants more than `Viewer`.
+    pub fn role_for(&self, client: &ClientInfo) -> Role {
+        if client.transport.is_local() {
+            return Role::Admin;
+        }
+        
+        match (&client.scopes, &client.identity) {
+            (Some(_), Actor::Operator(name)) => self.config.operators
+                .get(name)
+                .copied()
+                .unwrap_or(self.config.default_role),
+            _ => Role::Viewer,
+        }
+    }
+    
+    /// Check a command, returning the denial reason if not permitted
+    pub fn authorize(&self, client: &ClientInfo, event: &WSEvent) -> Result<(), String> {
+        let permission = required_permission(event);
+        
+        // API key clients are limited to the scopes on their key, within their role
+        if let Some(scopes) = &client.scopes {
+            if !scopes.contains(&permission) {
+                return Err(format!(
+                    "API key for {} lacks scope {} required for {}",
+                    client.identity,
+                    serde_json::to_value(permission).unwrap_or_default(),
+                    event.event_type()
+                ));
+            }
+        }
+        
+        let role = self.role_for(client);
+        if role.allows(permission) {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} has role {:?}, which lacks {:?} required for {}",
+                client.identity,
+                role,
+                permission,
+                event.event_type()
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn viewers_cannot_change_what_is_loaded_or_written() {
+        let id = "session_0123456789ab".to_string();
+        for event in [
+            WSEvent::LoadSession { session_id: id.clone() },
+            WSEvent::ExportSession { session_id: id.clone(), format: Default::default(), password: None },
+            WSEvent::VerifyArtifacts { session_id: id.clone() },
+        ] {
+            assert!(!Role::Viewer.allows(required_permission(&event)), "{:?}", event);
+            assert!(Role::Operator.allows(required_permission(&event)), "{:?}", event);
+        }
+        assert!(Role::Viewer.allows(required_permission(&WSEvent::GetSessionSummary { session_id: id })));
+    }
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::security::roe::Technique;
+use crate::state::{ActionType, OperationalMode, RiskLevel, SessionState};
+use crate::tools::ToolRisk;
+
+/// Computed risk of an action, with the factors that produced it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskAssessment {
+    /// 0-100, higher is riskier
+    pub score: u8,
+    pub level: RiskLevel,
+    /// One entry per contributing factor, e.g. `"+25 sensitive target"`
+    pub rationale: Vec<String>,
+}
+
+/// Running total of weighted factors
+#[derive(Default)]
+struct Score {
+    total: u32,
+    rationale: Vec<String>,
+}
+
+impl Score {
+    fn add(&mut self, weight: u32, reason: impl Into<String>) {
+        if weight > 0 {
+            self.total += weight;
+            self.rationale.push(format!("+{} {}", weight, reason.into()));
+        }
+    }
+}
+
+/// Score an action against a session.
+///
+/// Weighs the action type, target sensitivity and scope (from the session's
+/// scope), the destructiveness of the tool, and the operational mode.
+/// A known technique sets the tool weight; otherwise the tool registry's
+/// estimate, if any, is used. Out-of-scope targets always land in `Critical`.
+pub fn assess(
+    action_type: &ActionType,
+    tool_name: &str,
+    args: &HashMap<String, serde_json::Value>,
+    target: &str,
+    session: &SessionState,
+    tool_risk: Option<ToolRisk>,
+) -> RiskAssessment {
+    let mut score = Score::default();
+    
+    let action_weight = match action_type {
+        ActionType::BrowserNavigation => 5,
+        ActionType::ToolExecution | ActionType::FormSubmission => 15,
+        ActionType::FileWrite => 20,
+        ActionType::RootCommand => 35,
+    };
+    score.add(action_weight, format!("{:?} action", action_type));
+    
+    if session.check_target(target).is_err() {
+        score.add(60, "target outside engagement scope");
+    }
+    if session.scope.is_sensitive(target) {
+        score.add(25, "target marked sensitive in scope");
+    }
+    
+    let technique_weight = Technique::for_task(tool_name, args).into_iter()
+        .map(|technique| match technique {
+            Technique::DenialOfService => 35,
+            Technique::Exploitation => 30,
+            Technique::Bruteforce => 25,
+            Technique::SocialEngineering => 20,
+        })
+        .max()
+        .unwrap_or(0);
+    match tool_risk {
+        Some(risk) if technique_weight == 0 => score.add(risk.weight, risk.reason),
+        _ => score.add(technique_weight, format!("{} is a destructive tool", tool_name)),
+    }
+    
+    if session.mode == OperationalMode::Offensive {
+        score.add(10, "offensive mode");
+    }
+    
+    let total = score.total.min(100) as u8;
+    RiskAssessment {
+        score: total,
+        level: level_for(total),
+        rationale: score.rationale,
+    }
+}
+
+/// Map a score onto the coarse risk levels
+pub fn level_for(score: u8) -> RiskLevel {
+    match score {
+        0..=24 => RiskLevel::Low,
+        25..=49 => RiskLevel::Medium,
+        50..=74 => RiskLevel::High,
+        _ => RiskLevel::Critical,
+    }
+}
@@ -0,0 +1,170 @@
+use chrono::{DateTime, FixedOffset, NaiveTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+
+/// Rules of engagement for a session.
+///
+/// The executor consults these before starting each task: forbidden
+/// techniques fail the task, while tasks outside the allowed window or
+/// above the rate limit stay queued until they are permitted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RulesOfEngagement {
+    /// Time of day tasks may start; unrestricted when absent
+    #[serde(default)]
+    pub allowed_hours: Option<HoursWindow>,
+    /// Maximum task starts per minute for the session
+    #[serde(default)]
+    pub max_requests_per_minute: Option<u32>,
+    /// Techniques that must never run in this engagement
+    #[serde(default)]
+    pub forbidden_techniques: Vec<Technique>,
+}
+
+/// Daily window in which tasks may start
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HoursWindow {
+    /// Window start (`HH:MM`)
+    #[serde(with = "hhmm")]
+    pub start: NaiveTime,
+    /// Window end (`HH:MM`); may be earlier than start for overnight windows
+    #[serde(with = "hhmm")]
+    pub end: NaiveTime,
+    /// Client timezone as minutes east of UTC
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
+}
+
+/// Attack technique classes an engagement can forbid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Technique {
+    Bruteforce,
+    DenialOfService,
+    Exploitation,
+    SocialEngineering,
+}
+
+/// Tools whose primary use falls under a technique class
+const TOOL_TECHNIQUES: &[(&str, Technique)] = &[
+    ("hydra", Technique::Bruteforce),
+    ("medusa", Technique::Bruteforce),
+    ("ncrack", Technique::Bruteforce),
+    ("patator", Technique::Bruteforce),
+    ("hashcat", Technique::Bruteforce),
+    ("john", Technique::Bruteforce),
+    ("hping3", Technique::DenialOfService),
+    ("slowloris", Technique::DenialOfService),
+    ("t50", Technique::DenialOfService),
+    ("metasploit", Technique::Exploitation),
+    ("msfconsole", Technique::Exploitation),
+    ("sqlmap", Technique::Exploitation),
+    ("setoolkit", Technique::SocialEngineering),
+    ("gophish", Technique::SocialEngineering),
+];
+
+impl Technique {
+    /// Techniques a task uses: the tool table's, plus any named by a
+    /// `technique` arg. The arg can add a technique but never hide one.
+    pub fn for_task(tool_name: &str, args: &HashMap<String, serde_json::Value>) -> Vec<Self> {
+        let tool = tool_name.to_ascii_lowercase();
+        let mut techniques: Vec<Self> = TOOL_TECHNIQUES.iter()
+            .filter(|(name, _)| *name == tool)
+            .map(|(_, technique)| *technique)
+            .collect();
+        if let Some(technique) = args.get("technique").and_then(|v| serde_json::from_value(v.clone()).ok()) {
+            if !techniques.contains(&technique) {
+                techniques.push(technique);
+            }
+        }
+        techniques
+    }
+}
+
+impl HoursWindow {
+    /// Whether the given instant falls inside the window
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        let offset = FixedOffset::east_opt(self.utc_offset_minutes * 60)
+            .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap());
+        let local = now.with_timezone(&offset).time();
+        
+        if self.start <= self.end {
+            local >= self.start && local < self.end
+        } else {
+            local >= self.start || local < self.end
+        }
+    }
+}
+
+impl RulesOfEngagement {
+    /// Reason a task may never run under these rules
+    pub fn forbids(&self, tool_name: &str, args: &HashMap<String, serde_json::Value>) -> Option<String> {
+        let technique = Technique::for_task(tool_name, args).into_iter()
+            .find(|technique| self.forbidden_techniques.contains(technique))?;
+        Some(format!("Rules of engagement forbid {:?} ({})", technique, tool_name))
+    }
+    
+    /// Reason a task must wait, given recent task starts in the session
+    pub fn defer_reason(&self, now: DateTime<Utc>, starts_last_minute: usize) -> Option<String> {
+        if let Some(window) = &self.allowed_hours {
+            if !window.contains(now) {
+                return Some(format!(
+                    "Outside allowed hours {}-{}",
+                    window.start.format("%H:%M"),
+                    window.end.format("%H:%M"),
+                ));
+            }
+        }
+        
+        if let Some(limit) = self.max_requests_per_minute {
+            if starts_last_minute >= limit as usize {
+                return Some(format!("Rate limit of {} tasks per minute reached", limit));
+            }
+        }
+        
+        None
+    }
+}
+
+/// `HH:MM` (de)serialization for window bounds
+mod hhmm {
+    use super::*;
+    
+    pub fn serialize<S: Serializer>(time: &NaiveTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&time.format("%H:%M").to_string())
+    }
+    
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveTime, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        NaiveTime::parse_from_str(&value, "%H:%M")
+            .or_else(|_| NaiveTime::parse_from_str(&value, "%H:%M:%S"))
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    #[test]
+    fn technique_arg_cannot_hide_the_tools_technique() {
+        let rules = RulesOfEngagement {
+            forbidden_techniques: vec![Technique::Bruteforce],
+            ..Default::default()
+        };
+        let args = HashMap::from([("technique".to_string(), json!("exploitation"))]);
+        assert_eq!(Technique::for_task("hydra", &args), [Technique::Bruteforce, Technique::Exploitation]);
+        assert!(rules.forbids("hydra", &args).is_some());
+    }
+    
+    #[test]
+    fn technique_arg_adds_a_technique() {
+        let rules = RulesOfEngagement {
+            forbidden_techniques: vec![Technique::Exploitation],
+            ..Default::default()
+        };
+        let args = HashMap::from([("technique".to_string(), json!("exploitation"))]);
+        assert!(rules.forbids("nmap", &args).is_some());
+        assert!(rules.forbids("nmap", &HashMap::new()).is_none());
+    }
+}
@@ -0,0 +1,229 @@
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+/// Engagement scope for a session.
+///
+/// A target is in scope when it matches at least one CIDR, domain or URL
+/// pattern and none of the exclusions. Exclusions accept the same forms
+/// (CIDR/IP, domain, URL pattern). An empty scope permits every target,
+/// which keeps sessions created before scoping existed usable.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EngagementScope {
+    /// Networks and single addresses (`10.0.0.0/24`, `192.0.2.7/32`)
+    #[serde(default)]
+    pub cidrs: Vec<IpNet>,
+    /// Domains; `example.com` also covers subdomains, `*.example.com` only subdomains
+    #[serde(default)]
+    pub domains: Vec<String>,
+    /// URL patterns with `*` wildcards (`https://app.example.com/api/*`)
+    #[serde(default)]
+    pub url_patterns: Vec<String>,
+    /// Targets that are out of scope even if matched above
+    #[serde(default)]
+    pub exclusions: Vec<String>,
+    /// In-scope targets that need extra care (production databases, domain
+    /// controllers); same forms as exclusions, raises the risk score
+    #[serde(default)]
+    pub sensitive: Vec<String>,
+}
+
+/// A target string broken down for matching
+enum ParsedTarget<'a> {
+    Network(IpNet),
+    Host { host: String, url: Option<&'a str> },
+}
+
+impl EngagementScope {
+    /// Whether no scope has been defined
+    pub fn is_empty(&self) -> bool {
+        self.cidrs.is_empty() && self.domains.is_empty() && self.url_patterns.is_empty()
+    }
+    
+    /// Whether a target is flagged as sensitive
+    pub fn is_sensitive(&self, target: &str) -> bool {
+        parse_target(target.trim())
+            .is_some_and(|parsed| self.sensitive.iter().any(|e| matches_entry(e, &parsed)))
+    }
+    
+    /// Check a target, returning the reason if it is out of scope
+    pub fn check(&self, target: &str) -> Result<(), String> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        
+        let target = target.trim();
+        let parsed = parse_target(target).ok_or_else(|| format!("Cannot determine host for target '{}'", target))?;
+        
+        if let Some(exclusion) = self.exclusions.iter().find(|e| matches_entry(e, &parsed)) {
+            return Err(format!("Target '{}' matches exclusion '{}'", target, exclusion));
+        }
+        
+        let included = match &parsed {
+            ParsedTarget::Network(net) => self.cidrs.iter().any(|c| c.contains(net)),
+            ParsedTarget::Host { host, url } => {
+                let by_host = match host.parse::<IpAddr>() {
+                    Ok(ip) => self.cidrs.iter().any(|c| c.contains(&ip)),
+                    Err(_) => self.domains.iter().any(|d| domain_matches(d, host)),
+                };
+                by_host || url.is_some_and(|u| self.url_patterns.iter().any(|p| wildcard_match(p, u)))
+            }
+        };
+        
+        if included {
+            Ok(())
+        } else {
+            Err(format!("Target '{}' is outside the engagement scope", target))
+        }
+    }
+}
+
+/// Whether a target falls under a scope-style entry (CIDR/IP, domain or URL pattern)
+pub fn entry_matches(entry: &str, target: &str) -> bool {
+    parse_target(target.trim()).is_some_and(|parsed| matches_entry(entry, &parsed))
+}
+
+/// Host (IP or lowercase hostname) a target points at; single-address CIDRs count as hosts
+pub fn target_host(target: &str) -> Option<String> {
+    match parse_target(target.trim())? {
+        ParsedTarget::Network(net) if net.prefix_len() == net.max_prefix_len() => Some(net.addr().to_string()),
+        ParsedTarget::Network(_) => None,
+        ParsedTarget::Host { host, .. } => Some(host),
+    }
+}
+
+/// Parse an IP, CIDR, hostname, `host:port` or URL target.
+///
+/// Lists are refused: tools read `a.com,b.com` or `a.com b.com` as several
+/// targets, while matching would only see one.
+fn parse_target(target: &str) -> Option<ParsedTarget<'_>> {
+    if target.chars().any(|c| c.is_whitespace() || c.is_control() || c == ',') {
+        return None;
+    }
+    if let Ok(net) = target.parse::<IpNet>() {
+        return Some(ParsedTarget::Network(net));
+    }
+    
+    let (url, rest) = match target.split_once("://") {
+        Some((_, rest)) => (Some(target), rest),
+        None => (None, target),
+    };
+    
+    // Strip path, userinfo and port
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let authority = authority.rsplit('@').next()?;
+    let host = if let Some(bracketed) = authority.strip_prefix('[') {
+        bracketed.split(']').next()?
+    } else if authority.parse::<IpAddr>().is_ok() {
+        authority
+    } else {
+        authority.split(':').next()?
+    };
+    
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    let valid = host.parse::<IpAddr>().is_ok()
+        || host.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_'));
+    if host.is_empty() || !valid {
+        return None;
+    }
+    Some(ParsedTarget::Host { host, url })
+}
+
+/// Whether a target hits an exclusion or sensitive entry
+fn matches_entry(entry: &str, target: &ParsedTarget) -> bool {
+    let entry = entry.trim();
+    
+    let entry_net = entry.parse::<IpNet>().ok()
+        .or_else(|| entry.parse::<IpAddr>().ok().map(IpNet::from));
+    if let Some(entry_net) = entry_net {
+        return match target {
+            // Any overlap with the entry's network counts
+            ParsedTarget::Network(net) => entry_net.contains(net) || net.contains(&entry_net),
+            ParsedTarget::Host { host, .. } => host.parse::<IpAddr>().is_ok_and(|ip| entry_net.contains(&ip)),
+        };
+    }
+    
+    match target {
+        ParsedTarget::Network(_) => false,
+        ParsedTarget::Host { host, url } => {
+            if entry.contains("://") {
+                url.is_some_and(|u| wildcard_match(entry, u))
+            } else {
+                domain_matches(entry, host)
+            }
+        }
+    }
+}
+
+/// Match a host against a scope domain entry
+fn domain_matches(domain: &str, host: &str) -> bool {
+    let domain = domain.trim().trim_end_matches('.').to_ascii_lowercase();
+    match domain.strip_prefix("*.") {
+        Some(parent) => host.ends_with(&format!(".{}", parent)),
+        None => host == domain || host.ends_with(&format!(".{}", domain)),
+    }
+}
+
+/// Glob match where `*` matches any run of characters
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == value;
+    }
+    
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !value.starts_with(first) || value.len() < first.len() + last.len() || !value.ends_with(last) {
+        return false;
+    }
+    
+    let mut rest = &value[first.len()..value.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn scope() -> EngagementScope {
+        EngagementScope {
+            cidrs: vec!["192.0.2.0/24".parse().unwrap()],
+            domains: vec!["example.com".to_string()],
+            ..Default::default()
+        }
+    }
+    
+    #[test]
+    fn accepts_single_targets() {
+        let scope = scope();
+        assert!(scope.check("app.example.com").is_ok());
+        assert!(scope.check("https://app.example.com:8443/login?a=1").is_ok());
+        assert!(scope.check("192.0.2.10").is_ok());
+        assert!(scope.check("192.0.2.0/28").is_ok());
+        assert!(scope.check("evil.com").is_err());
+    }
+    
+    #[test]
+    fn rejects_target_lists() {
+        let scope = scope();
+        assert!(scope.check("evil.com,app.example.com").is_err());
+        assert!(scope.check("evil.com app.example.com").is_err());
+        assert!(scope.check("evil.com\tapp.example.com").is_err());
+        assert!(scope.check("https://evil.com,https://app.example.com").is_err());
+        assert!(scope.check("192.0.2.1,2").is_err());
+    }
+    
+    #[test]
+    fn rejects_invalid_host_characters() {
+        let scope = scope();
+        assert!(scope.check("evil.com;app.example.com").is_err());
+        assert!(scope.check("evil.com|app.example.com").is_err());
+        assert!(scope.check("$(id).example.com").is_err());
+        assert_eq!(target_host("APP.Example.com."), Some("app.example.com".to_string()));
+    }
+}
@@ -0,0 +1,213 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Vault file name under the base directory
+const VAULT_FILE: &str = "vault.json";
+
+/// Generated key file name under the base directory
+const VAULT_KEY_FILE: &str = "vault.key";
+
+/// Environment variable that supplies the vault key (64 hex chars)
+const VAULT_KEY_ENV: &str = "NEURORIFT_VAULT_KEY";
+
+/// One encrypted secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SealedSecret {
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Encrypted store for tool credentials.
+///
+/// Secrets are sealed individually with ChaCha20-Poly1305, using the
+/// secret name as associated data so ciphertexts cannot be swapped
+/// between names. Task args reference secrets as `{{secret:name}}` and
+/// are resolved only at dispatch time, so session files never contain
+/// the plaintext.
+pub struct SecretsVault {
+    path: PathBuf,
+    cipher: ChaCha20Poly1305,
+    secrets: RwLock<BTreeMap<String, SealedSecret>>,
+}
+
+impl SecretsVault {
+    /// Open the vault, creating a key on first use unless one is supplied
+    /// via `NEURORIFT_VAULT_KEY`
+    pub fn open(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let base_dir = base_dir.as_ref();
+        let key = load_or_create_key(base_dir)?;
+        
+        let path = base_dir.join(VAULT_FILE);
+        let secrets = if path.exists() {
+            let json = fs::read_to_string(&path)
+                .context("Failed to read vault")?;
+            serde_json::from_str(&json)
+                .context("Failed to parse vault")?
+        } else {
+            BTreeMap::new()
+        };
+        
+        Ok(Self {
+            path,
+            cipher: ChaCha20Poly1305::new(&key),
+            secrets: RwLock::new(secrets),
+        })
+    }
+    
+    /// Store or replace a secret
+    pub fn set(&self, name: &str, value: &str) -> Result<()> {
+        validate_name(name)?;
+        
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self.cipher
+            .encrypt(&nonce, Payload { msg: value.as_bytes(), aad: name.as_bytes() })
+            .map_err(|_| anyhow!("Failed to encrypt secret"))?;
+        
+        let mut secrets = self.secrets.write();
+        secrets.insert(name.to_string(), SealedSecret {
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        });
+        self.persist(&secrets)?;
+        
+        tracing::info!("Secret stored: {}", name);
+        Ok(())
+    }
+    
+    /// Decrypt a secret
+    pub fn get(&self, name: &str) -> Result<Option<String>> {
+        let secrets = self.secrets.read();
+        let Some(sealed) = secrets.get(name) else {
+            return Ok(None);
+        };
+        
+        let nonce = hex::decode(&sealed.nonce).context("Corrupt secret nonce")?;
+        if nonce.len() != 12 {
+            bail!("Corrupt secret nonce for {}", name);
+        }
+        let ciphertext = hex::decode(&sealed.ciphertext).context("Corrupt secret ciphertext")?;
+        let plaintext = self.cipher
+            .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: name.as_bytes() })
+            .map_err(|_| anyhow!("Failed to decrypt secret {} (wrong key or tampered vault)", name))?;
+        
+        Ok(Some(String::from_utf8(plaintext).context("Secret is not valid UTF-8")?))
+    }
+    
+    /// Delete a secret, returning whether it existed
+    pub fn delete(&self, name: &str) -> Result<bool> {
+        let mut secrets = self.secrets.write();
+        let existed = secrets.remove(name).is_some();
+        if existed {
+            self.persist(&secrets)?;
+            tracing::info!("Secret deleted: {}", name);
+        }
+        Ok(existed)
+    }
+    
+    /// Names of stored secrets
+    pub fn list(&self) -> Vec<String> {
+        self.secrets.read().keys().cloned().collect()
+    }
+    
+    /// Replace every `{{secret:name}}` reference in a JSON value
+    pub fn resolve(&self, value: &Value) -> Result<Value> {
+        Ok(match value {
+            Value::String(s) => Value::String(self.resolve_str(s)?),
+            Value::Array(items) => Value::Array(
+                items.iter().map(|v| self.resolve(v)).collect::<Result<_>>()?,
+            ),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| Ok((k.clone(), self.resolve(v)?)))
+                    .collect::<Result<_>>()?,
+            ),
+            other => other.clone(),
+        })
+    }
+    
+    /// Replace secret references inside a string
+    pub fn resolve_str(&self, input: &str) -> Result<String> {
+        const OPEN: &str = "{{secret:";
+        const CLOSE: &str = "}}";
+        
+        let mut output = String::with_capacity(input.len());
+        let mut rest = input;
+        while let Some(start) = rest.find(OPEN) {
+            output.push_str(&rest[..start]);
+            let after = &rest[start + OPEN.len()..];
+            let end = after.find(CLOSE)
+                .ok_or_else(|| anyhow!("Unterminated secret reference"))?;
+            let name = after[..end].trim();
+            let secret = self.get(name)?
+                .ok_or_else(|| anyhow!("Unknown secret referenced: {}", name))?;
+            output.push_str(&secret);
+            rest = &after[end + CLOSE.len()..];
+        }
+        output.push_str(rest);
+        
+        Ok(output)
+    }
+    
+    /// Write the vault with owner-only permissions
+    fn persist(&self, secrets: &BTreeMap<String, SealedSecret>) -> Result<()> {
+        let json = serde_json::to_string_pretty(secrets)?;
+        fs::write(&self.path, json).context("Failed to write vault")?;
+        restrict_permissions(&self.path)
+    }
+}
+
+/// Secret names are used inside `{{secret:...}}` references
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.') {
+        bail!("Invalid secret name: {:?}", name);
+    }
+    Ok(())
+}
+
+/// Vault key from the environment, the key file, or freshly generated
+fn load_or_create_key(base_dir: &Path) -> Result<Key> {
+    if let Ok(hex_key) = std::env::var(VAULT_KEY_ENV) {
+        return parse_key(hex_key.trim()).context("Invalid NEURORIFT_VAULT_KEY");
+    }
+    
+    let key_path = base_dir.join(VAULT_KEY_FILE);
+    if key_path.exists() {
+        let hex_key = fs::read_to_string(&key_path).context("Failed to read vault key")?;
+        return parse_key(hex_key.trim()).context("Invalid vault key file");
+    }
+    
+    fs::create_dir_all(base_dir)?;
+    let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+    fs::write(&key_path, hex::encode(key)).context("Failed to write vault key")?;
+    restrict_permissions(&key_path)?;
+    tracing::info!("Generated new vault key: {}", key_path.display());
+    Ok(key)
+}
+
+/// Decode a 32-byte hex key
+fn parse_key(hex_key: &str) -> Result<Key> {
+    let bytes = hex::decode(hex_key)?;
+    if bytes.len() != 32 {
+        bail!("Vault key must be 32 bytes, got {}", bytes.len());
+    }
+    Ok(*Key::from_slice(&bytes))
+}
+
+/// Make a file readable by its owner only
+pub(crate) fn restrict_permissions(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
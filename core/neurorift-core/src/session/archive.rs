@@ -0,0 +1,308 @@
+//! Password-protected export archives.
+//!
+//! An archive is a tar stream sealed with AES-256-GCM in 64 KiB chunks,
+//! using the STREAM construction so that modified, reordered or missing
+//! chunks are all detected. The key comes from the password through
+//! PBKDF2-HMAC-SHA256. `neurorift sessions decrypt` turns an archive
+//! back into the tar:
+//!
+//! ```text
+//! neurorift sessions decrypt export.nrx -o export.tar && tar xf export.tar
+//! ```
+//!
+//! Layout: the magic, PBKDF2 rounds (u32, big-endian), a 16-byte salt
+//! and a 7-byte nonce prefix, then each chunk's ciphertext followed by
+//! its tag. A chunk's nonce is the prefix, its index (u32, big-endian)
+//! and a byte that is 1 only for the last chunk; the header is the
+//! additional data of every chunk.
+//!
+//! The tar ends with `MANIFEST.json`, the SHA-256 of every entry.
+
+use anyhow::{bail, Context, Result};
+use openssl::hash::MessageDigest;
+use openssl::symm::{self, Cipher};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// PBKDF2 rounds for new archives; the count is stored in the header
+pub const PBKDF2_ITERATIONS: u32 = 600_000;
+
+/// Most rounds a header may ask for, so a crafted file cannot stall decryption
+const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+
+/// Shortest password accepted
+pub const MIN_PASSWORD_LEN: usize = 12;
+
+/// File extension of encrypted archives
+pub const EXTENSION: &str = "nrx";
+
+/// Name of the checksum list at the end of the archive
+const MANIFEST_NAME: &str = "MANIFEST.json";
+
+/// Magic that opens an archive, naming the format version
+const MAGIC: &[u8] = b"NRX\x00AES256GCM1";
+
+const SALT_LEN: usize = 16;
+const NONCE_PREFIX_LEN: usize = 7;
+const HEADER_LEN: usize = MAGIC.len() + 4 + SALT_LEN + NONCE_PREFIX_LEN;
+const TAG_LEN: usize = 16;
+
+/// Plaintext bytes per sealed chunk
+const CHUNK: usize = 64 * 1024;
+
+const BLOCK: usize = 512;
+
+/// One file to put in the archive
+pub struct Entry {
+    /// Path inside the archive, `/`-separated
+    pub name: String,
+    pub source: PathBuf,
+}
+
+#[derive(Serialize)]
+struct ManifestEntry<'a> {
+    name: &'a str,
+    sha256: String,
+    size: u64,
+}
+
+/// Write an encrypted archive of `entries` to `dest`
+pub fn write(dest: &Path, password: &str, entries: &[Entry]) -> Result<()> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        bail!("Export password must be at least {} characters", MIN_PASSWORD_LEN);
+    }
+    let file = File::create(dest)
+        .with_context(|| format!("Failed to create {}", dest.display()))?;
+    let mut writer = EncryptingWriter::new(BufWriter::new(file), password)?;
+    
+    let mut manifest = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let file = File::open(&entry.source)
+            .with_context(|| format!("Failed to open {}", entry.source.display()))?;
+        let size = file.metadata()?.len();
+        let sha256 = append(&mut writer, &entry.name, size, file)
+            .with_context(|| format!("Failed to archive {}", entry.source.display()))?;
+        manifest.push(ManifestEntry { name: &entry.name, sha256, size });
+    }
+    let manifest = serde_json::to_vec_pretty(&manifest)?;
+    append(&mut writer, MANIFEST_NAME, manifest.len() as u64, manifest.as_slice())?;
+    
+    // Two empty blocks end a tar stream
+    writer.write_all(&[0u8; BLOCK * 2])?;
+    writer.finish()?.flush().context("Failed to write archive")?;
+    Ok(())
+}
+
+/// Add one tar entry, returning the SHA-256 of its content
+fn append(writer: &mut impl Write, name: &str, size: u64, mut content: impl Read) -> Result<String> {
+    writer.write_all(&header(name, size)?)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut written = 0u64;
+    while written < size {
+        let want = buffer.len().min((size - written) as usize);
+        let read = content.read(&mut buffer[..want])?;
+        if read == 0 {
+            bail!("File shrank while being archived");
+        }
+        hasher.update(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+        written += read as u64;
+    }
+    let padding = (BLOCK - (size as usize % BLOCK)) % BLOCK;
+    writer.write_all(&vec![0u8; padding])?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// ustar header for a regular file; long paths are split at a `/` into
+/// the prefix and name fields
+fn header(path: &str, size: u64) -> Result<[u8; BLOCK]> {
+    let (prefix, name) = match path.len() {
+        0..=100 => ("", path),
+        _ => path.char_indices()
+            .filter(|&(i, c)| c == '/' && i <= 155 && path.len() - i - 1 <= 100)
+            .map(|(i, _)| (&path[..i], &path[i + 1..]))
+            .next()
+            .with_context(|| format!("Path too long for the archive: {}", path))?,
+    };
+    
+    let mut header = [0u8; BLOCK];
+    let mut field = |offset: usize, value: &[u8]| header[offset..offset + value.len()].copy_from_slice(value);
+    field(0, name.as_bytes());
+    field(100, b"0000600\0");
+    field(108, b"0000000\0");
+    field(116, b"0000000\0");
+    field(124, format!("{:011o}\0", size).as_bytes());
+    field(136, format!("{:011o}\0", chrono::Utc::now().timestamp()).as_bytes());
+    field(148, b"        ");
+    field(156, b"0");
+    field(257, b"ustar\0");
+    field(263, b"00");
+    field(345, prefix.as_bytes());
+    let checksum: u32 = header.iter().map(|&b| u32::from(b)).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+    Ok(header)
+}
+
+/// Decrypt an archive into the tar stream it holds. Fails on a wrong
+/// password or any modification; `out` may have received part of the
+/// tar by then and should be discarded.
+pub fn decrypt(src: &Path, password: &str, out: &mut impl Write) -> Result<()> {
+    let mut reader = File::open(src)
+        .with_context(|| format!("Failed to open {}", src.display()))?;
+    let mut header = [0u8; HEADER_LEN];
+    reader.read_exact(&mut header).context("Not a NeuroRift archive")?;
+    let (magic, rest) = header.split_at(MAGIC.len());
+    let (rounds, rest) = rest.split_at(4);
+    let (salt, prefix) = rest.split_at(SALT_LEN);
+    if magic != MAGIC {
+        bail!("Not a NeuroRift archive, or one from an unsupported version");
+    }
+    let rounds = u32::from_be_bytes(rounds.try_into()?);
+    if rounds == 0 || rounds > MAX_PBKDF2_ITERATIONS {
+        bail!("Archive asks for {} PBKDF2 rounds", rounds);
+    }
+    let key = derive_key(password, salt, rounds)?;
+    
+    let mut pending = Vec::with_capacity(CHUNK + TAG_LEN + 1);
+    for index in 0u32.. {
+        // One byte past a full chunk tells whether another follows
+        (&mut reader).take((CHUNK + TAG_LEN + 1 - pending.len()) as u64).read_to_end(&mut pending)?;
+        let last = pending.len() <= CHUNK + TAG_LEN;
+        let sealed: Vec<u8> = pending.drain(..pending.len().min(CHUNK + TAG_LEN)).collect();
+        if sealed.len() < TAG_LEN {
+            bail!("Archive is truncated");
+        }
+        let (ciphertext, tag) = sealed.split_at(sealed.len() - TAG_LEN);
+        let plain = symm::decrypt_aead(Cipher::aes_256_gcm(), &key, Some(&nonce(prefix, index, last)), &header, ciphertext, tag)
+            .map_err(|_| anyhow::anyhow!("Wrong password, or the archive was modified or truncated"))?;
+        out.write_all(&plain)?;
+        if last {
+            return Ok(());
+        }
+    }
+    bail!("Archive has too many chunks")
+}
+
+fn derive_key(password: &str, salt: &[u8], rounds: u32) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    openssl::pkcs5::pbkdf2_hmac(password.as_bytes(), salt, rounds as usize, MessageDigest::sha256(), &mut key)?;
+    Ok(key)
+}
+
+/// Nonce of chunk `index`; the final byte marks the last chunk so an
+/// archive cut at a chunk boundary does not verify
+fn nonce(prefix: &[u8], index: u32, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..11].copy_from_slice(&index.to_be_bytes());
+    nonce[11] = u8::from(last);
+    nonce
+}
+
+/// Seals everything written through it into archive chunks
+struct EncryptingWriter<W: Write> {
+    inner: W,
+    key: [u8; 32],
+    header: [u8; HEADER_LEN],
+    index: u32,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> EncryptingWriter<W> {
+    fn new(mut inner: W, password: &str) -> Result<Self> {
+        let mut header = [0u8; HEADER_LEN];
+        let (magic, rest) = header.split_at_mut(MAGIC.len());
+        let (rounds, random) = rest.split_at_mut(4);
+        magic.copy_from_slice(MAGIC);
+        rounds.copy_from_slice(&PBKDF2_ITERATIONS.to_be_bytes());
+        openssl::rand::rand_bytes(random)?;
+        let key = derive_key(password, &random[..SALT_LEN], PBKDF2_ITERATIONS)?;
+        
+        inner.write_all(&header)?;
+        Ok(Self { inner, key, header, index: 0, buffer: Vec::with_capacity(CHUNK * 2) })
+    }
+    
+    /// Seal the first `len` buffered bytes as the next chunk
+    fn seal(&mut self, len: usize, last: bool) -> Result<()> {
+        let prefix = &self.header[HEADER_LEN - NONCE_PREFIX_LEN..];
+        let mut tag = [0u8; TAG_LEN];
+        let ciphertext = symm::encrypt_aead(Cipher::aes_256_gcm(), &self.key, Some(&nonce(prefix, self.index, last)), &self.header, &self.buffer[..len], &mut tag)?;
+        self.inner.write_all(&ciphertext)?;
+        self.inner.write_all(&tag)?;
+        self.buffer.drain(..len);
+        self.index = self.index.checked_add(1).context("Archive too large")?;
+        Ok(())
+    }
+    
+    /// Seal what is left as the last chunk and hand back the inner writer
+    fn finish(mut self) -> Result<W> {
+        self.seal(self.buffer.len(), true)?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for EncryptingWriter<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(data);
+        // A full chunk is held back until more follows, since the last
+        // chunk is only known at `finish`
+        while self.buffer.len() > CHUNK {
+            self.seal(CHUNK, false).map_err(io::Error::other)?;
+        }
+        Ok(data.len())
+    }
+    
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const PASSWORD: &str = "correct horse battery";
+    
+    /// Archive a file of `size` bytes, returning the archive and the tar
+    fn sealed(dir: &Path, size: usize) -> (PathBuf, Vec<u8>) {
+        let source = dir.join("data.bin");
+        std::fs::write(&source, (0..size).map(|i| i as u8).collect::<Vec<_>>()).unwrap();
+        let archive = dir.join(format!("export.{}", EXTENSION));
+        write(&archive, PASSWORD, &[Entry { name: "data.bin".to_string(), source }]).unwrap();
+        let mut tar = Vec::new();
+        decrypt(&archive, PASSWORD, &mut tar).unwrap();
+        (archive, tar)
+    }
+    
+    #[test]
+    fn round_trips_across_chunk_boundaries() {
+        let dir = tempfile::tempdir().unwrap();
+        for size in [0, 100, CHUNK - BLOCK * 3, 3 * CHUNK + 7] {
+            let (_, tar) = sealed(dir.path(), size);
+            assert_eq!(tar.len() % BLOCK, 0);
+            assert_eq!(&tar[BLOCK..BLOCK + size.min(64)], &(0..size.min(64)).map(|i| i as u8).collect::<Vec<_>>()[..]);
+        }
+    }
+    
+    #[test]
+    fn rejects_wrong_password_tampering_and_truncation() {
+        let dir = tempfile::tempdir().unwrap();
+        let (archive, _) = sealed(dir.path(), 2 * CHUNK);
+        let bytes = std::fs::read(&archive).unwrap();
+        
+        assert!(decrypt(&archive, "wrong password!", &mut Vec::new()).is_err());
+        
+        let mut flipped = bytes.clone();
+        flipped[HEADER_LEN + 10] ^= 1;
+        std::fs::write(&archive, &flipped).unwrap();
+        assert!(decrypt(&archive, PASSWORD, &mut Vec::new()).is_err());
+        
+        // Cut after the first full chunk
+        std::fs::write(&archive, &bytes[..HEADER_LEN + CHUNK + TAG_LEN]).unwrap();
+        assert!(decrypt(&archive, PASSWORD, &mut Vec::new()).is_err());
+    }
+}
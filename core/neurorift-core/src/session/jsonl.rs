@@ -0,0 +1,137 @@
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use crate::state::{SessionState, TaskStatus};
+
+/// A single flattened session event
+struct StreamEvent {
+    timestamp: DateTime<Utc>,
+    event_type: &'static str,
+    data: Value,
+}
+
+/// Flatten a session into a chronological list of JSONL records.
+///
+/// Each record carries `timestamp`, `session_id`, `session_name`,
+/// `event_type` and the event payload under `data`, which is the shape
+/// SIEM/ELK pipelines expect for one-document-per-line ingestion.
+pub fn flatten(session: &SessionState) -> Vec<Value> {
+    let mut events = vec![StreamEvent {
+        timestamp: session.created_at,
+        event_type: "session_created",
+        data: json!({
+            "mode": session.mode,
+            "status": session.status,
+            "metadata": session.metadata,
+        }),
+    }];
+    
+    for task in &session.task_queue {
+        let summary = json!({
+            "task_id": task.id,
+            "tool_name": task.tool_name,
+            "target": task.target,
+        });
+        
+        events.push(StreamEvent {
+            timestamp: task.created_at,
+            event_type: "task_queued",
+            data: json!({
+                "task_id": task.id,
+                "tool_name": task.tool_name,
+                "target": task.target,
+                "args": task.args,
+                "created_by": task.created_by,
+            }),
+        });
+        
+        if let Some(started_at) = task.started_at {
+            events.push(StreamEvent {
+                timestamp: started_at,
+                event_type: "task_started",
+                data: summary.clone(),
+            });
+        }
+        
+        if let Some(completed_at) = task.completed_at {
+            let event_type = match task.status {
+                TaskStatus::Failed => "task_failed",
+                TaskStatus::Cancelled => "task_cancelled",
+                TaskStatus::Interrupted => "task_interrupted",
+                _ => "task_completed",
+            };
+            events.push(StreamEvent {
+                timestamp: completed_at,
+                event_type,
+                data: summary,
+            });
+        }
+    }
+    
+    for approval in &session.approval_queue {
+        events.push(StreamEvent {
+            timestamp: approval.created_at,
+            event_type: "approval_requested",
+            data: json!({
+                "approval_id": approval.id,
+                "action": approval.action,
+                "reason": approval.reason,
+                "status": approval.status,
+                "requested_by": approval.requested_by,
+            }),
+        });
+    }
+    
+    for finding in &session.findings {
+        events.push(StreamEvent {
+            timestamp: finding.discovered_at,
+            event_type: "finding_discovered",
+            data: serde_json::to_value(finding).unwrap_or(Value::Null),
+        });
+    }
+    
+    for artifact in &session.artifacts {
+        events.push(StreamEvent {
+            timestamp: artifact.created_at,
+            event_type: "artifact_created",
+            data: serde_json::to_value(artifact).unwrap_or(Value::Null),
+        });
+    }
+    
+    for entry in &session.timeline {
+        events.push(StreamEvent {
+            timestamp: entry.timestamp,
+            event_type: "agent_transition",
+            data: serde_json::to_value(entry).unwrap_or(Value::Null),
+        });
+    }
+    
+    for message in &session.chat_history {
+        events.push(StreamEvent {
+            timestamp: message.timestamp,
+            event_type: "chat_message",
+            data: serde_json::to_value(message).unwrap_or(Value::Null),
+        });
+    }
+    
+    for status in session.agent_states.values() {
+        events.push(StreamEvent {
+            timestamp: status.last_update,
+            event_type: "agent_status",
+            data: serde_json::to_value(status).unwrap_or(Value::Null),
+        });
+    }
+    
+    // Stable sort keeps per-entity ordering for identical timestamps
+    events.sort_by_key(|e| e.timestamp);
+    
+    events
+        .into_iter()
+        .map(|e| json!({
+            "timestamp": e.timestamp,
+            "session_id": session.id,
+            "session_name": session.name,
+            "event_type": e.event_type,
+            "data": e.data,
+        }))
+        .collect()
+}
@@ -1,9 +1,17 @@
-use anyhow::{Context, Result};
+pub mod archive;
+pub mod jsonl;
+pub mod siem;
+pub mod stix;
+
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::fs;
+use std::io::Write;
 use chrono::{DateTime, Utc};
-use crate::state::SessionState;
+use crate::security::audit::AuditRecord;
+use crate::session::siem::SiemFormat;
+use crate::state::{is_valid_session_id, SessionState};
 
 /// .nrs file format version
 const NRS_VERSION: &str = "1.0";
@@ -16,6 +24,25 @@ pub struct NrsFile {
     pub saved_at: DateTime<Utc>,
 }
 
+/// Session export format
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Native .nrs snapshot
+    #[default]
+    Nrs,
+    /// Chronological JSON Lines event stream
+    Jsonl,
+    /// Password-protected archive of the .nrs, event journal and artifacts
+    Archive,
+    /// CEF lines of findings and audit records, for SIEM ingestion
+    Cef,
+    /// LEEF lines of findings and audit records, for SIEM ingestion
+    Leef,
+    /// STIX 2.1 bundle of findings and assets
+    Stix,
+}
+
 /// Session persistence manager
 pub struct SessionManager {
     sessions_dir: PathBuf,
@@ -39,8 +66,7 @@ impl SessionManager {
             saved_at: Utc::now(),
         };
         
-        let filename = format!("{}.nrs", session.id);
-        let path = self.sessions_dir.join(&filename);
+        let path = self.session_path(&session.id)?;
         
         let json = serde_json::to_string_pretty(&nrs_file)
             .context("Failed to serialize session")?;
@@ -54,8 +80,7 @@ impl SessionManager {
     
     /// Load session from .nrs file
     pub fn load_session(&self, session_id: &str) -> Result<SessionState> {
-        let filename = format!("{}.nrs", session_id);
-        let path = self.sessions_dir.join(&filename);
+        let path = self.session_path(session_id)?;
         
         let json = fs::read_to_string(&path)
             .context("Failed to read session file")?;
@@ -72,6 +97,21 @@ impl SessionManager {
         Ok(nrs_file.session)
     }
     
+    /// Move an unreadable session file aside as `<id>.nrs.corrupt-<time>`
+    /// so that rebuilding the session does not overwrite it
+    pub fn set_aside(&self, session_id: &str) -> Result<Option<PathBuf>> {
+        let path = self.session_path(session_id)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let dest = path.with_file_name(format!("{}.nrs.corrupt-{}", session_id, Utc::now().format("%Y%m%dT%H%M%SZ")));
+        fs::rename(&path, &dest)
+            .context("Failed to move the session file aside")?;
+        
+        tracing::warn!("Session file moved aside: {} -> {}", path.display(), dest.display());
+        Ok(Some(dest))
+    }
+    
     /// List all sessions
     pub fn list_sessions(&self) -> Result<Vec<SessionMetadata>> {
         let mut sessions = Vec::new();
@@ -88,7 +128,7 @@ impl SessionManager {
         }
         
         // Sort by updated_at descending
-        sessions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.updated_at));
         
         Ok(sessions)
     }
@@ -112,8 +152,7 @@ impl SessionManager {
     
     /// Delete a session
     pub fn delete_session(&self, session_id: &str) -> Result<()> {
-        let filename = format!("{}.nrs", session_id);
-        let path = self.sessions_dir.join(&filename);
+        let path = self.session_path(session_id)?;
         
         fs::remove_file(&path)
             .context("Failed to delete session file")?;
@@ -124,8 +163,7 @@ impl SessionManager {
     
     /// Export session to a specific path
     pub fn export_session(&self, session_id: &str, dest_path: impl AsRef<Path>) -> Result<()> {
-        let filename = format!("{}.nrs", session_id);
-        let src_path = self.sessions_dir.join(&filename);
+        let src_path = self.session_path(session_id)?;
         
         fs::copy(&src_path, dest_path.as_ref())
             .context("Failed to export session")?;
@@ -133,14 +171,10 @@ impl SessionManager {
         tracing::info!("Session exported: {} -> {}", session_id, dest_path.as_ref().display());
         Ok(())
     }
-
+    
     /// Export session to default exports directory
     pub fn export_session_auto(&self, session_id: &str) -> Result<PathBuf> {
-        let exports_dir = self.sessions_dir.parent()
-            .unwrap_or_else(|| Path::new("."))
-            .join("exports");
-            
-        fs::create_dir_all(&exports_dir).context("Failed to create exports directory")?;
+        let exports_dir = self.exports_dir()?;
         
         let filename = format!("{}_{}.nrs", session_id, Utc::now().format("%Y%m%d_%H%M%S"));
         let dest_path = exports_dir.join(&filename);
@@ -149,6 +183,114 @@ impl SessionManager {
         
         Ok(dest_path)
     }
+    
+    /// Export session as a JSON Lines event stream to the exports directory
+    pub fn export_session_jsonl(&self, session: &SessionState) -> Result<PathBuf> {
+        let exports_dir = self.exports_dir()?;
+        
+        let filename = format!("{}_{}.jsonl", session.id, Utc::now().format("%Y%m%d_%H%M%S"));
+        let dest_path = exports_dir.join(&filename);
+        
+        let mut file = fs::File::create(&dest_path)
+            .context("Failed to create JSONL export")?;
+        for record in jsonl::flatten(session) {
+            writeln!(file, "{}", record).context("Failed to write JSONL export")?;
+        }
+        
+        tracing::info!("Session exported as JSONL: {} -> {}", session.id, dest_path.display());
+        Ok(dest_path)
+    }
+    
+    /// Export findings and the session's audit records as CEF or LEEF lines
+    /// to the exports directory
+    pub fn export_session_siem(&self, session: &SessionState, format: SiemFormat, audit: &[AuditRecord]) -> Result<PathBuf> {
+        let exports_dir = self.exports_dir()?;
+        
+        let filename = format!("{}_{}.{}", session.id, Utc::now().format("%Y%m%d_%H%M%S"), format.extension());
+        let dest_path = exports_dir.join(&filename);
+        
+        let mut file = fs::File::create(&dest_path)
+            .context("Failed to create SIEM export")?;
+        for finding in &session.findings {
+            writeln!(file, "{}", siem::finding_line(format, Some(&session.id), finding))
+                .context("Failed to write SIEM export")?;
+        }
+        for record in audit {
+            writeln!(file, "{}", siem::audit_line(format, record)).context("Failed to write SIEM export")?;
+        }
+        
+        tracing::info!("Session exported as {:?}: {} -> {}", format, session.id, dest_path.display());
+        Ok(dest_path)
+    }
+    
+    /// Export findings and assets as a STIX 2.1 bundle to the exports
+    /// directory
+    pub fn export_session_stix(&self, session: &SessionState) -> Result<PathBuf> {
+        let exports_dir = self.exports_dir()?;
+        
+        let filename = format!("{}_{}.stix.json", session.id, Utc::now().format("%Y%m%d_%H%M%S"));
+        let dest_path = exports_dir.join(&filename);
+        
+        let json = serde_json::to_string_pretty(&stix::bundle(session))?;
+        fs::write(&dest_path, json).context("Failed to write STIX export")?;
+        
+        tracing::info!("Session exported as STIX: {} -> {}", session.id, dest_path.display());
+        Ok(dest_path)
+    }
+    
+    /// Export the saved session, its event journal and every artifact file
+    /// as one encrypted archive in the exports directory
+    pub fn export_session_archive(&self, session: &SessionState, journal: &Path, password: &str) -> Result<PathBuf> {
+        let exports_dir = self.exports_dir()?;
+        
+        let mut entries = vec![archive::Entry {
+            name: format!("{}.nrs", session.id),
+            source: self.session_path(&session.id)?,
+        }];
+        if journal.exists() {
+            entries.push(archive::Entry { name: "journal.jsonl".to_string(), source: journal.to_path_buf() });
+        }
+        for artifact in &session.artifacts {
+            let source = PathBuf::from(&artifact.path);
+            if !source.is_file() {
+                tracing::warn!("Artifact {} missing from {}, left out of the archive", artifact.id, artifact.path);
+                continue;
+            }
+            let file_name = Path::new(&artifact.name).file_name()
+                .map(|n| n.to_string_lossy().chars().take(100).collect::<String>())
+                .unwrap_or_else(|| artifact.id.clone());
+            entries.push(archive::Entry { name: format!("artifacts/{}/{}", artifact.id, file_name), source });
+        }
+        
+        let filename = format!("{}_{}.{}", session.id, Utc::now().format("%Y%m%d_%H%M%S"), archive::EXTENSION);
+        let dest_path = exports_dir.join(&filename);
+        if let Err(e) = archive::write(&dest_path, password, &entries) {
+            let _ = fs::remove_file(&dest_path);
+            return Err(e);
+        }
+        
+        tracing::info!("Session exported as encrypted archive: {} -> {} ({} files)", session.id, dest_path.display(), entries.len());
+        Ok(dest_path)
+    }
+    
+    /// Default exports directory, created on demand
+    /// Path of a session's .nrs file; IDs come from clients, so anything
+    /// but a generated ID is refused before it reaches the file system
+    fn session_path(&self, session_id: &str) -> Result<PathBuf> {
+        if !is_valid_session_id(session_id) {
+            bail!("Invalid session ID {:?}", session_id);
+        }
+        Ok(self.sessions_dir.join(format!("{}.nrs", session_id)))
+    }
+    
+    fn exports_dir(&self) -> Result<PathBuf> {
+        let exports_dir = self.sessions_dir.parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join("exports");
+        
+        fs::create_dir_all(&exports_dir).context("Failed to create exports directory")?;
+        Ok(exports_dir)
+    }
 }
 
 /// Session metadata for listing
@@ -163,3 +305,30 @@ pub struct SessionMetadata {
     pub task_count: usize,
     pub finding_count: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::OperationalMode;
+    
+    #[test]
+    fn unreadable_files_are_told_apart_and_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = SessionManager::new(dir.path()).unwrap();
+        let session = SessionState::new("web".to_string(), OperationalMode::Defensive);
+        
+        let missing = manager.load_session(&session.id).unwrap_err();
+        assert!(missing.downcast_ref::<std::io::Error>().is_some_and(|e| e.kind() == std::io::ErrorKind::NotFound));
+        assert!(manager.set_aside(&session.id).unwrap().is_none());
+        
+        let path = manager.save_session(&session).unwrap();
+        fs::write(&path, "{\"version\": \"1.0\", \"sess").unwrap();
+        let corrupt = manager.load_session(&session.id).unwrap_err();
+        assert!(corrupt.downcast_ref::<serde_json::Error>().is_some());
+        
+        let kept = manager.set_aside(&session.id).unwrap().unwrap();
+        assert!(!path.exists());
+        assert!(kept.file_name().unwrap().to_str().unwrap().starts_with(&format!("{}.nrs.corrupt-", session.id)));
+        assert_eq!(fs::read_to_string(kept).unwrap(), "{\"version\": \"1.0\", \"sess");
+    }
+}
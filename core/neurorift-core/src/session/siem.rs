@@ -0,0 +1,184 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use crate::security::audit::AuditRecord;
+use crate::state::{Finding, Severity};
+
+const VENDOR: &str = "NeuroRift";
+const PRODUCT: &str = "NeuroRift Core";
+
+/// Event line format read by SIEMs
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SiemFormat {
+    /// ArcSight Common Event Format, as read by Splunk and ArcSight
+    #[default]
+    Cef,
+    /// Log Event Extended Format 1.0, as read by QRadar
+    Leef,
+}
+
+impl SiemFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Cef => "cef",
+            Self::Leef => "leef",
+        }
+    }
+}
+
+/// Where a value goes in each format
+enum Key {
+    /// A key both formats define, under their own names
+    Standard { cef: &'static str, leef: &'static str },
+    /// A key neither defines; CEF carries it in a labelled `csN` field
+    Custom(&'static str),
+}
+
+/// One event before it is rendered
+struct Event {
+    /// CEF signature ID and LEEF event ID
+    id: String,
+    name: String,
+    /// 0 to 10
+    severity: u8,
+    time: DateTime<Utc>,
+    fields: Vec<(Key, String)>,
+}
+
+/// One line for a finding, tagged with the session it was found in
+pub fn finding_line(format: SiemFormat, session_id: Option<&str>, finding: &Finding) -> String {
+    let mut fields = vec![
+        (Key::Standard { cef: "cat", leef: "cat" }, "finding".to_string()),
+        (Key::Standard { cef: "externalId", leef: "externalId" }, finding.id.clone()),
+        (Key::Standard { cef: "msg", leef: "msg" }, finding.description.clone()),
+        (Key::Standard { cef: "cnt", leef: "cnt" }, finding.seen_count.to_string()),
+        (Key::Custom("tool"), finding.tool_source.clone()),
+    ];
+    if let Some(session_id) = session_id {
+        fields.push((Key::Custom("sessionId"), session_id.to_string()));
+    }
+    if let Some(target) = &finding.target {
+        fields.push((Key::Custom("target"), target.clone()));
+    }
+    if let Some(vector) = &finding.cvss_vector {
+        fields.push((Key::Custom("cvssVector"), vector.clone()));
+    }
+    if let Some(score) = finding.cvss_score {
+        fields.push((Key::Custom("cvssScore"), format!("{:.1}", score)));
+    }
+    render(format, Event {
+        id: finding.tool_source.clone(),
+        name: finding.title.clone(),
+        severity: severity(&finding.severity),
+        time: finding.discovered_at,
+        fields,
+    })
+}
+
+/// One line for an audit record
+pub fn audit_line(format: SiemFormat, record: &AuditRecord) -> String {
+    let mut fields = vec![
+        (Key::Standard { cef: "cat", leef: "cat" }, "audit".to_string()),
+        (Key::Standard { cef: "externalId", leef: "externalId" }, record.seq.to_string()),
+        (Key::Standard { cef: "suser", leef: "usrName" }, record.actor.to_string()),
+        (Key::Standard { cef: "act", leef: "action" }, record.command.clone()),
+        (Key::Standard { cef: "outcome", leef: "outcome" }, record.outcome.clone()),
+        (Key::Custom("hash"), record.hash.clone()),
+    ];
+    if let Some(target) = &record.target {
+        fields.push((Key::Custom("target"), target.clone()));
+    }
+    if let Some(client_id) = &record.client_id {
+        fields.push((Key::Custom("client"), client_id.clone()));
+    }
+    render(format, Event {
+        id: record.command.clone(),
+        name: format!("{} {}", record.command, record.outcome),
+        severity: if record.outcome == "denied" { 5 } else { 2 },
+        time: record.timestamp,
+        fields,
+    })
+}
+
+/// CEF and LEEF severity for a finding
+fn severity(severity: &Severity) -> u8 {
+    match severity {
+        Severity::Info => 1,
+        Severity::Low => 3,
+        Severity::Medium => 5,
+        Severity::High => 8,
+        Severity::Critical => 10,
+    }
+}
+
+fn render(format: SiemFormat, event: Event) -> String {
+    let version = env!("CARGO_PKG_VERSION");
+    match format {
+        SiemFormat::Cef => {
+            let mut extension = vec![format!("rt={}", event.time.timestamp_millis())];
+            let mut custom = 0;
+            for (key, value) in &event.fields {
+                match key {
+                    Key::Standard { cef, .. } => extension.push(format!("{}={}", cef, cef_value(value))),
+                    // CEF has six custom string fields
+                    Key::Custom(label) if custom < 6 => {
+                        custom += 1;
+                        extension.push(format!("cs{}Label={}", custom, label));
+                        extension.push(format!("cs{}={}", custom, cef_value(value)));
+                    }
+                    Key::Custom(label) => tracing::debug!("No CEF field left for {}", label),
+                }
+            }
+            format!(
+                "CEF:0|{}|{}|{}|{}|{}|{}|{}",
+                cef_header(VENDOR),
+                cef_header(PRODUCT),
+                cef_header(version),
+                cef_header(&event.id),
+                cef_header(&event.name),
+                event.severity,
+                extension.join(" "),
+            )
+        }
+        SiemFormat::Leef => {
+            let mut attributes = vec![
+                format!("devTime={}", event.time.to_rfc3339_opts(SecondsFormat::Millis, true)),
+                "devTimeFormat=yyyy-MM-dd'T'HH:mm:ss.SSSX".to_string(),
+                format!("sev={}", event.severity),
+                format!("name={}", leef_value(&event.name)),
+            ];
+            for (key, value) in &event.fields {
+                let key = match key {
+                    Key::Standard { leef, .. } => leef,
+                    Key::Custom(label) => label,
+                };
+                attributes.push(format!("{}={}", key, leef_value(value)));
+            }
+            format!(
+                "LEEF:1.0|{}|{}|{}|{}|{}",
+                leef_header(VENDOR),
+                leef_header(PRODUCT),
+                leef_header(version),
+                leef_header(&event.id),
+                attributes.join("\t"),
+            )
+        }
+    }
+}
+
+fn cef_header(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+fn cef_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('=', "\\=").replace('\r', "\\r").replace('\n', "\\n")
+}
+
+fn leef_header(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\t', '\r', '\n'], " ")
+}
+
+/// Tabs separate attributes, so none may appear in a value
+fn leef_value(value: &str) -> String {
+    value.replace(['\t', '\r', '\n'], " ")
+}
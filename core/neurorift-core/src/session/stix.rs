@@ -0,0 +1,188 @@
+//! STIX 2.1 bundles for threat-intel platforms.
+//!
+//! Each finding becomes a `vulnerability`, each asset an `infrastructure`
+//! with an `indicator` matching its addresses and hostnames. Identifiers
+//! are UUIDv5 over the session and object IDs, so exporting a session
+//! again updates the same objects instead of duplicating them.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Value};
+use sha1::{Digest, Sha1};
+use std::net::IpAddr;
+use uuid::Uuid;
+use crate::enrichment::extract_cves;
+use crate::state::{Finding, SessionState};
+use crate::state::asset::{Asset, Protocol};
+
+const SPEC_VERSION: &str = "2.1";
+
+/// Namespace STIX 2.1 defines for deterministic identifiers
+const NAMESPACE: Uuid = Uuid::from_u128(0x00abedb4_aa42_466c_9c01_fed23315a9b7);
+
+/// Bundle of the session's findings and assets
+pub fn bundle(session: &SessionState) -> Value {
+    // One producer identity per session, so its creation time never conflicts
+    let identity = id("identity", &session.id);
+    let mut objects = vec![json!({
+        "type": "identity",
+        "spec_version": SPEC_VERSION,
+        "id": identity,
+        "created": timestamp(session.created_at),
+        "modified": timestamp(session.created_at),
+        "name": format!("NeuroRift: {}", session.name),
+        "identity_class": "system",
+    })];
+    
+    for asset in &session.assets {
+        let infrastructure = id("infrastructure", &format!("{}:{}", session.id, asset.id));
+        objects.push(infrastructure_object(asset, &infrastructure, &identity));
+        
+        if let Some(pattern) = pattern(asset) {
+            let indicator = id("indicator", &format!("{}:{}", session.id, asset.id));
+            objects.push(json!({
+                "type": "indicator",
+                "spec_version": SPEC_VERSION,
+                "id": indicator,
+                "created_by_ref": identity,
+                "created": timestamp(asset.first_seen),
+                "modified": timestamp(asset.last_seen),
+                "name": format!("Observed {}", label(asset)),
+                "indicator_types": ["unknown"],
+                "pattern": pattern,
+                "pattern_type": "stix",
+                "valid_from": timestamp(asset.first_seen),
+            }));
+            objects.push(relationship(session, &identity, &indicator, "indicates", &infrastructure, asset.first_seen));
+        }
+    }
+    
+    for finding in &session.findings {
+        let vulnerability = id("vulnerability", &format!("{}:{}", session.id, finding.id));
+        objects.push(vulnerability_object(finding, &vulnerability, &identity));
+        
+        let asset = finding.asset_id.as_deref()
+            .filter(|asset_id| session.assets.iter().any(|a| a.id == *asset_id));
+        if let Some(asset_id) = asset {
+            let infrastructure = id("infrastructure", &format!("{}:{}", session.id, asset_id));
+            objects.push(relationship(session, &identity, &infrastructure, "has", &vulnerability, finding.discovered_at));
+        }
+    }
+    
+    json!({
+        "type": "bundle",
+        "id": format!("bundle--{}", Uuid::new_v4()),
+        "objects": objects,
+    })
+}
+
+fn infrastructure_object(asset: &Asset, id: &str, identity: &str) -> Value {
+    let services: Vec<String> = asset.services.iter()
+        .map(|service| {
+            let protocol = match service.protocol {
+                Protocol::Tcp => "tcp",
+                Protocol::Udp => "udp",
+            };
+            let detail: Vec<&str> = [&service.name, &service.product, &service.version]
+                .into_iter()
+                .filter_map(|part| part.as_deref())
+                .collect();
+            format!("{}/{} {}", service.port, protocol, detail.join(" ")).trim_end().to_string()
+        })
+        .collect();
+    let mut object = json!({
+        "type": "infrastructure",
+        "spec_version": SPEC_VERSION,
+        "id": id,
+        "created_by_ref": identity,
+        "created": timestamp(asset.first_seen),
+        "modified": timestamp(asset.last_seen),
+        "name": label(asset),
+        "infrastructure_types": ["unknown"],
+        "first_seen": timestamp(asset.first_seen),
+        "last_seen": timestamp(asset.last_seen),
+    });
+    if !services.is_empty() {
+        object["description"] = json!(format!("Open services: {}", services.join(", ")));
+    }
+    if let Some(os) = &asset.os_guess {
+        object["x_neurorift_os_guess"] = json!(os);
+    }
+    object
+}
+
+fn vulnerability_object(finding: &Finding, id: &str, identity: &str) -> Value {
+    let references: Vec<Value> = extract_cves(finding).into_iter()
+        .map(|cve| json!({ "source_name": "cve", "external_id": cve }))
+        .collect();
+    let mut object = json!({
+        "type": "vulnerability",
+        "spec_version": SPEC_VERSION,
+        "id": id,
+        "created_by_ref": identity,
+        "created": timestamp(finding.discovered_at),
+        "modified": timestamp(finding.last_seen.unwrap_or(finding.discovered_at)),
+        "name": finding.title,
+        "description": finding.description,
+        "x_neurorift_severity": finding.severity,
+        "x_neurorift_tool": finding.tool_source,
+    });
+    if !references.is_empty() {
+        object["external_references"] = json!(references);
+    }
+    if let Some(target) = &finding.target {
+        object["x_neurorift_target"] = json!(target);
+    }
+    if let Some(vector) = &finding.cvss_vector {
+        object["x_neurorift_cvss_vector"] = json!(vector);
+    }
+    if let Some(score) = finding.cvss_score {
+        object["x_neurorift_cvss_score"] = json!(score);
+    }
+    object
+}
+
+fn relationship(session: &SessionState, identity: &str, source: &str, kind: &str, target: &str, at: DateTime<Utc>) -> Value {
+    json!({
+        "type": "relationship",
+        "spec_version": SPEC_VERSION,
+        "id": id("relationship", &format!("{}:{}:{}:{}", session.id, source, kind, target)),
+        "created_by_ref": identity,
+        "created": timestamp(at),
+        "modified": timestamp(at),
+        "relationship_type": kind,
+        "source_ref": source,
+        "target_ref": target,
+    })
+}
+
+/// Pattern matching any of the asset's addresses or hostnames
+fn pattern(asset: &Asset) -> Option<String> {
+    let addresses = asset.addresses.iter().map(|address| match address {
+        IpAddr::V4(v4) => format!("[ipv4-addr:value = '{}']", v4),
+        IpAddr::V6(v6) => format!("[ipv6-addr:value = '{}']", v6),
+    });
+    let hostnames = asset.hostnames.iter()
+        .map(|hostname| format!("[domain-name:value = '{}']", hostname.replace('\\', "\\\\").replace('\'', "\\'")));
+    let terms: Vec<String> = addresses.chain(hostnames).collect();
+    (!terms.is_empty()).then(|| terms.join(" OR "))
+}
+
+fn label(asset: &Asset) -> String {
+    asset.hostnames.first().cloned()
+        .or_else(|| asset.addresses.first().map(|ip| ip.to_string()))
+        .unwrap_or_else(|| asset.id.clone())
+}
+
+/// UUIDv5 identifier of a STIX object
+fn id(kind: &str, name: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(NAMESPACE.as_bytes());
+    hasher.update(format!("{}:{}", kind, name).as_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&hasher.finalize()[..16]);
+    format!("{}--{}", kind, uuid::Builder::from_sha1_bytes(bytes).into_uuid())
+}
+
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
@@ -0,0 +1,296 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use uuid::Uuid;
+
+/// A host discovered during the engagement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Asset {
+    pub id: String,
+    #[serde(default)]
+    pub addresses: Vec<IpAddr>,
+    #[serde(default)]
+    pub hostnames: Vec<String>,
+    #[serde(default)]
+    pub os_guess: Option<String>,
+    /// Open ports and what is listening on them
+    #[serde(default)]
+    pub services: Vec<Service>,
+    /// A, AAAA and CNAME records for its hostnames
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dns: Vec<DnsRecord>,
+    /// Tools that reported this host
+    #[serde(default)]
+    pub sources: Vec<String>,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// An open port on an asset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Service {
+    pub port: u16,
+    #[serde(default)]
+    pub protocol: Protocol,
+    /// Service name, e.g. `http` or `ssh`
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Product reported by version detection, e.g. `OpenSSH`
+    #[serde(default)]
+    pub product: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Raw banner or version string as the scanner printed it
+    #[serde(default)]
+    pub banner: Option<String>,
+    /// What an HTTP probe saw, for web services
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpInfo>,
+}
+
+impl Service {
+    /// Open port with nothing known about what is listening
+    pub fn new(port: u16, protocol: Protocol) -> Self {
+        Self {
+            port,
+            protocol,
+            name: None,
+            product: None,
+            version: None,
+            banner: None,
+            http: None,
+        }
+    }
+    
+    /// Short description, e.g. `22/tcp ssh OpenSSH 8.9p1`
+    pub fn label(&self) -> String {
+        let protocol = match self.protocol {
+            Protocol::Tcp => "tcp",
+            Protocol::Udp => "udp",
+        };
+        let mut label = format!("{}/{}", self.port, protocol);
+        for part in [&self.name, &self.product, &self.version].into_iter().flatten() {
+            label.push(' ');
+            label.push_str(part);
+        }
+        label
+    }
+    
+    /// Whether this is the same port as `other`
+    pub fn same_port(&self, other: &Service) -> bool {
+        self.port == other.port && self.protocol == other.protocol
+    }
+    
+    /// Take any detail `other` knows that this does not, returning whether anything changed
+    fn update(&mut self, other: Service) -> bool {
+        let mut changed = false;
+        for (field, value) in [
+            (&mut self.name, other.name),
+            (&mut self.product, other.product),
+            (&mut self.version, other.version),
+            (&mut self.banner, other.banner),
+        ] {
+            if value.is_some() && *field != value {
+                *field = value;
+                changed = true;
+            }
+        }
+        if other.http.is_some() && self.http != other.http {
+            self.http = other.http;
+            changed = true;
+        }
+        changed
+    }
+}
+
+/// Response to an HTTP probe of a service
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpInfo {
+    /// URL that was requested
+    pub url: String,
+    pub status: u16,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// `Server` header
+    #[serde(default)]
+    pub server: Option<String>,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// Where a redirect pointed, when redirects were not followed
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Technologies recognized from headers and markup, e.g. `nginx`, `WordPress`
+    #[serde(default)]
+    pub technologies: Vec<String>,
+    #[serde(default)]
+    pub tls: Option<TlsInfo>,
+}
+
+/// Certificate presented by a TLS service
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TlsInfo {
+    pub subject: String,
+    pub issuer: String,
+    /// DNS names from the subject alternative name extension
+    #[serde(default)]
+    pub names: Vec<String>,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    pub self_signed: bool,
+}
+
+/// A resolved DNS record
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnsRecord {
+    /// Name the record belongs to
+    pub name: String,
+    pub record_type: DnsRecordType,
+    /// Address, or the target name of a CNAME
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DnsRecordType {
+    A,
+    Aaaa,
+    Cname,
+}
+
+/// Transport protocol of a service
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    #[default]
+    Tcp,
+    Udp,
+}
+
+/// What a tool learned about a host, merged into the inventory by address or hostname
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssetObservation {
+    #[serde(default)]
+    pub address: Option<IpAddr>,
+    #[serde(default)]
+    pub hostname: Option<String>,
+    #[serde(default)]
+    pub os_guess: Option<String>,
+    #[serde(default)]
+    pub services: Vec<Service>,
+    #[serde(default)]
+    pub dns: Vec<DnsRecord>,
+}
+
+/// Result of recording an observation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssetUpsert {
+    /// A new asset with this ID was added
+    Added(String),
+    /// An existing asset with this ID gained new information
+    Updated(String),
+    /// The observation matched an asset and told us nothing new
+    Unchanged(String),
+}
+
+impl Asset {
+    /// Create an asset from its first observation
+    pub fn new(observation: AssetObservation, source: &str) -> Self {
+        let now = Utc::now();
+        let mut asset = Self {
+            id: format!("asset_{}", &Uuid::new_v4().to_string().replace("-", "")[..8]),
+            addresses: Vec::new(),
+            hostnames: Vec::new(),
+            os_guess: None,
+            services: Vec::new(),
+            dns: Vec::new(),
+            sources: Vec::new(),
+            first_seen: now,
+            last_seen: now,
+        };
+        asset.merge(observation, source);
+        asset
+    }
+    
+    /// Whether the observation describes this host
+    pub fn matches(&self, observation: &AssetObservation) -> bool {
+        observation.address.is_some_and(|ip| self.addresses.contains(&ip))
+            || observation.hostname.as_deref().is_some_and(|host| self.has_hostname(host))
+    }
+    
+    /// Whether a bare host (IP or hostname, as in a task target) is this asset
+    pub fn is_host(&self, host: &str) -> bool {
+        match host.parse::<IpAddr>() {
+            Ok(ip) => self.addresses.contains(&ip),
+            Err(_) => self.has_hostname(host),
+        }
+    }
+    
+    /// Fold an observation in, returning whether anything changed
+    pub fn merge(&mut self, observation: AssetObservation, source: &str) -> bool {
+        let mut changed = false;
+        
+        if let Some(ip) = observation.address {
+            if !self.addresses.contains(&ip) {
+                self.addresses.push(ip);
+                changed = true;
+            }
+        }
+        if let Some(host) = observation.hostname.map(|h| normalize_hostname(&h)).filter(|h| !h.is_empty()) {
+            if !self.hostnames.contains(&host) {
+                self.hostnames.push(host);
+                changed = true;
+            }
+        }
+        if observation.os_guess.is_some() && observation.os_guess != self.os_guess {
+            self.os_guess = observation.os_guess;
+            changed = true;
+        }
+        for service in observation.services {
+            match self.services.iter_mut().find(|s| s.same_port(&service)) {
+                Some(existing) => {
+                    changed |= existing.update(service);
+                }
+                None => {
+                    self.services.push(service);
+                    changed = true;
+                }
+            }
+        }
+        self.services.sort_by_key(|s| (s.port, s.protocol));
+        for record in observation.dns {
+            if !self.dns.contains(&record) {
+                self.dns.push(record);
+                changed = true;
+            }
+        }
+        
+        if !self.sources.iter().any(|s| s == source) {
+            self.sources.push(source.to_string());
+        }
+        self.last_seen = Utc::now();
+        changed
+    }
+    
+    /// Open ports on the asset
+    pub fn open_ports(&self) -> Vec<u16> {
+        let mut ports: Vec<u16> = self.services.iter().map(|s| s.port).collect();
+        ports.dedup();
+        ports
+    }
+    
+    /// Whether DNS records are known for a hostname
+    pub fn is_resolved(&self, host: &str) -> bool {
+        let host = normalize_hostname(host);
+        self.dns.iter().any(|r| r.name == host)
+    }
+    
+    fn has_hostname(&self, host: &str) -> bool {
+        let host = normalize_hostname(host);
+        self.hostnames.contains(&host)
+    }
+}
+
+/// Lowercase and drop the trailing root dot
+pub fn normalize_hostname(host: &str) -> String {
+    host.trim().trim_end_matches('.').to_ascii_lowercase()
+}
@@ -0,0 +1,227 @@
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::str::FromStr;
+use crate::state::Severity;
+
+/// Base metrics of a CVSS v3.x vector
+#[derive(Debug, Clone, PartialEq)]
+pub struct CvssVector {
+    attack_vector: f64,
+    attack_complexity: f64,
+    privileges_required: char,
+    user_interaction: f64,
+    scope_changed: bool,
+    confidentiality: f64,
+    integrity: f64,
+    availability: f64,
+}
+
+/// Base metric names, all of which must be present
+const BASE_METRICS: [&str; 8] = ["AV", "AC", "PR", "UI", "S", "C", "I", "A"];
+
+impl FromStr for CvssVector {
+    type Err = anyhow::Error;
+    
+    /// Parse a `CVSS:3.1/AV:N/AC:L/...` vector; temporal and environmental
+    /// metrics are accepted but do not affect the base score.
+    ///
+    /// `CVSS:3.0` vectors are scored with the 3.1 formulas. The base
+    /// metrics and weights did not change; 3.1 only redefined Roundup to
+    /// drop floating point error, so a 3.0 calculator can occasionally
+    /// show a score 0.1 higher than the one computed here.
+    fn from_str(vector: &str) -> Result<Self> {
+        let mut parts = vector.trim().split('/');
+        match parts.next() {
+            Some("CVSS:3.1") | Some("CVSS:3.0") => {}
+            _ => bail!("CVSS vector must start with CVSS:3.1 or CVSS:3.0"),
+        }
+        
+        let mut metrics = HashMap::new();
+        for part in parts {
+            let (name, value) = part.split_once(':')
+                .with_context(|| format!("Malformed CVSS metric '{}'", part))?;
+            if metrics.insert(name, value).is_some() {
+                bail!("Duplicate CVSS metric '{}'", name);
+            }
+        }
+        
+        let metric = |name: &str| -> Result<&str> {
+            metrics.get(name).copied().with_context(|| format!("Missing CVSS base metric {}", name))
+        };
+        for name in BASE_METRICS {
+            metric(name)?;
+        }
+        
+        let invalid = |name: &str| anyhow::anyhow!("Invalid value for CVSS metric {}", name);
+        let impact = |name: &str| -> Result<f64> {
+            match metric(name)? {
+                "H" => Ok(0.56),
+                "L" => Ok(0.22),
+                "N" => Ok(0.0),
+                _ => Err(invalid(name)),
+            }
+        };
+        
+        Ok(Self {
+            attack_vector: match metric("AV")? {
+                "N" => 0.85,
+                "A" => 0.62,
+                "L" => 0.55,
+                "P" => 0.2,
+                _ => return Err(invalid("AV")),
+            },
+            attack_complexity: match metric("AC")? {
+                "L" => 0.77,
+                "H" => 0.44,
+                _ => return Err(invalid("AC")),
+            },
+            privileges_required: match metric("PR")? {
+                value @ ("N" | "L" | "H") => value.chars().next().unwrap_or('N'),
+                _ => return Err(invalid("PR")),
+            },
+            user_interaction: match metric("UI")? {
+                "N" => 0.85,
+                "R" => 0.62,
+                _ => return Err(invalid("UI")),
+            },
+            scope_changed: match metric("S")? {
+                "U" => false,
+                "C" => true,
+                _ => return Err(invalid("S")),
+            },
+            confidentiality: impact("C")?,
+            integrity: impact("I")?,
+            availability: impact("A")?,
+        })
+    }
+}
+
+impl CvssVector {
+    /// CVSS v3.1 base score (0.0-10.0)
+    pub fn base_score(&self) -> f64 {
+        // Privileges weigh more when the scope changes
+        let privileges = match (self.privileges_required, self.scope_changed) {
+            ('N', _) => 0.85,
+            ('L', false) => 0.62,
+            ('L', true) => 0.68,
+            (_, false) => 0.27,
+            (_, true) => 0.5,
+        };
+        
+        let iss = 1.0 - (1.0 - self.confidentiality) * (1.0 - self.integrity) * (1.0 - self.availability);
+        let impact = if self.scope_changed {
+            7.52 * (iss - 0.029) - 3.25 * (iss - 0.02).powi(15)
+        } else {
+            6.42 * iss
+        };
+        let exploitability = 8.22 * self.attack_vector * self.attack_complexity * privileges * self.user_interaction;
+        
+        if impact <= 0.0 {
+            return 0.0;
+        }
+        if self.scope_changed {
+            roundup((1.08 * (impact + exploitability)).min(10.0))
+        } else {
+            roundup((impact + exploitability).min(10.0))
+        }
+    }
+}
+
+/// CVSS v3.1 Roundup: smallest one-decimal value >= input, robust to
+/// floating point error
+fn roundup(value: f64) -> f64 {
+    let scaled = (value * 100_000.0).round() as i64;
+    if scaled % 10_000 == 0 {
+        scaled as f64 / 100_000.0
+    } else {
+        ((scaled / 10_000) + 1) as f64 / 10.0
+    }
+}
+
+/// Qualitative severity rating for a base score
+pub fn severity_for(score: f64) -> Severity {
+    match score {
+        s if s >= 9.0 => Severity::Critical,
+        s if s >= 7.0 => Severity::High,
+        s if s >= 4.0 => Severity::Medium,
+        s if s > 0.0 => Severity::Low,
+        _ => Severity::Info,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn score(vector: &str) -> f64 {
+        vector.parse::<CvssVector>().unwrap().base_score()
+    }
+    
+    #[test]
+    fn scores_known_vectors() {
+        for (vector, expected) in [
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H", 9.8),
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:N/A:N", 7.5),
+            ("CVSS:3.1/AV:N/AC:H/PR:N/UI:N/S:U/C:H/I:N/A:N", 5.9),
+            ("CVSS:3.1/AV:L/AC:L/PR:L/UI:N/S:U/C:H/I:H/A:H", 7.8),
+            ("CVSS:3.1/AV:A/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:H", 6.5),
+            ("CVSS:3.1/AV:P/AC:H/PR:H/UI:R/S:U/C:L/I:N/A:N", 1.6),
+            ("CVSS:3.0/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H", 9.8),
+        ] {
+            assert_eq!(score(vector), expected, "{}", vector);
+        }
+    }
+    
+    #[test]
+    fn scores_scope_changed_vectors() {
+        for (vector, expected) in [
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H", 10.0),
+            ("CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N", 6.1),
+            ("CVSS:3.1/AV:N/AC:L/PR:L/UI:N/S:C/C:L/I:L/A:N", 6.4),
+            ("CVSS:3.1/AV:N/AC:L/PR:H/UI:N/S:C/C:H/I:H/A:H", 9.1),
+            ("CVSS:3.1/AV:L/AC:L/PR:L/UI:N/S:C/C:H/I:H/A:H", 8.8),
+        ] {
+            assert_eq!(score(vector), expected, "{}", vector);
+        }
+    }
+    
+    #[test]
+    fn no_impact_scores_zero() {
+        assert_eq!(score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:N"), 0.0);
+        assert_eq!(score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:N/I:N/A:N"), 0.0);
+        assert_eq!(severity_for(0.0), Severity::Info);
+    }
+    
+    #[test]
+    fn roundup_follows_the_specification() {
+        assert_eq!(roundup(4.0), 4.0);
+        assert_eq!(roundup(4.02), 4.1);
+        assert_eq!(roundup(4.00001), 4.1);
+        // Below the spec's five decimals a difference is floating point error
+        assert_eq!(roundup(4.000001), 4.0);
+        assert_eq!(roundup(0.1 + 0.2), 0.3);
+        assert_eq!(roundup(9.99), 10.0);
+    }
+    
+    #[test]
+    fn rejects_malformed_vectors() {
+        for vector in [
+            "CVSS:2.0/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H",
+            "AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H",
+            "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H",
+            "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:X",
+            "CVSS:3.1/AV:N/AV:L/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H",
+        ] {
+            assert!(vector.parse::<CvssVector>().is_err(), "{}", vector);
+        }
+    }
+    
+    #[test]
+    fn rates_scores_by_band() {
+        assert_eq!(severity_for(0.1), Severity::Low);
+        assert_eq!(severity_for(3.9), Severity::Low);
+        assert_eq!(severity_for(4.0), Severity::Medium);
+        assert_eq!(severity_for(7.0), Severity::High);
+        assert_eq!(severity_for(9.0), Severity::Critical);
+    }
+}
@@ -1,7 +1,16 @@
+pub mod asset;
+pub mod cvss;
+pub mod progress;
+pub mod stats;
+pub mod summary;
+
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use std::collections::{HashMap, VecDeque};
 use uuid::Uuid;
+use crate::artifacts::StoredFile;
+
+pub use asset::{Asset, AssetObservation, AssetUpsert};
 
 /// Operational mode for NeuroRift
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -31,6 +40,25 @@ pub enum AgentType {
     Scribe,
 }
 
+/// Who caused a state change: a human operator, an agent, or the core itself
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum Actor {
+    Operator(String),
+    Agent(AgentType),
+    System,
+}
+
+impl std::fmt::Display for Actor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Actor::Operator(name) => write!(f, "operator:{}", name),
+            Actor::Agent(agent) => write!(f, "agent:{:?}", agent),
+            Actor::System => write!(f, "system"),
+        }
+    }
+}
+
 /// Agent status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentStatus {
@@ -53,6 +81,19 @@ pub enum AgentState {
     Error,
 }
 
+/// Phase of an agent orchestration run
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentPhase {
+    Planning,
+    Operating,
+    Analyzing,
+    Reporting,
+    Done,
+    Failed,
+    Stopped,
+}
+
 /// Task in the execution queue
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
@@ -64,6 +105,17 @@ pub struct Task {
     pub created_at: DateTime<Utc>,
     pub started_at: Option<DateTime<Utc>>,
     pub completed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub created_by: Option<Actor>,
+    /// Trace ID following this task across the core and the Python bridge
+    #[serde(default)]
+    pub trace_id: Option<String>,
+    /// Approval gating this task, if it needed one
+    #[serde(default)]
+    pub approval_id: Option<String>,
+    /// Egress proxy for this task, overriding the global one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
 }
 
 /// Task status
@@ -75,6 +127,11 @@ pub enum TaskStatus {
     Completed,
     Failed,
     Cancelled,
+    /// Held until an operator decides its approval request
+    #[serde(rename = "awaiting_approval")]
+    AwaitingApproval,
+    /// Was running when the core stopped; it never reported an outcome
+    Interrupted,
 }
 
 /// Approval request for human-in-the-loop
@@ -85,6 +142,23 @@ pub struct ApprovalRequest {
     pub reason: String,
     pub created_at: DateTime<Utc>,
     pub status: ApprovalStatus,
+    #[serde(default)]
+    pub requested_by: Option<Actor>,
+    #[serde(default)]
+    pub decided_by: Option<Actor>,
+    #[serde(default)]
+    pub decided_at: Option<DateTime<Utc>>,
+    /// When the request is denied by default if nobody decides
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Times the request has been escalated; higher means more urgent
+    #[serde(default)]
+    pub escalation_level: u32,
+    #[serde(default)]
+    pub escalated_at: Option<DateTime<Utc>>,
+    /// Computed risk score and the factors behind it
+    #[serde(default)]
+    pub risk: Option<crate::security::risk::RiskAssessment>,
 }
 
 /// Action requiring approval
@@ -108,7 +182,7 @@ pub enum ActionType {
 }
 
 /// Risk level
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum RiskLevel {
     Low,
@@ -124,6 +198,7 @@ pub enum ApprovalStatus {
     Pending,
     Approved,
     Denied,
+    Expired,
 }
 
 /// Security finding
@@ -136,6 +211,113 @@ pub struct Finding {
     pub tool_source: String,
     pub discovered_at: DateTime<Utc>,
     pub details: serde_json::Value,
+    #[serde(default)]
+    pub added_by: Option<Actor>,
+    /// Host, URL or service the finding applies to
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Identity used to merge re-discoveries of the same issue
+    #[serde(default)]
+    pub dedup_key: String,
+    /// Times the finding has been reported, including the first
+    #[serde(default = "default_seen_count")]
+    pub seen_count: u32,
+    #[serde(default)]
+    pub last_seen: Option<DateTime<Utc>>,
+    /// CVSS v3.1 vector, e.g. `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`
+    #[serde(default)]
+    pub cvss_vector: Option<String>,
+    /// Base score computed from `cvss_vector`
+    #[serde(default)]
+    pub cvss_score: Option<f64>,
+    /// Artifacts that demonstrate the finding
+    #[serde(default)]
+    pub evidence: Vec<ArtifactRef>,
+    /// Host the finding was observed on
+    #[serde(default)]
+    pub asset_id: Option<String>,
+    /// Issue tracking the finding's remediation, e.g. `SEC-123`
+    #[serde(default)]
+    pub ticket: Option<String>,
+}
+
+fn default_seen_count() -> u32 {
+    1
+}
+
+/// A finding reported by a tool or agent, before deduplication
+#[derive(Debug, Clone)]
+pub struct NewFinding {
+    pub title: String,
+    pub severity: Severity,
+    pub description: String,
+    pub tool_source: String,
+    pub target: Option<String>,
+    pub details: serde_json::Value,
+    /// When set and valid, the severity is derived from its base score
+    pub cvss_vector: Option<String>,
+    /// Asset the finding applies to; resolved from `target` when omitted
+    pub asset_id: Option<String>,
+}
+
+/// Detail fields that change between otherwise identical scan results
+const VOLATILE_DETAIL_FIELDS: &[&str] = &["timestamp", "scan_time", "duration", "duration_ms", "elapsed", "request_id"];
+
+impl NewFinding {
+    /// Validated CVSS vector and its base score, if one was provided
+    fn cvss(&self) -> Option<(String, f64)> {
+        let vector = self.cvss_vector.as_deref()?;
+        match vector.parse::<cvss::CvssVector>() {
+            Ok(parsed) => Some((vector.trim().to_string(), parsed.base_score())),
+            Err(e) => {
+                tracing::warn!("Ignoring CVSS vector on '{}': {}", self.title, e);
+                None
+            }
+        }
+    }
+    
+    /// Dedup key: tool source, normalized title, target and a hash of the
+    /// details with volatile fields removed
+    pub fn dedup_key(&self) -> String {
+        use sha2::{Digest, Sha256};
+        
+        let mut details = self.details.clone();
+        strip_volatile(&mut details);
+        
+        let mut hasher = Sha256::new();
+        for part in [
+            self.tool_source.trim().to_lowercase(),
+            self.title.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase(),
+            self.target.as_deref().unwrap_or_default().trim().to_lowercase(),
+            // serde_json maps are sorted, so this is canonical
+            details.to_string(),
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0u8]);
+        }
+        hex::encode(&hasher.finalize()[..16])
+    }
+}
+
+/// Remove volatile fields from finding details, recursively
+fn strip_volatile(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.retain(|key, _| !VOLATILE_DETAIL_FIELDS.contains(&key.as_str()));
+            map.values_mut().for_each(strip_volatile);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_volatile),
+        _ => {}
+    }
+}
+
+/// Result of recording a finding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FindingUpsert {
+    /// A new finding with this ID was added
+    Added(String),
+    /// An existing finding with this ID was seen again
+    Merged(String),
 }
 
 /// Severity level
@@ -158,10 +340,16 @@ pub struct Artifact {
     pub path: String,
     pub created_at: DateTime<Utc>,
     pub metadata: HashMap<String, String>,
+    /// Hex SHA-256 of the content when registered; absent on artifacts
+    /// from sessions that predate checksums
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
 }
 
 /// Artifact type
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ArtifactType {
     Report,
@@ -171,6 +359,71 @@ pub enum ArtifactType {
     Other,
 }
 
+impl ArtifactType {
+    /// Best guess at the type of a file from its extension
+    pub fn from_path(path: &std::path::Path) -> Self {
+        let ext = path.extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match ext.as_str() {
+            "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" => ArtifactType::Screenshot,
+            "log" | "txt" | "har" | "http" | "req" | "resp" => ArtifactType::Log,
+            "pcap" | "pcapng" | "json" | "xml" | "csv" => ArtifactType::Data,
+            "md" | "html" | "pdf" => ArtifactType::Report,
+            _ => ArtifactType::Other,
+        }
+    }
+}
+
+/// Reference from a finding to an artifact that proves it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactRef {
+    pub artifact_id: String,
+    /// What the artifact shows, e.g. "login bypass response"
+    #[serde(default)]
+    pub caption: Option<String>,
+    pub attached_at: DateTime<Utc>,
+    #[serde(default)]
+    pub attached_by: Option<Actor>,
+}
+
+/// A target registered for the engagement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Target {
+    /// IP, CIDR, hostname or URL; matched like a scope entry
+    pub value: String,
+    /// Out-of-scope targets are registered so tasks against them are refused
+    pub in_scope: bool,
+    #[serde(default)]
+    pub note: Option<String>,
+    pub added_at: DateTime<Utc>,
+    #[serde(default)]
+    pub added_by: Option<Actor>,
+}
+
+/// One agent state transition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    pub timestamp: DateTime<Utc>,
+    pub agent: AgentType,
+    pub from: AgentState,
+    pub to: AgentState,
+    /// What the agent was working on after the transition
+    #[serde(default)]
+    pub task: Option<String>,
+}
+
+/// One turn of the operator's conversation with the AI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: crate::ai::Role,
+    pub content: String,
+    /// Model that wrote an assistant message
+    #[serde(default)]
+    pub model: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
 /// Complete session state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionState {
@@ -186,13 +439,38 @@ pub struct SessionState {
     pub findings: Vec<Finding>,
     pub artifacts: Vec<Artifact>,
     pub metadata: HashMap<String, String>,
+    /// Targets this engagement is authorized to touch
+    #[serde(default)]
+    pub scope: crate::security::scope::EngagementScope,
+    /// Constraints on when and how tasks may run
+    #[serde(default)]
+    pub roe: crate::security::roe::RulesOfEngagement,
+    /// Hosts discovered by tools
+    #[serde(default)]
+    pub assets: Vec<Asset>,
+    /// Targets registered by operators
+    #[serde(default)]
+    pub targets: Vec<Target>,
+    /// Conversation with the AI, oldest first
+    #[serde(default)]
+    pub chat_history: Vec<ChatMessage>,
+    /// Every agent state transition, oldest first
+    #[serde(default)]
+    pub timeline: Vec<TimelineEntry>,
+}
+
+/// Whether an ID has the form `SessionState::new` generates, and so is
+/// safe to use as a file name
+pub fn is_valid_session_id(id: &str) -> bool {
+    id.strip_prefix("session_")
+        .is_some_and(|hex| hex.len() == 12 && hex.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')))
 }
 
 impl SessionState {
     /// Create a new session
     pub fn new(name: String, mode: OperationalMode) -> Self {
         let now = Utc::now();
-        let id = format!("session_{}", Uuid::new_v4().to_string().replace("-", "")[..12].to_string());
+        let id = format!("session_{}", &Uuid::new_v4().to_string().replace("-", "")[..12]);
         
         let mut agent_states = HashMap::new();
         for agent in [AgentType::Planner, AgentType::Operator, AgentType::Navigator, AgentType::Analyst, AgentType::Scribe] {
@@ -217,6 +495,12 @@ impl SessionState {
             findings: Vec::new(),
             artifacts: Vec::new(),
             metadata: HashMap::new(),
+            scope: Default::default(),
+            roe: Default::default(),
+            assets: Vec::new(),
+            targets: Vec::new(),
+            chat_history: Vec::new(),
+            timeline: Vec::new(),
         }
     }
     
@@ -226,9 +510,9 @@ impl SessionState {
     }
     
     /// Add a task to the queue
-    pub fn queue_task(&mut self, tool_name: String, target: String, args: HashMap<String, serde_json::Value>) {
+    pub fn queue_task(&mut self, tool_name: String, target: String, args: HashMap<String, serde_json::Value>, proxy: Option<String>, created_by: Actor) {
         let task = Task {
-            id: format!("task_{}", Uuid::new_v4().to_string().replace("-", "")[..8].to_string()),
+            id: format!("task_{}", &Uuid::new_v4().to_string().replace("-", "")[..8]),
             tool_name,
             target,
             args,
@@ -236,20 +520,47 @@ impl SessionState {
             created_at: Utc::now(),
             started_at: None,
             completed_at: None,
+            created_by: Some(created_by),
+            trace_id: Some(crate::telemetry::TraceContext::new_root().trace_id),
+            approval_id: None,
+            proxy,
         };
         
         self.task_queue.push_back(task);
         self.touch();
     }
     
+    /// Mark tasks left `Running` by a core that stopped as interrupted,
+    /// skipping any `still_running` in this process; returns those marked
+    pub fn interrupt_stale_tasks(&mut self, still_running: impl Fn(&str) -> bool) -> Vec<Task> {
+        let now = Utc::now();
+        let mut interrupted = Vec::new();
+        for task in self.task_queue.iter_mut().filter(|t| t.status == TaskStatus::Running && !still_running(&t.id)) {
+            task.status = TaskStatus::Interrupted;
+            task.completed_at = Some(now);
+            interrupted.push(task.clone());
+        }
+        if !interrupted.is_empty() {
+            self.touch();
+        }
+        interrupted
+    }
+    
     /// Add an approval request
-    pub fn request_approval(&mut self, action: Action, reason: String) -> String {
+    pub fn request_approval(&mut self, action: Action, reason: String, requested_by: Actor) -> String {
         let approval = ApprovalRequest {
-            id: format!("approval_{}", Uuid::new_v4().to_string().replace("-", "")[..8].to_string()),
+            id: format!("approval_{}", &Uuid::new_v4().to_string().replace("-", "")[..8]),
             action,
             reason,
             created_at: Utc::now(),
             status: ApprovalStatus::Pending,
+            requested_by: Some(requested_by),
+            decided_by: None,
+            decided_at: None,
+            expires_at: None,
+            escalation_level: 0,
+            escalated_at: None,
+            risk: None,
         };
         
         let id = approval.id.clone();
@@ -258,19 +569,273 @@ impl SessionState {
         id
     }
     
-    /// Add a finding
-    pub fn add_finding(&mut self, title: String, severity: Severity, description: String, tool_source: String, details: serde_json::Value) {
+    /// Register an artifact produced for this session, with the checksum
+    /// of its file when it can be read
+    pub fn add_artifact(&mut self, artifact_type: ArtifactType, name: String, path: String, metadata: HashMap<String, String>) -> Artifact {
+        let checksum = crate::artifacts::hash_file(std::path::Path::new(&path)).ok();
+        self.push_artifact(artifact_type, name, path, metadata, checksum)
+    }
+    
+    /// Register content from the artifact store. Content the session
+    /// already has returns the existing artifact, with `false`.
+    pub fn add_stored_artifact(&mut self, artifact_type: ArtifactType, name: String, stored: &StoredFile, metadata: HashMap<String, String>) -> (Artifact, bool) {
+        if let Some(existing) = self.artifacts.iter().find(|a| a.sha256.as_deref() == Some(stored.sha256.as_str())) {
+            return (existing.clone(), false);
+        }
+        let path = stored.path.to_string_lossy().into_owned();
+        let checksum = Some((stored.sha256.clone(), stored.size));
+        (self.push_artifact(artifact_type, name, path, metadata, checksum), true)
+    }
+    
+    fn push_artifact(
+        &mut self,
+        artifact_type: ArtifactType,
+        name: String,
+        path: String,
+        metadata: HashMap<String, String>,
+        checksum: Option<(String, u64)>,
+    ) -> Artifact {
+        let (sha256, size) = checksum.unzip();
+        let artifact = Artifact {
+            id: format!("artifact_{}", &Uuid::new_v4().to_string().replace("-", "")[..8]),
+            artifact_type,
+            name,
+            path,
+            created_at: Utc::now(),
+            metadata,
+            sha256,
+            size,
+        };
+        
+        self.artifacts.push(artifact.clone());
+        self.touch();
+        artifact
+    }
+    
+    /// Register a target, replacing any existing entry with the same value
+    pub fn add_target(&mut self, value: String, in_scope: bool, note: Option<String>, added_by: Actor) -> Target {
+        let value = value.trim().to_string();
+        let target = Target {
+            value: value.clone(),
+            in_scope,
+            note,
+            added_at: Utc::now(),
+            added_by: Some(added_by),
+        };
+        
+        self.targets.retain(|t| !t.value.eq_ignore_ascii_case(&value));
+        self.targets.push(target.clone());
+        self.touch();
+        target
+    }
+    
+    /// Unregister a target, returning it if it was present
+    pub fn remove_target(&mut self, value: &str) -> Option<Target> {
+        let index = self.targets.iter().position(|t| t.value.eq_ignore_ascii_case(value.trim()))?;
+        self.touch();
+        Some(self.targets.remove(index))
+    }
+    
+    /// Set an agent's state and task, recording the transition on the
+    /// timeline when either changed
+    pub fn set_agent_state(&mut self, agent: AgentType, state: AgentState, current_task: Option<String>) -> Option<AgentStatus> {
+        let now = Utc::now();
+        let status = self.agent_states.get_mut(&agent)?;
+        let changed = status.state != state || status.current_task != current_task;
+        let from = std::mem::replace(&mut status.state, state.clone());
+        status.current_task = current_task.clone();
+        status.last_update = now;
+        let status = status.clone();
+        
+        if changed {
+            self.timeline.push(TimelineEntry {
+                timestamp: now,
+                agent,
+                from,
+                to: state,
+                task: current_task,
+            });
+        }
+        self.touch();
+        Some(status)
+    }
+    
+    /// Append a message to the chat history
+    pub fn add_chat_message(&mut self, role: crate::ai::Role, content: String, model: Option<String>) -> ChatMessage {
+        let message = ChatMessage {
+            role,
+            content,
+            model,
+            timestamp: Utc::now(),
+        };
+        
+        self.chat_history.push(message.clone());
+        self.touch();
+        message
+    }
+    
+    /// Check a task target against the registered targets and the
+    /// engagement scope, returning the reason if it may not be tested.
+    ///
+    /// Targets marked out of scope always refuse. Once any in-scope target
+    /// is registered, tasks must aim at one of them.
+    pub fn check_target(&self, target: &str) -> Result<(), String> {
+        use crate::security::scope::entry_matches;
+        
+        if let Some(excluded) = self.targets.iter().find(|t| !t.in_scope && entry_matches(&t.value, target)) {
+            return Err(format!("Target '{}' matches out-of-scope target '{}'", target, excluded.value));
+        }
+        self.scope.check(target)?;
+        
+        let mut registered = self.targets.iter().filter(|t| t.in_scope).peekable();
+        if registered.peek().is_some() && !registered.any(|t| entry_matches(&t.value, target)) {
+            return Err(format!("Target '{}' is not in the session's target list", target));
+        }
+        Ok(())
+    }
+    
+    /// Merge a tool's observation of a host into the asset inventory;
+    /// observations with neither an address nor a hostname are ignored
+    pub fn upsert_asset(&mut self, observation: AssetObservation, source: &str) -> Option<AssetUpsert> {
+        if observation.address.is_none() && observation.hostname.is_none() {
+            return None;
+        }
+        
+        let upsert = match self.assets.iter_mut().find(|a| a.matches(&observation)) {
+            Some(asset) => {
+                let id = asset.id.clone();
+                if asset.merge(observation, source) {
+                    AssetUpsert::Updated(id)
+                } else {
+                    AssetUpsert::Unchanged(id)
+                }
+            }
+            None => {
+                let asset = Asset::new(observation, source);
+                let id = asset.id.clone();
+                self.assets.push(asset);
+                AssetUpsert::Added(id)
+            }
+        };
+        self.touch();
+        Some(upsert)
+    }
+    
+    /// Asset whose address or hostname is the host part of a target
+    pub fn asset_for_target(&self, target: &str) -> Option<&Asset> {
+        let host = crate::security::scope::target_host(target)?;
+        self.assets.iter().find(|a| a.is_host(&host))
+    }
+    
+    /// Remove an artifact and any evidence links to it
+    pub fn remove_artifact(&mut self, artifact_id: &str) -> Option<Artifact> {
+        let index = self.artifacts.iter().position(|a| a.id == artifact_id)?;
+        let artifact = self.artifacts.remove(index);
+        for finding in &mut self.findings {
+            finding.evidence.retain(|e| e.artifact_id != artifact_id);
+        }
+        self.touch();
+        Some(artifact)
+    }
+    
+    /// Link an artifact to a finding as evidence; re-attaching updates the caption
+    pub fn attach_evidence(&mut self, finding_id: &str, artifact_id: &str, caption: Option<String>, attached_by: Actor) -> Option<ArtifactRef> {
+        let finding = self.findings.iter_mut().find(|f| f.id == finding_id)?;
+        
+        let evidence = match finding.evidence.iter_mut().find(|e| e.artifact_id == artifact_id) {
+            Some(existing) => {
+                if caption.is_some() {
+                    existing.caption = caption;
+                }
+                existing.clone()
+            }
+            None => {
+                let evidence = ArtifactRef {
+                    artifact_id: artifact_id.to_string(),
+                    caption,
+                    attached_at: Utc::now(),
+                    attached_by: Some(attached_by),
+                };
+                finding.evidence.push(evidence.clone());
+                evidence
+            }
+        };
+        self.touch();
+        Some(evidence)
+    }
+    
+    /// Add a finding, merging it into an existing one with the same dedup key
+    pub fn add_finding(&mut self, mut new: NewFinding, added_by: Actor) -> FindingUpsert {
+        let now = Utc::now();
+        let dedup_key = new.dedup_key();
+        let cvss = new.cvss();
+        if let Some((_, score)) = &cvss {
+            new.severity = cvss::severity_for(*score);
+        }
+        
+        if let Some(existing) = self.findings.iter_mut().find(|f| f.dedup_key == dedup_key) {
+            existing.seen_count += 1;
+            existing.last_seen = Some(now);
+            existing.severity = existing.severity.clone().max(new.severity);
+            if let Some((vector, score)) = cvss {
+                existing.cvss_vector = Some(vector);
+                existing.cvss_score = Some(score);
+            }
+            // Keep the latest volatile values (timestamps, durations) but
+            // not at the cost of enrichment already attached
+            let nvd = existing.details.get("nvd").cloned();
+            existing.details = new.details;
+            if let (Some(nvd), Some(details)) = (nvd, existing.details.as_object_mut()) {
+                details.entry("nvd").or_insert(nvd);
+            }
+            let id = existing.id.clone();
+            self.touch();
+            return FindingUpsert::Merged(id);
+        }
+        
+        let asset_id = new.asset_id.take()
+            .or_else(|| new.target.as_deref().and_then(|t| self.asset_for_target(t)).map(|a| a.id.clone()));
         let finding = Finding {
-            id: format!("finding_{}", Uuid::new_v4().to_string().replace("-", "")[..8].to_string()),
-            title,
-            severity,
-            description,
-            tool_source,
-            discovered_at: Utc::now(),
-            details,
+            id: format!("finding_{}", &Uuid::new_v4().to_string().replace("-", "")[..8]),
+            title: new.title,
+            severity: new.severity,
+            description: new.description,
+            tool_source: new.tool_source,
+            discovered_at: now,
+            details: new.details,
+            added_by: Some(added_by),
+            target: new.target,
+            dedup_key,
+            seen_count: 1,
+            last_seen: Some(now),
+            cvss_score: cvss.as_ref().map(|(_, score)| *score),
+            cvss_vector: cvss.map(|(vector, _)| vector),
+            evidence: Vec::new(),
+            asset_id,
+            ticket: None,
         };
         
+        let id = finding.id.clone();
         self.findings.push(finding);
         self.touch();
+        FindingUpsert::Added(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn generated_session_ids_are_valid() {
+        let session = SessionState::new("test".to_string(), OperationalMode::Offensive);
+        assert!(is_valid_session_id(&session.id));
+    }
+    
+    #[test]
+    fn path_like_session_ids_are_rejected() {
+        for id in ["../session_0123456789ab", "session_0123456789ab/..", "session_../../../x", "session_0123456789AB", "session_0123456789a", "_core", ""] {
+            assert!(!is_valid_session_id(id), "{:?} accepted", id);
+        }
     }
 }
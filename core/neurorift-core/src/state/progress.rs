@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use super::{AgentPhase, SessionState, TaskStatus};
+
+/// Share of an agent run, out of 100, taken by its planning cycles; the
+/// rest is report writing
+const CYCLES_SHARE: f64 = 90.0;
+
+/// Where an agent run on a session stands
+#[derive(Debug, Clone, Copy)]
+pub struct RunProgress {
+    pub phase: AgentPhase,
+    /// Current planning round, from 1
+    pub cycle: u32,
+    pub max_cycles: u32,
+    /// When the run entered `phase`
+    pub since: DateTime<Utc>,
+}
+
+/// Overall completion of a session, 0 to 100.
+///
+/// During an agent run each cycle takes an equal share of the first 90%,
+/// split between planning, operating (advancing as the tasks the cycle
+/// queued finish) and analysis; reporting fills the rest. Otherwise it is
+/// the share of queued tasks that have finished.
+pub fn percent(session: &SessionState, run: Option<&RunProgress>) -> u8 {
+    let Some(run) = run else {
+        return task_percent(session, None);
+    };
+    let cycle_share = CYCLES_SHARE / f64::from(run.max_cycles.max(1));
+    let cycles_done = f64::from(run.cycle.saturating_sub(1)) * cycle_share;
+    let within = match run.phase {
+        AgentPhase::Planning => 0.0,
+        AgentPhase::Operating => 0.1 + 0.75 * task_fraction(session, Some(run.since)).unwrap_or(0.0),
+        AgentPhase::Analyzing => 0.85,
+        AgentPhase::Reporting => return CYCLES_SHARE as u8,
+        AgentPhase::Done => return 100,
+        AgentPhase::Failed | AgentPhase::Stopped => return task_percent(session, None),
+    };
+    (cycles_done + within * cycle_share).round().min(99.0) as u8
+}
+
+fn task_percent(session: &SessionState, since: Option<DateTime<Utc>>) -> u8 {
+    task_fraction(session, since).map_or(0, |f| (f * 100.0).round() as u8)
+}
+
+/// Share of tasks queued since a time (or ever) that have finished, or
+/// `None` when there are none
+fn task_fraction(session: &SessionState, since: Option<DateTime<Utc>>) -> Option<f64> {
+    let tasks: Vec<_> = session.task_queue.iter()
+        .filter(|t| since.is_none_or(|since| t.created_at >= since))
+        .collect();
+    if tasks.is_empty() {
+        return None;
+    }
+    let finished = tasks.iter()
+        .filter(|t| matches!(t.status, TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled | TaskStatus::Interrupted))
+        .count();
+    Some(finished as f64 / tasks.len() as f64)
+}
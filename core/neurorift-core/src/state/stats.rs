@@ -0,0 +1,142 @@
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use super::{Severity, SessionState};
+
+/// Sessions whose findings span more than this are charted by day
+const HOURLY_SPAN_HOURS: i64 = 48;
+
+/// Width of each point in the discovery series
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendBucket {
+    Hour,
+    Day,
+}
+
+impl TrendBucket {
+    fn duration(self) -> Duration {
+        match self {
+            Self::Hour => Duration::hours(1),
+            Self::Day => Duration::days(1),
+        }
+    }
+}
+
+/// Findings discovered in one bucket of time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendPoint {
+    pub start: DateTime<Utc>,
+    pub discovered: usize,
+    /// Findings known by the end of the bucket
+    pub total: usize,
+    pub by_severity: BTreeMap<Severity, usize>,
+}
+
+/// Findings on one asset, or on an unmatched target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetCount {
+    pub asset_id: Option<String>,
+    /// Hostname or address of the asset, or the finding's target
+    pub label: String,
+    pub count: usize,
+    pub highest: Severity,
+}
+
+/// Counts and discovery series over a session's findings, for charts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindingsStats {
+    pub total: usize,
+    /// Every severity, zero when none were found
+    pub by_severity: BTreeMap<Severity, usize>,
+    pub by_tool: BTreeMap<String, usize>,
+    /// Most findings first
+    pub by_asset: Vec<AssetCount>,
+    pub bucket: TrendBucket,
+    /// Consecutive buckets from the first discovery to the last
+    pub trend: Vec<TrendPoint>,
+}
+
+impl FindingsStats {
+    pub fn of(session: &SessionState) -> Self {
+        let findings = &session.findings;
+        let mut by_severity = empty_severities();
+        let mut by_tool = BTreeMap::new();
+        let mut by_asset: HashMap<(Option<&str>, String), AssetCount> = HashMap::new();
+        for finding in findings {
+            *by_severity.entry(finding.severity.clone()).or_default() += 1;
+            *by_tool.entry(finding.tool_source.clone()).or_default() += 1;
+            
+            let asset = finding.asset_id.as_deref()
+                .and_then(|id| session.assets.iter().find(|a| a.id == id));
+            let label = asset
+                .and_then(|a| a.hostnames.first().cloned().or_else(|| a.addresses.first().map(|ip| ip.to_string())))
+                .or_else(|| finding.target.clone())
+                .unwrap_or_else(|| "unassigned".to_string());
+            let asset_id = asset.map(|a| a.id.as_str());
+            let count = by_asset.entry((asset_id, label.clone())).or_insert_with(|| AssetCount {
+                asset_id: asset_id.map(str::to_string),
+                label,
+                count: 0,
+                highest: finding.severity.clone(),
+            });
+            count.count += 1;
+            count.highest = count.highest.clone().max(finding.severity.clone());
+        }
+        let mut by_asset: Vec<AssetCount> = by_asset.into_values().collect();
+        by_asset.sort_by(|a, b| b.count.cmp(&a.count).then(b.highest.cmp(&a.highest)).then(a.label.cmp(&b.label)));
+        
+        let first = findings.iter().map(|f| f.discovered_at).min();
+        let last = findings.iter().map(|f| f.discovered_at).max();
+        let bucket = match (first, last) {
+            (Some(first), Some(last)) if last - first > Duration::hours(HOURLY_SPAN_HOURS) => TrendBucket::Day,
+            _ => TrendBucket::Hour,
+        };
+        let trend = match (first, last) {
+            (Some(first), Some(last)) => trend(session, bucket, first, last),
+            _ => Vec::new(),
+        };
+        
+        Self {
+            total: findings.len(),
+            by_severity,
+            by_tool,
+            by_asset,
+            bucket,
+            trend,
+        }
+    }
+}
+
+/// Discovery counts for every bucket between `first` and `last`
+fn trend(session: &SessionState, bucket: TrendBucket, first: DateTime<Utc>, last: DateTime<Utc>) -> Vec<TrendPoint> {
+    let width = bucket.duration();
+    let start_of = |time: DateTime<Utc>| time.duration_trunc(width).unwrap_or(time);
+    
+    let mut points = Vec::new();
+    let mut start = start_of(first);
+    while start <= last {
+        points.push(TrendPoint { start, discovered: 0, total: 0, by_severity: empty_severities() });
+        start += width;
+    }
+    for finding in &session.findings {
+        let index = ((start_of(finding.discovered_at) - points[0].start).num_seconds() / width.num_seconds()) as usize;
+        if let Some(point) = points.get_mut(index) {
+            point.discovered += 1;
+            *point.by_severity.entry(finding.severity.clone()).or_default() += 1;
+        }
+    }
+    let mut total = 0;
+    for point in &mut points {
+        total += point.discovered;
+        point.total = total;
+    }
+    points
+}
+
+fn empty_severities() -> BTreeMap<Severity, usize> {
+    [Severity::Info, Severity::Low, Severity::Medium, Severity::High, Severity::Critical]
+        .into_iter()
+        .map(|s| (s, 0))
+        .collect()
+}
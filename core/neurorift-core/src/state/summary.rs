@@ -0,0 +1,167 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use crate::security::scope::entry_matches;
+use super::{OperationalMode, SessionState, SessionStatus, Severity, TaskStatus};
+
+/// Findings listed in a summary
+const TOP_FINDINGS: usize = 5;
+
+/// Task outcomes in a session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskCounts {
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub cancelled: usize,
+    /// Cut off by a core restart
+    pub interrupted: usize,
+    /// Queued, running or awaiting approval
+    pub pending: usize,
+    /// Completed over completed and failed; absent until a task finishes
+    pub success_rate: Option<f64>,
+}
+
+/// A finding worth leading with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopFinding {
+    pub id: String,
+    pub title: String,
+    pub severity: Severity,
+    pub target: Option<String>,
+    pub cvss_score: Option<f64>,
+}
+
+/// How much of the registered scope has been worked
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScopeCoverage {
+    /// In-scope targets registered by operators
+    pub targets: usize,
+    /// Targets at least one finished task ran against
+    pub tested: usize,
+    pub untested: Vec<String>,
+    pub assets: usize,
+    pub assets_with_findings: usize,
+}
+
+/// Compact digest of a session for the home screen and report drafting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub name: String,
+    pub mode: OperationalMode,
+    pub status: SessionStatus,
+    pub started_at: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
+    pub duration_secs: i64,
+    pub tasks: TaskCounts,
+    pub findings: usize,
+    pub by_severity: BTreeMap<Severity, usize>,
+    /// Most severe first
+    pub top_findings: Vec<TopFinding>,
+    pub coverage: ScopeCoverage,
+}
+
+impl SessionSummary {
+    pub fn of(session: &SessionState) -> Self {
+        let mut tasks = TaskCounts { total: session.task_queue.len(), ..Default::default() };
+        for task in &session.task_queue {
+            match task.status {
+                TaskStatus::Completed => tasks.completed += 1,
+                TaskStatus::Failed => tasks.failed += 1,
+                TaskStatus::Cancelled => tasks.cancelled += 1,
+                TaskStatus::Interrupted => tasks.interrupted += 1,
+                TaskStatus::Queued | TaskStatus::Running | TaskStatus::AwaitingApproval => tasks.pending += 1,
+            }
+        }
+        let finished = tasks.completed + tasks.failed;
+        tasks.success_rate = (finished > 0).then(|| tasks.completed as f64 / finished as f64);
+        
+        let mut by_severity = BTreeMap::new();
+        for finding in &session.findings {
+            *by_severity.entry(finding.severity.clone()).or_default() += 1;
+        }
+        let mut ranked: Vec<_> = session.findings.iter().collect();
+        ranked.sort_by(|a, b| {
+            b.severity.cmp(&a.severity)
+                .then(b.cvss_score.unwrap_or(0.0).total_cmp(&a.cvss_score.unwrap_or(0.0)))
+                .then(b.seen_count.cmp(&a.seen_count))
+        });
+        let top_findings = ranked.into_iter()
+            .take(TOP_FINDINGS)
+            .map(|f| TopFinding {
+                id: f.id.clone(),
+                title: f.title.clone(),
+                severity: f.severity.clone(),
+                target: f.target.clone(),
+                cvss_score: f.cvss_score,
+            })
+            .collect();
+        
+        let mut coverage = ScopeCoverage { assets: session.assets.len(), ..Default::default() };
+        for target in session.targets.iter().filter(|t| t.in_scope) {
+            coverage.targets += 1;
+            let tested = session.task_queue.iter()
+                .filter(|t| matches!(t.status, TaskStatus::Completed | TaskStatus::Failed))
+                .any(|t| t.target == target.value || entry_matches(&target.value, &t.target));
+            if tested {
+                coverage.tested += 1;
+            } else {
+                coverage.untested.push(target.value.clone());
+            }
+        }
+        coverage.assets_with_findings = session.assets.iter()
+            .filter(|a| session.findings.iter().any(|f| f.asset_id.as_deref() == Some(a.id.as_str())))
+            .count();
+        
+        Self {
+            session_id: session.id.clone(),
+            name: session.name.clone(),
+            mode: session.mode,
+            status: session.status,
+            started_at: session.created_at,
+            last_activity: session.updated_at,
+            duration_secs: (session.updated_at - session.created_at).num_seconds(),
+            tasks,
+            findings: session.findings.len(),
+            by_severity,
+            top_findings,
+            coverage,
+        }
+    }
+    
+    /// Plain-text rendering, for prompts
+    pub fn digest(&self) -> String {
+        let hours = self.duration_secs / 3600;
+        let minutes = self.duration_secs % 3600 / 60;
+        let mut lines = vec![format!("- Duration: {}h {:02}m", hours, minutes)];
+        
+        let rate = self.tasks.success_rate
+            .map(|r| format!(", {:.0}% succeeded", r * 100.0))
+            .unwrap_or_default();
+        lines.push(format!("- Tasks: {} run, {} failed, {} pending{}", self.tasks.completed + self.tasks.failed, self.tasks.failed, self.tasks.pending, rate));
+        
+        let severities: Vec<String> = self.by_severity.iter()
+            .rev()
+            .map(|(severity, count)| format!("{} {}", count, format!("{:?}", severity).to_uppercase()))
+            .collect();
+        if severities.is_empty() {
+            lines.push("- Findings: none".to_string());
+        } else {
+            lines.push(format!("- Findings: {}", severities.join(", ")));
+        }
+        for finding in &self.top_findings {
+            let severity = format!("{:?}", finding.severity).to_uppercase();
+            match &finding.target {
+                Some(target) => lines.push(format!("  - [{}] {} on {}", severity, finding.title, target)),
+                None => lines.push(format!("  - [{}] {}", severity, finding.title)),
+            }
+        }
+        
+        if self.coverage.targets > 0 {
+            lines.push(format!("- Scope coverage: {} of {} targets tested", self.coverage.tested, self.coverage.targets));
+        }
+        lines.push(format!("- Assets: {} discovered, {} with findings", self.coverage.assets, self.coverage.assets_with_findings));
+        lines.join("\n")
+    }
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// W3C trace context carried across the core and the Python bridge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceContext {
+    /// 32 hex chars, shared by every span of one operation
+    pub trace_id: String,
+    /// 16 hex chars, identifies the current span
+    pub span_id: String,
+}
+
+impl TraceContext {
+    /// Start a new trace
+    pub fn new_root() -> Self {
+        Self {
+            trace_id: Uuid::new_v4().simple().to_string(),
+            span_id: new_span_id(),
+        }
+    }
+    
+    /// Continue an existing trace with a fresh span
+    pub fn from_trace_id(trace_id: impl Into<String>) -> Self {
+        Self {
+            trace_id: trace_id.into(),
+            span_id: new_span_id(),
+        }
+    }
+    
+    /// Child span within the same trace
+    pub fn child(&self) -> Self {
+        Self::from_trace_id(self.trace_id.clone())
+    }
+    
+    /// `traceparent` header value (version 00, sampled)
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-01", self.trace_id, self.span_id)
+    }
+    
+    /// Parse a `traceparent` header value
+    pub fn parse_traceparent(value: &str) -> Option<Self> {
+        let mut parts = value.split('-');
+        let (_version, trace_id, span_id) = (parts.next()?, parts.next()?, parts.next()?);
+        if trace_id.len() != 32 || span_id.len() != 16 {
+            return None;
+        }
+        Some(Self {
+            trace_id: trace_id.to_string(),
+            span_id: span_id.to_string(),
+        })
+    }
+}
+
+/// Random 64-bit span ID as hex
+fn new_span_id() -> String {
+    Uuid::new_v4().simple().to_string()[..16].to_string()
+}
@@ -0,0 +1,196 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use crate::state::RiskLevel;
+use crate::tools::{which, Capability};
+
+/// Directory under the base dir holding `<tool>.toml` descriptors
+pub const TOOLS_DIR: &str = "tools";
+
+/// Static description of a tool: what it does, what it needs and what it accepts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolDescriptor {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Executable looked up on PATH
+    pub binary: String,
+    /// Other executables the tool needs (browsers, interpreters)
+    #[serde(default)]
+    pub requires: Vec<String>,
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+    /// Inherent intrusiveness, used for risk when no adapter estimates it
+    #[serde(default = "default_risk_class")]
+    pub risk_class: RiskLevel,
+    /// Arguments a task may pass
+    #[serde(default)]
+    pub args: Vec<ArgumentSpec>,
+    /// Writes large artifacts (screenshots, captures, crawls); held while
+    /// the disk is critically low
+    #[serde(default)]
+    pub artifact_heavy: bool,
+    /// Reaches onion services, so its traffic goes through Tor
+    #[serde(default)]
+    pub tor: bool,
+    /// Implemented inside the core; `binary` is not used
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+fn default_risk_class() -> RiskLevel {
+    RiskLevel::Low
+}
+
+/// Type of a tool argument, for validation and form building
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgKind {
+    String,
+    Integer,
+    Boolean,
+    /// Array of strings; a comma-separated string is accepted too
+    List,
+    /// One of `choices`
+    Choice,
+}
+
+/// One argument in a tool's schema
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArgumentSpec {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: ArgKind,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<String>,
+}
+
+impl ArgumentSpec {
+    /// Optional argument of a kind
+    pub fn new(name: &str, kind: ArgKind, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            description: description.to_string(),
+            required: false,
+            default: None,
+            choices: Vec::new(),
+        }
+    }
+    
+    /// Choice argument over the given values
+    pub fn choice(name: &str, choices: &[&str], description: &str) -> Self {
+        Self {
+            choices: choices.iter().map(|c| c.to_string()).collect(),
+            ..Self::new(name, ArgKind::Choice, description)
+        }
+    }
+    
+    /// Mark the argument required
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+    
+    /// Value used when the argument is omitted
+    pub fn with_default(mut self, value: Value) -> Self {
+        self.default = Some(value);
+        self
+    }
+    
+    /// Reason a value does not fit this argument
+    fn check(&self, value: &Value) -> Result<(), String> {
+        let fits = match self.kind {
+            ArgKind::String => value.is_string() || value.is_number(),
+            ArgKind::Integer => value.is_i64() || value.is_u64() || value.as_str().is_some_and(|s| s.trim().parse::<i64>().is_ok()),
+            ArgKind::Boolean => value.is_boolean(),
+            ArgKind::List => value.is_string() || value.as_array().is_some_and(|items| items.iter().all(|i| i.is_string() || i.is_number())),
+            ArgKind::Choice => {
+                return match value.as_str() {
+                    Some(choice) if self.choices.iter().any(|c| c == choice) => Ok(()),
+                    _ => Err(format!("'{}' must be one of {}", self.name, self.choices.join(", "))),
+                };
+            }
+        };
+        match fits {
+            true => Ok(()),
+            false => Err(format!("'{}' must be {:?}, got {}", self.name, self.kind, value)),
+        }
+    }
+}
+
+impl ToolDescriptor {
+    /// Check task arguments against the schema; arguments it does not list pass through
+    pub fn validate_args(&self, args: &HashMap<String, Value>) -> Result<()> {
+        let mut problems = Vec::new();
+        for spec in &self.args {
+            match args.get(&spec.name).filter(|v| !v.is_null()) {
+                Some(value) => problems.extend(spec.check(value).err()),
+                None if spec.required => problems.push(format!("'{}' is required", spec.name)),
+                None => {}
+            }
+        }
+        if !problems.is_empty() {
+            bail!("Invalid arguments for {}: {}", self.name, problems.join("; "));
+        }
+        Ok(())
+    }
+    
+    /// Required executables not found on PATH
+    pub fn missing_binaries(&self) -> Vec<String> {
+        std::iter::once(&self.binary)
+            .filter(|_| !self.builtin)
+            .chain(&self.requires)
+            .filter(|binary| which(binary).is_none())
+            .cloned()
+            .collect()
+    }
+}
+
+/// A tool as offered to clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCatalogEntry {
+    #[serde(flatten)]
+    pub descriptor: ToolDescriptor,
+    /// Whether every required binary is installed
+    pub available: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing: Vec<String>,
+    /// Whether the core builds and parses this tool itself
+    pub adapter: bool,
+}
+
+/// Read every `*.toml` descriptor in a directory, skipping files that do not parse
+pub fn load_descriptors(dir: &Path) -> Result<Vec<ToolDescriptor>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read tool descriptors in {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+    
+    let mut descriptors = Vec::new();
+    for path in paths {
+        let parsed = fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| toml::from_str::<ToolDescriptor>(&text).map_err(anyhow::Error::from));
+        match parsed {
+            Ok(descriptor) => descriptors.push(descriptor),
+            Err(e) => tracing::warn!("Skipping tool descriptor {}: {:#}", path.display(), e),
+        }
+    }
+    Ok(descriptors)
+}
@@ -0,0 +1,135 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use crate::config::CoreConfig;
+use crate::parsers::{ffuf::FfufParser, ParsedOutput, ToolOutputParser};
+use crate::state::RiskLevel;
+use crate::tools::{self, ArgKind, Flag, FlagValue, ArgumentSpec, Capability, ToolAdapter, ToolCommand, ToolDescriptor, ToolRisk};
+
+/// Wordlist used when the task names none, under the wordlist directories
+const DEFAULT_WORDLIST: &str = "dirb/common.txt";
+
+/// Thread count ffuf uses by default
+const DEFAULT_THREADS: u64 = 40;
+
+/// Options passed through from `flags`
+const ALLOWED_FLAGS: &[Flag] = &[
+    Flag("-ac", FlagValue::Switch),
+    Flag("-r", FlagValue::Switch),
+    Flag("-H", FlagValue::Text),
+    Flag("-timeout", FlagValue::Number),
+    Flag("-maxtime", FlagValue::Number),
+    Flag("-recursion-depth", FlagValue::Number),
+    Flag("-fw", FlagValue::Numbers),
+    Flag("-fl", FlagValue::Numbers),
+    Flag("-mr", FlagValue::Text),
+    Flag("-fr", FlagValue::Text),
+];
+
+/// ffuf content discovery, always emitting JSON lines for the parser
+pub struct FfufAdapter {
+    descriptor: ToolDescriptor,
+    wordlist_dirs: Vec<PathBuf>,
+}
+
+impl FfufAdapter {
+    pub fn new(wordlist_dirs: Vec<PathBuf>) -> Self {
+        Self {
+            descriptor: ToolDescriptor {
+                name: "ffuf".to_string(),
+                description: "Web content and parameter fuzzer".to_string(),
+                binary: "ffuf".to_string(),
+                capabilities: vec![Capability::ContentDiscovery],
+                requires: Vec::new(),
+                risk_class: RiskLevel::Medium,
+                args: vec![
+                    ArgumentSpec::new("wordlist", ArgKind::String, "Wordlist file under a configured wordlist directory")
+                        .with_default(Value::from(DEFAULT_WORDLIST)),
+                    ArgumentSpec::new("extensions", ArgKind::List, "Extensions appended to each word, e.g. .php"),
+                    ArgumentSpec::new("match_codes", ArgKind::List, "Status codes to report"),
+                    ArgumentSpec::new("filter_codes", ArgKind::List, "Status codes to hide"),
+                    ArgumentSpec::new("filter_size", ArgKind::List, "Response sizes to hide"),
+                    ArgumentSpec::new("threads", ArgKind::Integer, "Concurrent requests")
+                        .with_default(Value::from(DEFAULT_THREADS)),
+                    ArgumentSpec::new("rate", ArgKind::Integer, "Maximum requests per second"),
+                    ArgumentSpec::new("recursion", ArgKind::Boolean, "Recurse into discovered directories"),
+                    ArgumentSpec::new("flags", ArgKind::List, "Additional allowed ffuf options"),
+                ],
+                artifact_heavy: false,
+                tor: false,
+                builtin: false,
+            },
+            wordlist_dirs,
+        }
+    }
+}
+
+impl Default for FfufAdapter {
+    fn default() -> Self {
+        Self::new(CoreConfig::default().wordlist_dirs)
+    }
+}
+
+/// Fuzz URL for a target: adds a scheme and a trailing `FUZZ` keyword if missing
+fn fuzz_url(target: &str) -> String {
+    let url = match target.contains("://") {
+        true => target.to_string(),
+        false => format!("http://{}", target),
+    };
+    match url.contains("FUZZ") {
+        true => url,
+        false => format!("{}/FUZZ", url.trim_end_matches('/')),
+    }
+}
+
+impl ToolAdapter for FfufAdapter {
+    fn describe(&self) -> &ToolDescriptor {
+        &self.descriptor
+    }
+    
+    fn build_command(&self, target: &str, args: &HashMap<String, Value>) -> Result<ToolCommand> {
+        let target = tools::check_target(target)?;
+        let wordlist = tools::str_arg(args, "wordlist")?.unwrap_or_else(|| DEFAULT_WORDLIST.to_string());
+        let wordlist = tools::confined_path(&wordlist, &self.wordlist_dirs, "wordlist")?;
+        
+        let mut command = ToolCommand::new(&self.descriptor.binary);
+        command.opt("-u", fuzz_url(target)).opt("-w", wordlist.display().to_string()).arg("-json").arg("-s");
+        
+        for (key, flag) in [("extensions", "-e"), ("match_codes", "-mc"), ("filter_codes", "-fc"), ("filter_size", "-fs")] {
+            let values = tools::list_arg(args, key)?;
+            if !values.is_empty() {
+                command.opt(flag, values.join(","));
+            }
+        }
+        if let Some(threads) = tools::uint_arg(args, "threads")? {
+            command.opt("-t", threads.to_string());
+        }
+        if let Some(rate) = tools::uint_arg(args, "rate")? {
+            command.opt("-rate", rate.to_string());
+        }
+        if tools::bool_arg(args, "recursion")? {
+            command.arg("-recursion");
+        }
+        command.args.extend(tools::flags_arg(args, ALLOWED_FLAGS)?);
+        Ok(command)
+    }
+    
+    fn parse_output(&self, output: &str) -> Result<ParsedOutput> {
+        FfufParser.parse(output)
+    }
+    
+    fn estimate_risk(&self, args: &HashMap<String, Value>) -> ToolRisk {
+        let threads = tools::uint_arg(args, "threads").ok().flatten().unwrap_or(DEFAULT_THREADS);
+        let rate_limited = tools::uint_arg(args, "rate").ok().flatten().is_some();
+        if threads > DEFAULT_THREADS && !rate_limited {
+            ToolRisk::new(20, format!("ffuf path brute force at {} threads", threads))
+        } else {
+            ToolRisk::new(10, "ffuf path brute force")
+        }
+    }
+    
+    fn proxy_args(&self, proxy: &str) -> Option<Vec<String>> {
+        Some(vec!["-x".to_string(), proxy.to_string()])
+    }
+}
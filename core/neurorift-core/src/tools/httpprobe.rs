@@ -0,0 +1,90 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use crate::parsers::ParsedOutput;
+use crate::prober::{self, ProbeOptions};
+use crate::scanner;
+use crate::state::RiskLevel;
+use crate::tools::{self, ArgKind, ArgumentSpec, Capability, ToolAdapter, ToolCommand, ToolDescriptor, ToolRisk};
+
+/// The core's own HTTP prober and fingerprinter, for hosts without httpx
+pub struct HttpProbeAdapter {
+    descriptor: ToolDescriptor,
+}
+
+impl HttpProbeAdapter {
+    pub fn new() -> Self {
+        Self {
+            descriptor: ToolDescriptor {
+                name: prober::TOOL_NAME.to_string(),
+                description: "Built-in HTTP prober: status, title, server, TLS certificate and technologies".to_string(),
+                binary: String::new(),
+                capabilities: vec![Capability::HttpProbe, Capability::ServiceDetection],
+                requires: Vec::new(),
+                risk_class: RiskLevel::Low,
+                args: vec![
+                    ArgumentSpec::new("ports", ArgKind::List, "Ports to try on a bare host; 443 and 80 by default"),
+                    ArgumentSpec::new("timeout_ms", ArgKind::Integer, "Per-request timeout in milliseconds"),
+                    ArgumentSpec::new("follow_redirects", ArgKind::Boolean, "Follow redirects and report the final page"),
+                    ArgumentSpec::new("concurrency", ArgKind::Integer, "Requests in flight at once"),
+                ],
+                artifact_heavy: false,
+                tor: false,
+                builtin: true,
+            },
+        }
+    }
+}
+
+impl Default for HttpProbeAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Prober options from task arguments
+pub fn options(args: &HashMap<String, Value>) -> Result<ProbeOptions> {
+    let mut options = ProbeOptions {
+        ports: scanner::parse_ports(&tools::list_arg(args, "ports")?)?,
+        follow_redirects: tools::bool_arg(args, "follow_redirects")?,
+        ..Default::default()
+    };
+    if let Some(timeout) = tools::uint_arg(args, "timeout_ms")? {
+        options.timeout = Duration::from_millis(timeout.max(1));
+    }
+    if let Some(concurrency) = tools::uint_arg(args, "concurrency")? {
+        options.concurrency = concurrency.max(1) as usize;
+    }
+    Ok(options)
+}
+
+impl ToolAdapter for HttpProbeAdapter {
+    fn describe(&self) -> &ToolDescriptor {
+        &self.descriptor
+    }
+    
+    /// Never executed; shows what the probe will do in logs and previews
+    fn build_command(&self, target: &str, args: &HashMap<String, Value>) -> Result<ToolCommand> {
+        let target = tools::check_target(target)?;
+        let options = options(args)?;
+        let mut command = ToolCommand::new(prober::TOOL_NAME);
+        command.opt("--timeout-ms", options.timeout.as_millis().to_string());
+        if !options.ports.is_empty() {
+            command.opt("--ports", options.ports.iter().map(u16::to_string).collect::<Vec<_>>().join(","));
+        }
+        if options.follow_redirects {
+            command.arg("--follow-redirects");
+        }
+        command.arg(target);
+        Ok(command)
+    }
+    
+    fn parse_output(&self, output: &str) -> Result<ParsedOutput> {
+        Ok(ParsedOutput { assets: prober::parse_output(output), ..Default::default() })
+    }
+    
+    fn estimate_risk(&self, _args: &HashMap<String, Value>) -> ToolRisk {
+        ToolRisk::new(5, "HTTP requests to discover what a web service runs")
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::HashMap;
+use crate::parsers::{httpx::HttpxParser, ParsedOutput, ToolOutputParser};
+use crate::state::RiskLevel;
+use crate::tools::{self, ArgKind, Flag, FlagValue, ArgumentSpec, Capability, ToolAdapter, ToolCommand, ToolDescriptor, ToolRisk};
+
+/// Options passed through from `flags`
+const ALLOWED_FLAGS: &[Flag] = &[
+    Flag("-fr", FlagValue::Switch),
+    Flag("-probe", FlagValue::Switch),
+    Flag("-timeout", FlagValue::Number),
+    Flag("-retries", FlagValue::Number),
+    Flag("-H", FlagValue::Text),
+    Flag("-path", FlagValue::UrlPath),
+];
+
+/// httpx HTTP probing, always emitting JSON lines for the parser
+pub struct HttpxAdapter {
+    descriptor: ToolDescriptor,
+}
+
+impl HttpxAdapter {
+    pub fn new() -> Self {
+        Self {
+            descriptor: ToolDescriptor {
+                name: "httpx".to_string(),
+                description: "HTTP prober reporting status, title, server and technologies".to_string(),
+                binary: "httpx".to_string(),
+                capabilities: vec![Capability::HttpProbe, Capability::ServiceDetection],
+                requires: Vec::new(),
+                risk_class: RiskLevel::Low,
+                args: vec![
+                    ArgumentSpec::new("ports", ArgKind::List, "Ports to probe instead of the URL's own"),
+                    ArgumentSpec::new("threads", ArgKind::Integer, "Concurrent probes"),
+                    ArgumentSpec::new("follow_redirects", ArgKind::Boolean, "Follow HTTP redirects"),
+                    ArgumentSpec::new("flags", ArgKind::List, "Additional allowed httpx options"),
+                ],
+                artifact_heavy: false,
+                tor: false,
+                builtin: false,
+            },
+        }
+    }
+}
+
+impl Default for HttpxAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ToolAdapter for HttpxAdapter {
+    fn describe(&self) -> &ToolDescriptor {
+        &self.descriptor
+    }
+    
+    fn build_command(&self, target: &str, args: &HashMap<String, Value>) -> Result<ToolCommand> {
+        let target = tools::check_target(target)?;
+        let mut command = ToolCommand::new(&self.descriptor.binary);
+        command.opt("-u", target)
+            .arg("-json")
+            .arg("-silent")
+            .arg("-status-code")
+            .arg("-title")
+            .arg("-web-server")
+            .arg("-tech-detect");
+        
+        let ports = tools::list_arg(args, "ports")?;
+        if !ports.is_empty() {
+            command.opt("-ports", ports.join(","));
+        }
+        if let Some(threads) = tools::uint_arg(args, "threads")? {
+            command.opt("-threads", threads.to_string());
+        }
+        if tools::bool_arg(args, "follow_redirects")? {
+            command.arg("-fr");
+        }
+        command.args.extend(tools::flags_arg(args, ALLOWED_FLAGS)?);
+        Ok(command)
+    }
+    
+    fn parse_output(&self, output: &str) -> Result<ParsedOutput> {
+        HttpxParser.parse(output)
+    }
+    
+    fn estimate_risk(&self, _args: &HashMap<String, Value>) -> ToolRisk {
+        ToolRisk::new(0, "httpx sends ordinary HTTP requests")
+    }
+    
+    fn proxy_args(&self, proxy: &str) -> Option<Vec<String>> {
+        Some(vec!["-http-proxy".to_string(), proxy.to_string()])
+    }
+}
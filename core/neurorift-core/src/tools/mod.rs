@@ -0,0 +1,514 @@
+pub mod catalog;
+pub mod ffuf;
+pub mod httpx;
+pub mod nmap;
+pub mod nuclei;
+pub mod portscan;
+pub mod httpprobe;
+
+use anyhow::{anyhow, bail, Result};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::config::CoreConfig;
+use crate::parsers::ParsedOutput;
+use crate::state::RiskLevel;
+
+pub use catalog::{ArgKind, ArgumentSpec, ToolCatalogEntry, ToolDescriptor};
+
+/// What a tool can be used for; the planner picks tools by capability
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    PortScan,
+    ServiceDetection,
+    OsDetection,
+    HttpProbe,
+    ContentDiscovery,
+    VulnerabilityScan,
+}
+
+/// Fully built invocation, run without a shell
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    /// Set on top of the core's own environment
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<(String, String)>,
+}
+
+impl ToolCommand {
+    /// Start a command for a program
+    pub fn new(program: impl Into<String>) -> Self {
+        Self { program: program.into(), args: Vec::new(), env: Vec::new() }
+    }
+    
+    /// Append one argument
+    pub fn arg(&mut self, arg: impl Into<String>) -> &mut Self {
+        self.args.push(arg.into());
+        self
+    }
+    
+    /// Append a flag and its value
+    pub fn opt(&mut self, flag: &str, value: impl Into<String>) -> &mut Self {
+        self.arg(flag).arg(value)
+    }
+}
+
+impl fmt::Display for ToolCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.program)?;
+        for arg in &self.args {
+            if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || r#"'"$`\;&|<>*?"#.contains(c)) {
+                write!(f, " '{}'", arg.replace('\'', r"'\''"))?;
+            } else {
+                write!(f, " {}", arg)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A tool's own estimate of how intrusive a run is
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolRisk {
+    /// Added to the risk score, same scale as the technique weights
+    pub weight: u32,
+    pub reason: String,
+}
+
+impl ToolRisk {
+    /// Risk with a weight and the reason for it
+    pub fn new(weight: u32, reason: impl Into<String>) -> Self {
+        Self { weight, reason: reason.into() }
+    }
+    
+    /// Risk implied by a descriptor's risk class
+    pub fn for_class(tool_name: &str, class: &RiskLevel) -> Self {
+        let weight = match class {
+            RiskLevel::Low => 0,
+            RiskLevel::Medium => 10,
+            RiskLevel::High => 20,
+            RiskLevel::Critical => 30,
+        };
+        Self::new(weight, format!("{} is a {:?}-risk tool", tool_name, class).to_lowercase())
+    }
+}
+
+/// Everything the core knows about driving one tool
+pub trait ToolAdapter: Send + Sync {
+    /// Name, binary and capabilities
+    fn describe(&self) -> &ToolDescriptor;
+    
+    /// Build the invocation for a target, rejecting arguments the tool does not accept
+    fn build_command(&self, target: &str, args: &HashMap<String, Value>) -> Result<ToolCommand>;
+    
+    /// Extract assets and findings from the tool's raw output
+    fn parse_output(&self, output: &str) -> Result<ParsedOutput>;
+    
+    /// How intrusive a run with these arguments is
+    fn estimate_risk(&self, args: &HashMap<String, Value>) -> ToolRisk;
+    
+    /// Options sending all of the tool's traffic through a proxy; `None`
+    /// when it cannot be proxied and so must not run natively behind one
+    fn proxy_args(&self, _proxy: &str) -> Option<Vec<String>> {
+        None
+    }
+}
+
+/// Known tools and their adapters, consulted by the executor and the planner.
+///
+/// Descriptors come from the built-in adapters and from TOML files in
+/// `<base>/tools`; a file named after a built-in tool replaces its
+/// descriptor but keeps the adapter.
+pub struct ToolRegistry {
+    adapters: RwLock<Vec<Arc<dyn ToolAdapter>>>,
+    /// Descriptors by lowercase tool name
+    descriptors: RwLock<BTreeMap<String, ToolDescriptor>>,
+}
+
+impl ToolRegistry {
+    /// Empty registry
+    pub fn new() -> Self {
+        Self {
+            adapters: RwLock::new(Vec::new()),
+            descriptors: RwLock::new(BTreeMap::new()),
+        }
+    }
+    
+    /// Built-in adapters plus the descriptors under `<base>/tools`
+    pub fn load(config: &CoreConfig) -> Result<Self> {
+        let registry = Self::with_builtin(config);
+        for descriptor in catalog::load_descriptors(&config.base_dir.join(catalog::TOOLS_DIR))? {
+            registry.register_descriptor(descriptor);
+        }
+        Ok(registry)
+    }
+    
+    /// Registry with the built-in adapters
+    pub fn with_builtin(config: &CoreConfig) -> Self {
+        let registry = Self::new();
+        registry.register(Arc::new(nmap::NmapAdapter::new()));
+        registry.register(Arc::new(nuclei::NucleiAdapter::new(config.nuclei_template_dirs.clone())));
+        registry.register(Arc::new(ffuf::FfufAdapter::new(config.wordlist_dirs.clone())));
+        registry.register(Arc::new(httpx::HttpxAdapter::new()));
+        registry.register(Arc::new(portscan::PortScanAdapter::new()));
+        registry.register(Arc::new(httpprobe::HttpProbeAdapter::new()));
+        registry
+    }
+    
+    /// Add an adapter, replacing any registered under the same name
+    pub fn register(&self, adapter: Arc<dyn ToolAdapter>) {
+        self.register_descriptor(adapter.describe().clone());
+        let mut adapters = self.adapters.write();
+        adapters.retain(|a| a.describe().name != adapter.describe().name);
+        adapters.push(adapter);
+    }
+    
+    /// Add or replace a tool's descriptor
+    pub fn register_descriptor(&self, descriptor: ToolDescriptor) {
+        self.descriptors.write().insert(descriptor.name.to_ascii_lowercase(), descriptor);
+    }
+    
+    /// Adapter for a tool name
+    pub fn get(&self, tool_name: &str) -> Option<Arc<dyn ToolAdapter>> {
+        self.adapters.read().iter()
+            .find(|a| a.describe().name.eq_ignore_ascii_case(tool_name))
+            .cloned()
+    }
+    
+    /// Descriptor for a tool name
+    pub fn describe(&self, tool_name: &str) -> Option<ToolDescriptor> {
+        self.descriptors.read().get(&tool_name.to_ascii_lowercase()).cloned()
+    }
+    
+    /// Descriptors of every known tool, by name
+    pub fn list(&self) -> Vec<ToolDescriptor> {
+        self.descriptors.read().values().cloned().collect()
+    }
+    
+    /// Descriptors of tools offering a capability
+    pub fn with_capability(&self, capability: Capability) -> Vec<ToolDescriptor> {
+        self.descriptors.read().values()
+            .filter(|d| d.capabilities.contains(&capability))
+            .cloned()
+            .collect()
+    }
+    
+    /// Every known tool with whether it is installed, for clients
+    pub fn catalog(&self) -> Vec<ToolCatalogEntry> {
+        self.list().into_iter()
+            .map(|descriptor| {
+                let missing = descriptor.missing_binaries();
+                ToolCatalogEntry {
+                    available: missing.is_empty(),
+                    missing,
+                    adapter: self.get(&descriptor.name).is_some(),
+                    descriptor,
+                }
+            })
+            .collect()
+    }
+    
+    /// How intrusive a run is: the adapter's estimate, else the descriptor's risk class
+    pub fn estimate_risk(&self, tool_name: &str, args: &HashMap<String, Value>) -> Option<ToolRisk> {
+        match self.get(tool_name) {
+            Some(adapter) => Some(adapter.estimate_risk(args)),
+            None => self.describe(tool_name).map(|d| ToolRisk::for_class(&d.name, &d.risk_class)),
+        }
+    }
+}
+
+impl Default for ToolRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Locate an executable on `PATH`
+pub fn which(name: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Reject targets that would be read as an option or as several targets
+pub(crate) fn check_target(target: &str) -> Result<&str> {
+    let target = target.trim();
+    if target.is_empty() {
+        bail!("Target is empty");
+    }
+    if target.starts_with('-') {
+        bail!("Target '{}' looks like an option", target);
+    }
+    if target.chars().any(|c| c.is_whitespace() || c.is_control() || c == ',') {
+        bail!("Target '{}' must be a single host, network or URL", target);
+    }
+    Ok(target)
+}
+
+/// Optional string argument; numbers are accepted and values may not look like options
+pub(crate) fn str_arg(args: &HashMap<String, Value>, key: &str) -> Result<Option<String>> {
+    let value = match args.get(key) {
+        None | Some(Value::Null) => return Ok(None),
+        Some(Value::String(s)) => s.trim().to_string(),
+        Some(Value::Number(n)) => n.to_string(),
+        Some(other) => bail!("Argument '{}' must be a string, got {}", key, other),
+    };
+    if value.starts_with('-') {
+        bail!("Argument '{}' may not start with '-'", key);
+    }
+    Ok(Some(value).filter(|v| !v.is_empty()))
+}
+
+/// Optional list argument; a comma-separated string is accepted too
+pub(crate) fn list_arg(args: &HashMap<String, Value>, key: &str) -> Result<Vec<String>> {
+    let items: Vec<String> = match args.get(key) {
+        None | Some(Value::Null) => return Ok(Vec::new()),
+        Some(Value::String(s)) => s.split(',').map(|s| s.trim().to_string()).collect(),
+        Some(Value::Array(items)) => items.iter()
+            .map(|item| match item {
+                Value::String(s) => Ok(s.trim().to_string()),
+                Value::Number(n) => Ok(n.to_string()),
+                other => Err(anyhow!("Argument '{}' must list strings, got {}", key, other)),
+            })
+            .collect::<Result<_>>()?,
+        Some(other) => bail!("Argument '{}' must be a list, got {}", key, other),
+    };
+    if let Some(item) = items.iter().find(|i| i.starts_with('-')) {
+        bail!("Argument '{}' may not contain options ('{}')", key, item);
+    }
+    Ok(items.into_iter().filter(|i| !i.is_empty()).collect())
+}
+
+/// Resolve `name` to a file under one of `dirs`, following symlinks; anything
+/// that ends up outside them is rejected
+pub(crate) fn confined_path(name: &str, dirs: &[PathBuf], what: &str) -> Result<PathBuf> {
+    let roots: Vec<PathBuf> = dirs.iter().filter_map(|dir| dir.canonicalize().ok()).collect();
+    let candidates: Vec<PathBuf> = if Path::new(name).is_absolute() {
+        vec![PathBuf::from(name)]
+    } else {
+        dirs.iter().map(|dir| dir.join(name)).collect()
+    };
+    candidates.iter()
+        .filter_map(|candidate| candidate.canonicalize().ok())
+        .find(|path| roots.iter().any(|root| path.starts_with(root)))
+        .ok_or_else(|| {
+            let dirs: Vec<String> = dirs.iter().map(|dir| dir.display().to_string()).collect();
+            anyhow!("{} '{}' not found in the {} directories ({})", what, name, what, dirs.join(", "))
+        })
+}
+
+/// Optional unsigned integer argument
+pub(crate) fn uint_arg(args: &HashMap<String, Value>, key: &str) -> Result<Option<u64>> {
+    match args.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(n)) => n.as_u64().map(Some).ok_or_else(|| anyhow!("Argument '{}' must be a positive integer", key)),
+        Some(Value::String(s)) => s.trim().parse().map(Some).map_err(|_| anyhow!("Argument '{}' must be a positive integer", key)),
+        Some(other) => bail!("Argument '{}' must be a positive integer, got {}", key, other),
+    }
+}
+
+/// Optional boolean argument
+pub(crate) fn bool_arg(args: &HashMap<String, Value>, key: &str) -> Result<bool> {
+    match args.get(key) {
+        None | Some(Value::Null) => Ok(false),
+        Some(Value::Bool(b)) => Ok(*b),
+        Some(other) => bail!("Argument '{}' must be true or false, got {}", key, other),
+    }
+}
+
+/// An option adapters pass through from `flags`, with the value it takes
+pub(crate) struct Flag(pub &'static str, pub FlagValue);
+
+/// What follows a passed-through option, as `-o value` or `-o=value`
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum FlagValue {
+    /// A switch without a value
+    Switch,
+    /// A non-negative integer
+    Number,
+    /// Comma-separated integers and ranges, e.g. `0,404-410`
+    Numbers,
+    /// An integer with an optional `ms`, `s`, `m` or `h` suffix
+    Duration,
+    /// An nmap port list, e.g. `22,80,T:8000-8100`
+    Ports,
+    /// NSE script names and categories, e.g. `http-title,vuln`
+    Scripts,
+    /// A URL path starting with `/`
+    UrlPath,
+    /// Free text on one line, e.g. a header or a pattern
+    Text,
+}
+
+impl FlagValue {
+    fn check(self, value: &str) -> bool {
+        let all = |allowed: fn(char) -> bool| !value.is_empty() && value.chars().all(allowed);
+        match self {
+            FlagValue::Switch => false,
+            FlagValue::Number => all(|c| c.is_ascii_digit()),
+            FlagValue::Numbers => all(|c| c.is_ascii_digit() || c == ',' || c == '-') && !value.starts_with('-'),
+            FlagValue::Duration => {
+                let digits = value.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+                matches!(&value[digits.len()..], "" | "ms" | "s" | "m" | "h")
+                    && !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+            }
+            FlagValue::Ports => all(|c| c.is_ascii_digit() || matches!(c, ',' | '-' | 'T' | 'U' | 'S' | ':')) && !value.starts_with('-'),
+            FlagValue::Scripts => script_list(value),
+            // httpx reads the value as a list of paths when it names a file
+            FlagValue::UrlPath => value.starts_with('/')
+                && !value.chars().any(|c| c.is_whitespace() || c.is_control())
+                && !Path::new(value).exists(),
+            FlagValue::Text => !value.is_empty() && !value.starts_with('-') && !value.chars().any(|c| c.is_control()),
+        }
+    }
+}
+
+/// Whether a value only names NSE scripts or categories
+pub(crate) fn script_list(value: &str) -> bool {
+    !value.is_empty()
+        && value.split(',').all(|name| !name.is_empty() && !name.starts_with('-'))
+        && value.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | ',' | '-'))
+}
+
+/// Raw `flags` passed through after the adapter's own options. Every item
+/// is an allowed option or the value it takes, so nothing here can add a
+/// target or a file the scope check never saw.
+pub(crate) fn flags_arg(args: &HashMap<String, Value>, allowed: &[Flag]) -> Result<Vec<String>> {
+    let items: Vec<String> = match args.get("flags") {
+        None | Some(Value::Null) => return Ok(Vec::new()),
+        Some(Value::Array(items)) => items.iter()
+            .map(|item| item.as_str().map(str::to_string).ok_or_else(|| anyhow!("Argument 'flags' must list strings")))
+            .collect::<Result<_>>()?,
+        Some(Value::String(s)) => s.split_whitespace().map(str::to_string).collect(),
+        Some(other) => bail!("Argument 'flags' must be a list, got {}", other),
+    };
+    
+    let mut flags = Vec::with_capacity(items.len());
+    let mut items = items.into_iter();
+    while let Some(item) = items.next() {
+        if !item.starts_with('-') {
+            bail!("Flag value '{}' does not follow an option that takes one", item);
+        }
+        let (name, inline) = match item.split_once('=') {
+            Some((name, value)) => (name.to_string(), Some(value.to_string())),
+            None => (item.clone(), None),
+        };
+        let Some(Flag(_, kind)) = allowed.iter().find(|flag| flag.0 == name) else {
+            bail!("Flag '{}' is not allowed", name);
+        };
+        match (kind, inline) {
+            (FlagValue::Switch, None) => flags.push(item),
+            (FlagValue::Switch, Some(_)) => bail!("Flag '{}' takes no value", name),
+            (kind, Some(value)) => {
+                if !kind.check(&value) {
+                    bail!("Invalid value '{}' for flag '{}'", value, name);
+                }
+                flags.push(item);
+            }
+            (kind, None) => {
+                let value = items.next().ok_or_else(|| anyhow!("Flag '{}' needs a value", name))?;
+                if !kind.check(&value) {
+                    bail!("Invalid value '{}' for flag '{}'", value, name);
+                }
+                flags.push(item);
+                flags.push(value);
+            }
+        }
+    }
+    Ok(flags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    const FLAGS: &[Flag] = &[
+        Flag("-Pn", FlagValue::Switch),
+        Flag("-p", FlagValue::Ports),
+        Flag("--host-timeout", FlagValue::Duration),
+        Flag("--script", FlagValue::Scripts),
+    ];
+    
+    fn flags(value: Value) -> Result<Vec<String>> {
+        flags_arg(&HashMap::from([("flags".to_string(), value)]), FLAGS)
+    }
+    
+    #[test]
+    fn values_follow_only_flags_that_take_one() {
+        assert!(flags(json!(["-Pn", "203.0.113.0/24"])).is_err());
+        assert!(flags(json!(["-Pn=1"])).is_err());
+        assert_eq!(flags(json!(["-Pn", "-p", "22,80", "--host-timeout=30s"])).unwrap(), ["-Pn", "-p", "22,80", "--host-timeout=30s"]);
+    }
+    
+    #[test]
+    fn values_are_validated() {
+        assert!(flags(json!(["-p"])).is_err());
+        assert!(flags(json!(["-p", "22 203.0.113.1"])).is_err());
+        assert!(flags(json!(["--host-timeout", "30x"])).is_err());
+        assert!(flags(json!(["-sS"])).is_err());
+    }
+    
+    #[test]
+    fn scripts_are_names_or_categories() {
+        assert!(flags(json!(["--script", "http-title,vuln"])).is_ok());
+        assert!(flags(json!(["--script", "/tmp/upload.nse"])).is_err());
+        assert!(flags(json!(["--script=./evil"])).is_err());
+        assert!(!script_list("Default"));
+    }
+    
+    #[test]
+    fn clients_cannot_pick_a_proxy() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("dirb")).unwrap();
+        std::fs::write(dir.path().join("dirb/common.txt"), "admin\n").unwrap();
+        let ffuf = ffuf::FfufAdapter::new(vec![dir.path().to_path_buf()]);
+        assert!(ffuf.build_command("https://www.example.test", &HashMap::new()).is_ok());
+        
+        let attempts: [(&dyn ToolAdapter, &str); 3] = [
+            (&ffuf, "-x"),
+            (&httpx::HttpxAdapter::new(), "-http-proxy"),
+            (&nuclei::NucleiAdapter::default(), "-proxy"),
+        ];
+        for (adapter, flag) in attempts {
+            let args = HashMap::from([("flags".to_string(), json!([flag, "http://198.51.100.9:8080"]))]);
+            assert!(adapter.build_command("https://www.example.test", &args).is_err(), "{} accepted {}", adapter.describe().name, flag);
+        }
+    }
+    
+    #[test]
+    fn wordlists_and_templates_stay_in_their_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let lists = dir.path().join("wordlists");
+        std::fs::create_dir_all(lists.join("dirb")).unwrap();
+        std::fs::write(lists.join("dirb/common.txt"), "admin\n").unwrap();
+        std::fs::write(dir.path().join("vault.key"), "secret").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(dir.path().join("vault.key"), lists.join("key.txt")).unwrap();
+        let dirs = vec![lists.clone()];
+        
+        let resolved = confined_path("dirb/common.txt", &dirs, "wordlist").unwrap();
+        assert_eq!(resolved, lists.canonicalize().unwrap().join("dirb/common.txt"));
+        assert!(confined_path(&resolved.display().to_string(), &dirs, "wordlist").is_ok());
+        for name in ["../vault.key", "dirb/../../vault.key", "key.txt", "/etc/passwd", "missing.txt"] {
+            assert!(confined_path(name, &dirs, "wordlist").is_err(), "{} was accepted", name);
+        }
+        let absolute = dir.path().join("vault.key").display().to_string();
+        assert!(confined_path(&absolute, &dirs, "wordlist").is_err());
+        
+        let ffuf = ffuf::FfufAdapter::new(dirs);
+        let args = HashMap::from([("wordlist".to_string(), json!(absolute))]);
+        assert!(ffuf.build_command("https://www.example.test", &args).is_err());
+        let nuclei = nuclei::NucleiAdapter::new(vec![dir.path().join("templates")]);
+        let args = HashMap::from([("templates".to_string(), json!(["../vault.key"]))]);
+        assert!(nuclei.build_command("https://www.example.test", &args).is_err());
+    }
+}
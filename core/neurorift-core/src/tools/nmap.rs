@@ -0,0 +1,164 @@
+use anyhow::{bail, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use crate::parsers::{nmap::NmapParser, ParsedOutput, ToolOutputParser};
+use crate::state::RiskLevel;
+use crate::tools::{self, ArgKind, Flag, FlagValue, ArgumentSpec, Capability, ToolAdapter, ToolCommand, ToolDescriptor, ToolRisk};
+
+/// Options passed through from `flags`
+const ALLOWED_FLAGS: &[Flag] = &[
+    Flag("-F", FlagValue::Switch),
+    Flag("-Pn", FlagValue::Switch),
+    Flag("-n", FlagValue::Switch),
+    Flag("-sT", FlagValue::Switch),
+    Flag("-sS", FlagValue::Switch),
+    Flag("-sU", FlagValue::Switch),
+    Flag("-sV", FlagValue::Switch),
+    Flag("-sC", FlagValue::Switch),
+    Flag("-O", FlagValue::Switch),
+    Flag("-A", FlagValue::Switch),
+    Flag("-6", FlagValue::Switch),
+    Flag("-T0", FlagValue::Switch),
+    Flag("-T1", FlagValue::Switch),
+    Flag("-T2", FlagValue::Switch),
+    Flag("-T3", FlagValue::Switch),
+    Flag("-T4", FlagValue::Switch),
+    Flag("-T5", FlagValue::Switch),
+    Flag("--open", FlagValue::Switch),
+    Flag("--reason", FlagValue::Switch),
+    Flag("-p", FlagValue::Ports),
+    Flag("--top-ports", FlagValue::Number),
+    Flag("--version-intensity", FlagValue::Number),
+    Flag("--max-retries", FlagValue::Number),
+    Flag("--min-rate", FlagValue::Number),
+    Flag("--max-rate", FlagValue::Number),
+    Flag("--host-timeout", FlagValue::Duration),
+    Flag("--script", FlagValue::Scripts),
+];
+
+/// NSE script categories that change what a scan does to the target
+const SCRIPT_RISKS: &[(&str, u32, &str)] = &[
+    ("dos", 35, "denial-of-service scripts"),
+    ("exploit", 30, "exploit scripts"),
+    ("brute", 25, "brute-force scripts"),
+    ("intrusive", 20, "intrusive scripts"),
+    ("vuln", 15, "vulnerability scripts"),
+];
+
+/// nmap port and service scans, always emitting XML for the parser
+pub struct NmapAdapter {
+    descriptor: ToolDescriptor,
+}
+
+impl NmapAdapter {
+    pub fn new() -> Self {
+        Self {
+            descriptor: ToolDescriptor {
+                name: "nmap".to_string(),
+                description: "Port scanner with service, OS and NSE script detection".to_string(),
+                binary: "nmap".to_string(),
+                capabilities: vec![Capability::PortScan, Capability::ServiceDetection, Capability::OsDetection],
+                requires: Vec::new(),
+                risk_class: RiskLevel::Medium,
+                args: vec![
+                    ArgumentSpec::choice("scan_type", &["connect", "syn", "udp", "ping"], "Scan technique; nmap's default if omitted"),
+                    ArgumentSpec::new("ports", ArgKind::String, "Port specification, e.g. 22,80,8000-8100"),
+                    ArgumentSpec::new("top_ports", ArgKind::Integer, "Scan the N most common ports instead of a port list"),
+                    ArgumentSpec::new("service_detection", ArgKind::Boolean, "Probe open ports for service versions"),
+                    ArgumentSpec::new("os_detection", ArgKind::Boolean, "Guess the operating system"),
+                    ArgumentSpec::new("timing", ArgKind::Integer, "Timing template 0-5"),
+                    ArgumentSpec::new("scripts", ArgKind::List, "NSE scripts or categories"),
+                    ArgumentSpec::new("flags", ArgKind::List, "Additional allowed nmap options"),
+                ],
+                artifact_heavy: false,
+                tor: false,
+                builtin: false,
+            },
+        }
+    }
+}
+
+impl Default for NmapAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ToolAdapter for NmapAdapter {
+    fn describe(&self) -> &ToolDescriptor {
+        &self.descriptor
+    }
+    
+    fn build_command(&self, target: &str, args: &HashMap<String, Value>) -> Result<ToolCommand> {
+        let target = tools::check_target(target)?;
+        let mut command = ToolCommand::new(&self.descriptor.binary);
+        
+        match tools::str_arg(args, "scan_type")?.as_deref() {
+            None => {}
+            Some("connect") => { command.arg("-sT"); }
+            Some("syn") => { command.arg("-sS"); }
+            Some("udp") => { command.arg("-sU"); }
+            Some("ping") => { command.arg("-sn"); }
+            Some(other) => bail!("Unknown nmap scan_type '{}'", other),
+        }
+        if let Some(ports) = tools::str_arg(args, "ports")? {
+            if !FlagValue::Ports.check(&ports) {
+                bail!("Invalid nmap port specification '{}'", ports);
+            }
+            command.opt("-p", ports);
+        } else if let Some(top) = tools::uint_arg(args, "top_ports")? {
+            command.opt("--top-ports", top.to_string());
+        }
+        if tools::bool_arg(args, "service_detection")? {
+            command.arg("-sV");
+        }
+        if tools::bool_arg(args, "os_detection")? {
+            command.arg("-O");
+        }
+        if let Some(timing) = tools::uint_arg(args, "timing")? {
+            if timing > 5 {
+                bail!("nmap timing must be 0-5");
+            }
+            command.arg(format!("-T{}", timing));
+        }
+        let scripts = tools::list_arg(args, "scripts")?.join(",");
+        if !scripts.is_empty() {
+            // Names and categories only: a path would run arbitrary Lua
+            if !tools::script_list(&scripts) {
+                bail!("nmap scripts must be NSE script names or categories, got '{}'", scripts);
+            }
+            command.opt("--script", scripts);
+        }
+        command.args.extend(tools::flags_arg(args, ALLOWED_FLAGS)?);
+        
+        command.opt("-oX", "-").arg(target);
+        Ok(command)
+    }
+    
+    fn parse_output(&self, output: &str) -> Result<ParsedOutput> {
+        NmapParser.parse(output)
+    }
+    
+    fn estimate_risk(&self, args: &HashMap<String, Value>) -> ToolRisk {
+        let mut scripts = tools::list_arg(args, "scripts").unwrap_or_default();
+        let flags = tools::flags_arg(args, ALLOWED_FLAGS).unwrap_or_default();
+        if let Some(index) = flags.iter().position(|f| f == "--script") {
+            scripts.extend(flags.get(index + 1).into_iter().flat_map(|s| s.split(',')).map(str::to_string));
+        }
+        scripts.extend(flags.iter().filter_map(|f| f.strip_prefix("--script=")).flat_map(|s| s.split(',')).map(str::to_string));
+        
+        let scripts = scripts.join(",").to_ascii_lowercase();
+        let mut risk = SCRIPT_RISKS.iter()
+            .find(|(category, _, _)| scripts.contains(category))
+            .map(|(_, weight, reason)| ToolRisk::new(*weight, format!("nmap with {}", reason)))
+            .unwrap_or_else(|| ToolRisk::new(5, "nmap network scan"));
+        
+        let aggressive = tools::uint_arg(args, "timing").ok().flatten().is_some_and(|t| t >= 5)
+            || flags.iter().any(|f| f == "-T5" || f == "-A");
+        if aggressive {
+            risk.weight += 10;
+            risk.reason.push_str(", aggressive timing");
+        }
+        risk
+    }
+}
@@ -0,0 +1,112 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use crate::config::CoreConfig;
+use crate::parsers::{nuclei::NucleiParser, ParsedOutput, ToolOutputParser};
+use crate::state::RiskLevel;
+use crate::tools::{self, ArgKind, Flag, FlagValue, ArgumentSpec, Capability, ToolAdapter, ToolCommand, ToolDescriptor, ToolRisk};
+
+/// Options passed through from `flags`
+const ALLOWED_FLAGS: &[Flag] = &[
+    Flag("-nc", FlagValue::Switch),
+    Flag("-ni", FlagValue::Switch),
+    Flag("-fr", FlagValue::Switch),
+    Flag("-headless", FlagValue::Switch),
+    Flag("-stats", FlagValue::Switch),
+    Flag("-timeout", FlagValue::Number),
+    Flag("-retries", FlagValue::Number),
+    Flag("-c", FlagValue::Number),
+    Flag("-bs", FlagValue::Number),
+    Flag("-H", FlagValue::Text),
+];
+
+/// nuclei template scans, always emitting JSON lines for the parser
+pub struct NucleiAdapter {
+    descriptor: ToolDescriptor,
+    template_dirs: Vec<PathBuf>,
+}
+
+impl NucleiAdapter {
+    pub fn new(template_dirs: Vec<PathBuf>) -> Self {
+        Self {
+            descriptor: ToolDescriptor {
+                name: "nuclei".to_string(),
+                description: "Template-based vulnerability scanner".to_string(),
+                binary: "nuclei".to_string(),
+                capabilities: vec![Capability::VulnerabilityScan],
+                requires: Vec::new(),
+                risk_class: RiskLevel::High,
+                args: vec![
+                    ArgumentSpec::new("templates", ArgKind::List, "Template files or directories under a configured template directory"),
+                    ArgumentSpec::new("tags", ArgKind::List, "Run templates with these tags"),
+                    ArgumentSpec::new("exclude_tags", ArgKind::List, "Skip templates with these tags"),
+                    ArgumentSpec::new("severity", ArgKind::List, "Only run templates of these severities"),
+                    ArgumentSpec::new("rate_limit", ArgKind::Integer, "Maximum requests per second"),
+                    ArgumentSpec::new("flags", ArgKind::List, "Additional allowed nuclei options"),
+                ],
+                artifact_heavy: false,
+                tor: false,
+                builtin: false,
+            },
+            template_dirs,
+        }
+    }
+}
+
+impl Default for NucleiAdapter {
+    fn default() -> Self {
+        Self::new(CoreConfig::default().nuclei_template_dirs)
+    }
+}
+
+impl ToolAdapter for NucleiAdapter {
+    fn describe(&self) -> &ToolDescriptor {
+        &self.descriptor
+    }
+    
+    fn build_command(&self, target: &str, args: &HashMap<String, Value>) -> Result<ToolCommand> {
+        let target = tools::check_target(target)?;
+        let mut command = ToolCommand::new(&self.descriptor.binary);
+        command.opt("-u", target).arg("-jsonl").arg("-silent");
+        
+        for template in tools::list_arg(args, "templates")? {
+            let template = tools::confined_path(&template, &self.template_dirs, "template")?;
+            command.opt("-t", template.display().to_string());
+        }
+        for (key, flag) in [("tags", "-tags"), ("exclude_tags", "-etags"), ("severity", "-severity")] {
+            let values = tools::list_arg(args, key)?;
+            if !values.is_empty() {
+                command.opt(flag, values.join(","));
+            }
+        }
+        if let Some(rate) = tools::uint_arg(args, "rate_limit")? {
+            command.opt("-rl", rate.to_string());
+        }
+        command.args.extend(tools::flags_arg(args, ALLOWED_FLAGS)?);
+        Ok(command)
+    }
+    
+    fn parse_output(&self, output: &str) -> Result<ParsedOutput> {
+        NucleiParser.parse(output)
+    }
+    
+    fn estimate_risk(&self, args: &HashMap<String, Value>) -> ToolRisk {
+        let mut selected = tools::list_arg(args, "tags").unwrap_or_default();
+        selected.extend(tools::list_arg(args, "templates").unwrap_or_default());
+        let excluded = tools::list_arg(args, "exclude_tags").unwrap_or_default();
+        let uses = |tag: &str| selected.iter().any(|s| s.to_ascii_lowercase().contains(tag)) && !excluded.iter().any(|e| e == tag);
+        
+        if uses("dos") {
+            ToolRisk::new(35, "nuclei with denial-of-service templates")
+        } else if uses("intrusive") || uses("fuzz") {
+            ToolRisk::new(25, "nuclei with intrusive templates")
+        } else {
+            ToolRisk::new(15, "nuclei active vulnerability scan")
+        }
+    }
+    
+    fn proxy_args(&self, proxy: &str) -> Option<Vec<String>> {
+        Some(vec!["-proxy".to_string(), proxy.to_string()])
+    }
+}
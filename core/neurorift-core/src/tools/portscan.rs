@@ -0,0 +1,95 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use crate::parsers::ParsedOutput;
+use crate::scanner::{self, ScanMethod, ScanOptions};
+use crate::state::RiskLevel;
+use crate::tools::{self, ArgKind, ArgumentSpec, Capability, ToolAdapter, ToolCommand, ToolDescriptor, ToolRisk};
+
+/// The core's own TCP port scanner, for hosts without nmap
+pub struct PortScanAdapter {
+    descriptor: ToolDescriptor,
+}
+
+impl PortScanAdapter {
+    pub fn new() -> Self {
+        Self {
+            descriptor: ToolDescriptor {
+                name: scanner::TOOL_NAME.to_string(),
+                description: "Built-in TCP port scanner (SYN where permitted, otherwise connect)".to_string(),
+                binary: String::new(),
+                capabilities: vec![Capability::PortScan],
+                requires: Vec::new(),
+                risk_class: RiskLevel::Low,
+                args: vec![
+                    ArgumentSpec::new("ports", ArgKind::List, "Ports or ranges, e.g. 22,80,8000-8100; common ports by default"),
+                    ArgumentSpec::choice("method", &["auto", "syn", "connect"], "Probe method; auto uses SYN where permitted"),
+                    ArgumentSpec::new("timeout_ms", ArgKind::Integer, "Per-port timeout in milliseconds"),
+                    ArgumentSpec::new("concurrency", ArgKind::Integer, "Connections in flight per host"),
+                ],
+                artifact_heavy: false,
+                tor: false,
+                builtin: true,
+            },
+        }
+    }
+}
+
+impl Default for PortScanAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Scanner options from task arguments
+pub fn options(args: &HashMap<String, Value>) -> Result<ScanOptions> {
+    let mut options = ScanOptions::default();
+    let ports = scanner::parse_ports(&tools::list_arg(args, "ports")?)?;
+    if !ports.is_empty() {
+        options.ports = ports;
+    }
+    options.method = match tools::str_arg(args, "method")?.as_deref() {
+        Some("syn") => Some(ScanMethod::Syn),
+        Some("connect") => Some(ScanMethod::Connect),
+        _ => None,
+    };
+    if let Some(timeout) = tools::uint_arg(args, "timeout_ms")? {
+        options.timeout = Duration::from_millis(timeout.max(1));
+    }
+    if let Some(concurrency) = tools::uint_arg(args, "concurrency")? {
+        options.concurrency = concurrency.max(1) as usize;
+    }
+    Ok(options)
+}
+
+impl ToolAdapter for PortScanAdapter {
+    fn describe(&self) -> &ToolDescriptor {
+        &self.descriptor
+    }
+    
+    /// Never executed; shows what the scan will do in logs and previews
+    fn build_command(&self, target: &str, args: &HashMap<String, Value>) -> Result<ToolCommand> {
+        let target = tools::check_target(target)?;
+        let options = options(args)?;
+        let mut command = ToolCommand::new(scanner::TOOL_NAME);
+        command.opt("--ports", options.ports.len().to_string())
+            .opt("--timeout-ms", options.timeout.as_millis().to_string())
+            .arg(target);
+        if let Some(method) = options.method {
+            command.opt("--method", format!("{:?}", method).to_lowercase());
+        }
+        Ok(command)
+    }
+    
+    fn parse_output(&self, output: &str) -> Result<ParsedOutput> {
+        Ok(ParsedOutput { assets: scanner::parse_output(output), ..Default::default() })
+    }
+    
+    fn estimate_risk(&self, args: &HashMap<String, Value>) -> ToolRisk {
+        match options(args).map(|o| o.ports.len()).unwrap_or_default() {
+            ports if ports > 1000 => ToolRisk::new(10, format!("TCP port scan of {} ports", ports)),
+            _ => ToolRisk::new(5, "TCP port scan"),
+        }
+    }
+}
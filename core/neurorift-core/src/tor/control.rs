@@ -0,0 +1,143 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::TcpStream;
+
+/// An authenticated connection to a Tor control port
+pub struct ControlConnection {
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+}
+
+impl ControlConnection {
+    /// Connect and authenticate with the password if given, otherwise with
+    /// no credentials or the auth cookie, whichever Tor offers
+    pub async fn connect(addr: SocketAddr, password: Option<&str>) -> Result<Self> {
+        let stream = TcpStream::connect(addr).await
+            .with_context(|| format!("Failed to reach the Tor control port at {}", addr))?;
+        let (reader, writer) = stream.into_split();
+        let mut connection = Self { reader: BufReader::new(reader), writer };
+        connection.authenticate(password).await?;
+        Ok(connection)
+    }
+    
+    async fn authenticate(&mut self, password: Option<&str>) -> Result<()> {
+        let credential = match password {
+            Some(password) => format!(" {}", quote(password)),
+            None => {
+                let info = self.command("PROTOCOLINFO 1").await?;
+                let auth = info.iter()
+                    .find_map(|line| line.strip_prefix("AUTH "))
+                    .context("Tor did not list its auth methods")?;
+                let methods = field(auth, "METHODS").unwrap_or_default();
+                let methods: Vec<&str> = methods.split(',').collect();
+                if methods.contains(&"NULL") {
+                    String::new()
+                } else if methods.contains(&"COOKIE") {
+                    let path = field(auth, "COOKIEFILE").context("Tor offered cookie auth without a cookie file")?;
+                    let cookie = std::fs::read(PathBuf::from(&path))
+                        .with_context(|| format!("Failed to read the Tor auth cookie {}", path))?;
+                    format!(" {}", hex::encode(cookie))
+                } else {
+                    bail!("Tor control port needs a password (set control_password in tor.json)");
+                }
+            }
+        };
+        self.command(&format!("AUTHENTICATE{}", credential)).await
+            .context("Tor rejected the control credentials")?;
+        Ok(())
+    }
+    
+    /// Values of `GETINFO` keys, in the order asked
+    pub async fn get_info(&mut self, keys: &[&str]) -> Result<Vec<String>> {
+        let lines = self.command(&format!("GETINFO {}", keys.join(" "))).await?;
+        keys.iter()
+            .map(|key| {
+                let prefix = format!("{}=", key);
+                lines.iter()
+                    .find_map(|line| line.strip_prefix(&prefix))
+                    .map(|value| value.trim_start_matches('\n').to_string())
+                    .ok_or_else(|| anyhow!("Tor did not answer {}", key))
+            })
+            .collect()
+    }
+    
+    /// Send `SIGNAL <name>`, e.g. `NEWNYM`
+    pub async fn signal(&mut self, name: &str) -> Result<()> {
+        self.command(&format!("SIGNAL {}", name)).await?;
+        Ok(())
+    }
+    
+    /// Send one command and collect its reply lines without status codes;
+    /// a data block is joined onto its key line with newlines
+    async fn command(&mut self, command: &str) -> Result<Vec<String>> {
+        self.writer.write_all(format!("{}\r\n", command).as_bytes()).await?;
+        
+        let mut lines = Vec::new();
+        loop {
+            let line = self.read_line().await?;
+            if line.len() < 4 {
+                bail!("Malformed Tor control reply: {}", line);
+            }
+            let (status, separator, text) = (&line[..3], &line[3..4], &line[4..]);
+            if status != "250" {
+                bail!("Tor control error {}: {}", status, text);
+            }
+            match separator {
+                " " => {
+                    lines.push(text.to_string());
+                    return Ok(lines);
+                }
+                "+" => {
+                    let mut block = text.to_string();
+                    loop {
+                        let data = self.read_line().await?;
+                        if data == "." {
+                            break;
+                        }
+                        block.push('\n');
+                        block.push_str(data.strip_prefix('.').unwrap_or(&data));
+                    }
+                    lines.push(block);
+                }
+                _ => lines.push(text.to_string()),
+            }
+        }
+    }
+    
+    async fn read_line(&mut self) -> Result<String> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line).await? == 0 {
+            bail!("Tor closed the control connection");
+        }
+        Ok(line.trim_end_matches(['\r', '\n']).to_string())
+    }
+}
+
+/// `KEY=value` or `KEY="quoted value"` from a reply line
+fn field(line: &str, key: &str) -> Option<String> {
+    let start = line.find(&format!("{}=", key))? + key.len() + 1;
+    let rest = &line[start..];
+    match rest.strip_prefix('"') {
+        Some(quoted) => {
+            let mut value = String::new();
+            let mut chars = quoted.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => value.extend(chars.next()),
+                    '"' => return Some(value),
+                    c => value.push(c),
+                }
+            }
+            None
+        }
+        None => Some(rest.split_whitespace().next().unwrap_or_default().to_string()),
+    }
+}
+
+/// Control-protocol quoted string
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
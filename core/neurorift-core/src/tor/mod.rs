@@ -0,0 +1,322 @@
+pub mod control;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use crate::websocket::events::WSEvent;
+use crate::websocket::WebSocketServer;
+use self::control::ControlConnection;
+
+/// Tor configuration file name under the base directory
+const TOR_FILE: &str = "tor.json";
+
+/// Longest a status check may take before Tor is reported unreachable
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a rotation waits for Tor to build a fresh circuit
+const ROTATE_SETTLE: Duration = Duration::from_secs(15);
+
+const SETTLE_POLL: Duration = Duration::from_millis(500);
+
+/// Connection to a system Tor, used for dark-web tasks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Tor's SOCKS port, which routed traffic goes through
+    #[serde(default = "default_socks_addr")]
+    pub socks_addr: SocketAddr,
+    #[serde(default = "default_control_addr")]
+    pub control_addr: SocketAddr,
+    /// For `HashedControlPassword`; without it cookie or no auth is used
+    #[serde(default)]
+    pub control_password: Option<String>,
+    /// Seconds between circuit status checks
+    #[serde(default = "default_poll_secs")]
+    pub poll_secs: u64,
+    /// Switch to a new identity after this many routed requests
+    #[serde(default)]
+    pub rotate_every: Option<u64>,
+    /// Switch to a new identity when routed output looks like a ban
+    #[serde(default)]
+    pub rotate_on_ban: bool,
+    /// Case-insensitive markers of a ban in routed output
+    #[serde(default = "default_ban_patterns")]
+    pub ban_patterns: Vec<String>,
+}
+
+impl Default for TorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            socks_addr: default_socks_addr(),
+            control_addr: default_control_addr(),
+            control_password: None,
+            poll_secs: default_poll_secs(),
+            rotate_every: None,
+            rotate_on_ban: false,
+            ban_patterns: default_ban_patterns(),
+        }
+    }
+}
+
+fn default_socks_addr() -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], 9050))
+}
+
+fn default_control_addr() -> SocketAddr {
+    SocketAddr::from(([127, 0, 0, 1], 9051))
+}
+
+fn default_poll_secs() -> u64 {
+    15
+}
+
+fn default_ban_patterns() -> Vec<String> {
+    ["403 forbidden", "429 too many requests", "captcha", "access denied", "you have been blocked"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+impl TorConfig {
+    /// Load Tor config from the base directory (disabled if absent)
+    pub fn load(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let path = base_dir.as_ref().join(TOR_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        
+        let json = fs::read_to_string(&path)
+            .context("Failed to read Tor config")?;
+        let config = serde_json::from_str(&json)
+            .context("Failed to parse Tor config")?;
+        
+        Ok(config)
+    }
+}
+
+/// What the control port last reported
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TorState {
+    /// Tor has built a circuit and can carry traffic
+    pub connected: bool,
+    /// The circuit new streams are likely to use, as `id: relay, relay, relay`
+    pub circuit: Option<String>,
+    /// Bootstrap progress, percent
+    pub bootstrap: Option<u8>,
+    /// Why the control port could not be queried
+    pub error: Option<String>,
+    /// When the identity was last switched
+    pub rotated_at: Option<DateTime<Utc>>,
+}
+
+/// Watches a system Tor through its control port and hands out its SOCKS
+/// proxy to traffic that must not leave any other way
+pub struct TorService {
+    config: TorConfig,
+    state: RwLock<TorState>,
+    /// Requests routed since the last rotation
+    routed: AtomicU64,
+    /// Why a rotation was asked for, until `run` performs it
+    pending_rotation: Mutex<Option<String>>,
+    rotation_requested: Notify,
+    ws_server: Arc<WebSocketServer>,
+}
+
+impl TorService {
+    pub fn new(config: TorConfig, ws_server: Arc<WebSocketServer>) -> Self {
+        Self {
+            config,
+            state: RwLock::new(TorState::default()),
+            routed: AtomicU64::new(0),
+            pending_rotation: Mutex::new(None),
+            rotation_requested: Notify::new(),
+            ws_server,
+        }
+    }
+    
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+    
+    pub fn state(&self) -> TorState {
+        self.state.read().clone()
+    }
+    
+    /// `TorStatus` for the current state
+    pub fn status_event(&self) -> WSEvent {
+        let state = self.state();
+        WSEvent::TorStatus {
+            connected: state.connected,
+            circuit: state.circuit,
+            bootstrap: state.bootstrap,
+            error: state.error,
+            rotated_at: state.rotated_at,
+        }
+    }
+    
+    /// Proxy for traffic that should go through Tor: `None` when Tor is not
+    /// configured (the tool uses its own defaults), an error when it is but
+    /// has no circuit, so nothing leaks out directly.
+    ///
+    /// Each routed request counts towards `rotate_every`.
+    pub fn route(&self) -> Result<Option<String>> {
+        if !self.config.enabled {
+            return Ok(None);
+        }
+        let state = self.state();
+        if !state.connected {
+            bail!("Tor is not connected{}", state.error.map(|e| format!(": {}", e)).unwrap_or_default());
+        }
+        let routed = self.routed.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.config.rotate_every.is_some_and(|every| every > 0 && routed >= every) {
+            self.request_rotation(format!("{} requests on this identity", routed))?;
+        }
+        Ok(Some(format!("socks5h://{}", self.config.socks_addr)))
+    }
+    
+    /// Look over the outcome of a routed request, asking for a new identity
+    /// if it matches a ban pattern and `rotate_on_ban` is set
+    pub fn observe(&self, outcome: &Result<Value>) {
+        if !self.config.enabled || !self.config.rotate_on_ban {
+            return;
+        }
+        let text = match outcome {
+            Ok(value) => value.to_string(),
+            Err(e) => format!("{:#}", e),
+        }
+        .to_lowercase();
+        if let Some(pattern) = self.config.ban_patterns.iter().find(|p| text.contains(&p.to_lowercase())) {
+            let _ = self.request_rotation(format!("ban detected (\"{}\")", pattern));
+        }
+    }
+    
+    /// Have the polling task switch to a new identity as soon as it can
+    pub fn request_rotation(&self, reason: impl Into<String>) -> Result<()> {
+        if !self.config.enabled {
+            bail!("Tor is not enabled (see {})", TOR_FILE);
+        }
+        self.pending_rotation.lock().get_or_insert_with(|| reason.into());
+        self.rotation_requested.notify_one();
+        Ok(())
+    }
+    
+    /// Ask Tor for a new identity (`SIGNAL NEWNYM`) and wait briefly for a
+    /// fresh circuit, broadcasting the resulting `TorStatus`
+    pub async fn rotate(&self, reason: &str) -> Result<TorState> {
+        let previous = self.state().circuit;
+        self.connect().await?.signal("NEWNYM").await
+            .context("Tor refused to switch identity")?;
+        self.routed.store(0, Ordering::Relaxed);
+        let rotated_at = Utc::now();
+        tracing::info!("🧅 Tor identity switched: {}", reason);
+        
+        let deadline = Instant::now() + ROTATE_SETTLE;
+        let mut state = self.check().await;
+        while state.circuit == previous && state.error.is_none() && Instant::now() < deadline {
+            tokio::time::sleep(SETTLE_POLL).await;
+            state = self.check().await;
+        }
+        state.rotated_at = Some(rotated_at);
+        self.update(state.clone());
+        Ok(state)
+    }
+    
+    /// Query the control port for bootstrap and circuit status
+    pub async fn check(&self) -> TorState {
+        match tokio::time::timeout(CHECK_TIMEOUT, self.query()).await {
+            Ok(Ok(state)) => state,
+            Ok(Err(e)) => TorState { error: Some(format!("{:#}", e)), ..TorState::default() },
+            Err(_) => TorState { error: Some("Tor control port timed out".to_string()), ..TorState::default() },
+        }
+    }
+    
+    async fn query(&self) -> Result<TorState> {
+        let mut control = self.connect().await?;
+        let info = control.get_info(&["status/circuit-established", "status/bootstrap-phase", "circuit-status"]).await?;
+        Ok(TorState {
+            connected: info[0] == "1",
+            bootstrap: bootstrap_progress(&info[1]),
+            circuit: general_circuit(&info[2]),
+            ..TorState::default()
+        })
+    }
+    
+    /// Open an authenticated control connection
+    pub async fn connect(&self) -> Result<ControlConnection> {
+        ControlConnection::connect(self.config.control_addr, self.config.control_password.as_deref()).await
+    }
+    
+    /// Record a check, broadcasting `TorStatus` when anything changed
+    pub fn update(&self, state: TorState) {
+        {
+            let mut current = self.state.write();
+            let state = TorState { rotated_at: state.rotated_at.or(current.rotated_at), ..state };
+            if *current == state {
+                return;
+            }
+            match (&state.error, current.connected, state.connected) {
+                (Some(e), _, _) => tracing::warn!("🧅 Tor unavailable: {}", e),
+                (None, false, true) => tracing::info!("🧅 Tor connected"),
+                (None, true, false) => tracing::warn!("🧅 Tor lost its circuits"),
+                _ => {}
+            }
+            *current = state;
+        }
+        self.ws_server.broadcast(self.status_event());
+    }
+    
+    /// Poll the control port for as long as the core runs, switching
+    /// identity whenever that is asked for
+    pub async fn run(self: Arc<Self>) {
+        if !self.config.enabled {
+            return;
+        }
+        tracing::info!("🧅 Tor: SOCKS {}, control {}", self.config.socks_addr, self.config.control_addr);
+        let mut interval = tokio::time::interval(Duration::from_secs(self.config.poll_secs.max(1)));
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let state = self.check().await;
+                    self.update(state);
+                }
+                _ = self.rotation_requested.notified() => {
+                    let Some(reason) = self.pending_rotation.lock().take() else { continue };
+                    if let Err(e) = self.rotate(&reason).await {
+                        tracing::warn!("🧅 Failed to switch Tor identity ({}): {:#}", reason, e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// `PROGRESS=` of a bootstrap status line
+fn bootstrap_progress(phase: &str) -> Option<u8> {
+    phase.split_whitespace()
+        .find_map(|part| part.strip_prefix("PROGRESS="))
+        .and_then(|progress| progress.parse().ok())
+}
+
+/// First built general-purpose circuit, as `id: relay, relay, relay`
+fn general_circuit(status: &str) -> Option<String> {
+    status.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .find(|parts| parts.len() > 2 && parts[1] == "BUILT" && parts.contains(&"PURPOSE=GENERAL"))
+        .map(|parts| {
+            let relays: Vec<&str> = parts[2].split(',')
+                .map(|hop| hop.rsplit(['~', '=']).next().unwrap_or(hop))
+                .collect();
+            format!("{}: {}", parts[0], relays.join(", "))
+        })
+}
@@ -0,0 +1,457 @@
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::header::{HeaderValue, AUTHORIZATION};
+use tokio_tungstenite::tungstenite::Message;
+use crate::session::SessionMetadata;
+use crate::state::{ApprovalRequest, ApprovalStatus, RiskLevel, Task, TaskStatus};
+use crate::websocket::events::{LogLevel, WSEvent};
+
+/// Log lines kept on screen
+const MAX_LOG_LINES: usize = 500;
+
+/// How long the key reader waits before checking whether the console quit
+const KEY_POLL: Duration = Duration::from_millis(200);
+
+/// How long to wait for the core to acknowledge the close on quit
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Where and as whom the console connects
+pub struct ConsoleOptions {
+    /// WebSocket URL of the core, e.g. `ws://127.0.0.1:8765`
+    pub url: String,
+    /// Sent as `X-NeuroRift-Operator` so decisions are attributed
+    pub operator: String,
+    pub api_key: Option<String>,
+}
+
+/// Connect to a running core and run the console until the operator quits
+pub async fn run(options: ConsoleOptions) -> Result<()> {
+    let mut request = options.url.as_str().into_client_request()
+        .with_context(|| format!("Invalid core URL {}", options.url))?;
+    if let Some(key) = &options.api_key {
+        request.headers_mut().insert(AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {}", key))?);
+    }
+    request.headers_mut().insert("x-neurorift-operator", HeaderValue::from_str(&options.operator)?);
+    let (socket, _) = tokio_tungstenite::connect_async(request).await
+        .with_context(|| format!("Failed to connect to the core at {}", options.url))?;
+    let (mut sink, mut stream) = socket.split();
+    
+    let (event_tx, events) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Some(Ok(message)) = stream.next().await {
+            let Message::Text(text) = message else {
+                continue;
+            };
+            match serde_json::from_str::<WSEvent>(&text) {
+                Ok(event) => {
+                    if event_tx.send(Some(event)).is_err() {
+                        return;
+                    }
+                }
+                Err(e) => tracing::debug!("Ignoring unreadable event: {}", e),
+            }
+        }
+        let _ = event_tx.send(None);
+    });
+    
+    let (command_tx, mut commands) = mpsc::unbounded_channel::<WSEvent>();
+    let writer = tokio::spawn(async move {
+        while let Some(command) = commands.recv().await {
+            let Ok(text) = serde_json::to_string(&command) else {
+                continue;
+            };
+            if sink.send(Message::Text(text)).await.is_err() {
+                return;
+            }
+        }
+        let _ = sink.close().await;
+    });
+    
+    // Terminal input blocks, so it is read on its own thread
+    let (key_tx, keys) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        while !key_tx.is_closed() {
+            match event::poll(KEY_POLL) {
+                Ok(true) => {
+                    if let Ok(Event::Key(key)) = event::read() {
+                        if key.kind == KeyEventKind::Press && key_tx.send(key).is_err() {
+                            return;
+                        }
+                    }
+                }
+                Ok(false) => {}
+                Err(_) => return,
+            }
+        }
+    });
+    
+    let mut app = Console::new(command_tx, options.url);
+    app.send(WSEvent::GetSessionList);
+    
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal, events, keys).await;
+    ratatui::restore();
+    
+    // Closing the command channel lets the writer close the socket cleanly
+    drop(app);
+    let _ = tokio::time::timeout(CLOSE_TIMEOUT, writer).await;
+    result
+}
+
+/// Which list the arrow keys move in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Sessions,
+    Approvals,
+}
+
+/// Console state, built from the core's events
+struct Console {
+    commands: mpsc::UnboundedSender<WSEvent>,
+    url: String,
+    connected: bool,
+    focus: Focus,
+    sessions: Vec<SessionMetadata>,
+    session_list: ListState,
+    active_session: Option<String>,
+    tasks: Vec<Task>,
+    approvals: Vec<ApprovalRequest>,
+    approval_list: ListState,
+    logs: VecDeque<Line<'static>>,
+}
+
+impl Console {
+    fn new(commands: mpsc::UnboundedSender<WSEvent>, url: String) -> Self {
+        Self {
+            commands,
+            url,
+            connected: true,
+            focus: Focus::Sessions,
+            sessions: Vec::new(),
+            session_list: ListState::default(),
+            active_session: None,
+            tasks: Vec::new(),
+            approvals: Vec::new(),
+            approval_list: ListState::default(),
+            logs: VecDeque::new(),
+        }
+    }
+    
+    async fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        mut events: mpsc::UnboundedReceiver<Option<WSEvent>>,
+        mut keys: mpsc::UnboundedReceiver<KeyEvent>,
+    ) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            tokio::select! {
+                Some(event) = events.recv() => match event {
+                    Some(event) => self.on_event(event),
+                    None => {
+                        self.connected = false;
+                        self.log(LogLevel::Error, "Disconnected from the core".to_string());
+                    }
+                },
+                key = keys.recv() => match key {
+                    Some(key) => {
+                        if !self.on_key(key) {
+                            return Ok(());
+                        }
+                    }
+                    None => return Ok(()),
+                },
+            }
+        }
+    }
+    
+    fn send(&mut self, command: WSEvent) {
+        if self.commands.send(command).is_err() {
+            self.connected = false;
+        }
+    }
+    
+    /// Handle a key press; false quits
+    fn on_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    Focus::Sessions => Focus::Approvals,
+                    Focus::Approvals => Focus::Sessions,
+                };
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Char('r') => self.send(WSEvent::GetSessionList),
+            KeyCode::Enter if self.focus == Focus::Sessions => {
+                if let Some(session) = self.session_list.selected().and_then(|i| self.sessions.get(i)) {
+                    let session_id = session.id.clone();
+                    self.send(WSEvent::LoadSession { session_id });
+                }
+            }
+            KeyCode::Char('a') => {
+                if let Some(approval_id) = self.selected_approval() {
+                    self.send(WSEvent::ApproveAction { approval_id });
+                }
+            }
+            KeyCode::Char('d') => {
+                if let Some(approval_id) = self.selected_approval() {
+                    let reason = Some("Denied from the console".to_string());
+                    self.send(WSEvent::DenyAction { approval_id, reason });
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+    
+    fn selected_approval(&self) -> Option<String> {
+        let index = self.approval_list.selected()?;
+        self.approvals.get(index).map(|a| a.id.clone())
+    }
+    
+    fn move_selection(&mut self, step: isize) {
+        let (state, len) = match self.focus {
+            Focus::Sessions => (&mut self.session_list, self.sessions.len()),
+            Focus::Approvals => (&mut self.approval_list, self.approvals.len()),
+        };
+        if len == 0 {
+            state.select(None);
+            return;
+        }
+        let current = state.selected().unwrap_or(0) as isize;
+        state.select(Some((current + step).clamp(0, len as isize - 1) as usize));
+    }
+    
+    fn on_event(&mut self, event: WSEvent) {
+        match event {
+            WSEvent::SessionList { sessions } => {
+                self.sessions = sessions;
+                clamp_selection(&mut self.session_list, self.sessions.len());
+            }
+            WSEvent::SessionCreated { session_id, name, .. } => {
+                self.log(LogLevel::Info, format!("Session {} created ({})", name, session_id));
+                self.send(WSEvent::GetSessionList);
+            }
+            WSEvent::SessionLoaded { session_id, state } => {
+                self.log(LogLevel::Info, format!("Loaded session {} ({})", state.name, session_id));
+                self.active_session = Some(session_id);
+                self.tasks = state.task_queue.into_iter().collect();
+                self.approvals = state.approval_queue.into_iter()
+                    .filter(|a| a.status == ApprovalStatus::Pending)
+                    .collect();
+                clamp_selection(&mut self.approval_list, self.approvals.len());
+            }
+            WSEvent::TaskQueued { task } => {
+                self.log(LogLevel::Info, format!("Queued {} against {}", task.tool_name, task.target));
+                self.tasks.retain(|t| t.id != task.id);
+                self.tasks.push(task);
+            }
+            WSEvent::TaskStarted { task_id, .. } => {
+                self.set_task_status(&task_id, TaskStatus::Running);
+            }
+            WSEvent::TaskCompleted { task_id, .. } => {
+                let label = self.set_task_status(&task_id, TaskStatus::Completed);
+                self.log(LogLevel::Info, format!("{} completed", label));
+            }
+            WSEvent::TaskFailed { task_id, error } => {
+                let label = self.set_task_status(&task_id, TaskStatus::Failed);
+                self.log(LogLevel::Error, format!("{} failed: {}", label, error));
+            }
+            WSEvent::TaskCancelled { task_id, reason } => {
+                let label = self.set_task_status(&task_id, TaskStatus::Cancelled);
+                self.log(LogLevel::Warn, format!("{} cancelled: {}", label, reason));
+            }
+            WSEvent::TaskDeferred { task_id, reason } => {
+                self.log(LogLevel::Debug, format!("Task {} deferred: {}", task_id, reason));
+            }
+            WSEvent::ApprovalRequired { approval } | WSEvent::ApprovalEscalated { approval } => {
+                self.log(LogLevel::Warn, format!("Approval needed: {}", approval.action.description));
+                match self.approvals.iter_mut().find(|a| a.id == approval.id) {
+                    Some(existing) => *existing = approval,
+                    None => self.approvals.push(approval),
+                }
+                clamp_selection(&mut self.approval_list, self.approvals.len());
+            }
+            WSEvent::ApprovalGranted { approval_id, .. } => self.remove_approval(&approval_id, "granted"),
+            WSEvent::ApprovalDenied { approval_id, .. } => self.remove_approval(&approval_id, "denied"),
+            WSEvent::ApprovalExpired { approval_id, .. } => self.remove_approval(&approval_id, "expired"),
+            WSEvent::FindingDiscovered { finding } => {
+                self.log(LogLevel::Warn, format!("[{:?}] {}", finding.severity, finding.title));
+            }
+            WSEvent::LogEntry { level, agent, message, .. } => match agent {
+                Some(agent) => self.log(level, format!("{:?}: {}", agent, message)),
+                None => self.log(level, message),
+            },
+            WSEvent::KillSwitchEngaged { .. } => {
+                self.log(LogLevel::Error, "Kill switch engaged".to_string());
+            }
+            WSEvent::KillSwitchReleased { .. } => {
+                self.log(LogLevel::Info, "Kill switch released".to_string());
+            }
+            WSEvent::Error { message, details } => match details {
+                Some(details) => self.log(LogLevel::Error, format!("{}: {}", message, details)),
+                None => self.log(LogLevel::Error, message),
+            },
+            WSEvent::PermissionDenied { command, reason } => {
+                self.log(LogLevel::Error, format!("{} refused: {}", command, reason));
+            }
+            _ => {}
+        }
+    }
+    
+    /// Update a task's status, returning a label for the log
+    fn set_task_status(&mut self, task_id: &str, status: TaskStatus) -> String {
+        match self.tasks.iter_mut().find(|t| t.id == task_id) {
+            Some(task) => {
+                task.status = status;
+                format!("{} against {}", task.tool_name, task.target)
+            }
+            None => format!("Task {}", task_id),
+        }
+    }
+    
+    fn remove_approval(&mut self, approval_id: &str, outcome: &str) {
+        if let Some(index) = self.approvals.iter().position(|a| a.id == approval_id) {
+            let approval = self.approvals.remove(index);
+            self.log(LogLevel::Info, format!("Approval {}: {}", outcome, approval.action.description));
+        }
+        clamp_selection(&mut self.approval_list, self.approvals.len());
+    }
+    
+    fn log(&mut self, level: LogLevel, message: String) {
+        let stamp = chrono::Local::now().format("%H:%M:%S").to_string();
+        let style = match level {
+            LogLevel::Debug => Style::default().fg(Color::DarkGray),
+            LogLevel::Info => Style::default(),
+            LogLevel::Warn => Style::default().fg(Color::Yellow),
+            LogLevel::Error => Style::default().fg(Color::Red),
+        };
+        self.logs.push_back(Line::from(vec![
+            Span::styled(format!("{} ", stamp), Style::default().fg(Color::DarkGray)),
+            Span::styled(message, style),
+        ]));
+        while self.logs.len() > MAX_LOG_LINES {
+            self.logs.pop_front();
+        }
+    }
+    
+    fn draw(&mut self, frame: &mut Frame) {
+        let [top, approvals, logs, help] = Layout::vertical([
+            Constraint::Percentage(40),
+            Constraint::Length(8),
+            Constraint::Min(5),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [sessions, tasks] = Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)]).areas(top);
+        
+        self.draw_sessions(frame, sessions);
+        self.draw_tasks(frame, tasks);
+        self.draw_approvals(frame, approvals);
+        
+        let visible = logs.height.saturating_sub(2) as usize;
+        let lines: Vec<Line> = self.logs.iter().skip(self.logs.len().saturating_sub(visible)).cloned().collect();
+        frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Log")), logs);
+        
+        let status = if self.connected {
+            Span::styled(format!(" {} ", self.url), Style::default().fg(Color::Green))
+        } else {
+            Span::styled(" disconnected ", Style::default().fg(Color::Red))
+        };
+        let keys = " Tab switch  ↑/↓ move  Enter load  a approve  d deny  r refresh  q quit";
+        frame.render_widget(Paragraph::new(Line::from(vec![status, Span::raw(keys)])), help);
+    }
+    
+    fn draw_sessions(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self.sessions.iter()
+            .map(|s| {
+                let marker = if self.active_session.as_deref() == Some(s.id.as_str()) { "* " } else { "  " };
+                ListItem::new(format!("{}{} ({:?}, {} tasks)", marker, s.name, s.status, s.task_count))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(pane("Sessions", self.focus == Focus::Sessions))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.session_list);
+    }
+    
+    fn draw_tasks(&self, frame: &mut Frame, area: Rect) {
+        let visible = area.height.saturating_sub(3) as usize;
+        let rows: Vec<Row> = self.tasks.iter()
+            .skip(self.tasks.len().saturating_sub(visible))
+            .map(|t| {
+                let style = match t.status {
+                    TaskStatus::Running => Style::default().fg(Color::Cyan),
+                    TaskStatus::Completed => Style::default().fg(Color::Green),
+                    TaskStatus::Failed | TaskStatus::Interrupted => Style::default().fg(Color::Red),
+                    TaskStatus::AwaitingApproval => Style::default().fg(Color::Yellow),
+                    TaskStatus::Queued | TaskStatus::Cancelled => Style::default(),
+                };
+                Row::new(vec![t.tool_name.clone(), t.target.clone(), format!("{:?}", t.status)]).style(style)
+            })
+            .collect();
+        let title = match &self.active_session {
+            Some(id) => format!("Tasks ({})", id),
+            None => "Tasks (Enter loads a session)".to_string(),
+        };
+        let table = Table::new(rows, [Constraint::Length(14), Constraint::Min(20), Constraint::Length(16)])
+            .header(Row::new(vec!["TOOL", "TARGET", "STATUS"]).style(Style::default().add_modifier(Modifier::BOLD)))
+            .block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(table, area);
+    }
+    
+    fn draw_approvals(&mut self, frame: &mut Frame, area: Rect) {
+        let now = chrono::Utc::now();
+        let items: Vec<ListItem> = self.approvals.iter()
+            .map(|a| {
+                let color = match a.action.risk_level {
+                    RiskLevel::Low => Color::Green,
+                    RiskLevel::Medium => Color::Yellow,
+                    RiskLevel::High | RiskLevel::Critical => Color::Red,
+                };
+                let mut spans = vec![
+                    Span::styled(format!("{:<9}", format!("{:?}", a.action.risk_level)), Style::default().fg(color)),
+                    Span::raw(format!("{} - {}", a.action.description, a.reason)),
+                ];
+                if let Some(expires_at) = a.expires_at {
+                    let minutes = (expires_at - now).num_minutes().max(0);
+                    spans.push(Span::styled(format!(" (expires in {}m)", minutes), Style::default().fg(Color::DarkGray)));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let title = format!("Pending approvals ({})", self.approvals.len());
+        let list = List::new(items)
+            .block(pane(&title, self.focus == Focus::Approvals))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.approval_list);
+    }
+}
+
+/// Bordered block, highlighted when it has focus
+fn pane(title: &str, focused: bool) -> Block<'static> {
+    let style = if focused { Style::default().fg(Color::Cyan) } else { Style::default() };
+    Block::default().borders(Borders::ALL).border_style(style).title(title.to_string())
+}
+
+/// Keep a selection inside a list that may have shrunk or grown from empty
+fn clamp_selection(state: &mut ListState, len: usize) {
+    match (state.selected(), len) {
+        (_, 0) => state.select(None),
+        (None, _) => state.select(Some(0)),
+        (Some(i), _) if i >= len => state.select(Some(len - 1)),
+        _ => {}
+    }
+}
@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use crate::state::*;
+use crate::state::stats::FindingsStats;
+use crate::state::summary::SessionSummary as Summary;
+use crate::artifacts::ArtifactCheck;
+use crate::misp::MispMatch;
+use crate::artifacts::transfer::TransferDirection;
 
 /// WebSocket event protocol
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,14 +15,24 @@ pub enum WSEvent {
     SessionCreated {
         session_id: String,
         name: String,
+        mode: OperationalMode,
     },
     SessionLoaded {
         session_id: String,
-        state: SessionState,
+        state: Box<SessionState>,
+    },
+    /// A session was reconstructed by replaying its event journal
+    SessionRebuilt {
+        session_id: String,
+        reason: RebuildReason,
+        /// Journal entries replayed
+        entries: usize,
+        /// Where the unreadable session file was moved
+        corrupt_copy: Option<String>,
     },
     SessionUpdated {
         session_id: String,
-        delta: SessionDelta,
+        delta: Box<SessionDelta>,
     },
     SessionSaved {
         session_id: String,
@@ -29,14 +44,89 @@ pub enum WSEvent {
     SessionList {
         sessions: Vec<crate::session::SessionMetadata>,
     },
+    ScopeUpdated {
+        session_id: String,
+        scope: crate::security::scope::EngagementScope,
+    },
+    RulesOfEngagementUpdated {
+        session_id: String,
+        roe: crate::security::roe::RulesOfEngagement,
+    },
+    TargetAdded {
+        session_id: String,
+        target: Target,
+    },
+    TargetRemoved {
+        session_id: String,
+        target: String,
+    },
+    TargetList {
+        session_id: String,
+        targets: Vec<Target>,
+    },
+    Timeline {
+        session_id: String,
+        entries: Vec<TimelineEntry>,
+    },
+    FindingsStats {
+        session_id: String,
+        stats: FindingsStats,
+    },
+    SessionSummary {
+        summary: Summary,
+    },
     
     // Agent events
     AgentStatusChanged {
         agent: AgentType,
         status: AgentStatus,
     },
+    /// A busy agent reported no progress for too long and was set to `Error`
+    AgentStalled {
+        session_id: String,
+        agent: AgentType,
+        /// State it was stuck in
+        state: AgentState,
+        current_task: Option<String>,
+        last_update: DateTime<Utc>,
+    },
+    /// Free space under the base directory fell below a threshold
+    DiskSpaceLow {
+        path: String,
+        free_bytes: u64,
+        threshold_bytes: u64,
+        /// Below the critical threshold; artifact-heavy tasks are held
+        critical: bool,
+        timestamp: DateTime<Utc>,
+    },
+    /// Overall completion of a session changed
+    SessionProgress {
+        session_id: String,
+        percent: u8,
+        /// Phase of the agent run, absent when tasks are run by hand
+        phase: Option<AgentPhase>,
+    },
+    /// An agent orchestration run moved on
+    AgentRunPhase {
+        session_id: String,
+        /// Absent when a run was stopped from outside
+        run_id: Option<String>,
+        phase: AgentPhase,
+        cycle: u32,
+        detail: Option<String>,
+    },
+    /// A message one agent handed another
+    AgentMessage {
+        envelope: crate::agents::bus::AgentEnvelope,
+    },
     PlanGenerated {
-        plan: Vec<ScanRequest>,
+        session_id: String,
+        run_id: String,
+        cycle: u32,
+        source: crate::agents::PlanSource,
+        plan: Vec<crate::agents::ScanRequest>,
+        /// Rule-planner steps the plan left out
+        baseline_missed: Vec<crate::agents::ScanRequest>,
     },
     
     // Task events
@@ -47,11 +137,23 @@ pub enum WSEvent {
         task_id: String,
         started_at: DateTime<Utc>,
     },
+    TaskDeferred {
+        task_id: String,
+        reason: String,
+    },
     TaskProgress {
         task_id: String,
         progress: f32,
         message: Option<String>,
     },
+    TaskOutput {
+        task_id: String,
+        /// One or more stdout lines, newline-separated when batched
+        line: String,
+        /// Lines dropped by throttling since the previous event
+        #[serde(default)]
+        skipped: u64,
+    },
     TaskCompleted {
         task_id: String,
         result: TaskResult,
@@ -60,6 +162,16 @@ pub enum WSEvent {
         task_id: String,
         error: String,
     },
+    TaskCancelled {
+        task_id: String,
+        reason: String,
+    },
+    /// Tasks found running when a session was loaded, left over from a
+    /// core that stopped
+    TasksInterrupted {
+        session_id: String,
+        tasks: Vec<InterruptedTask>,
+    },
     
     // Approval events
     ApprovalRequired {
@@ -74,11 +186,143 @@ pub enum WSEvent {
         denied_at: DateTime<Utc>,
         reason: Option<String>,
     },
+    ApprovalEscalated {
+        approval: ApprovalRequest,
+    },
+    ApprovalExpired {
+        approval_id: String,
+        expired_at: DateTime<Utc>,
+    },
     
     // Finding events
     FindingDiscovered {
         finding: Finding,
     },
+    FindingUpdated {
+        finding: Finding,
+    },
+    FindingEnriched {
+        finding: Finding,
+        cves: Vec<String>,
+    },
+    /// Findings were shared as (or added to) a MISP event
+    MispEventPushed {
+        session_id: String,
+        event_id: String,
+        findings: usize,
+        attributes: usize,
+    },
+    /// MISP attributes matching what the session saw; `findings` lists the
+    /// findings they were attached to
+    MispEnriched {
+        session_id: String,
+        matches: Vec<MispMatch>,
+        findings: Vec<String>,
+    },
+    /// A Jira issue was opened for a finding
+    TicketCreated {
+        session_id: String,
+        finding_id: String,
+        key: String,
+        url: String,
+    },
+    /// Findings were uploaded to a DefectDojo engagement
+    DefectDojoPushed {
+        session_id: String,
+        engagement_id: u64,
+        test_id: u64,
+        created: usize,
+        updated: usize,
+    },
+    EvidenceAttached {
+        session_id: String,
+        finding_id: String,
+        evidence: ArtifactRef,
+    },
+    
+    // Asset events
+    AssetDiscovered {
+        session_id: String,
+        asset: Asset,
+    },
+    AssetUpdated {
+        session_id: String,
+        asset: Asset,
+    },
+    ServiceDiscovered {
+        session_id: String,
+        asset_id: String,
+        service: asset::Service,
+    },
+    /// New or changed HTTP probe results for a web service
+    WebServiceProbed {
+        session_id: String,
+        asset_id: String,
+        port: u16,
+        http: asset::HttpInfo,
+    },
+    /// Names from a recon tool, queued for resolution
+    SubdomainsDiscovered {
+        session_id: String,
+        source: String,
+        hostnames: Vec<String>,
+    },
+    /// Any name under `domain` resolves to `addresses`; enumerated names
+    /// that only resolve there are dropped
+    DnsWildcard {
+        session_id: String,
+        domain: String,
+        addresses: Vec<std::net::IpAddr>,
+    },
+    
+    // Artifact events
+    ArtifactCreated {
+        session_id: String,
+        artifact: Artifact,
+    },
+    /// Retention will delete an artifact at `deletes_at`; archive it
+    /// before then to keep it
+    ArtifactExpiring {
+        session_id: String,
+        artifact: Artifact,
+        deletes_at: DateTime<Utc>,
+    },
+    ArtifactDeleted {
+        session_id: String,
+        artifact_id: String,
+        reason: String,
+    },
+    /// Every artifact in a session re-hashed and compared with its checksum
+    ArtifactsVerified {
+        session_id: String,
+        verified_at: DateTime<Utc>,
+        checks: Vec<ArtifactCheck>,
+    },
+    /// A transfer was accepted; its binary frames start with the 16 bytes
+    /// of `transfer_id`
+    TransferStarted {
+        transfer_id: String,
+        direction: TransferDirection,
+        session_id: String,
+        /// Artifact being downloaded
+        artifact_id: Option<String>,
+        name: String,
+        size: u64,
+        chunk_size: usize,
+    },
+    TransferProgress {
+        transfer_id: String,
+        bytes: u64,
+        total: u64,
+    },
+    TransferCompleted {
+        transfer_id: String,
+        artifact: Artifact,
+    },
+    TransferFailed {
+        transfer_id: String,
+        reason: String,
+    },
     
     // Log events
     LogEntry {
@@ -87,16 +331,58 @@ pub enum WSEvent {
         message: String,
         timestamp: DateTime<Utc>,
     },
+    /// Log filter now applied to this client
+    LogsSubscribed {
+        min_level: LogLevel,
+        agent: Option<AgentType>,
+    },
     
     // System events
     SystemHealth {
+        /// Host CPU use, percent
         cpu: f32,
+        /// Host memory in use, percent
         memory: f32,
+        /// 1, 5 and 15 minute load averages
+        #[serde(default)]
+        load: [f64; 3],
+        /// Tools running natively
+        #[serde(default)]
+        tasks: Vec<TaskResourceUsage>,
         timestamp: DateTime<Utc>,
     },
+    BridgeStatus {
+        status: crate::python_bridge::BridgeStatus,
+    },
     TorStatus {
         connected: bool,
         circuit: Option<String>,
+        /// Bootstrap progress, percent
+        #[serde(default)]
+        bootstrap: Option<u8>,
+        /// Why the control port could not be queried
+        #[serde(default)]
+        error: Option<String>,
+        /// When the identity was last switched
+        #[serde(default)]
+        rotated_at: Option<DateTime<Utc>>,
+    },
+    KillSwitchEngaged {
+        engaged_by: Actor,
+        cancelled: Vec<String>,
+        timestamp: DateTime<Utc>,
+    },
+    KillSwitchReleased {
+        released_by: Actor,
+        timestamp: DateTime<Utc>,
+    },
+    ConfigReloaded {
+        reloaded_by: Actor,
+        /// Settings now in effect
+        changed: Vec<String>,
+        /// Settings that differ on disk but need a restart
+        restart_required: Vec<String>,
+        timestamp: DateTime<Utc>,
     },
     BrowserStatus {
         active: bool,
@@ -108,6 +394,17 @@ pub enum WSEvent {
         message: String,
         details: Option<String>,
     },
+    PermissionDenied {
+        command: String,
+        reason: String,
+    },
+    ScopeViolation {
+        session_id: String,
+        task_id: Option<String>,
+        tool_name: String,
+        target: String,
+        reason: String,
+    },
     
     // Client commands
     CreateSession {
@@ -126,11 +423,153 @@ pub enum WSEvent {
     },
     ExportSession {
         session_id: String,
+        #[serde(default)]
+        format: crate::session::ExportFormat,
+        /// Encrypts the `archive` format; never echoed to other clients
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        password: Option<String>,
+    },
+    RebuildSession {
+        session_id: String,
+    },
+    GenerateReport {
+        session_id: String,
+        #[serde(default)]
+        format: crate::report::ReportFormat,
+        /// User template from `<base>/templates` to render instead of the built-in layout
+        template: Option<String>,
+    },
+    ListReportTemplates,
+    /// Attach a session artifact to a finding; files from the client are
+    /// sent with `UploadArtifact` first
+    AttachEvidence {
+        finding_id: String,
+        artifact_id: String,
+        caption: Option<String>,
+    },
+    /// Stream an artifact to the requesting client as binary frames
+    DownloadArtifact {
+        session_id: String,
+        artifact_id: String,
+    },
+    /// Announce an upload; the client then sends `size` bytes as binary
+    /// frames tagged with the transfer ID from `transfer_started`
+    UploadArtifact {
+        session_id: String,
+        name: String,
+        size: u64,
+        /// Hex SHA-256 the content must match
+        sha256: Option<String>,
+        /// Guessed from the name's extension if omitted
+        artifact_type: Option<ArtifactType>,
+    },
+    CancelTransfer {
+        transfer_id: String,
+    },
+    /// Re-hash a session's artifact files; answered by an
+    /// `artifacts_verified` broadcast
+    VerifyArtifacts {
+        session_id: String,
+    },
+    ReportTemplateList {
+        templates: Vec<String>,
+    },
+    SetScope {
+        session_id: String,
+        scope: crate::security::scope::EngagementScope,
+    },
+    SetRulesOfEngagement {
+        session_id: String,
+        roe: crate::security::roe::RulesOfEngagement,
+    },
+    AddTarget {
+        session_id: String,
+        target: String,
+        #[serde(default = "default_in_scope")]
+        in_scope: bool,
+        note: Option<String>,
+    },
+    RemoveTarget {
+        session_id: String,
+        target: String,
+    },
+    ListTargets {
+        session_id: String,
+    },
+    /// Agent state transitions, optionally for one agent or from a time
+    GetTimeline {
+        session_id: String,
+        agent: Option<AgentType>,
+        since: Option<DateTime<Utc>>,
+    },
+    /// Digest of duration, task outcomes, top findings and scope coverage;
+    /// answered with `SessionSummary`
+    GetSessionSummary {
+        session_id: String,
+    },
+    /// Finding counts and discovery series; answered with `FindingsStats`
+    GetFindingsStats {
+        session_id: String,
+    },
+    /// Share findings with MISP: the listed ones, or all at or above the
+    /// configured severity
+    PushToMisp {
+        session_id: String,
+        #[serde(default)]
+        finding_ids: Option<Vec<String>>,
+    },
+    /// Search MISP for the session's CVEs, targets and assets
+    PullFromMisp {
+        session_id: String,
+    },
+    /// Open a Jira issue for a finding; answered with `TicketCreated`
+    CreateTicket {
+        finding_id: String,
+    },
+    /// Upload findings to DefectDojo: the listed ones, or all at or above
+    /// the configured severity
+    PushToDefectDojo {
+        session_id: String,
+        #[serde(default)]
+        finding_ids: Option<Vec<String>>,
+    },
+    /// Limit the `LogEntry` events sent to this client; answered with
+    /// `LogsSubscribed`
+    SubscribeLogs {
+        #[serde(default)]
+        min_level: LogLevel,
+        /// Only entries from this agent
+        #[serde(default)]
+        agent: Option<AgentType>,
+    },
+    GetToolCatalog,
+    /// Answered with `TorStatus`
+    GetTorStatus,
+    /// Switch Tor to a new identity; answered by a `TorStatus` broadcast
+    RotateTorCircuit,
+    ToolCatalog {
+        tools: Vec<crate::tools::ToolCatalogEntry>,
     },
     QueueTask {
         tool_name: String,
         target: String,
         args: serde_json::Value,
+        /// Egress proxy for this task instead of the global one
+        #[serde(default)]
+        proxy: Option<String>,
+    },
+    KillSwitch,
+    ReleaseKillSwitch,
+    /// Re-read the config files without restarting
+    ReloadConfig,
+    /// Run the Planner→Operator→Analyst→Scribe loop toward a goal
+    StartAgents {
+        session_id: String,
+        goal: String,
+        max_cycles: Option<u32>,
+    },
+    StopAgents {
+        session_id: String,
     },
     ApproveAction {
         approval_id: String,
@@ -139,20 +578,68 @@ pub enum WSEvent {
         approval_id: String,
         reason: Option<String>,
     },
+    CreateApiKey {
+        name: String,
+        scopes: Vec<crate::security::rbac::Permission>,
+    },
+    RevokeApiKey {
+        key_id: String,
+    },
+    ListApiKeys,
+    ApiKeyCreated {
+        key: crate::security::api_keys::ApiKeyInfo,
+        secret: String,
+    },
+    ApiKeyRevoked {
+        key: crate::security::api_keys::ApiKeyInfo,
+    },
+    ApiKeyList {
+        keys: Vec<crate::security::api_keys::ApiKeyInfo>,
+    },
+    SetSecret {
+        name: String,
+        value: String,
+    },
+    DeleteSecret {
+        name: String,
+    },
+    ListSecrets,
+    SecretList {
+        names: Vec<String>,
+    },
     GetSessionList,
     GetAgentStatus {
         agent: AgentType,
     },
+    /// Ask the AI; with a session, prior turns are sent as context and the
+    /// exchange is kept in its chat history
     Chat {
         message: String,
         model: Option<String>,
+        session_id: Option<String>,
+        /// Echoed on the reply's chunks and final response; generated if absent
+        request_id: Option<String>,
+        /// Answer with this agent's system prompt
+        agent: Option<AgentType>,
+    },
+    /// Part of a reply still being written
+    ChatResponseChunk {
+        request_id: String,
+        delta: String,
     },
+    /// The whole reply, sent once it is complete
     ChatResponse {
+        request_id: String,
         response: String,
         model: String,
+        session_id: Option<String>,
     },
 }
 
+fn default_in_scope() -> bool {
+    true
+}
+
 /// Session delta for incremental updates
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionDelta {
@@ -165,13 +652,26 @@ pub struct SessionDelta {
     pub status_changed: Option<SessionStatus>,
 }
 
-/// Scan request from planner
+/// Why a session was rebuilt from its journal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RebuildReason {
+    /// A client asked for it
+    Requested,
+    /// The session file does not exist
+    MissingFile,
+    /// The session file could not be parsed
+    CorruptFile,
+}
+
+/// A task cut off by a core restart
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ScanRequest {
+pub struct InterruptedTask {
+    pub task_id: String,
     pub tool_name: String,
     pub target: String,
-    pub args: serde_json::Value,
-    pub reasoning: Option<String>,
+    /// Task queued to run it again, when interrupted tasks are requeued
+    pub requeued_as: Option<String>,
 }
 
 /// Task execution result
@@ -181,12 +681,29 @@ pub struct TaskResult {
     pub output: String,
     pub structured_data: Option<serde_json::Value>,
     pub duration_ms: u64,
+    /// Process exit status, for tools run natively
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    /// Captured stderr, for tools run natively
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stderr: Option<String>,
+}
+
+/// Resources used by one task's tool process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskResourceUsage {
+    pub task_id: String,
+    pub pid: u32,
+    /// Percent of one core
+    pub cpu: f32,
+    pub memory_bytes: u64,
 }
 
 /// Log level
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum LogLevel {
+    #[default]
     Debug,
     Info,
     Warn,
@@ -194,6 +711,27 @@ pub enum LogLevel {
 }
 
 impl WSEvent {
+    /// Wire name of this event (its `type` tag)
+    pub fn event_type(&self) -> String {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|v| v.get("type").and_then(|t| t.as_str()).map(str::to_string))
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+    
+    /// Whether the event carries plaintext secrets and must not be echoed
+    pub fn is_sensitive(&self) -> bool {
+        matches!(self, Self::SetSecret { .. } | Self::ExportSession { password: Some(_), .. })
+    }
+    
+    /// Whether the event is only for live viewers and is not journaled
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::TaskOutput { .. } | Self::BridgeStatus { .. } | Self::ChatResponseChunk { .. } | Self::SystemHealth { .. } | Self::SessionProgress { .. }
+        )
+    }
+    
     /// Create a log entry event
     pub fn log(level: LogLevel, message: impl Into<String>, agent: Option<AgentType>) -> Self {
         Self::LogEntry {
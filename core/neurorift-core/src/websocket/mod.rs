@@ -1,36 +1,250 @@
 pub mod events;
+pub mod stdio;
 
 use anyhow::Result;
 use futures_util::{SinkExt, StreamExt};
 use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::broadcast;
-use tokio_tungstenite::{accept_async, tungstenite::Message};
-use crate::websocket::events::WSEvent;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use tokio::sync::{broadcast, mpsc, OwnedSemaphorePermit};
+use tokio_tungstenite::{accept_hdr_async, tungstenite::Message};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::{header::ORIGIN, StatusCode};
+use crate::metrics::METRICS;
+use crate::security::api_keys::{ApiKeyInfo, ApiKeyStore};
+use crate::security::rbac::Permission;
+use crate::state::{Actor, AgentType};
+use crate::websocket::events::{LogLevel, WSEvent};
+
+/// Handshake header carrying the operator name
+const OPERATOR_HEADER: &str = "x-neurorift-operator";
+
+/// Binary frames from clients waiting for the core; reading a client's
+/// socket pauses while this is full
+const FRAME_QUEUE: usize = 64;
+
+/// Transport a client is connected over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    Tcp,
+    Unix,
+    Stdio,
+}
+
+impl Transport {
+    /// Whether access is already limited to the local user by the OS
+    pub fn is_local(&self) -> bool {
+        matches!(self, Transport::Unix | Transport::Stdio)
+    }
+}
+
+/// Identity of a connected client
+#[derive(Debug, Clone)]
+pub struct ClientInfo {
+    /// Unique per-connection identifier
+    pub client_id: String,
+    /// Who is acting through this connection
+    pub identity: Actor,
+    /// How the client is connected
+    pub transport: Transport,
+    /// Scopes granted by the API key the client authenticated with
+    pub scopes: Option<Vec<Permission>>,
+}
+
+impl ClientInfo {
+    /// Create client info for a new connection
+    pub fn new(identity: Actor, transport: Transport) -> Self {
+        Self {
+            client_id: format!("client_{}", &uuid::Uuid::new_v4().simple().to_string()[..8]),
+            identity,
+            transport,
+            scopes: None,
+        }
+    }
+}
+
+/// Which log entries a client receives; clients without one get them all
+#[derive(Debug, Clone, Copy)]
+pub struct LogFilter {
+    pub min_level: LogLevel,
+    /// Only entries from this agent
+    pub agent: Option<AgentType>,
+}
+
+impl LogFilter {
+    fn admits(&self, level: LogLevel, agent: Option<AgentType>) -> bool {
+        level >= self.min_level && self.agent.is_none_or(|wanted| agent == Some(wanted))
+    }
+}
+
+/// A command received from a specific client
+#[derive(Debug, Clone)]
+pub struct ClientCommand {
+    pub client: ClientInfo,
+    pub event: WSEvent,
+}
+
+/// A binary frame received from a specific client
+#[derive(Debug)]
+pub struct ClientFrame {
+    pub client_id: String,
+    pub data: Vec<u8>,
+}
+
+/// Something queued for one client
+#[derive(Debug)]
+pub enum Outbound {
+    Event(Box<WSEvent>),
+    /// Binary frame; the permit, if any, is released once the frame is
+    /// written to the socket or dropped
+    Binary(Vec<u8>, Option<OwnedSemaphorePermit>),
+}
 
 /// WebSocket server for real-time communication
 pub struct WebSocketServer {
     addr: SocketAddr,
+    unix_socket: Option<PathBuf>,
     event_tx: broadcast::Sender<WSEvent>,
+    command_tx: broadcast::Sender<ClientCommand>,
+    /// Direct channels to individual clients
+    clients: DashMap<String, mpsc::UnboundedSender<Outbound>>,
+    /// Log filters set by clients with `SubscribeLogs`
+    log_filters: DashMap<String, LogFilter>,
+    /// Binary frames from clients, for the core
+    frame_tx: mpsc::Sender<ClientFrame>,
+    frame_rx: Mutex<Option<mpsc::Receiver<ClientFrame>>>,
+    /// API keys accepted during the handshake
+    api_keys: Option<Arc<ApiKeyStore>>,
+    /// Reject TCP clients without a valid API key
+    require_api_key: bool,
+    /// Browser origins allowed to connect
+    allowed_origins: Vec<String>,
 }
 
 impl WebSocketServer {
     /// Create a new WebSocket server
     pub fn new(addr: SocketAddr) -> Self {
         let (event_tx, _) = broadcast::channel(1000);
+        let (command_tx, _) = broadcast::channel(1000);
+        let (frame_tx, frame_rx) = mpsc::channel(FRAME_QUEUE);
         
         Self {
             addr,
+            unix_socket: None,
             event_tx,
+            command_tx,
+            clients: DashMap::new(),
+            log_filters: DashMap::new(),
+            frame_tx,
+            frame_rx: Mutex::new(Some(frame_rx)),
+            api_keys: None,
+            require_api_key: false,
+            allowed_origins: Vec::new(),
         }
     }
     
+    /// Authenticate clients with API keys from the given store
+    pub fn with_api_keys(mut self, store: Arc<ApiKeyStore>, required: bool) -> Self {
+        self.api_keys = Some(store);
+        self.require_api_key = required;
+        self
+    }
+    
+    /// Accept browser connections only from these origins
+    pub fn with_allowed_origins(mut self, origins: Vec<String>) -> Self {
+        self.allowed_origins = origins;
+        self
+    }
+    
+    /// Also listen on a Unix domain socket (owner-only permissions)
+    pub fn with_unix_socket(mut self, path: impl Into<PathBuf>) -> Self {
+        self.unix_socket = Some(path.into());
+        self
+    }
+    
+    /// Unix socket path, if configured
+    pub fn unix_socket(&self) -> Option<&Path> {
+        self.unix_socket.as_deref()
+    }
+    
     /// Get a sender for broadcasting events
     pub fn get_sender(&self) -> broadcast::Sender<WSEvent> {
         self.event_tx.clone()
     }
     
+    /// Subscribe to commands sent by clients, tagged with their identity
+    pub fn subscribe_commands(&self) -> broadcast::Receiver<ClientCommand> {
+        self.command_tx.subscribe()
+    }
+    
+    /// Take the stream of binary frames sent by clients; only the first
+    /// caller gets it
+    pub fn take_frames(&self) -> Option<mpsc::Receiver<ClientFrame>> {
+        self.frame_rx.lock().take()
+    }
+    
+    /// Submit a client command for processing.
+    ///
+    /// Commands are echoed to all clients by the core once authorized.
+    pub fn submit(&self, client: ClientInfo, event: WSEvent) {
+        let _ = self.command_tx.send(ClientCommand { client, event });
+    }
+    
+    /// Register a client for direct messages
+    pub fn register_client(&self, client: &ClientInfo) -> mpsc::UnboundedReceiver<Outbound> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.clients.insert(client.client_id.clone(), tx);
+        rx
+    }
+    
+    /// Remove a client's direct channel
+    pub fn unregister_client(&self, client_id: &str) {
+        self.clients.remove(client_id);
+        self.log_filters.remove(client_id);
+    }
+    
+    /// Send a client only the log entries passing `filter`
+    pub fn filter_logs(&self, client_id: &str, filter: LogFilter) {
+        self.log_filters.insert(client_id.to_string(), filter);
+        self.send_to(client_id, WSEvent::LogsSubscribed {
+            min_level: filter.min_level,
+            agent: filter.agent,
+        });
+    }
+    
+    /// Whether a broadcast event should go to a client
+    fn wants(&self, client_id: &str, event: &WSEvent) -> bool {
+        match event {
+            WSEvent::LogEntry { level, agent, .. } => self.log_filters
+                .get(client_id)
+                .is_none_or(|filter| filter.admits(*level, *agent)),
+            _ => true,
+        }
+    }
+    
+    /// Send an event to a single client, returning false if it is gone
+    pub fn send_to(&self, client_id: &str, event: WSEvent) -> bool {
+        self.clients
+            .get(client_id)
+            .map(|tx| tx.send(Outbound::Event(Box::new(event))).is_ok())
+            .unwrap_or(false)
+    }
+    
+    /// Send a binary frame to a single client, in order with its events.
+    /// `permit` is held until the frame is written, for flow control.
+    pub fn send_binary(&self, client_id: &str, data: Vec<u8>, permit: Option<OwnedSemaphorePermit>) -> bool {
+        self.clients
+            .get(client_id)
+            .map(|tx| tx.send(Outbound::Binary(data, permit)).is_ok())
+            .unwrap_or(false)
+    }
+    
     /// Start the WebSocket server
     pub async fn run(self: Arc<Self>) -> Result<()> {
         let listener = TcpListener::bind(self.addr).await?;
@@ -41,8 +255,46 @@ impl WebSocketServer {
                 Ok((stream, peer_addr)) => {
                     tracing::info!("New connection from {}", peer_addr);
                     let server = self.clone();
+                    let fallback = Actor::Operator(format!("anonymous@{}", peer_addr));
+                    tokio::spawn(async move {
+                        if let Err(e) = server.handle_connection(stream, fallback, Transport::Tcp).await {
+                            tracing::error!("Connection error: {}", e);
+                        }
+                    });
+                }
+                Err(e) => {
+                    tracing::error!("Accept error: {}", e);
+                }
+            }
+        }
+    }
+    
+    /// Start the WebSocket server on the configured Unix domain socket
+    #[cfg(unix)]
+    pub async fn run_unix(self: Arc<Self>) -> Result<()> {
+        let Some(path) = self.unix_socket.clone() else {
+            return Ok(());
+        };
+        
+        // Remove a stale socket left behind by a previous run
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+        let listener = bind_private(&path)?;
+        tracing::info!("WebSocket server listening on unix:{}", path.display());
+        
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tracing::info!("New connection on unix:{}", path.display());
+                    // Socket permissions already restrict access to the owner
+                    let fallback = match stream.peer_cred() {
+                        Ok(cred) => Actor::Operator(format!("uid:{}", cred.uid())),
+                        Err(_) => Actor::Operator("local".to_string()),
+                    };
+                    let server = self.clone();
                     tokio::spawn(async move {
-                        if let Err(e) = server.handle_connection(stream).await {
+                        if let Err(e) = server.handle_connection(stream, fallback, Transport::Unix).await {
                             tracing::error!("Connection error: {}", e);
                         }
                     });
@@ -55,33 +307,111 @@ impl WebSocketServer {
     }
     
     /// Handle a single WebSocket connection
-    async fn handle_connection(&self, stream: TcpStream) -> Result<()> {
-        let ws_stream = accept_async(stream).await?;
+    ///
+    /// A valid API key (`Authorization: Bearer` header or `api_key` query
+    /// parameter) sets the identity and scopes. Otherwise the operator is
+    /// taken from the `X-NeuroRift-Operator` handshake header, falling back
+    /// to an identity derived from the transport; that name only labels the
+    /// client and grants no role. Browser requests must come from an allowed
+    /// origin, so web pages cannot drive a local core.
+    // The handshake callback's error type is fixed by tungstenite
+    #[allow(clippy::result_large_err)]
+    async fn handle_connection<S>(self: Arc<Self>, stream: S, fallback: Actor, transport: Transport) -> Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let mut operator = None;
+        let mut api_key: Option<ApiKeyInfo> = None;
+        let ws_stream = accept_hdr_async(stream, |req: &Request, resp: Response| {
+            if let Some(origin) = req.headers().get(ORIGIN) {
+                let origin = origin.to_str().unwrap_or_default().trim_end_matches('/');
+                if !self.allowed_origins.iter().any(|allowed| allowed.trim_end_matches('/').eq_ignore_ascii_case(origin)) {
+                    tracing::warn!("Rejected WebSocket handshake from origin {:?}", origin);
+                    return Err(forbidden("origin not allowed"));
+                }
+            }
+            
+            operator = req.headers()
+                .get(OPERATOR_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty());
+            
+            match presented_key(req) {
+                Some(secret) => {
+                    let key = self.api_keys.as_ref().and_then(|store| store.authenticate(&secret));
+                    if key.is_none() {
+                        return Err(reject("invalid or revoked API key"));
+                    }
+                    api_key = key;
+                }
+                None if self.require_api_key && !transport.is_local() => {
+                    return Err(reject("API key required"));
+                }
+                None => {}
+            }
+            Ok(resp)
+        }).await?;
         let (mut ws_sender, mut ws_receiver) = ws_stream.split();
         
-        // Subscribe to broadcast events
+        let client = match api_key {
+            Some(key) => {
+                let mut client = ClientInfo::new(Actor::Operator(key.name), transport);
+                client.scopes = Some(key.scopes);
+                client
+            }
+            None => ClientInfo::new(operator.map(Actor::Operator).unwrap_or(fallback), transport),
+        };
+        tracing::info!("Client {} connected as {}", client.client_id, client.identity);
+        METRICS.ws_clients.inc();
+        
+        // Subscribe to broadcast events and direct messages
         let mut event_rx = self.event_tx.subscribe();
+        let mut direct_rx = self.register_client(&client);
+        let client_id = client.client_id.clone();
         
-        // Spawn task to forward broadcast events to this client
+        // Spawn task to forward events to this client
+        let server = self.clone();
+        let forward_id = client_id.clone();
         let mut send_task = tokio::spawn(async move {
-            while let Ok(event) = event_rx.recv().await {
-                let json = serde_json::to_string(&event).unwrap();
-                if ws_sender.send(Message::Text(json)).await.is_err() {
+            loop {
+                let outbound = tokio::select! {
+                    event = event_rx.recv() => match event {
+                        Ok(event) if !server.wants(&forward_id, &event) => continue,
+                        Ok(event) => Outbound::Event(Box::new(event)),
+                        Err(_) => break,
+                    },
+                    outbound = direct_rx.recv() => match outbound {
+                        Some(outbound) => outbound,
+                        None => break,
+                    },
+                };
+                let sent = match outbound {
+                    Outbound::Event(event) => ws_sender.send(Message::Text(serde_json::to_string(&event).unwrap())).await,
+                    // The permit drops after the write, letting the sender queue more
+                    Outbound::Binary(data, _permit) => ws_sender.send(Message::Binary(data)).await,
+                };
+                if sent.is_err() {
                     break;
                 }
             }
         });
         
         // Handle incoming messages from client
-        let event_tx = self.event_tx.clone();
+        let server = self.clone();
         let mut recv_task = tokio::spawn(async move {
             while let Some(msg) = ws_receiver.next().await {
                 match msg {
                     Ok(Message::Text(text)) => {
                         // Parse client command
                         if let Ok(event) = serde_json::from_str::<WSEvent>(&text) {
-                            // Broadcast to all clients (including sender)
-                            let _ = event_tx.send(event);
+                            server.submit(client.clone(), event);
+                        }
+                    }
+                    Ok(Message::Binary(data)) => {
+                        let frame = ClientFrame { client_id: client.client_id.clone(), data };
+                        if server.frame_tx.send(frame).await.is_err() {
+                            tracing::debug!("Binary frame from {} dropped, nothing reads frames", client.client_id);
                         }
                     }
                     Ok(Message::Close(_)) => {
@@ -107,11 +437,71 @@ impl WebSocketServer {
             }
         }
         
+        self.unregister_client(&client_id);
+        METRICS.ws_clients.dec();
+        
         Ok(())
     }
     
     /// Broadcast an event to all connected clients
     pub fn broadcast(&self, event: WSEvent) {
+        METRICS.events_broadcast.inc();
         let _ = self.event_tx.send(event);
     }
 }
+
+/// API key presented in the handshake, if any
+fn presented_key(req: &Request) -> Option<String> {
+    let from_header = req.headers()
+        .get(tokio_tungstenite::tungstenite::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|v| v.trim().to_string());
+    
+    // Browsers cannot set headers on WebSocket requests
+    let from_query = || req.uri().query().and_then(|query| {
+        query.split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == "api_key")
+            .map(|(_, value)| value.to_string())
+    });
+    
+    from_header.or_else(from_query)
+}
+
+/// Bind a Unix socket that is never reachable with looser permissions than
+/// 0600: it is created in a fresh 0700 directory, restricted, then moved
+/// into place
+#[cfg(unix)]
+fn bind_private(path: &std::path::Path) -> Result<UnixListener> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    
+    let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(std::path::Path::new("."));
+    std::fs::create_dir_all(parent)?;
+    let staging = parent.join(format!(".{}.{}", path.file_name().unwrap_or_default().to_string_lossy(), uuid::Uuid::new_v4().simple()));
+    std::fs::DirBuilder::new().mode(0o700).create(&staging)?;
+    
+    let staged = staging.join("sock");
+    let result = UnixListener::bind(&staged)
+        .map_err(anyhow::Error::from)
+        .and_then(|listener| {
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+            std::fs::rename(&staged, path)?;
+            Ok(listener)
+        });
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+/// 401 handshake response
+fn reject(reason: &str) -> ErrorResponse {
+    let mut response = ErrorResponse::new(Some(reason.to_string()));
+    *response.status_mut() = StatusCode::UNAUTHORIZED;
+    response
+}
+
+fn forbidden(reason: &str) -> ErrorResponse {
+    let mut response = ErrorResponse::new(Some(reason.to_string()));
+    *response.status_mut() = StatusCode::FORBIDDEN;
+    response
+}
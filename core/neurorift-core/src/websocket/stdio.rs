@@ -0,0 +1,149 @@
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use crate::state::Actor;
+use crate::websocket::{ClientInfo, Outbound, Transport, WebSocketServer, events::WSEvent};
+
+/// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Serve the WSEvent protocol as JSON-RPC 2.0 over stdin/stdout.
+///
+/// Client commands arrive as requests whose `method` is the event type
+/// (e.g. `create_session`) and whose `params` are the event fields.
+/// Broadcast events are written back as notifications in the same shape.
+pub async fn serve_stdio(server: Arc<WebSocketServer>) -> Result<()> {
+    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<Value>();
+    
+    // Single writer so responses and notifications never interleave
+    let writer_task = tokio::spawn(async move {
+        let mut stdout = tokio::io::stdout();
+        while let Some(message) = out_rx.recv().await {
+            let mut line = message.to_string();
+            line.push('\n');
+            if stdout.write_all(line.as_bytes()).await.is_err() || stdout.flush().await.is_err() {
+                break;
+            }
+        }
+    });
+    
+    // The embedding process acts as the local user
+    let operator = std::env::var("USER").unwrap_or_else(|_| "stdio".to_string());
+    let client = ClientInfo::new(Actor::Operator(operator), Transport::Stdio);
+    
+    // Forward broadcast events and direct messages as notifications
+    let mut event_rx = server.get_sender().subscribe();
+    let mut direct_rx = server.register_client(&client);
+    let notify_tx = out_tx.clone();
+    let forward_server = server.clone();
+    let client_id = client.client_id.clone();
+    let forward_task = tokio::spawn(async move {
+        loop {
+            let event = tokio::select! {
+                event = event_rx.recv() => match event {
+                    Ok(event) if !forward_server.wants(&client_id, &event) => continue,
+                    Ok(event) => event,
+                    Err(_) => break,
+                },
+                outbound = direct_rx.recv() => match outbound {
+                    Some(Outbound::Event(event)) => *event,
+                    // JSON-RPC has no binary frames; transfers refuse stdio clients
+                    Some(Outbound::Binary(..)) => continue,
+                    None => break,
+                },
+            };
+            if let Some(notification) = to_notification(&event) {
+                if notify_tx.send(notification).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    
+    // Read requests until stdin closes
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_request(&server, &client, &line) {
+            let _ = out_tx.send(response);
+        }
+    }
+    
+    tracing::info!("stdin closed, leaving stdio mode");
+    server.unregister_client(&client.client_id);
+    forward_task.abort();
+    drop(out_tx);
+    let _ = writer_task.await;
+    Ok(())
+}
+
+/// Handle one JSON-RPC request line, returning the response (if any)
+fn handle_request(server: &WebSocketServer, client: &ClientInfo, line: &str) -> Option<Value> {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, &e.to_string())),
+    };
+    
+    // Requests without an id are notifications and get no response
+    let id = request.get("id").cloned();
+    let reply_id = id.clone().unwrap_or(Value::Null);
+    
+    let Some(method) = request.get("method").and_then(|m| m.as_str()) else {
+        return Some(error_response(reply_id, INVALID_REQUEST, "missing method"));
+    };
+    
+    let mut fields = match request.get("params") {
+        None | Some(Value::Null) => serde_json::Map::new(),
+        Some(Value::Object(obj)) => obj.clone(),
+        Some(_) => {
+            return id.map(|id| error_response(id, INVALID_PARAMS, "params must be an object"));
+        }
+    };
+    fields.insert("type".to_string(), Value::String(method.to_string()));
+    
+    match serde_json::from_value::<WSEvent>(Value::Object(fields)) {
+        Ok(event) => {
+            server.submit(client.clone(), event);
+            id.map(|id| json!({ "jsonrpc": "2.0", "id": id, "result": { "accepted": true } }))
+        }
+        Err(e) => {
+            let message = e.to_string();
+            let code = if message.starts_with("unknown variant") {
+                METHOD_NOT_FOUND
+            } else {
+                INVALID_PARAMS
+            };
+            id.map(|id| error_response(id, code, &message))
+        }
+    }
+}
+
+/// Convert a broadcast event into a JSON-RPC notification
+fn to_notification(event: &WSEvent) -> Option<Value> {
+    let Value::Object(mut fields) = serde_json::to_value(event).ok()? else {
+        return None;
+    };
+    let method = fields.remove("type")?;
+    
+    Some(json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "params": fields,
+    }))
+}
+
+/// Build a JSON-RPC error response
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
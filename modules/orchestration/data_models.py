@@ -35,3 +35,4 @@ class ScanRequest(BaseModel):
     target: str
     args: Dict[str, Any] = {}
     mode_override: Optional[ToolMode] = None
+    proxy: Optional[str] = None  # e.g. socks5h://127.0.0.1:9050 for Tor
@@ -1,5 +1,6 @@
 import asyncio
 import logging
+import os
 import shlex
 import subprocess
 from datetime import datetime
@@ -68,7 +69,8 @@ class ExecutionManager:
             process = await asyncio.create_subprocess_exec(
                 *cmd_list,
                 stdout=asyncio.subprocess.PIPE,
-                stderr=asyncio.subprocess.PIPE
+                stderr=asyncio.subprocess.PIPE,
+                env=proxy_env(request.proxy)
             )
             
             stdout, stderr = await process.communicate()
@@ -122,3 +124,14 @@ class ExecutionManager:
             }
             for t in self.tools.values()
         ]
+
+
+def proxy_env(proxy: Optional[str]) -> Optional[Dict[str, str]]:
+    """Environment routing a tool's traffic through `proxy`, or None to inherit ours"""
+    if not proxy:
+        return None
+    env = dict(os.environ)
+    for name in ("ALL_PROXY", "HTTP_PROXY", "HTTPS_PROXY"):
+        env[name] = proxy
+        env[name.lower()] = proxy
+    return env
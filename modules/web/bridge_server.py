@@ -6,8 +6,17 @@ Thin bridge between Rust core and Python tools/AI
 from fastapi import FastAPI, HTTPException
 from pydantic import BaseModel
 from typing import Dict, Any, Optional
+from pathlib import Path
 import asyncio
+import hashlib
+import hmac
+import json
 import logging
+import os
+import secrets
+import socket
+import time
+import uuid
 
 # Import existing NeuroRift modules
 from modules.ai.ai_integration import OllamaClient, AIAnalyzer
@@ -19,13 +28,150 @@ from modules.tools.base import ToolMode
 logging.basicConfig(level=logging.INFO)
 logger = logging.getLogger(__name__)
 
+# Shared secret with the Rust core; see python_bridge/auth.rs
+BRIDGE_SECRET_ENV = "NEURORIFT_BRIDGE_SECRET"
+BRIDGE_KEY_FILE = "bridge.key"
+TIMESTAMP_HEADER = "x-neurorift-timestamp"
+SIGNATURE_HEADER = "x-neurorift-signature"
+
+# Serve on this Unix socket instead of localhost TCP; the core reads it too
+BRIDGE_SOCKET_ENV = "NEURORIFT_BRIDGE_SOCKET"
+
+# How far a request's timestamp may drift from our clock
+MAX_CLOCK_SKEW_SECONDS = 60
+
+
+def load_bridge_secret() -> bytes:
+    """Secret from the environment, the key file, or freshly generated"""
+    secret = os.environ.get(BRIDGE_SECRET_ENV, "").strip()
+    if secret:
+        return secret.encode()
+
+    base_dir = Path(os.environ.get("NEURORIFT_HOME", Path.home() / ".neurorift")).expanduser()
+    key_path = base_dir / BRIDGE_KEY_FILE
+    if key_path.exists():
+        secret = key_path.read_text().strip()
+        if not secret:
+            raise RuntimeError(f"Bridge key file {key_path} is empty")
+        return secret.encode()
+
+    base_dir.mkdir(parents=True, exist_ok=True)
+    secret = secrets.token_hex(32)
+    fd = os.open(key_path, os.O_WRONLY | os.O_CREAT | os.O_EXCL, 0o600)
+    with os.fdopen(fd, "w") as f:
+        f.write(secret)
+    logger.info(f"Generated new bridge key: {key_path}")
+    return secret.encode()
+
+
+def sign_parts(secret: bytes, *parts: bytes) -> str:
+    """Hex HMAC-SHA256 over the concatenated parts"""
+    return hmac.new(secret, b"".join(parts), hashlib.sha256).hexdigest()
+
+
+def check_request_signature(secret: bytes, method: str, path: str,
+                            timestamp: str, signature: str, body: bytes) -> Optional[str]:
+    """Why a request from the core fails verification, or None if it is genuine"""
+    if not timestamp or not signature:
+        return "Request is not signed"
+    try:
+        skew = abs(time.time() - int(timestamp))
+    except ValueError:
+        return "Malformed request timestamp"
+    if skew > MAX_CLOCK_SKEW_SECONDS:
+        return "Request timestamp is outside the allowed window"
+    expected = sign_parts(secret, f"{timestamp}\n{method}\n{path}\n".encode(), body)
+    if not hmac.compare_digest(expected, signature):
+        return "Request signature does not match"
+    return None
+
+
+class SignatureMiddleware:
+    """Accept only requests signed by the core and sign every response.
+
+    Responses are signed over the request's signature and the body, so the
+    core can tell it is talking to this bridge and not an impostor.
+    """
+
+    def __init__(self, app, secret: bytes):
+        self.app = app
+        self.secret = secret
+
+    async def __call__(self, scope, receive, send):
+        if scope["type"] != "http":
+            return await self.app(scope, receive, send)
+
+        body = b""
+        more_body = True
+        while more_body:
+            message = await receive()
+            body += message.get("body", b"")
+            more_body = message.get("more_body", False)
+
+        headers = {k.decode("latin-1").lower(): v.decode("latin-1") for k, v in scope["headers"]}
+        error = check_request_signature(
+            self.secret, scope["method"], scope["path"],
+            headers.get(TIMESTAMP_HEADER, ""), headers.get(SIGNATURE_HEADER, ""), body,
+        )
+        if error:
+            logger.warning(f"Rejected {scope['method']} {scope['path']}: {error}")
+            payload = json.dumps({"detail": error}).encode()
+            await send({
+                "type": "http.response.start",
+                "status": 401,
+                "headers": [(b"content-type", b"application/json"),
+                            (b"content-length", str(len(payload)).encode())],
+            })
+            await send({"type": "http.response.body", "body": payload})
+            return
+
+        replayed = False
+
+        async def replay_receive():
+            nonlocal replayed
+            if not replayed:
+                replayed = True
+                return {"type": "http.request", "body": body, "more_body": False}
+            return await receive()
+
+        request_signature = headers[SIGNATURE_HEADER].encode()
+        start = None
+        chunks = []
+
+        async def signing_send(message):
+            nonlocal start
+            if message["type"] == "http.response.start":
+                start = message
+                return
+            if message["type"] != "http.response.body":
+                return await send(message)
+            chunks.append(message.get("body", b""))
+            if message.get("more_body", False):
+                return
+            response_body = b"".join(chunks)
+            signature = sign_parts(self.secret, request_signature, b"\n", response_body)
+            response_headers = [(k, v) for k, v in start.get("headers", []) if k.lower() != b"content-length"]
+            response_headers += [
+                (b"content-length", str(len(response_body)).encode()),
+                (SIGNATURE_HEADER.encode(), signature.encode()),
+            ]
+            await send({**start, "headers": response_headers})
+            await send({"type": "http.response.body", "body": response_body})
+
+        await self.app(scope, replay_receive, signing_send)
+
+
 app = FastAPI(title="NeuroRift Python Bridge")
+app.add_middleware(SignatureMiddleware, secret=load_bridge_secret())
 
 # Initialize components
 ollama = OllamaClient()
 ai_analyzer = AIAnalyzer(ollama)
 execution_manager = ExecutionManager()
 
+# Finished jobs are kept this long for the core to collect
+JOB_RETENTION_SECONDS = 600
+
 
 class Command(BaseModel):
     """Generic command structure"""
@@ -38,12 +184,95 @@ class Response(BaseModel):
     success: bool
     data: Optional[Dict[str, Any]] = None
     error: Optional[str] = None
+    trace_id: Optional[str] = None
+
+
+class Job:
+    """A command running in the background"""
+
+    def __init__(self, command: Dict[str, Any]):
+        self.id = uuid.uuid4().hex
+        self.command = command
+        self.status = "running"
+        self.result: Optional[Response] = None
+        self.finished_at: Optional[float] = None
+        self.task: Optional[asyncio.Task] = None
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "job_id": self.id,
+            "status": self.status,
+            "result": self.result.dict() if self.result else None,
+        }
+
+
+jobs: Dict[str, Job] = {}
+
+
+def prune_jobs() -> None:
+    """Forget finished jobs nobody collected"""
+    cutoff = time.monotonic() - JOB_RETENTION_SECONDS
+    for job_id in [j.id for j in jobs.values() if j.finished_at and j.finished_at < cutoff]:
+        del jobs[job_id]
+
+
+async def run_job(job: Job) -> None:
+    """Run a job's command and record the outcome"""
+    try:
+        job.result = await run_command(job.command)
+        job.status = "completed" if job.result.success else "failed"
+    except asyncio.CancelledError:
+        job.status = "cancelled"
+        job.result = Response(success=False, error="Job cancelled", trace_id=job.command.get("trace_id"))
+    finally:
+        job.finished_at = time.monotonic()
 
 
 @app.post("/execute", response_model=Response)
 async def execute_command(command: Dict[str, Any]) -> Response:
+    """Execute a command from Rust core and wait for the result"""
+    return await run_command(command)
+
+
+@app.post("/jobs")
+async def submit_job(command: Dict[str, Any]) -> Dict[str, Any]:
+    """Start a command in the background and return its job ID"""
+    prune_jobs()
+    job = Job(command)
+    jobs[job.id] = job
+    job.task = asyncio.create_task(run_job(job))
+    logger.info(f"[trace={command.get('trace_id')}] Job {job.id} submitted ({command.get('type')})")
+    return job.to_dict()
+
+
+@app.get("/jobs/{job_id}")
+async def get_job(job_id: str) -> Dict[str, Any]:
+    """Status of a job, with its result once finished"""
+    job = jobs.get(job_id)
+    if job is None:
+        raise HTTPException(status_code=404, detail=f"Unknown job: {job_id}")
+    return job.to_dict()
+
+
+@app.delete("/jobs/{job_id}")
+async def cancel_job(job_id: str) -> Dict[str, Any]:
+    """Cancel a running job"""
+    job = jobs.get(job_id)
+    if job is None:
+        raise HTTPException(status_code=404, detail=f"Unknown job: {job_id}")
+    if job.task and not job.task.done():
+        job.task.cancel()
+        try:
+            await job.task
+        except asyncio.CancelledError:
+            pass
+        logger.info(f"Job {job_id} cancelled")
+    return job.to_dict()
+
+
+async def run_command(command: Dict[str, Any]) -> Response:
     """
-    Execute a command from Rust core
+    Run one command from the Rust core
     
     Command types:
     - ai_generate: Generate AI response
@@ -51,8 +280,12 @@ async def execute_command(command: Dict[str, Any]) -> Response:
     - robin_search: Dark web search via Robin
     - browser_action: Browser automation action
     """
+    # Trace context propagated by the Rust core
+    trace_id = command.get("trace_id")
+    
     try:
         cmd_type = command.get("type")
+        logger.info(f"[trace={trace_id}] Executing {cmd_type}")
         
         if cmd_type == "ai_generate":
             result = await handle_ai_generate(command)
@@ -65,11 +298,13 @@ async def execute_command(command: Dict[str, Any]) -> Response:
         else:
             raise HTTPException(status_code=400, detail=f"Unknown command type: {cmd_type}")
         
-        return Response(success=True, data=result)
+        return Response(success=True, data=result, trace_id=trace_id)
     
+    except asyncio.CancelledError:
+        raise
     except Exception as e:
-        logger.error(f"Command execution failed: {e}", exc_info=True)
-        return Response(success=False, error=str(e))
+        logger.error(f"[trace={trace_id}] Command execution failed: {e}", exc_info=True)
+        return Response(success=False, error=str(e), trace_id=trace_id)
 
 
 async def handle_ai_generate(command: Dict[str, Any]) -> Dict[str, Any]:
@@ -95,7 +330,8 @@ async def handle_tool_execute(command: Dict[str, Any]) -> Dict[str, Any]:
     scan_request = ScanRequest(
         tool_name=tool_name,
         target=target,
-        args=args
+        args=args,
+        proxy=command.get("proxy")
     )
     
     # Create minimal session context
@@ -122,18 +358,26 @@ async def handle_tool_execute(command: Dict[str, Any]) -> Dict[str, Any]:
 async def handle_robin_search(command: Dict[str, Any]) -> Dict[str, Any]:
     """Execute Robin dark web search"""
     query = command.get("query", "")
+    proxy = command.get("proxy")
     
     # TODO: Integrate with Robin module
     # For now, return placeholder
     return {
         "query": query,
+        "proxy": proxy,
         "results": [],
         "message": "Robin integration pending"
     }
 
 
 async def handle_browser_action(command: Dict[str, Any]) -> Dict[str, Any]:
-    """Execute browser automation action"""
+    """
+    Execute browser automation action
+    
+    Images the action takes are listed under "screenshots" as
+    {"path", "url"} or {"data" (base64), "format", "url"}; the core
+    stores them as session artifacts.
+    """
     action = command.get("action", "")
     params = command.get("params", {})
     
@@ -142,6 +386,7 @@ async def handle_browser_action(command: Dict[str, Any]) -> Dict[str, Any]:
     return {
         "action": action,
         "success": True,
+        "screenshots": [],
         "message": "Browser automation integration pending"
     }
 
@@ -156,9 +401,29 @@ async def health_check():
 async def startup_event():
     """Startup event"""
     logger.info("🐍 NeuroRift Python Bridge started")
-    logger.info("📡 Listening on http://127.0.0.1:8766")
+    socket_path = os.environ.get(BRIDGE_SOCKET_ENV)
+    logger.info(f"📡 Listening on {'unix:' + socket_path if socket_path else 'http://127.0.0.1:8766'}")
+
+
+def bind_unix_socket(path: str) -> socket.socket:
+    """Bind a Unix socket only this user can connect to"""
+    if os.path.exists(path):
+        os.unlink(path)
+    sock = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+    old_umask = os.umask(0o177)
+    try:
+        sock.bind(path)
+    finally:
+        os.umask(old_umask)
+    return sock
 
 
 if __name__ == "__main__":
     import uvicorn
-    uvicorn.run(app, host="127.0.0.1", port=8766, log_level="info")
+    socket_path = os.environ.get(BRIDGE_SOCKET_ENV)
+    if socket_path:
+        # Bound here rather than with --uds, which would make the socket world-writable
+        sock = bind_unix_socket(socket_path)
+        uvicorn.run(app, fd=sock.fileno(), log_level="info")
+    else:
+        uvicorn.run(app, host="127.0.0.1", port=8766, log_level="info")
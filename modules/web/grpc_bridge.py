@@ -0,0 +1,186 @@
+"""
+NeuroRift gRPC Bridge
+Serves the neurorift.bridge.v1 contract (proto/neurorift/bridge/v1/bridge.proto)
+with the same handlers as the HTTP bridge. The core uses it when bridge.json
+sets "transport": "grpc".
+"""
+
+import asyncio
+import json
+import logging
+import os
+import sys
+from pathlib import Path
+from typing import Any, Dict, Optional
+
+import grpc
+
+from modules.web.bridge_server import (
+    BRIDGE_SOCKET_ENV,
+    SIGNATURE_HEADER,
+    TIMESTAMP_HEADER,
+    check_request_signature,
+    handle_ai_generate,
+    handle_browser_action,
+    handle_robin_search,
+    handle_tool_execute,
+    load_bridge_secret,
+    sign_parts,
+)
+
+# Compile the contract at import time instead of checking in generated stubs
+PROTO_ROOT = Path(__file__).resolve().parents[2] / "proto"
+sys.path.insert(0, str(PROTO_ROOT))
+bridge_pb2, bridge_pb2_grpc = grpc.protos_and_services("neurorift/bridge/v1/bridge.proto")
+
+logger = logging.getLogger(__name__)
+
+SERVICE = "neurorift.bridge.v1.Bridge"
+
+# Bumped on incompatible contract changes; must match the core
+PROTOCOL_VERSION = 1
+
+GRPC_PORT = 8767
+
+
+class BridgeService(bridge_pb2_grpc.BridgeServicer):
+    """Bridge RPCs; every call is authenticated and every reply signed"""
+
+    def __init__(self, secret: bytes):
+        self.secret = secret
+
+    async def authenticate(self, method: str, request, context) -> bytes:
+        """Abort with UNAUTHENTICATED unless the core signed this call"""
+        metadata = dict(context.invocation_metadata())
+        signature = metadata.get(SIGNATURE_HEADER, "")
+        error = check_request_signature(
+            self.secret, "POST", f"/{SERVICE}/{method}",
+            metadata.get(TIMESTAMP_HEADER, ""), signature, request.SerializeToString(),
+        )
+        if error:
+            logger.warning(f"Rejected {method}: {error}")
+            await context.abort(grpc.StatusCode.UNAUTHENTICATED, error)
+        return signature.encode()
+
+    def sign_reply(self, context, request_signature: bytes, *messages) -> None:
+        """Sign everything sent for this call in the trailing metadata"""
+        body = b"".join(m.SerializeToString() for m in messages)
+        signature = sign_parts(self.secret, request_signature, b"\n", body)
+        context.set_trailing_metadata(((SIGNATURE_HEADER, signature),))
+
+    async def Health(self, request, context):
+        request_signature = await self.authenticate("Health", request, context)
+        reply = bridge_pb2.HealthResponse(status="healthy", protocol_version=PROTOCOL_VERSION)
+        self.sign_reply(context, request_signature, reply)
+        return reply
+
+    async def ExecuteTool(self, request, context):
+        request_signature = await self.authenticate("ExecuteTool", request, context)
+        args = await parse_json(request.args_json, "args_json", context)
+        trace = trace_id(context)
+        logger.info(f"[trace={trace}] Executing {request.tool} against {request.target}")
+
+        result = await run_handler(handle_tool_execute, {
+            "tool": request.tool,
+            "target": request.target,
+            "args": args,
+            "proxy": request.proxy if request.HasField("proxy") else None,
+        }, context, trace)
+        event = bridge_pb2.ToolEvent(result=result)
+        self.sign_reply(context, request_signature, event)
+        yield event
+
+    async def Generate(self, request, context):
+        request_signature = await self.authenticate("Generate", request, context)
+        model = request.model if request.HasField("model") else None
+        result = await call_handler(handle_ai_generate, {"prompt": request.prompt, "model": model},
+                                    context, trace_id(context))
+        chunk = bridge_pb2.GenerateChunk(text=result.get("response") or "", model=result.get("model") or "")
+        self.sign_reply(context, request_signature, chunk)
+        yield chunk
+
+    async def BrowserAction(self, request, context):
+        request_signature = await self.authenticate("BrowserAction", request, context)
+        params = await parse_json(request.params_json, "params_json", context)
+        reply = await run_handler(handle_browser_action, {"action": request.action, "params": params},
+                                  context, trace_id(context))
+        self.sign_reply(context, request_signature, reply)
+        return reply
+
+    async def RobinSearch(self, request, context):
+        request_signature = await self.authenticate("RobinSearch", request, context)
+        proxy = request.proxy if request.HasField("proxy") else None
+        reply = await run_handler(handle_robin_search, {"query": request.query, "proxy": proxy},
+                                  context, trace_id(context))
+        self.sign_reply(context, request_signature, reply)
+        return reply
+
+
+def trace_id(context) -> Optional[str]:
+    """Trace ID from the core's traceparent metadata"""
+    traceparent = dict(context.invocation_metadata()).get("traceparent", "")
+    parts = traceparent.split("-")
+    return parts[1] if len(parts) == 4 else None
+
+
+async def parse_json(value: str, field: str, context) -> Dict[str, Any]:
+    """Decode a JSON object field, aborting with INVALID_ARGUMENT if malformed"""
+    if not value:
+        return {}
+    try:
+        parsed = json.loads(value)
+    except json.JSONDecodeError as e:
+        await context.abort(grpc.StatusCode.INVALID_ARGUMENT, f"{field} is not valid JSON: {e}")
+    if not isinstance(parsed, dict):
+        await context.abort(grpc.StatusCode.INVALID_ARGUMENT, f"{field} must be a JSON object")
+    return parsed
+
+
+async def call_handler(handler, command: Dict[str, Any], context, trace: Optional[str]) -> Dict[str, Any]:
+    """Run a bridge handler, turning its exceptions into gRPC statuses"""
+    try:
+        return await handler(command)
+    except asyncio.CancelledError:
+        raise
+    except (ValueError, KeyError) as e:
+        await context.abort(grpc.StatusCode.INVALID_ARGUMENT, str(e))
+    except Exception as e:
+        logger.error(f"[trace={trace}] {handler.__name__} failed: {e}", exc_info=True)
+        await context.abort(grpc.StatusCode.INTERNAL, str(e))
+
+
+async def run_handler(handler, command: Dict[str, Any], context, trace: Optional[str]):
+    """Run a bridge handler into a CommandResult"""
+    data = await call_handler(handler, command, context, trace)
+    return bridge_pb2.CommandResult(success=True, data_json=json.dumps(data, default=str))
+
+
+async def serve(port: int = GRPC_PORT) -> None:
+    """Serve the bridge on localhost, or the configured Unix socket, until cancelled"""
+    server = grpc.aio.server()
+    bridge_pb2_grpc.add_BridgeServicer_to_server(BridgeService(load_bridge_secret()), server)
+
+    socket_path = os.environ.get(BRIDGE_SOCKET_ENV)
+    if socket_path:
+        if os.path.exists(socket_path):
+            os.unlink(socket_path)
+        address = f"unix:{socket_path}"
+        # Keep the socket private to this user
+        old_umask = os.umask(0o177)
+        try:
+            server.add_insecure_port(address)
+            await server.start()
+        finally:
+            os.umask(old_umask)
+    else:
+        address = f"127.0.0.1:{port}"
+        server.add_insecure_port(address)
+        await server.start()
+
+    logger.info(f"📡 gRPC bridge listening on {address}")
+    await server.wait_for_termination()
+
+
+if __name__ == "__main__":
+    logging.basicConfig(level=logging.INFO)
+    asyncio.run(serve())
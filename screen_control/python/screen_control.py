@@ -4,6 +4,10 @@ import os
 import platform
 import json
 
+class ScreenControlError(Exception):
+    """Raised when a native screen control call reports a failure."""
+
+
 class ScreenControl:
     """A multi-language, modular screen control system."""
     
@@ -26,11 +30,41 @@ class ScreenControl:
                 print("C++ library not found, using fallback")
 
             # Rust Library
-            rust_lib_path = os.path.join(self.base_path, 'rust/target/release/librust_control.so')
+            rust_lib_path = os.path.join(self.base_path, 'rust/target/release/libscreen_rust.so')
             if os.path.exists(rust_lib_path):
                 self.rust_lib = ctypes.CDLL(rust_lib_path)
-                self.rust_lib.type_text.argtypes = [ctypes.c_char_p]
-                self.rust_lib.scroll.argtypes = [ctypes.c_int]
+                self.rust_lib.type_text_rust.argtypes = [ctypes.c_char_p]
+                self.rust_lib.scroll_rust.argtypes = [ctypes.c_int]
+                self.rust_lib.move_mouse_rust.argtypes = [ctypes.c_int, ctypes.c_int]
+                self.rust_lib.click_rust.argtypes = [ctypes.c_int]
+                self.rust_lib.double_click_rust.argtypes = [ctypes.c_int]
+                self.rust_lib.drag_rust.argtypes = [ctypes.c_int, ctypes.c_int, ctypes.c_int, ctypes.c_int]
+                self.rust_lib.send_key_combo_rust.argtypes = [ctypes.c_char_p]
+                self.rust_lib.capture_screen_rust.argtypes = [ctypes.c_char_p]
+                self.rust_lib.capture_region_rust.argtypes = [ctypes.c_char_p, ctypes.c_int, ctypes.c_int, ctypes.c_uint, ctypes.c_uint]
+                self.rust_lib.monitor_geometry_rust.argtypes = [ctypes.c_int] + [ctypes.POINTER(ctypes.c_int)] * 2 + \
+                    [ctypes.POINTER(ctypes.c_uint)] * 2 + [ctypes.POINTER(ctypes.c_int)]
+                self.rust_lib.move_mouse_on_monitor_rust.argtypes = [ctypes.c_int, ctypes.c_int, ctypes.c_int]
+                self.rust_lib.set_clipboard_rust.argtypes = [ctypes.c_char_p]
+                self.rust_lib.get_clipboard_rust.argtypes = [ctypes.POINTER(ctypes.c_char_p)]
+                self.rust_lib.paste_text_rust.argtypes = [ctypes.c_char_p]
+                self.rust_lib.list_windows_rust.argtypes = [ctypes.POINTER(ctypes.c_char_p)]
+                self.rust_lib.focus_window_rust.argtypes = [ctypes.c_char_p]
+                self.rust_lib.type_text_in_window_rust.argtypes = [ctypes.c_char_p, ctypes.c_char_p]
+                self.rust_lib.type_text_paced_rust.argtypes = [ctypes.c_char_p, ctypes.c_uint, ctypes.c_uint, ctypes.c_char_p]
+                self.rust_lib.ocr_region_rust.argtypes = [ctypes.c_int, ctypes.c_int, ctypes.c_uint, ctypes.c_uint,
+                                                          ctypes.c_char_p, ctypes.POINTER(ctypes.c_char_p)]
+                self.rust_lib.stop_recording_rust.argtypes = [ctypes.c_char_p]
+                self.rust_lib.play_macro_rust.argtypes = [ctypes.c_char_p, ctypes.c_double]
+                for name in ('type_text_rust', 'scroll_rust', 'move_mouse_rust', 'click_rust', 'double_click_rust',
+                             'drag_rust', 'send_key_combo_rust', 'capture_screen_rust', 'capture_region_rust',
+                             'start_recording_rust', 'stop_recording_rust', 'play_macro_rust',
+                             'monitor_count_rust', 'monitor_geometry_rust', 'move_mouse_on_monitor_rust',
+                             'set_clipboard_rust', 'get_clipboard_rust', 'paste_text_rust',
+                             'list_windows_rust', 'focus_window_rust', 'type_text_in_window_rust',
+                             'type_text_paced_rust', 'ocr_region_rust'):
+                    getattr(self.rust_lib, name).restype = ctypes.c_int
+                self.rust_lib.last_error_message.restype = ctypes.c_char_p
                 self.rust_available = True
             else:
                 self.rust_available = False
@@ -53,34 +87,177 @@ class ScreenControl:
             self.rust_available = False
             self.asm_available = False
 
-    def move_mouse(self, x: int, y: int):
-        """Move mouse to coordinates using C++ library or fallback."""
-        if self.cpp_available:
+    def _rust(self, name: str, *args):
+        """Call a Rust library function, raising ScreenControlError if it fails."""
+        status = getattr(self.rust_lib, name)(*args)
+        if status != 0:
+            message = self.rust_lib.last_error_message()
+            raise ScreenControlError(f"{name} failed ({status}): {message.decode('utf-8') if message else 'unknown error'}")
+
+    def monitors(self) -> list:
+        """Geometry of each monitor, primary first, as dicts of x, y, width, height and primary."""
+        if not self.rust_available:
+            raise ScreenControlError("Monitor enumeration needs the Rust library")
+        count = self.rust_lib.monitor_count_rust()
+        if count < 0:
+            self._rust('monitor_count_rust')
+        monitors = []
+        for index in range(count):
+            x, y, primary = ctypes.c_int(), ctypes.c_int(), ctypes.c_int()
+            width, height = ctypes.c_uint(), ctypes.c_uint()
+            self._rust('monitor_geometry_rust', index, ctypes.byref(x), ctypes.byref(y),
+                       ctypes.byref(width), ctypes.byref(height), ctypes.byref(primary))
+            monitors.append({'index': index, 'x': x.value, 'y': y.value, 'width': width.value,
+                             'height': height.value, 'primary': bool(primary.value)})
+        return monitors
+
+    def move_mouse(self, x: int, y: int, monitor: int = None):
+        """Move mouse to coordinates using C++ or Rust library or fallback.
+
+        With a monitor index, x and y are relative to that monitor's top-left corner.
+        """
+        if monitor is not None:
+            if not self.rust_available:
+                raise ScreenControlError("Per-monitor coordinates need the Rust library")
+            self._rust('move_mouse_on_monitor_rust', monitor, x, y)
+        elif self.cpp_available:
             self.cpp_lib.move_mouse_cpp(x, y)
+        elif self.rust_available:
+            self._rust('move_mouse_rust', x, y)
         else:
             # Fallback using xdotool
             subprocess.run(['xdotool', 'mousemove', str(x), str(y)])
 
     def click(self, button: int = 1):
-        """Click mouse button using C++ library or fallback."""
+        """Click mouse button using C++ or Rust library or fallback."""
         if self.cpp_available:
             self.cpp_lib.click_cpp(button)
+        elif self.rust_available:
+            self._rust('click_rust', button)
         else:
             # Fallback using xdotool
             subprocess.run(['xdotool', 'click', str(button)])
 
-    def type_text(self, text: str):
-        """Type text using Rust library or fallback."""
+    def double_click(self, button: int = 1):
+        """Double-click mouse button using Rust library or fallback."""
         if self.rust_available:
-            self.rust_lib.type_text(text.encode('utf-8'))
+            self._rust('double_click_rust', button)
         else:
             # Fallback using xdotool
-            subprocess.run(['xdotool', 'type', text])
+            subprocess.run(['xdotool', 'click', '--repeat', '2', str(button)])
+
+    def drag(self, x1: int, y1: int, x2: int, y2: int):
+        """Drag with the left button from one point to another using Rust library or fallback."""
+        if self.rust_available:
+            self._rust('drag_rust', x1, y1, x2, y2)
+        else:
+            # Fallback using xdotool
+            subprocess.run(['xdotool', 'mousemove', str(x1), str(y1), 'mousedown', '1',
+                            'mousemove', str(x2), str(y2), 'mouseup', '1'])
+
+    def list_windows(self) -> list:
+        """Top-level windows as dicts with id, title, x, y, width and height."""
+        if self.rust_available:
+            text = ctypes.c_char_p()
+            self._rust('list_windows_rust', ctypes.byref(text))
+            return json.loads(text.value.decode('utf-8')) if text.value else []
+        # Fallback using wmctrl
+        result = subprocess.run(['wmctrl', '-lG'], capture_output=True, text=True)
+        windows = []
+        for line in result.stdout.splitlines():
+            fields = line.split(None, 7)
+            if len(fields) < 7:
+                continue
+            windows.append({'id': int(fields[0], 16), 'title': fields[7] if len(fields) > 7 else '',
+                            'x': int(fields[2]), 'y': int(fields[3]),
+                            'width': int(fields[4]), 'height': int(fields[5])})
+        return windows
+
+    def focus_window(self, title: str):
+        """Focus the first window whose title contains `title`, ignoring case."""
+        if self.rust_available:
+            self._rust('focus_window_rust', title.encode('utf-8'))
+        else:
+            # Fallback using xdotool
+            result = subprocess.run(['xdotool', 'search', '--limit', '1', '--name', f'(?i){title}',
+                                     'windowactivate', '--sync'])
+            if result.returncode != 0:
+                raise ScreenControlError(f"no window titled like '{title}'")
+
+    def type_text(self, text: str, window: str = None, delay_ms: int = 0, jitter_ms: int = 0):
+        """Type text using Rust library or fallback.
+
+        With `window`, the text is typed only once a window whose title
+        contains it is confirmed to have focus. A `delay_ms` or `jitter_ms`
+        types one character at a time, pausing `delay_ms` give or take up to
+        `jitter_ms` after each.
+        """
+        paced = delay_ms > 0 or jitter_ms > 0
+        if self.rust_available:
+            if paced:
+                self._rust('type_text_paced_rust', text.encode('utf-8'), delay_ms, jitter_ms,
+                           window.encode('utf-8') if window is not None else None)
+            elif window is not None:
+                self._rust('type_text_in_window_rust', window.encode('utf-8'), text.encode('utf-8'))
+            else:
+                self._rust('type_text_rust', text.encode('utf-8'))
+        else:
+            if window is not None:
+                self.focus_window(window)
+                active = subprocess.run(['xdotool', 'getactivewindow', 'getwindowname'],
+                                        capture_output=True, text=True).stdout.strip()
+                if window.lower() not in active.lower():
+                    raise ScreenControlError(f"focused window is '{active}', not '{window}'")
+            # Fallback using xdotool
+            if jitter_ms > 0:
+                import random
+                import time
+                for char in text:
+                    subprocess.run(['xdotool', 'type', char])
+                    time.sleep(max(0, delay_ms + random.randint(-jitter_ms, jitter_ms)) / 1000)
+            else:
+                subprocess.run(['xdotool', 'type', '--delay', str(delay_ms), text])
+
+    def set_clipboard(self, text: str):
+        """Put text on the clipboard using Rust library or fallback."""
+        if self.rust_available:
+            self._rust('set_clipboard_rust', text.encode('utf-8'))
+        else:
+            # Fallback using xclip
+            subprocess.run(['xclip', '-selection', 'clipboard'], input=text.encode('utf-8'))
+
+    def get_clipboard(self) -> str:
+        """Read text from the clipboard using Rust library or fallback."""
+        if self.rust_available:
+            text = ctypes.c_char_p()
+            self._rust('get_clipboard_rust', ctypes.byref(text))
+            return text.value.decode('utf-8') if text.value else ''
+        else:
+            # Fallback using xclip
+            result = subprocess.run(['xclip', '-selection', 'clipboard', '-o'], capture_output=True)
+            return result.stdout.decode('utf-8', errors='replace')
+
+    def paste_text(self, text: str):
+        """Paste text via the clipboard instead of typing it character by character."""
+        if self.rust_available:
+            self._rust('paste_text_rust', text.encode('utf-8'))
+        else:
+            self.set_clipboard(text)
+            self.send_key_combo('ctrl+v')
+
+    def send_key_combo(self, combo: str) -> bool:
+        """Press a key chord like 'ctrl+shift+t' using Rust library or fallback."""
+        if self.rust_available:
+            self._rust('send_key_combo_rust', combo.encode('utf-8'))
+            return True
+        else:
+            # Fallback using xdotool
+            return subprocess.run(['xdotool', 'key', combo]).returncode == 0
 
     def scroll(self, direction: int):
         """Scroll using Rust library or fallback."""
         if self.rust_available:
-            self.rust_lib.scroll(direction)
+            self._rust('scroll_rust', direction)
         else:
             # Fallback using xdotool
             if direction > 0:
@@ -88,6 +265,65 @@ class ScreenControl:
             else:
                 subprocess.run(['xdotool', 'key', 'Up'])
 
+    def capture_screen(self, path: str, region: tuple = None, monitor: int = None) -> bool:
+        """Save a PNG of the screen, or of an (x, y, width, height) region or one monitor, using Rust library or fallback."""
+        if monitor is not None:
+            geometry = self.monitors()[monitor]
+            region = (geometry['x'], geometry['y'], geometry['width'], geometry['height'])
+        if self.rust_available:
+            if region:
+                x, y, width, height = region
+                self._rust('capture_region_rust', path.encode('utf-8'), x, y, width, height)
+                return True
+            self._rust('capture_screen_rust', path.encode('utf-8'))
+            return True
+        else:
+            # Fallback using ImageMagick
+            command = ['import', '-window', 'root']
+            if region:
+                x, y, width, height = region
+                command += ['-crop', f'{width}x{height}+{x}+{y}']
+            result = subprocess.run(command + [path])
+            return result.returncode == 0
+
+    def read_text(self, region: tuple, lang: str = 'eng', monitor: int = None) -> str:
+        """OCR the text in an (x, y, width, height) region, or a whole monitor, using Rust library or fallback."""
+        if monitor is not None:
+            geometry = self.monitors()[monitor]
+            region = (geometry['x'], geometry['y'], geometry['width'], geometry['height'])
+        if self.rust_available:
+            x, y, width, height = region
+            text = ctypes.c_char_p()
+            self._rust('ocr_region_rust', x, y, width, height, lang.encode('utf-8'), ctypes.byref(text))
+            return text.value.decode('utf-8') if text.value else ''
+        # Fallback using ImageMagick and the tesseract command
+        import tempfile
+        with tempfile.NamedTemporaryFile(suffix='.png') as image:
+            if not self.capture_screen(image.name, region):
+                raise ScreenControlError("Screen capture failed")
+            result = subprocess.run(['tesseract', image.name, 'stdout', '-l', lang], capture_output=True, text=True)
+        if result.returncode != 0:
+            raise ScreenControlError(f"OCR failed: {result.stderr.strip()}")
+        return result.stdout.rstrip()
+
+    def start_recording(self):
+        """Start recording keyboard and mouse input (Rust library only)."""
+        if not self.rust_available:
+            raise ScreenControlError("Recording needs the Rust library")
+        self._rust('start_recording_rust')
+
+    def stop_recording(self, path: str):
+        """Stop recording and save the macro as JSON at path."""
+        if not self.rust_available:
+            raise ScreenControlError("Recording needs the Rust library")
+        self._rust('stop_recording_rust', path.encode('utf-8'))
+
+    def play_macro(self, path: str, speed: float = 1.0):
+        """Replay a JSON macro file with its recorded timing, scaled by speed."""
+        if not self.rust_available:
+            raise ScreenControlError("Macro replay needs the Rust library")
+        self._rust('play_macro_rust', path.encode('utf-8'), speed)
+
     def calculate_offset(self, base: int, offset: int) -> int:
         """Calculate offset using Assembly library or fallback."""
         if self.asm_available:
@@ -101,13 +337,42 @@ class ScreenControl:
         try:
             cmd_type = command.get('type')
             if cmd_type == 'move_mouse':
-                self.move_mouse(command['x'], command['y'])
+                self.move_mouse(command['x'], command['y'], command.get('monitor'))
             elif cmd_type == 'click':
                 self.click(command.get('button', 1))
+            elif cmd_type == 'double_click':
+                self.double_click(command.get('button', 1))
+            elif cmd_type == 'drag':
+                self.drag(command['x1'], command['y1'], command['x2'], command['y2'])
             elif cmd_type == 'type':
-                self.type_text(command['text'])
+                self.type_text(command['text'], command.get('window'),
+                               command.get('delay_ms', 0), command.get('jitter_ms', 0))
+            elif cmd_type == 'focus_window':
+                self.focus_window(command['title'])
+            elif cmd_type == 'list_windows':
+                return self.list_windows()
+            elif cmd_type == 'paste':
+                self.paste_text(command['text'])
+            elif cmd_type == 'set_clipboard':
+                self.set_clipboard(command['text'])
+            elif cmd_type == 'get_clipboard':
+                return self.get_clipboard()
+            elif cmd_type == 'key_combo':
+                self.send_key_combo(command['combo'])
             elif cmd_type == 'scroll':
                 self.scroll(command['direction'])
+            elif cmd_type == 'capture':
+                region = command.get('region')
+                if self.capture_screen(command['path'], tuple(region) if region else None, command.get('monitor')):
+                    # Same shape the core picks up from task results as screenshot artifacts
+                    return {'screenshots': [{'path': os.path.abspath(command['path'])}]}
+            elif cmd_type == 'read_text':
+                region = command.get('region')
+                return self.read_text(tuple(region) if region else None, command.get('lang', 'eng'), command.get('monitor'))
+            elif cmd_type == 'play_macro':
+                self.play_macro(command['path'], command.get('speed', 1.0))
+            elif cmd_type == 'get_screen_geometry':
+                return self.monitors()
             elif cmd_type == 'wait':
                 import time
                 time.sleep(command['seconds'])
@@ -0,0 +1,47 @@
+use enigo::{Enigo, Keyboard};
+use std::thread;
+use std::time::Duration;
+use crate::error::{Result, ScreenError};
+
+/// Longest pause allowed between characters
+const MAX_DELAY_MS: u64 = 5_000;
+
+/// Timing for typing one character at a time, like a person would
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Cadence {
+    /// Pause after each character
+    pub delay_ms: u64,
+    /// Most the pause varies either way, chosen at random per character
+    pub jitter_ms: u64,
+}
+
+impl Cadence {
+    pub fn new(delay_ms: u64, jitter_ms: u64) -> Result<Self> {
+        if delay_ms > MAX_DELAY_MS || jitter_ms > MAX_DELAY_MS {
+            return Err(ScreenError::InvalidArgument(format!(
+                "typing delay and jitter must be at most {} ms", MAX_DELAY_MS
+            )));
+        }
+        Ok(Self { delay_ms, jitter_ms })
+    }
+    
+    /// The next pause, never negative
+    fn pause(&self) -> Duration {
+        let jitter = self.jitter_ms as i64;
+        let offset = if jitter > 0 { fastrand::i64(-jitter..=jitter) } else { 0 };
+        Duration::from_millis((self.delay_ms as i64 + offset).max(0) as u64)
+    }
+}
+
+/// Types text one character at a time with the cadence's pauses between.
+pub(crate) fn type_text(enigo: &mut Enigo, text: &str, cadence: Cadence) -> Result<()> {
+    let mut buffer = [0; 4];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        enigo.text(c.encode_utf8(&mut buffer))?;
+        if chars.peek().is_some() {
+            thread::sleep(cadence.pause());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use std::path::Path;
+use crate::error::{Result, ScreenError};
+
+#[cfg(target_os = "linux")]
+mod x11;
+
+/// The screen is only captured through X11.
+#[cfg(not(target_os = "linux"))]
+mod x11 {
+    use std::error::Error;
+    use std::path::Path;
+    use super::Region;
+    
+    pub(super) fn grab(_path: &Path, _region: Option<Region>) -> std::result::Result<(), Box<dyn Error>> {
+        Err("screen capture needs X11".into())
+    }
+}
+
+/// A rectangle of the screen, in pixels from the top-left corner
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Saves the whole screen, or `region` of it, as a PNG at `path`.
+pub fn capture_screen(path: &Path, region: Option<Region>) -> Result<()> {
+    x11::grab(path, region).map_err(|e| ScreenError::Capture(e.to_string()))
+}
@@ -0,0 +1,54 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{ConnectionExt, ImageFormat, ImageOrder};
+use super::Region;
+
+pub(super) fn grab(path: &Path, region: Option<Region>) -> std::result::Result<(), Box<dyn Error>> {
+    let (conn, screen_num) = x11rb::connect(None)?;
+    let setup = conn.setup();
+    let screen = &setup.roots[screen_num];
+    
+    // Clip the requested region to the screen
+    let region = region.unwrap_or(Region { x: 0, y: 0, width: screen.width_in_pixels.into(), height: screen.height_in_pixels.into() });
+    let left = region.x.clamp(0, screen.width_in_pixels.into());
+    let top = region.y.clamp(0, screen.height_in_pixels.into());
+    let right = (i64::from(region.x) + i64::from(region.width)).clamp(0, screen.width_in_pixels.into()) as i32;
+    let bottom = (i64::from(region.y) + i64::from(region.height)).clamp(0, screen.height_in_pixels.into()) as i32;
+    if right <= left || bottom <= top {
+        return Err("region is outside the screen".into());
+    }
+    let (width, height) = ((right - left) as u16, (bottom - top) as u16);
+    
+    let image = conn.get_image(ImageFormat::Z_PIXMAP, screen.root, left as i16, top as i16, width, height, !0)?.reply()?;
+    let bits_per_pixel = setup.pixmap_formats.iter()
+        .find(|f| f.depth == image.depth)
+        .map(|f| f.bits_per_pixel)
+        .ok_or("no pixmap format for the screen depth")?;
+    if bits_per_pixel != 32 {
+        return Err(format!("unsupported {} bits per pixel", bits_per_pixel).into());
+    }
+    let visual = screen.allowed_depths.iter()
+        .flat_map(|d| &d.visuals)
+        .find(|v| v.visual_id == screen.root_visual)
+        .ok_or("root visual not found")?;
+    
+    let channel = |pixel: u32, mask: u32| ((pixel & mask) >> mask.trailing_zeros()) as u8;
+    let mut rgb = Vec::with_capacity(usize::from(width) * usize::from(height) * 3);
+    for chunk in image.data.chunks_exact(4) {
+        let bytes = [chunk[0], chunk[1], chunk[2], chunk[3]];
+        let pixel = match setup.image_byte_order {
+            ImageOrder::LSB_FIRST => u32::from_le_bytes(bytes),
+            _ => u32::from_be_bytes(bytes),
+        };
+        rgb.extend([channel(pixel, visual.red_mask), channel(pixel, visual.green_mask), channel(pixel, visual.blue_mask)]);
+    }
+    
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), width.into(), height.into());
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&rgb)?;
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use arboard::Clipboard;
+use std::sync::Mutex;
+use crate::error::{Result, ScreenError};
+
+/// X11 only serves the clipboard while its owner is alive, so the
+/// connection is kept for as long as the library is loaded
+static CLIPBOARD: Mutex<Option<Clipboard>> = Mutex::new(None);
+
+fn with_clipboard<T>(action: impl FnOnce(&mut Clipboard) -> std::result::Result<T, arboard::Error>) -> Result<T> {
+    let mut clipboard = CLIPBOARD.lock().unwrap_or_else(|e| e.into_inner());
+    if clipboard.is_none() {
+        *clipboard = Some(Clipboard::new().map_err(|e| ScreenError::NoDisplay(e.to_string()))?);
+    }
+    let clipboard = clipboard.as_mut().expect("clipboard was just opened");
+    action(clipboard).map_err(|e| ScreenError::Clipboard(e.to_string()))
+}
+
+/// Puts text on the clipboard.
+pub fn set_clipboard(text: &str) -> Result<()> {
+    with_clipboard(|clipboard| clipboard.set_text(text))
+}
+
+/// Reads text from the clipboard.
+pub fn get_clipboard() -> Result<String> {
+    with_clipboard(|clipboard| clipboard.get_text())
+}
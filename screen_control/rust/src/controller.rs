@@ -0,0 +1,166 @@
+use enigo::{Axis, Button, Coordinate, Direction, Enigo, Keyboard, Mouse, Settings};
+use std::path::Path;
+use crate::cadence::{self, Cadence};
+use crate::capture::{self, Region};
+use crate::clipboard;
+use crate::display::{self, Monitor};
+use crate::error::{Result, ScreenError};
+use crate::keys;
+use crate::macros::{self, Macro};
+use crate::ocr;
+use crate::window::{self, Window};
+
+/// A mouse button
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+impl MouseButton {
+    /// From an X11 button number: 1 left, 2 middle, 3 right
+    pub fn from_number(number: i32) -> Result<Self> {
+        match number {
+            1 => Ok(Self::Left),
+            2 => Ok(Self::Middle),
+            3 => Ok(Self::Right),
+            other => Err(ScreenError::InvalidArgument(format!("unknown mouse button {}", other))),
+        }
+    }
+}
+
+impl From<MouseButton> for Button {
+    fn from(button: MouseButton) -> Self {
+        match button {
+            MouseButton::Left => Button::Left,
+            MouseButton::Middle => Button::Middle,
+            MouseButton::Right => Button::Right,
+        }
+    }
+}
+
+/// A connection to the display for simulating input and capturing the screen
+pub struct ScreenController {
+    enigo: Enigo,
+    cadence: Option<Cadence>,
+}
+
+impl ScreenController {
+    /// Connects to the default display.
+    pub fn new() -> Result<Self> {
+        Ok(Self { enigo: Enigo::new(&Settings::default())?, cadence: None })
+    }
+    
+    /// Types character by character with pauses from now on, or all at
+    /// once again with `None`.
+    pub fn set_cadence(&mut self, cadence: Option<Cadence>) {
+        self.cadence = cadence;
+    }
+    
+    /// Types text using the native keyboard, paced by the cadence if set.
+    pub fn type_text(&mut self, text: &str) -> Result<()> {
+        match self.cadence {
+            Some(cadence) => cadence::type_text(&mut self.enigo, text, cadence),
+            None => Ok(self.enigo.text(text)?),
+        }
+    }
+    
+    /// Types text only if the focused window's title contains `window_title`,
+    /// focusing it first when it is not already in front.
+    pub fn type_text_in(&mut self, window_title: &str, text: &str) -> Result<()> {
+        if window::ensure_focus(window_title).is_err() {
+            window::focus_window(window_title)?;
+        }
+        window::ensure_focus(window_title)?;
+        self.type_text(text)
+    }
+    
+    /// Top-level windows with their titles and geometry.
+    pub fn windows(&self) -> Result<Vec<Window>> {
+        window::windows()
+    }
+    
+    /// Focuses the first window whose title contains `pattern`.
+    pub fn focus_window(&self, pattern: &str) -> Result<Window> {
+        window::focus_window(pattern)
+    }
+    
+    /// Pastes text through the clipboard with ctrl+v, which is much faster
+    /// than typing long payloads.
+    pub fn paste_text(&mut self, text: &str) -> Result<()> {
+        clipboard::set_clipboard(text)?;
+        self.send_key_combo("ctrl+v")
+    }
+    
+    /// Presses a key chord such as `ctrl+shift+t`.
+    pub fn send_key_combo(&mut self, combo: &str) -> Result<()> {
+        let combo = keys::parse_combo(combo)?;
+        Ok(keys::send_combo(&mut self.enigo, &combo)?)
+    }
+    
+    /// Scrolls the mouse wheel; positive is down, negative is up.
+    pub fn scroll(&mut self, amount: i32) -> Result<()> {
+        Ok(self.enigo.scroll(amount, Axis::Vertical)?)
+    }
+    
+    /// Moves the pointer to absolute screen coordinates.
+    pub fn move_mouse(&mut self, x: i32, y: i32) -> Result<()> {
+        Ok(self.enigo.move_mouse(x, y, Coordinate::Abs)?)
+    }
+    
+    /// Moves the pointer to a point relative to monitor `index`'s top-left corner.
+    pub fn move_mouse_on(&mut self, index: usize, x: i32, y: i32) -> Result<()> {
+        let (x, y) = display::monitor(index)?.to_desktop(x, y)?;
+        self.move_mouse(x, y)
+    }
+    
+    /// Monitors on the display, primary first.
+    pub fn monitors(&self) -> Result<Vec<Monitor>> {
+        display::monitors()
+    }
+    
+    /// Clicks a button where the pointer is.
+    pub fn click(&mut self, button: MouseButton) -> Result<()> {
+        Ok(self.enigo.button(button.into(), Direction::Click)?)
+    }
+    
+    /// Double-clicks a button where the pointer is.
+    pub fn double_click(&mut self, button: MouseButton) -> Result<()> {
+        self.click(button)?;
+        self.click(button)
+    }
+    
+    /// Drags with the left button held from one point to another. The
+    /// button is released even if a move fails.
+    pub fn drag(&mut self, from: (i32, i32), to: (i32, i32)) -> Result<()> {
+        let result = self.move_mouse(from.0, from.1)
+            .and_then(|_| Ok(self.enigo.button(Button::Left, Direction::Press)?))
+            .and_then(|_| self.move_mouse(to.0, to.1));
+        let release = self.enigo.button(Button::Left, Direction::Release);
+        result?;
+        Ok(release?)
+    }
+    
+    /// Replays a macro; `speed` 2.0 runs it twice as fast.
+    pub fn play_macro(&mut self, recorded: &Macro, speed: f64) -> Result<()> {
+        macros::play(&mut self.enigo, recorded, speed)
+    }
+    
+    /// Saves a PNG of the whole screen, or of `region`, at `path`.
+    pub fn capture(&self, path: &Path, region: Option<Region>) -> Result<()> {
+        capture::capture_screen(path, region)
+    }
+    
+    /// Saves a PNG of one monitor.
+    pub fn capture_monitor(&self, path: &Path, index: usize) -> Result<()> {
+        let monitor = display::monitor(index)?;
+        capture::capture_screen(path, Some(Region { x: monitor.x, y: monitor.y, width: monitor.width, height: monitor.height }))
+    }
+    
+    /// Recognizes the text shown in `region`; `language` is a tesseract
+    /// language code such as `eng`.
+    pub fn read_text(&self, region: Region, language: &str) -> Result<String> {
+        ocr::read_region(region, language)
+    }
+}
@@ -0,0 +1,66 @@
+use serde::Serialize;
+use crate::error::{Result, ScreenError};
+
+#[cfg(target_os = "linux")]
+mod x11;
+
+/// Monitors are only listed through X11.
+#[cfg(not(target_os = "linux"))]
+mod x11 {
+    use crate::error::{Result, ScreenError};
+    use super::Monitor;
+    
+    pub(super) fn monitors() -> Result<Vec<Monitor>> {
+        Err(ScreenError::NoDisplay("listing monitors needs X11".to_string()))
+    }
+}
+
+/// One monitor's place on the combined desktop
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Monitor {
+    /// Output name such as `HDMI-1`, or `screen` when RandR is unavailable
+    pub name: String,
+    pub primary: bool,
+    /// Top-left corner in desktop coordinates
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Monitor {
+    /// Desktop coordinates of a point given relative to this monitor's
+    /// top-left corner. Points off the monitor are rejected so a click
+    /// never lands on a neighbouring screen.
+    pub fn to_desktop(&self, x: i32, y: i32) -> Result<(i32, i32)> {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return Err(ScreenError::InvalidArgument(format!(
+                "({}, {}) is outside monitor {} ({}x{})", x, y, self.name, self.width, self.height,
+            )));
+        }
+        Ok((self.x + x, self.y + y))
+    }
+    
+    /// Whether a desktop point is on this monitor
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && y >= self.y
+            && i64::from(x) < i64::from(self.x) + i64::from(self.width)
+            && i64::from(y) < i64::from(self.y) + i64::from(self.height)
+    }
+}
+
+/// Monitors on the default display, primary first then left to right.
+/// Without RandR 1.5 the whole screen is reported as one monitor.
+pub fn monitors() -> Result<Vec<Monitor>> {
+    x11::monitors()
+}
+
+/// The monitor at `index` in [`monitors`] order.
+pub fn monitor(index: usize) -> Result<Monitor> {
+    let mut monitors = monitors()?;
+    let count = monitors.len();
+    if index >= count {
+        return Err(ScreenError::InvalidArgument(format!("no monitor {} (there are {})", index, count)));
+    }
+    Ok(monitors.swap_remove(index))
+}
@@ -0,0 +1,45 @@
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::randr::{self, ConnectionExt as _};
+use x11rb::protocol::xproto::ConnectionExt as _;
+use crate::error::{Result, ScreenError};
+use super::Monitor;
+
+pub(super) fn monitors() -> Result<Vec<Monitor>> {
+    let (conn, screen_num) = x11rb::connect(None).map_err(|e| ScreenError::NoDisplay(e.to_string()))?;
+    let screen = &conn.setup().roots[screen_num];
+    let whole = Monitor {
+        name: "screen".to_string(),
+        primary: true,
+        x: 0,
+        y: 0,
+        width: screen.width_in_pixels.into(),
+        height: screen.height_in_pixels.into(),
+    };
+    
+    let has_randr = conn.extension_information(randr::X11_EXTENSION_NAME)
+        .map_err(|e| ScreenError::NoDisplay(e.to_string()))?
+        .is_some();
+    let reply = match has_randr {
+        true => conn.randr_get_monitors(screen.root, true).ok().and_then(|cookie| cookie.reply().ok()),
+        false => None,
+    };
+    let Some(reply) = reply.filter(|r| !r.monitors.is_empty()) else {
+        return Ok(vec![whole]);
+    };
+    
+    let mut monitors: Vec<Monitor> = reply.monitors.iter()
+        .map(|info| Monitor {
+            name: conn.get_atom_name(info.name).ok()
+                .and_then(|cookie| cookie.reply().ok())
+                .map(|reply| String::from_utf8_lossy(&reply.name).into_owned())
+                .unwrap_or_default(),
+            primary: info.primary,
+            x: info.x.into(),
+            y: info.y.into(),
+            width: info.width.into(),
+            height: info.height.into(),
+        })
+        .collect();
+    monitors.sort_by_key(|m| (!m.primary, m.x, m.y));
+    Ok(monitors)
+}
@@ -0,0 +1,53 @@
+use std::fmt;
+
+/// Why a screen control action failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScreenError {
+    /// An argument was missing, malformed or out of range
+    InvalidArgument(String),
+    /// No display could be opened
+    NoDisplay(String),
+    /// The display was reached but simulating input failed
+    Input(String),
+    /// The screen could not be read or the image not written
+    Capture(String),
+    /// Input could not be recorded, or a macro not read or written
+    Macro(String),
+    /// The clipboard could not be read or written
+    Clipboard(String),
+    /// A window could not be found, listed or focused
+    Window(String),
+    /// Text could not be recognized
+    Ocr(String),
+}
+
+impl fmt::Display for ScreenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidArgument(message) => write!(f, "invalid argument: {}", message),
+            Self::NoDisplay(message) => write!(f, "no display: {}", message),
+            Self::Input(message) => write!(f, "input failed: {}", message),
+            Self::Capture(message) => write!(f, "capture failed: {}", message),
+            Self::Macro(message) => write!(f, "macro failed: {}", message),
+            Self::Clipboard(message) => write!(f, "clipboard failed: {}", message),
+            Self::Window(message) => write!(f, "window control failed: {}", message),
+            Self::Ocr(message) => write!(f, "OCR failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ScreenError {}
+
+impl From<enigo::NewConError> for ScreenError {
+    fn from(error: enigo::NewConError) -> Self {
+        Self::NoDisplay(error.to_string())
+    }
+}
+
+impl From<enigo::InputError> for ScreenError {
+    fn from(error: enigo::InputError) -> Self {
+        Self::Input(error.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, ScreenError>;
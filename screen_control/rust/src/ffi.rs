@@ -0,0 +1,306 @@
+//! C interface over [`ScreenController`]. Every function returns a status
+//! code; on failure `last_error_message` says why.
+
+use std::cell::RefCell;
+use std::sync::Mutex;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::path::Path;
+use std::ptr;
+use crate::cadence::Cadence;
+use crate::capture::Region;
+use crate::clipboard;
+use crate::controller::{MouseButton, ScreenController};
+use crate::display;
+use crate::error::{Result, ScreenError};
+use crate::macros::Macro;
+use crate::ocr;
+use crate::record::Recorder;
+use crate::window;
+
+/// The call succeeded.
+pub const STATUS_OK: i32 = 0;
+/// A pointer argument was null or not valid UTF-8, or a value made no sense.
+pub const STATUS_INVALID_ARGUMENT: i32 = -1;
+/// No display could be opened.
+pub const STATUS_NO_DISPLAY: i32 = -2;
+/// The display was reached but the action failed.
+pub const STATUS_FAILED: i32 = -3;
+
+/// Recording in progress between `start_recording_rust` and `stop_recording_rust`
+static RECORDING: Mutex<Option<Recorder>> = Mutex::new(None);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+    /// Text handed out by the last call returning a string on this thread
+    static TEXT_OUT: RefCell<CString> = RefCell::new(CString::default());
+}
+
+impl ScreenError {
+    /// Status code reported over FFI
+    pub fn status(&self) -> i32 {
+        match self {
+            Self::InvalidArgument(_) => STATUS_INVALID_ARGUMENT,
+            Self::NoDisplay(_) => STATUS_NO_DISPLAY,
+            Self::Input(_) | Self::Capture(_) | Self::Macro(_) | Self::Clipboard(_) | Self::Window(_)
+            | Self::Ocr(_) => STATUS_FAILED,
+        }
+    }
+}
+
+/// Records the outcome of a call on this thread and returns its status.
+fn status(result: Result<()>) -> i32 {
+    let (code, message) = match result {
+        Ok(()) => (STATUS_OK, None),
+        Err(e) => (e.status(), CString::new(e.to_string().replace('\0', " ")).ok()),
+    };
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+    code
+}
+
+/// Message describing the last failed call on this thread, or null if the
+/// last call succeeded. Valid until the next call on the same thread.
+#[no_mangle]
+pub extern "C" fn last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |m| m.as_ptr()))
+}
+
+/// Keeps a string for the caller to read; valid until the next call
+/// returning a string on this thread.
+fn hand_out(value: String) -> *const c_char {
+    let value = CString::new(value.replace('\0', " ")).unwrap_or_default();
+    TEXT_OUT.with(|slot| {
+        *slot.borrow_mut() = value;
+        slot.borrow().as_ptr()
+    })
+}
+
+/// Borrows a C string as UTF-8.
+fn c_str<'a>(text: *const c_char, name: &str) -> Result<&'a str> {
+    if text.is_null() {
+        return Err(ScreenError::InvalidArgument(format!("{} is null", name)));
+    }
+    unsafe { CStr::from_ptr(text) }.to_str()
+        .map_err(|_| ScreenError::InvalidArgument(format!("{} is not valid UTF-8", name)))
+}
+
+/// Types text using the native keyboard.
+#[no_mangle]
+pub extern "C" fn type_text_rust(text: *const c_char) -> i32 {
+    status(c_str(text, "text").and_then(|text| ScreenController::new()?.type_text(text)))
+}
+
+/// Types text one character at a time, pausing `delay_ms` give or take up
+/// to `jitter_ms` after each. A non-null `window` is a title pattern the
+/// focused window must match, as in `type_text_in_window_rust`.
+#[no_mangle]
+pub extern "C" fn type_text_paced_rust(text: *const c_char, delay_ms: u32, jitter_ms: u32, window: *const c_char) -> i32 {
+    status(c_str(text, "text").and_then(|text| {
+        let cadence = Cadence::new(delay_ms.into(), jitter_ms.into())?;
+        let window = if window.is_null() { None } else { Some(c_str(window, "window")?) };
+        let mut controller = ScreenController::new()?;
+        controller.set_cadence(Some(cadence));
+        match window {
+            Some(window) => controller.type_text_in(window, text),
+            None => controller.type_text(text),
+        }
+    }))
+}
+
+/// Presses a key chord such as `ctrl+shift+t`.
+#[no_mangle]
+pub extern "C" fn send_key_combo_rust(combo: *const c_char) -> i32 {
+    status(c_str(combo, "combo").and_then(|combo| {
+        // Parse first so a bad chord is reported even without a display
+        crate::keys::parse_combo(combo)?;
+        ScreenController::new()?.send_key_combo(combo)
+    }))
+}
+
+/// Scrolls the mouse wheel up or down.
+#[no_mangle]
+pub extern "C" fn scroll_rust(direction: i32) -> i32 {
+    // Positive for down, negative for up
+    status(ScreenController::new().and_then(|mut screen| screen.scroll(direction)))
+}
+
+/// Moves the mouse pointer to absolute screen coordinates.
+#[no_mangle]
+pub extern "C" fn move_mouse_rust(x: i32, y: i32) -> i32 {
+    status(ScreenController::new().and_then(|mut screen| screen.move_mouse(x, y)))
+}
+
+/// Clicks a mouse button (1 left, 2 middle, 3 right) where the pointer is.
+#[no_mangle]
+pub extern "C" fn click_rust(button: i32) -> i32 {
+    status(MouseButton::from_number(button).and_then(|button| ScreenController::new()?.click(button)))
+}
+
+/// Double-clicks a mouse button where the pointer is.
+#[no_mangle]
+pub extern "C" fn double_click_rust(button: i32) -> i32 {
+    status(MouseButton::from_number(button).and_then(|button| ScreenController::new()?.double_click(button)))
+}
+
+/// Drags with the left button held from one point to another.
+#[no_mangle]
+pub extern "C" fn drag_rust(x1: i32, y1: i32, x2: i32, y2: i32) -> i32 {
+    status(ScreenController::new().and_then(|mut screen| screen.drag((x1, y1), (x2, y2))))
+}
+
+/// Saves a PNG of the whole screen.
+#[no_mangle]
+pub extern "C" fn capture_screen_rust(path: *const c_char) -> i32 {
+    status(c_str(path, "path").and_then(|path| crate::capture::capture_screen(Path::new(path), None)))
+}
+
+/// Saves a PNG of a screen region.
+#[no_mangle]
+pub extern "C" fn capture_region_rust(path: *const c_char, x: i32, y: i32, width: u32, height: u32) -> i32 {
+    let region = Region { x, y, width, height };
+    status(c_str(path, "path").and_then(|path| crate::capture::capture_screen(Path::new(path), Some(region))))
+}
+
+/// Starts recording keyboard and mouse input.
+#[no_mangle]
+pub extern "C" fn start_recording_rust() -> i32 {
+    let mut recording = RECORDING.lock().unwrap_or_else(|e| e.into_inner());
+    if recording.is_some() {
+        return status(Err(ScreenError::Macro("already recording".to_string())));
+    }
+    status(Recorder::start().map(|recorder| *recording = Some(recorder)))
+}
+
+/// Stops recording and saves the macro as JSON at `path`.
+#[no_mangle]
+pub extern "C" fn stop_recording_rust(path: *const c_char) -> i32 {
+    let recorder = RECORDING.lock().unwrap_or_else(|e| e.into_inner()).take();
+    status(c_str(path, "path").and_then(|path| {
+        let recorder = recorder.ok_or_else(|| ScreenError::Macro("not recording".to_string()))?;
+        recorder.stop()?.save(Path::new(path))
+    }))
+}
+
+/// Replays a JSON macro file; `speed` 1.0 keeps the recorded timing.
+#[no_mangle]
+pub extern "C" fn play_macro_rust(path: *const c_char, speed: f64) -> i32 {
+    status(c_str(path, "path").and_then(|path| {
+        crate::macros::check_speed(speed)?;
+        let recorded = Macro::load(Path::new(path))?;
+        ScreenController::new()?.play_macro(&recorded, speed)
+    }))
+}
+
+/// Number of monitors, or a negative status on failure.
+#[no_mangle]
+pub extern "C" fn monitor_count_rust() -> i32 {
+    let result = display::monitors();
+    let count = result.as_ref().map_or(0, |m| m.len() as i32);
+    match status(result.map(|_| ())) {
+        STATUS_OK => count,
+        failed => failed,
+    }
+}
+
+/// Writes monitor `index`'s desktop position and size into the out-pointers
+/// and whether it is the primary monitor (0 or 1) into `primary`.
+///
+/// # Safety
+/// Each pointer must be null or valid for a write of its type.
+#[no_mangle]
+pub unsafe extern "C" fn monitor_geometry_rust(index: i32, x: *mut i32, y: *mut i32, width: *mut u32, height: *mut u32, primary: *mut i32) -> i32 {
+    if [x.is_null(), y.is_null(), width.is_null(), height.is_null(), primary.is_null()].contains(&true) {
+        return status(Err(ScreenError::InvalidArgument("output pointer is null".to_string())));
+    }
+    let index = usize::try_from(index).map_err(|_| ScreenError::InvalidArgument(format!("no monitor {}", index)));
+    status(index.and_then(display::monitor).map(|monitor| {
+        *x = monitor.x;
+        *y = monitor.y;
+        *width = monitor.width;
+        *height = monitor.height;
+        *primary = monitor.primary.into();
+    }))
+}
+
+/// Moves the pointer to a point relative to monitor `index`'s top-left corner.
+#[no_mangle]
+pub extern "C" fn move_mouse_on_monitor_rust(index: i32, x: i32, y: i32) -> i32 {
+    let index = usize::try_from(index).map_err(|_| ScreenError::InvalidArgument(format!("no monitor {}", index)));
+    status(index.and_then(|index| {
+        let (x, y) = display::monitor(index)?.to_desktop(x, y)?;
+        ScreenController::new()?.move_mouse(x, y)
+    }))
+}
+
+/// Puts text on the clipboard.
+#[no_mangle]
+pub extern "C" fn set_clipboard_rust(text: *const c_char) -> i32 {
+    status(c_str(text, "text").and_then(clipboard::set_clipboard))
+}
+
+/// Reads the clipboard text into `*text`, which stays valid until the next
+/// call returning a string on the same thread.
+///
+/// # Safety
+/// `text` must be null or valid for a pointer write.
+#[no_mangle]
+pub unsafe extern "C" fn get_clipboard_rust(text: *mut *const c_char) -> i32 {
+    if text.is_null() {
+        return status(Err(ScreenError::InvalidArgument("output pointer is null".to_string())));
+    }
+    status(clipboard::get_clipboard().map(|value| *text = hand_out(value)))
+}
+
+/// Pastes text through the clipboard with ctrl+v.
+#[no_mangle]
+pub extern "C" fn paste_text_rust(text: *const c_char) -> i32 {
+    status(c_str(text, "text").and_then(|text| ScreenController::new()?.paste_text(text)))
+}
+
+/// Writes a JSON array of top-level windows (`id`, `title`, `x`, `y`,
+/// `width`, `height`) into `*json`, valid until the next call returning a
+/// string on the same thread.
+///
+/// # Safety
+/// `json` must be null or valid for a pointer write.
+#[no_mangle]
+pub unsafe extern "C" fn list_windows_rust(json: *mut *const c_char) -> i32 {
+    if json.is_null() {
+        return status(Err(ScreenError::InvalidArgument("output pointer is null".to_string())));
+    }
+    status(window::windows().map(|windows| {
+        *json = hand_out(serde_json::to_string(&windows).unwrap_or_default());
+    }))
+}
+
+/// Focuses the first window whose title contains `pattern`, ignoring case.
+#[no_mangle]
+pub extern "C" fn focus_window_rust(pattern: *const c_char) -> i32 {
+    status(c_str(pattern, "pattern").and_then(|pattern| window::focus_window(pattern).map(|_| ())))
+}
+
+/// Types text only into a window whose title contains `pattern`, focusing
+/// it first; fails without typing if focus cannot be confirmed.
+#[no_mangle]
+pub extern "C" fn type_text_in_window_rust(pattern: *const c_char, text: *const c_char) -> i32 {
+    status(c_str(pattern, "pattern").and_then(|pattern| {
+        let text = c_str(text, "text")?;
+        ScreenController::new()?.type_text_in(pattern, text)
+    }))
+}
+
+/// Recognizes the text in a screen region into `*text`, valid until the
+/// next call returning a string on the same thread. A null `language`
+/// means English.
+///
+/// # Safety
+/// `text` must be null or valid for a pointer write.
+#[no_mangle]
+pub unsafe extern "C" fn ocr_region_rust(x: i32, y: i32, width: u32, height: u32, language: *const c_char, text: *mut *const c_char) -> i32 {
+    if text.is_null() {
+        return status(Err(ScreenError::InvalidArgument("output pointer is null".to_string())));
+    }
+    let language = if language.is_null() { Ok(ocr::DEFAULT_LANGUAGE) } else { c_str(language, "language") };
+    status(language.and_then(|language| ocr::read_region(Region { x, y, width, height }, language))
+        .map(|value| *text = hand_out(value)))
+}
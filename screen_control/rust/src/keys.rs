@@ -0,0 +1,98 @@
+use enigo::{Direction, Enigo, InputResult, Key, Keyboard};
+use crate::error::{Result, ScreenError};
+
+/// A parsed chord such as `ctrl+shift+t`: modifiers held while `key` is pressed
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyCombo {
+    pub modifiers: Vec<Key>,
+    pub key: Key,
+}
+
+/// Parses a `+`-separated chord. Modifiers come first, then exactly one key:
+/// a named key (`enter`, `f5`, `pageup`, ...) or a single character.
+pub fn parse_combo(combo: &str) -> Result<KeyCombo> {
+    let parts: Vec<String> = combo.split('+').map(|p| p.trim().to_lowercase()).collect();
+    let Some((last, modifiers)) = parts.split_last() else {
+        return Err(ScreenError::InvalidArgument("empty key combination".to_string()));
+    };
+    let modifiers = modifiers.iter()
+        .map(|m| modifier(m).ok_or_else(|| ScreenError::InvalidArgument(format!("unknown modifier '{}'", m))))
+        .collect::<Result<Vec<_>>>()?;
+    let key = modifier(last)
+        .or_else(|| named_key(last))
+        .or_else(|| single_char(last))
+        .ok_or_else(|| ScreenError::InvalidArgument(format!("unknown key '{}'", last)))?;
+    Ok(KeyCombo { modifiers, key })
+}
+
+fn modifier(name: &str) -> Option<Key> {
+    match name {
+        "ctrl" | "control" => Some(Key::Control),
+        "shift" => Some(Key::Shift),
+        "alt" => Some(Key::Alt),
+        "meta" | "super" | "win" | "cmd" => Some(Key::Meta),
+        _ => None,
+    }
+}
+
+fn named_key(name: &str) -> Option<Key> {
+    let key = match name {
+        "enter" | "return" => Key::Return,
+        "tab" => Key::Tab,
+        "esc" | "escape" => Key::Escape,
+        "space" => Key::Space,
+        "backspace" => Key::Backspace,
+        "delete" | "del" => Key::Delete,
+        "insert" | "ins" => Key::Insert,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" | "pgup" => Key::PageUp,
+        "pagedown" | "pgdn" => Key::PageDown,
+        "up" => Key::UpArrow,
+        "down" => Key::DownArrow,
+        "left" => Key::LeftArrow,
+        "right" => Key::RightArrow,
+        // `+` itself cannot be written inside a chord, so it has a name
+        "plus" => Key::Unicode('+'),
+        _ => return function_key(name),
+    };
+    Some(key)
+}
+
+fn function_key(name: &str) -> Option<Key> {
+    let keys = [
+        Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6,
+        Key::F7, Key::F8, Key::F9, Key::F10, Key::F11, Key::F12,
+    ];
+    let number: usize = name.strip_prefix('f')?.parse().ok()?;
+    keys.get(number.checked_sub(1)?).copied()
+}
+
+fn single_char(name: &str) -> Option<Key> {
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(Key::Unicode(c)),
+        _ => None,
+    }
+}
+
+/// Presses the modifiers, clicks the key, then releases the modifiers in
+/// reverse order. Modifiers are released even if a press fails.
+pub(crate) fn send_combo(enigo: &mut Enigo, combo: &KeyCombo) -> InputResult<()> {
+    let mut pressed = Vec::new();
+    let mut result = Ok(());
+    for modifier in &combo.modifiers {
+        result = enigo.key(*modifier, Direction::Press);
+        if result.is_err() {
+            break;
+        }
+        pressed.push(*modifier);
+    }
+    if result.is_ok() {
+        result = enigo.key(combo.key, Direction::Click);
+    }
+    for modifier in pressed.iter().rev() {
+        let _ = enigo.key(*modifier, Direction::Release);
+    }
+    result
+}
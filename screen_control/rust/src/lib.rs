@@ -1,25 +1,28 @@
-use enigo::{Enigo, MouseControllable, KeyboardControllable, MouseButton};
-use std::ffi::CStr;
-use std::os::raw::c_char;
+//! Native screen control: keyboard and mouse input, screen capture,
+//! window focus, OCR of screen regions, and recording and replay of input
+//! macros.
+//!
+//! [`ScreenController`] is the Rust API; [`ffi`] exposes the same actions to
+//! C and Python as `*_rust` functions returning status codes.
 
-/// Types text using the native keyboard.
-#[no_mangle]
-pub extern "C" fn type_text_rust(text: *const c_char) {
-    if text.is_null() { return; }
-    let c_str = unsafe { CStr::from_ptr(text) };
-    let text_str = match c_str.to_str() {
-        Ok(s) => s,
-        Err(_) => return,
-    };
-    
-    let mut enigo = Enigo::new();
-    enigo.key_sequence(text_str);
-}
+pub mod cadence;
+pub mod capture;
+pub mod clipboard;
+pub mod controller;
+pub mod display;
+pub mod error;
+pub mod ffi;
+pub mod keys;
+pub mod macros;
+pub mod ocr;
+pub mod record;
+pub mod window;
 
-/// Scrolls the mouse wheel up or down.
-#[no_mangle]
-pub extern "C" fn scroll_rust(direction: i32) {
-    let mut enigo = Enigo::new();
-    // Positive for down, negative for up
-    enigo.mouse_scroll_y(direction);
-} 
\ No newline at end of file
+pub use cadence::Cadence;
+pub use capture::Region;
+pub use controller::{MouseButton, ScreenController};
+pub use display::Monitor;
+pub use error::{Result, ScreenError};
+pub use macros::{Macro, MacroAction, TimedAction};
+pub use record::Recorder;
+pub use window::Window;
@@ -0,0 +1,108 @@
+use enigo::{Button, Direction, Enigo, Keyboard, Mouse};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use crate::error::{Result, ScreenError};
+
+/// One recorded or hand-written step of a macro
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum MacroAction {
+    MoveMouse { x: i32, y: i32 },
+    /// X11 button numbers: 1 left, 2 middle, 3 right, 4-7 scroll, 8 back, 9 forward
+    ButtonPress { button: u8 },
+    ButtonRelease { button: u8 },
+    /// Hardware keycodes, so replay does not depend on the keyboard layout
+    KeyPress { keycode: u16 },
+    KeyRelease { keycode: u16 },
+    Click { button: u8 },
+    TypeText { text: String },
+    KeyCombo { combo: String },
+    Scroll { amount: i32 },
+}
+
+/// An action and how long to wait before it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimedAction {
+    #[serde(default)]
+    pub delay_ms: u64,
+    #[serde(flatten)]
+    pub action: MacroAction,
+}
+
+/// A replayable sequence of input actions, stored as JSON
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Macro {
+    pub actions: Vec<TimedAction>,
+}
+
+impl Macro {
+    /// Reads a macro file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| ScreenError::Macro(format!("cannot read {}: {}", path.display(), e)))?;
+        serde_json::from_str(&json)
+            .map_err(|e| ScreenError::Macro(format!("cannot parse {}: {}", path.display(), e)))
+    }
+    
+    /// Writes the macro as pretty-printed JSON.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| ScreenError::Macro(e.to_string()))?;
+        fs::write(path, json)
+            .map_err(|e| ScreenError::Macro(format!("cannot write {}: {}", path.display(), e)))
+    }
+    
+    /// Total time the macro takes at normal speed
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.actions.iter().map(|a| a.delay_ms).sum())
+    }
+}
+
+/// Maps X11 button numbers to enigo buttons.
+pub(crate) fn x11_button(number: u8) -> Result<Button> {
+    match number {
+        1 => Ok(Button::Left),
+        2 => Ok(Button::Middle),
+        3 => Ok(Button::Right),
+        4 => Ok(Button::ScrollUp),
+        5 => Ok(Button::ScrollDown),
+        6 => Ok(Button::ScrollLeft),
+        7 => Ok(Button::ScrollRight),
+        8 => Ok(Button::Back),
+        9 => Ok(Button::Forward),
+        other => Err(ScreenError::InvalidArgument(format!("unknown mouse button {}", other))),
+    }
+}
+
+/// Rejects replay speeds that are not positive.
+pub(crate) fn check_speed(speed: f64) -> Result<()> {
+    if !(speed.is_finite() && speed > 0.0) {
+        return Err(ScreenError::InvalidArgument(format!("speed must be positive, got {}", speed)));
+    }
+    Ok(())
+}
+
+/// Replays a macro, waiting each action's delay divided by `speed`.
+pub(crate) fn play(enigo: &mut Enigo, recorded: &Macro, speed: f64) -> Result<()> {
+    check_speed(speed)?;
+    for step in &recorded.actions {
+        if step.delay_ms > 0 {
+            thread::sleep(Duration::from_secs_f64(step.delay_ms as f64 / 1000.0 / speed));
+        }
+        match &step.action {
+            MacroAction::MoveMouse { x, y } => enigo.move_mouse(*x, *y, enigo::Coordinate::Abs)?,
+            MacroAction::ButtonPress { button } => enigo.button(x11_button(*button)?, Direction::Press)?,
+            MacroAction::ButtonRelease { button } => enigo.button(x11_button(*button)?, Direction::Release)?,
+            MacroAction::KeyPress { keycode } => enigo.raw(*keycode, Direction::Press)?,
+            MacroAction::KeyRelease { keycode } => enigo.raw(*keycode, Direction::Release)?,
+            MacroAction::Click { button } => enigo.button(x11_button(*button)?, Direction::Click)?,
+            MacroAction::TypeText { text } => enigo.text(text)?,
+            MacroAction::KeyCombo { combo } => crate::keys::send_combo(enigo, &crate::keys::parse_combo(combo)?)?,
+            MacroAction::Scroll { amount } => enigo.scroll(*amount, enigo::Axis::Vertical)?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use crate::capture::{self, Region};
+use crate::error::{Result, ScreenError};
+
+/// Language used when the caller names none
+pub const DEFAULT_LANGUAGE: &str = "eng";
+
+/// Numbers the temporary captures so concurrent reads do not collide
+static CAPTURES: AtomicU32 = AtomicU32::new(0);
+
+/// Recognizes the text in a region of the screen. `language` is a
+/// tesseract language code such as `eng` or `eng+deu`.
+pub fn read_region(region: Region, language: &str) -> Result<String> {
+    check_language(language)?;
+    let path = std::env::temp_dir().join(format!(
+        "screen_rust_ocr_{}_{}.png", std::process::id(), CAPTURES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _cleanup = Cleanup(path.clone());
+    capture::capture_screen(&path, Some(region))?;
+    recognize(&path, language).map(|text| text.trim_end().to_string())
+}
+
+fn check_language(language: &str) -> Result<()> {
+    let valid = !language.is_empty()
+        && language.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '+');
+    if !valid {
+        return Err(ScreenError::InvalidArgument(format!("invalid OCR language '{}'", language)));
+    }
+    Ok(())
+}
+
+/// Runs tesseract in-process through leptess.
+#[cfg(feature = "leptess")]
+fn recognize(path: &Path, language: &str) -> Result<String> {
+    let error = |e: &dyn std::fmt::Display| ScreenError::Ocr(e.to_string());
+    let mut tess = leptess::LepTess::new(None, language).map_err(|e| error(&e))?;
+    tess.set_image(path).map_err(|e| error(&e))?;
+    tess.get_utf8_text().map_err(|e| error(&e))
+}
+
+/// Runs the `tesseract` command, which must be on the PATH.
+#[cfg(not(feature = "leptess"))]
+fn recognize(path: &Path, language: &str) -> Result<String> {
+    let output = std::process::Command::new("tesseract")
+        .arg(path)
+        .arg("stdout")
+        .args(["-l", language])
+        .output()
+        .map_err(|e| ScreenError::Ocr(format!("cannot run tesseract: {}", e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(ScreenError::Ocr(stderr.lines().last().unwrap_or("tesseract failed").to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Deletes the temporary capture however reading ends
+struct Cleanup(PathBuf);
+
+impl Drop for Cleanup {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
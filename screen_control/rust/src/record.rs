@@ -0,0 +1,25 @@
+#[cfg(target_os = "linux")]
+mod x11;
+
+#[cfg(not(target_os = "linux"))]
+mod x11 {
+    use crate::error::{Result, ScreenError};
+    use crate::macros::Macro;
+    
+    /// Input is only recorded through X11.
+    pub struct Recorder(());
+    
+    impl Recorder {
+        /// Fails: there is no X server to record from.
+        pub fn start() -> Result<Self> {
+            Err(ScreenError::NoDisplay("recording input needs X11".to_string()))
+        }
+        
+        /// Returns an empty macro, since nothing can have been recorded.
+        pub fn stop(self) -> Result<Macro> {
+            Ok(Macro::default())
+        }
+    }
+}
+
+pub use x11::Recorder;
@@ -0,0 +1,125 @@
+use std::thread::{self, JoinHandle};
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::record::{self, ConnectionExt as _};
+use x11rb::protocol::xproto::{BUTTON_PRESS_EVENT, BUTTON_RELEASE_EVENT, KEY_PRESS_EVENT, KEY_RELEASE_EVENT, MOTION_NOTIFY_EVENT};
+use x11rb::rust_connection::RustConnection;
+use crate::error::{Result, ScreenError};
+use crate::macros::{Macro, MacroAction, TimedAction};
+
+/// Size of a core X event on the wire
+const EVENT_LEN: usize = 32;
+
+/// Category of intercepted data sent by the server
+const FROM_SERVER: u8 = 0;
+
+/// Pointer motion closer together than this is merged into one move
+const MOTION_MERGE_MS: u64 = 15;
+
+fn error(message: impl std::fmt::Display) -> ScreenError {
+    ScreenError::Macro(message.to_string())
+}
+
+/// Records the user's keyboard and mouse input through the X RECORD
+/// extension until stopped.
+pub struct Recorder {
+    control: RustConnection,
+    context: record::Context,
+    worker: Option<JoinHandle<Result<Macro>>>,
+}
+
+impl Recorder {
+    /// Starts recording all key, button and motion events on the display.
+    pub fn start() -> Result<Self> {
+        let (control, _) = x11rb::connect(None).map_err(|e| ScreenError::NoDisplay(e.to_string()))?;
+        if control.extension_information(record::X11_EXTENSION_NAME).map_err(error)?.is_none() {
+            return Err(error("the X server has no RECORD extension"));
+        }
+        let context = control.generate_id().map_err(error)?;
+        let range = record::Range {
+            device_events: record::Range8 { first: KEY_PRESS_EVENT, last: MOTION_NOTIFY_EVENT },
+            ..Default::default()
+        };
+        control.record_create_context(context, 0, &[record::CS::ALL_CLIENTS.into()], &[range])
+            .map_err(error)?
+            .check()
+            .map_err(error)?;
+        
+        // Enabled contexts block their connection, so events arrive on a second one
+        let (data, _) = x11rb::connect(None).map_err(|e| ScreenError::NoDisplay(e.to_string()))?;
+        let worker = thread::spawn(move || collect(&data, context));
+        Ok(Self { control, context, worker: Some(worker) })
+    }
+    
+    /// Stops recording and returns what was captured.
+    pub fn stop(mut self) -> Result<Macro> {
+        self.finish()
+    }
+    
+    fn finish(&mut self) -> Result<Macro> {
+        let Some(worker) = self.worker.take() else {
+            return Ok(Macro::default());
+        };
+        self.control.record_disable_context(self.context)
+            .map_err(error)?
+            .check()
+            .map_err(error)?;
+        let recorded = worker.join().map_err(|_| error("recording thread panicked"))?;
+        let _ = self.control.record_free_context(self.context);
+        let _ = self.control.flush();
+        recorded
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+
+/// Reads intercepted events until the context is disabled.
+fn collect(data: &RustConnection, context: record::Context) -> Result<Macro> {
+    let mut recorded = Macro::default();
+    let mut last_time: Option<u32> = None;
+    for reply in data.record_enable_context(context).map_err(error)? {
+        let reply = reply.map_err(error)?;
+        if reply.category != FROM_SERVER {
+            continue;
+        }
+        for event in reply.data.chunks_exact(EVENT_LEN) {
+            let Some(action) = action(event) else {
+                continue;
+            };
+            let time = u32::from_ne_bytes([event[4], event[5], event[6], event[7]]);
+            let delay_ms = last_time.map_or(0, |last| u64::from(time.wrapping_sub(last)));
+            last_time = Some(time);
+            
+            // Keep the latest position of a quick run of moves
+            if let (MacroAction::MoveMouse { .. }, Some(previous)) = (&action, recorded.actions.last_mut()) {
+                if matches!(previous.action, MacroAction::MoveMouse { .. }) && delay_ms < MOTION_MERGE_MS {
+                    previous.action = action;
+                    previous.delay_ms += delay_ms;
+                    continue;
+                }
+            }
+            recorded.actions.push(TimedAction { delay_ms, action });
+        }
+    }
+    Ok(recorded)
+}
+
+/// The macro action for a core input event, if it is one
+fn action(event: &[u8]) -> Option<MacroAction> {
+    let detail = event[1];
+    let action = match event[0] & 0x7f {
+        KEY_PRESS_EVENT => MacroAction::KeyPress { keycode: detail.into() },
+        KEY_RELEASE_EVENT => MacroAction::KeyRelease { keycode: detail.into() },
+        BUTTON_PRESS_EVENT => MacroAction::ButtonPress { button: detail },
+        BUTTON_RELEASE_EVENT => MacroAction::ButtonRelease { button: detail },
+        MOTION_NOTIFY_EVENT => MacroAction::MoveMouse {
+            x: i16::from_ne_bytes([event[20], event[21]]).into(),
+            y: i16::from_ne_bytes([event[22], event[23]]).into(),
+        },
+        _ => return None,
+    };
+    Some(action)
+}
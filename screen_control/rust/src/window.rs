@@ -0,0 +1,73 @@
+use serde::Serialize;
+use crate::error::{Result, ScreenError};
+
+#[cfg(target_os = "linux")]
+mod x11;
+
+/// Windows are only managed through X11.
+#[cfg(not(target_os = "linux"))]
+mod x11 {
+    use crate::error::{Result, ScreenError};
+    use super::Window;
+    
+    pub(super) fn windows() -> Result<Vec<Window>> {
+        Err(ScreenError::NoDisplay("listing windows needs X11".to_string()))
+    }
+    
+    pub(super) fn active_window() -> Result<Option<Window>> {
+        Err(ScreenError::NoDisplay("finding the focused window needs X11".to_string()))
+    }
+    
+    pub(super) fn focus_window(_pattern: &str) -> Result<Window> {
+        Err(ScreenError::NoDisplay("focusing windows needs X11".to_string()))
+    }
+}
+
+/// A top-level window as the window manager lists it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Window {
+    pub id: u32,
+    pub title: String,
+    /// Position of the window's top-left corner on the desktop
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Window {
+    /// Whether the title contains `pattern`, ignoring case
+    pub fn matches(&self, pattern: &str) -> bool {
+        self.title.to_lowercase().contains(&pattern.to_lowercase())
+    }
+}
+
+fn error(message: impl std::fmt::Display) -> ScreenError {
+    ScreenError::Window(message.to_string())
+}
+
+/// Top-level windows, in the window manager's stacking order.
+pub fn windows() -> Result<Vec<Window>> {
+    x11::windows()
+}
+
+/// The window that has focus, if the window manager reports one.
+pub fn active_window() -> Result<Option<Window>> {
+    x11::active_window()
+}
+
+/// Activates the first window whose title contains `pattern` and waits
+/// until the window manager reports it active.
+pub fn focus_window(pattern: &str) -> Result<Window> {
+    x11::focus_window(pattern)
+}
+
+/// Fails unless the focused window's title contains `pattern`, so input
+/// never lands in whatever happens to be in front.
+pub fn ensure_focus(pattern: &str) -> Result<Window> {
+    match active_window()? {
+        Some(active) if active.matches(pattern) => Ok(active),
+        Some(active) => Err(error(format!("focused window is '{}', not '{}'", active.title, pattern))),
+        None => Err(error("no window has focus")),
+    }
+}
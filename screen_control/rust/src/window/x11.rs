@@ -0,0 +1,121 @@
+use std::thread;
+use std::time::{Duration, Instant};
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{
+    AtomEnum, ClientMessageEvent, ConnectionExt as _, EventMask, Window as WindowId,
+};
+use x11rb::rust_connection::RustConnection;
+use crate::error::{Result, ScreenError};
+use super::{error, Window};
+
+/// How long to wait for the window manager to hand focus over
+const FOCUS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Pause between checks of the active window
+const FOCUS_POLL: Duration = Duration::from_millis(50);
+
+struct Display {
+    conn: RustConnection,
+    root: WindowId,
+}
+
+impl Display {
+    fn open() -> Result<Self> {
+        let (conn, screen_num) = x11rb::connect(None).map_err(|e| ScreenError::NoDisplay(e.to_string()))?;
+        let root = conn.setup().roots[screen_num].root;
+        Ok(Self { conn, root })
+    }
+    
+    fn atom(&self, name: &str) -> Result<u32> {
+        Ok(self.conn.intern_atom(false, name.as_bytes()).map_err(error)?.reply().map_err(error)?.atom)
+    }
+    
+    /// Window IDs in a root window property such as `_NET_CLIENT_LIST`
+    fn window_property(&self, name: &str) -> Result<Vec<WindowId>> {
+        let atom = self.atom(name)?;
+        let reply = self.conn.get_property(false, self.root, atom, AtomEnum::WINDOW, 0, u32::MAX / 4)
+            .map_err(error)?
+            .reply()
+            .map_err(error)?;
+        Ok(reply.value32().map(|ids| ids.collect()).unwrap_or_default())
+    }
+    
+    fn title(&self, window: WindowId) -> Result<String> {
+        let utf8 = self.atom("UTF8_STRING")?;
+        let net_name = self.atom("_NET_WM_NAME")?;
+        for (property, kind) in [(net_name, utf8), (AtomEnum::WM_NAME.into(), AtomEnum::STRING.into())] {
+            let reply = self.conn.get_property(false, window, property, kind, 0, 1024)
+                .map_err(error)?
+                .reply()
+                .map_err(error)?;
+            if !reply.value.is_empty() {
+                return Ok(String::from_utf8_lossy(&reply.value).into_owned());
+            }
+        }
+        Ok(String::new())
+    }
+    
+    fn describe(&self, window: WindowId) -> Result<Window> {
+        let geometry = self.conn.get_geometry(window).map_err(error)?.reply().map_err(error)?;
+        let origin = self.conn.translate_coordinates(window, self.root, 0, 0)
+            .map_err(error)?
+            .reply()
+            .map_err(error)?;
+        Ok(Window {
+            id: window,
+            title: self.title(window)?,
+            x: origin.dst_x.into(),
+            y: origin.dst_y.into(),
+            width: geometry.width.into(),
+            height: geometry.height.into(),
+        })
+    }
+    
+    fn active(&self) -> Result<Option<Window>> {
+        match self.window_property("_NET_ACTIVE_WINDOW")?.first() {
+            Some(&id) if id != 0 => self.describe(id).map(Some),
+            _ => Ok(None),
+        }
+    }
+    
+    /// Asks the window manager to activate a window, as a pager would
+    fn activate(&self, window: WindowId) -> Result<()> {
+        let active = self.atom("_NET_ACTIVE_WINDOW")?;
+        // Source indication 2: the request comes from a pager-like tool
+        let event = ClientMessageEvent::new(32, window, active, [2, 0, 0, 0, 0]);
+        self.conn.send_event(false, self.root, EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY, event)
+            .map_err(error)?;
+        self.conn.flush().map_err(error)?;
+        Ok(())
+    }
+}
+
+pub(super) fn windows() -> Result<Vec<Window>> {
+    let display = Display::open()?;
+    let ids = display.window_property("_NET_CLIENT_LIST")?;
+    // Windows can close while being listed
+    Ok(ids.into_iter().filter_map(|id| display.describe(id).ok()).collect())
+}
+
+pub(super) fn active_window() -> Result<Option<Window>> {
+    Display::open()?.active()
+}
+
+pub(super) fn focus_window(pattern: &str) -> Result<Window> {
+    let display = Display::open()?;
+    let window = display.window_property("_NET_CLIENT_LIST")?
+        .into_iter()
+        .filter_map(|id| display.describe(id).ok())
+        .find(|w| w.matches(pattern))
+        .ok_or_else(|| error(format!("no window titled like '{}'", pattern)))?;
+    
+    display.activate(window.id)?;
+    let deadline = Instant::now() + FOCUS_TIMEOUT;
+    while Instant::now() < deadline {
+        if display.active()?.is_some_and(|active| active.id == window.id) {
+            return Ok(window);
+        }
+        thread::sleep(FOCUS_POLL);
+    }
+    Err(error(format!("window '{}' did not take focus", window.title)))
+}
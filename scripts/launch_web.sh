@@ -59,7 +59,12 @@ sleep 2
 
 # Start Python bridge
 echo "🐍 Starting Python bridge..."
-python3 -m uvicorn modules.web.bridge_server:app --host 127.0.0.1 --port 8766 --log-level warning &
+if [ -n "$NEURORIFT_BRIDGE_SOCKET" ]; then
+    # Serves on the Unix socket; the core picks up the same variable
+    python3 -m modules.web.bridge_server &
+else
+    python3 -m uvicorn modules.web.bridge_server:app --host 127.0.0.1 --port 8766 --log-level warning &
+fi
 PYTHON_PID=$!
 echo "   PID: $PYTHON_PID"
 
@@ -120,7 +125,7 @@ fi
 echo ""
 echo "Services:"
 echo "  • Rust Core:      ws://localhost:8765"
-echo "  • Python Bridge:  http://localhost:8766"
+echo "  • Python Bridge:  ${NEURORIFT_BRIDGE_SOCKET:+unix:}${NEURORIFT_BRIDGE_SOCKET:-http://localhost:8766}"
 echo "  • Frontend:       http://localhost:3000"
 
 if [ "$ONLINE_MODE" = true ]; then
@@ -28,23 +28,46 @@ export function ChatWidget() {
     const [isTyping, setIsTyping] = useState(false);
 
     useEffect(() => {
+        // Grow the reply in place as chunks arrive
+        const handleChatChunk = (event: CustomEvent) => {
+            const { request_id, delta } = event.detail;
+            setIsTyping(false);
+
+            setMessages(prev => prev.some(m => m.id === request_id)
+                ? prev.map(m => m.id === request_id ? { ...m, content: m.content + delta } : m)
+                : [
+                    ...prev,
+                    {
+                        id: request_id,
+                        role: 'assistant',
+                        content: delta,
+                        timestamp: new Date()
+                    }
+                ]);
+        };
+
+        // The final response replaces whatever was streamed
         const handleChatResponse = (event: CustomEvent) => {
-            const { response } = event.detail;
+            const { request_id, response } = event.detail;
             setIsTyping(false);
 
-            setMessages(prev => [
-                ...prev,
-                {
-                    id: Date.now().toString(),
-                    role: 'assistant',
-                    content: response,
-                    timestamp: new Date()
-                }
-            ]);
+            setMessages(prev => prev.some(m => m.id === request_id)
+                ? prev.map(m => m.id === request_id ? { ...m, content: response } : m)
+                : [
+                    ...prev,
+                    {
+                        id: request_id ?? Date.now().toString(),
+                        role: 'assistant',
+                        content: response,
+                        timestamp: new Date()
+                    }
+                ]);
         };
 
+        window.addEventListener('neurorift:chat_response_chunk', handleChatChunk as EventListener);
         window.addEventListener('neurorift:chat_response', handleChatResponse as EventListener);
         return () => {
+            window.removeEventListener('neurorift:chat_response_chunk', handleChatChunk as EventListener);
             window.removeEventListener('neurorift:chat_response', handleChatResponse as EventListener);
         };
     }, []);
@@ -74,7 +97,8 @@ export function ChatWidget() {
         ws.send({
             type: 'chat',
             message: userMsg.content,
-            model: 'llama3' // Default model
+            model: 'llama3', // Default model
+            request_id: `chat-${userMsg.id}`
         });
     };
 
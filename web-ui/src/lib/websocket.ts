@@ -5,17 +5,33 @@ class MockWebSocket {
 
     send(payload: WebSocketPayload) {
         switch (payload.type) {
-            case 'chat':
+            case 'chat': {
+                const response = `Echoed intent received: ${payload.message}`;
+                const words = response.split(' ');
+                words.forEach((word, i) => {
+                    setTimeout(() => {
+                        window.dispatchEvent(
+                            new CustomEvent('neurorift:chat_response_chunk', {
+                                detail: {
+                                    request_id: payload.request_id,
+                                    delta: i === 0 ? word : ` ${word}`
+                                }
+                            })
+                        );
+                    }, 500 + i * 60);
+                });
                 setTimeout(() => {
                     window.dispatchEvent(
                         new CustomEvent('neurorift:chat_response', {
                             detail: {
-                                response: `Echoed intent received: ${payload.message}`
+                                request_id: payload.request_id,
+                                response
                             }
                         })
                     );
-                }, 500);
+                }, 500 + words.length * 60);
                 break;
+            }
             case 'get_session_list':
                 setTimeout(() => {
                     window.dispatchEvent(